@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{AuthError, CurrentUser, TokenVerifier};
+
+/// start of every api key, tells them apart from the access tokens
+pub const API_KEY_PREFIX: &str = "hak_";
+
+/// a verified key is trusted this long without asking again, so a revoked key may keep working
+/// for as long
+const CACHE_DURATION: Duration = Duration::from_secs(30);
+
+/// Body of the verification request to the authentication service
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyApiKey {
+    pub api_key: String,
+}
+
+/// Owner and scopes of a valid api key, as answered by the authentication service
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedApiKey {
+    pub key_id: Uuid,
+    pub user_id: Uuid,
+    pub scopes: Vec<String>,
+}
+
+impl From<VerifiedApiKey> for CurrentUser {
+    fn from(key: VerifiedApiKey) -> Self {
+        CurrentUser {
+            user_id: key.user_id,
+            session_id: key.key_id,
+            scopes: Some(key.scopes),
+        }
+    }
+}
+
+/// Verifies the api keys with the authentication service, only it holds their hashes
+pub struct ApiKeyVerifier {
+    verify_url: String,
+    http_client: reqwest::Client,
+    verified: RwLock<HashMap<String, (CurrentUser, Instant)>>,
+}
+
+impl ApiKeyVerifier {
+    pub fn new(verify_url: &str) -> Self {
+        ApiKeyVerifier {
+            verify_url: verify_url.to_string(),
+            http_client: reqwest::Client::new(),
+            verified: RwLock::default(),
+        }
+    }
+
+    /// keys verified at `AUTH_API_KEY_URL`
+    pub fn from_env() -> Self {
+        let verify_url =
+            std::env::var("AUTH_API_KEY_URL").expect("Could not fetch the api key url.");
+
+        Self::new(&verify_url)
+    }
+
+    async fn fetch(&self, api_key: &str) -> Result<CurrentUser, AuthError> {
+        let unavailable = |err: reqwest::Error| AuthError::KeysUnavailable(err.to_string());
+
+        let response = self
+            .http_client
+            .post(&self.verify_url)
+            .timeout(Duration::from_secs(5))
+            .json(&VerifyApiKey {
+                api_key: api_key.to_string(),
+            })
+            .send()
+            .await
+            .map_err(unavailable)?;
+
+        if response.status().is_client_error() {
+            return Err(AuthError::InvalidToken);
+        }
+
+        let key: VerifiedApiKey = response
+            .error_for_status()
+            .map_err(unavailable)?
+            .json()
+            .await
+            .map_err(unavailable)?;
+
+        Ok(key.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for ApiKeyVerifier {
+    async fn verify(&self, api_key: &str) -> Result<CurrentUser, AuthError> {
+        if !api_key.starts_with(API_KEY_PREFIX) {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if let Some((user, verified_at)) = self.verified.read().await.get(api_key) {
+            if verified_at.elapsed() < CACHE_DURATION {
+                return Ok(user.clone());
+            }
+        }
+
+        let user = self.fetch(api_key).await?;
+
+        let mut verified = self.verified.write().await;
+        verified.retain(|_, (_, verified_at)| verified_at.elapsed() < CACHE_DURATION);
+        verified.insert(api_key.to_string(), (user.clone(), Instant::now()));
+
+        Ok(user)
+    }
+}
@@ -0,0 +1,75 @@
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AuthError {
+    #[error("Missing bearer token")]
+    MissingToken,
+    #[error("Invalid or expired token")]
+    InvalidToken,
+    #[error("Token lacks the {0} scope")]
+    InsufficientScope(String),
+    #[error("Signing keys could not be fetched: {0}")]
+    KeysUnavailable(String),
+}
+
+impl AuthError {
+    /// Stable machine readable code, the same envelope as the errors of the services
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingToken => "MISSING_TOKEN",
+            Self::InvalidToken => "INVALID_TOKEN",
+            Self::InsufficientScope(_) => "INSUFFICIENT_SCOPE",
+            Self::KeysUnavailable(_) => "AUTHENTICATION_UNAVAILABLE",
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::MissingToken | Self::InvalidToken => StatusCode::UNAUTHORIZED,
+            Self::InsufficientScope(_) => StatusCode::FORBIDDEN,
+            Self::KeysUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::MissingToken => "Authorization header with a bearer token is required.".into(),
+            Self::InvalidToken => "Token is invalid or has expired.".into(),
+            Self::InsufficientScope(scope) => format!("Token lacks the {scope} scope."),
+            Self::KeysUnavailable(_) => "Authentication is temporarily unavailable.".into(),
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        if let Self::KeysUnavailable(reason) = &self {
+            log::error!("Could not fetch the signing keys: {reason}");
+        }
+
+        let body = json!({ "code": self.code(), "message": self.message() });
+        let mut response = (self.status_code(), Json(body)).into_response();
+
+        let challenge = match &self {
+            Self::MissingToken => Some("Bearer"),
+            Self::InvalidToken => Some(r#"Bearer error="invalid_token""#),
+            Self::InsufficientScope(_) => Some(r#"Bearer error="insufficient_scope""#),
+            Self::KeysUnavailable(_) => None,
+        };
+
+        if let Some(challenge) = challenge {
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static(challenge),
+            );
+        }
+
+        response
+    }
+}
@@ -0,0 +1,325 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{header, Method},
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use crate::{AuthError, BearerToken, CurrentUser, TokenVerifier, API_KEY_PREFIX};
+
+/// Rejects the requests without a valid access token, except on the public paths, and adds the
+/// [`CurrentUser`] and its [`BearerToken`] to the extensions of the others
+#[derive(Clone)]
+pub struct AuthLayer {
+    verifier: Arc<dyn TokenVerifier + Send + Sync>,
+    api_key_verifier: Option<Arc<dyn TokenVerifier + Send + Sync>>,
+    public_paths: Arc<HashSet<String>>,
+    service: Option<&'static str>,
+}
+
+impl AuthLayer {
+    pub fn new(verifier: Arc<dyn TokenVerifier + Send + Sync>) -> Self {
+        AuthLayer {
+            verifier,
+            api_key_verifier: None,
+            public_paths: Arc::default(),
+            service: None,
+        }
+    }
+
+    /// path answered without a token, matched exactly
+    pub fn public_path(mut self, path: &str) -> Self {
+        Arc::make_mut(&mut self.public_paths).insert(path.to_string());
+        self
+    }
+
+    /// also accept the api keys, tokens starting with [`API_KEY_PREFIX`]
+    pub fn api_keys(mut self, verifier: Arc<dyn TokenVerifier + Send + Sync>) -> Self {
+        self.api_key_verifier = Some(verifier);
+        self
+    }
+
+    /// tokens limited to some scopes need `<service>:read` to read and `<service>:write`
+    /// for anything else
+    pub fn scoped(mut self, service: &'static str) -> Self {
+        self.service = Some(service);
+        self
+    }
+
+    async fn authenticate(&self, request: &mut Request) -> Result<(), AuthError> {
+        if self.public_paths.contains(request.uri().path()) {
+            return Ok(());
+        }
+
+        let authorization = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        let write = !matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        );
+
+        let (user, token) = self.authorize(authorization, write).await?;
+
+        request.extensions_mut().insert::<CurrentUser>(user);
+        request.extensions_mut().insert(token);
+
+        Ok(())
+    }
+
+    /// caller of an `Authorization` value, allowed to read or to write, for the transports the
+    /// layer can't wrap like gRPC
+    pub async fn authorize(
+        &self,
+        authorization: Option<&str>,
+        write: bool,
+    ) -> Result<(CurrentUser, BearerToken), AuthError> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or(AuthError::MissingToken)?
+            .to_string();
+
+        let user = if token.starts_with(API_KEY_PREFIX) {
+            self.api_key_verifier
+                .as_ref()
+                .ok_or(AuthError::InvalidToken)?
+                .verify(&token)
+                .await?
+        } else {
+            self.verifier.verify(&token).await?
+        };
+
+        if let Some(service) = self.service {
+            let access = if write { "write" } else { "read" };
+            let scope = format!("{service}:{access}");
+
+            if !user.has_scope(&scope) {
+                return Err(AuthError::InsufficientScope(scope));
+            }
+        }
+
+        Ok((user, BearerToken(token)))
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = Auth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Auth {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service wrapped by an [`AuthLayer`]
+#[derive(Clone)]
+pub struct Auth<S> {
+    inner: S,
+    layer: AuthLayer,
+}
+
+impl<S> Service<Request> for Auth<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        // the service polled ready is the one that must handle the request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            match layer.authenticate(&mut request).await {
+                Ok(()) => inner.call(request).await,
+                Err(err) => Ok(err.into_response()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::StatusCode,
+        routing::{get, post},
+        Router,
+    };
+    use jsonwebtoken::{jwk::JwkSet, Algorithm, EncodingKey, Header};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::{Claims, JwksVerifier};
+
+    use super::*;
+
+    const KEY_ID: &str = "test-key";
+
+    fn token(issuer: &str, scope: Option<&str>, expires_in: i64) -> String {
+        let now = now();
+        let claims = Claims {
+            sub: Uuid::new_v4(),
+            sid: Uuid::new_v4(),
+            iss: issuer.to_string(),
+            iat: now,
+            exp: now + expires_in,
+            scope: scope.map(String::from),
+        };
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(KEY_ID.to_string());
+
+        let key = EncodingKey::from_rsa_pem(include_bytes!("../testdata/jwt.pem")).unwrap();
+
+        jsonwebtoken::encode(&header, &claims, &key).unwrap()
+    }
+
+    fn now() -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    /// the only api key is `hak_read`, limited to reading the budget
+    struct ReadApiKey;
+
+    #[async_trait::async_trait]
+    impl TokenVerifier for ReadApiKey {
+        async fn verify(&self, api_key: &str) -> Result<CurrentUser, AuthError> {
+            if api_key != "hak_read" {
+                return Err(AuthError::InvalidToken);
+            }
+
+            Ok(CurrentUser {
+                user_id: Uuid::new_v4(),
+                session_id: Uuid::new_v4(),
+                scopes: Some(vec![String::from("budget:read")]),
+            })
+        }
+    }
+
+    fn app() -> Router {
+        let jwks: JwkSet = serde_json::from_str(include_str!("../testdata/jwks.json")).unwrap();
+        let verifier = JwksVerifier::from_jwks("home_app", &jwks).unwrap();
+
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route(
+                "/records",
+                get(|user: CurrentUser| async move { user.user_id.to_string() }),
+            )
+            .route("/records", post(|| async { StatusCode::CREATED }))
+            .layer(
+                AuthLayer::new(Arc::new(verifier))
+                    .api_keys(Arc::new(ReadApiKey))
+                    .public_path("/health")
+                    .scoped("budget"),
+            )
+    }
+
+    async fn status(method: Method, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().method(method).uri(uri);
+
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn should_authenticate_every_route_but_the_public_ones() {
+        let valid = token("home_app", None, 60);
+
+        assert_eq!(status(Method::GET, "/health", None).await, StatusCode::OK);
+        assert_eq!(
+            status(Method::GET, "/records", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(Method::GET, "/records", Some(&valid)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::POST, "/records", Some(&valid)).await,
+            StatusCode::CREATED
+        );
+
+        for invalid in [
+            token("other", None, 60),
+            token("home_app", None, -120),
+            String::from("not a token"),
+        ] {
+            assert_eq!(
+                status(Method::GET, "/records", Some(&invalid)).await,
+                StatusCode::UNAUTHORIZED
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_forbid_the_scopes_the_token_lacks() {
+        let read = token("home_app", Some("budget:read work_notes:write"), 60);
+        let write = token("home_app", Some("budget:write"), 60);
+
+        assert_eq!(
+            status(Method::GET, "/records", Some(&read)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::POST, "/records", Some(&read)).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(Method::GET, "/records", Some(&write)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::POST, "/records", Some(&write)).await,
+            StatusCode::CREATED
+        );
+    }
+
+    #[tokio::test]
+    async fn should_accept_api_keys_within_their_scopes() {
+        assert_eq!(
+            status(Method::GET, "/records", Some("hak_read")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::POST, "/records", Some("hak_read")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(Method::GET, "/records", Some("hak_revoked")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
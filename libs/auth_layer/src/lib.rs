@@ -0,0 +1,14 @@
+//! Authentication shared by the services: a tower layer accepting the access tokens issued by the
+//! authentication service, or its api keys, and handing the caller to the routes.
+
+mod api_keys;
+mod errors;
+mod layer;
+mod user;
+mod verifier;
+
+pub use api_keys::{ApiKeyVerifier, VerifiedApiKey, VerifyApiKey, API_KEY_PREFIX};
+pub use errors::AuthError;
+pub use layer::{Auth, AuthLayer};
+pub use user::{BearerToken, Claims, CurrentUser};
+pub use verifier::{JwksVerifier, TokenVerifier};
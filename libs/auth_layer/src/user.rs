@@ -0,0 +1,90 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AuthError;
+
+/// Claims of the access tokens
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Claims {
+    /// user id
+    pub sub: Uuid,
+    /// session the token was issued for
+    pub sid: Uuid,
+    pub iss: String,
+    pub iat: i64,
+    pub exp: i64,
+    /// space separated scopes the token is limited to, tokens of a login have none and can do
+    /// anything their user can
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Caller of the request, added to the request extensions by the layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentUser {
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    /// none when the token is not limited to some scopes
+    pub scopes: Option<Vec<String>>,
+}
+
+/// Token the request was authenticated with, for the services that call other services on behalf
+/// of the caller
+#[derive(Debug, Clone, PartialEq)]
+pub struct BearerToken(pub String);
+
+impl From<Claims> for CurrentUser {
+    fn from(claims: Claims) -> Self {
+        CurrentUser {
+            user_id: claims.sub,
+            session_id: claims.sid,
+            scopes: claims
+                .scope
+                .map(|scope| scope.split_whitespace().map(String::from).collect()),
+        }
+    }
+}
+
+impl CurrentUser {
+    /// `service:write` also grants `service:read`
+    pub fn has_scope(&self, scope: &str) -> bool {
+        let Some(scopes) = &self.scopes else {
+            return true;
+        };
+
+        let write = scope
+            .strip_suffix(":read")
+            .map(|service| format!("{service}:write"));
+
+        scopes
+            .iter()
+            .any(|granted| granted == scope || Some(granted) == write.as_ref())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or(AuthError::MissingToken)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for BearerToken {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<BearerToken>()
+            .cloned()
+            .ok_or(AuthError::MissingToken)
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use tokio::sync::RwLock;
+
+use crate::{AuthError, Claims, CurrentUser};
+
+/// an unknown key id triggers a new fetch of the keys at most this often
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[async_trait::async_trait]
+pub trait TokenVerifier {
+    async fn verify(&self, token: &str) -> Result<CurrentUser, AuthError>;
+}
+
+/// Verifies the access tokens with the public keys published by the authentication service,
+/// the keys are fetched on the first request and again when a token names an unknown key
+pub struct JwksVerifier {
+    issuer: String,
+    jwks_url: Option<String>,
+    http_client: reqwest::Client,
+    keys: RwLock<Keys>,
+}
+
+#[derive(Default)]
+struct Keys {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Option<Instant>,
+}
+
+impl JwksVerifier {
+    pub fn new(issuer: &str, jwks_url: &str) -> Self {
+        JwksVerifier {
+            issuer: issuer.to_string(),
+            jwks_url: Some(jwks_url.to_string()),
+            http_client: reqwest::Client::new(),
+            keys: RwLock::default(),
+        }
+    }
+
+    /// keys published at `AUTH_JWKS_URL` for the tokens of the `JWT_ISSUER`, home_app by default
+    pub fn from_env() -> Self {
+        let jwks_url = std::env::var("AUTH_JWKS_URL").expect("Could not fetch the JWKS url.");
+        let issuer = std::env::var("JWT_ISSUER").unwrap_or_else(|_| String::from("home_app"));
+
+        Self::new(&issuer, &jwks_url)
+    }
+
+    /// verifier of a fixed set of keys, never fetched again
+    pub fn from_jwks(issuer: &str, jwks: &JwkSet) -> Result<Self, AuthError> {
+        Ok(JwksVerifier {
+            issuer: issuer.to_string(),
+            jwks_url: None,
+            http_client: reqwest::Client::new(),
+            keys: RwLock::new(Keys {
+                keys: decoding_keys(jwks)?,
+                fetched_at: Some(Instant::now()),
+            }),
+        })
+    }
+
+    async fn key(&self, kid: &str) -> Result<DecodingKey, AuthError> {
+        {
+            let keys = self.keys.read().await;
+
+            if let Some(key) = keys.keys.get(kid) {
+                return Ok(key.clone());
+            }
+
+            if keys
+                .fetched_at
+                .is_some_and(|fetched_at| fetched_at.elapsed() < REFRESH_INTERVAL)
+            {
+                return Err(AuthError::InvalidToken);
+            }
+        }
+
+        let Some(jwks_url) = &self.jwks_url else {
+            return Err(AuthError::InvalidToken);
+        };
+
+        let mut keys = self.keys.write().await;
+
+        // another request may have fetched them while this one waited for the lock
+        if !keys.keys.contains_key(kid)
+            && keys
+                .fetched_at
+                .is_none_or(|fetched_at| fetched_at.elapsed() >= REFRESH_INTERVAL)
+        {
+            let jwks = self.fetch(jwks_url).await?;
+
+            keys.keys = decoding_keys(&jwks)?;
+            keys.fetched_at = Some(Instant::now());
+        }
+
+        keys.keys.get(kid).cloned().ok_or(AuthError::InvalidToken)
+    }
+
+    async fn fetch(&self, jwks_url: &str) -> Result<JwkSet, AuthError> {
+        let unavailable = |err: reqwest::Error| AuthError::KeysUnavailable(err.to_string());
+
+        self.http_client
+            .get(jwks_url)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(unavailable)?
+            .json()
+            .await
+            .map_err(unavailable)
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for JwksVerifier {
+    async fn verify(&self, token: &str) -> Result<CurrentUser, AuthError> {
+        let header = jsonwebtoken::decode_header(token).map_err(|_| AuthError::InvalidToken)?;
+        let kid = header.kid.ok_or(AuthError::InvalidToken)?;
+        let key = self.key(&kid).await?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+
+        jsonwebtoken::decode::<Claims>(token, &key, &validation)
+            .map(|data| data.claims.into())
+            .map_err(|_| AuthError::InvalidToken)
+    }
+}
+
+fn decoding_keys(jwks: &JwkSet) -> Result<HashMap<String, DecodingKey>, AuthError> {
+    jwks.keys
+        .iter()
+        .filter_map(|jwk| Some((jwk.common.key_id.clone()?, jwk)))
+        .map(|(kid, jwk)| {
+            DecodingKey::from_jwk(jwk)
+                .map(|key| (kid, key))
+                .map_err(|err| AuthError::KeysUnavailable(err.to_string()))
+        })
+        .collect()
+}
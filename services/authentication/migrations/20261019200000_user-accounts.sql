@@ -0,0 +1,21 @@
+-- the users table only had its key, it never held any row
+ALTER TABLE users
+    ADD COLUMN email VARCHAR(320) NOT NULL,
+    ADD COLUMN name VARCHAR(100) NOT NULL,
+    ADD COLUMN password_hash VARCHAR NOT NULL,
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN updated_at TIMESTAMPTZ;
+
+-- emails are stored lowercase
+CREATE UNIQUE INDEX users_email_key ON users (email);
+
+CREATE TABLE sessions (
+    session_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (user_id),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX sessions_user_id_idx ON sessions (user_id);
@@ -0,0 +1,14 @@
+-- sessions become the family of their refresh tokens, access tokens are signed and never stored,
+-- the opaque tokens issued until now stop working
+UPDATE sessions SET revoked_at = now() WHERE revoked_at IS NULL;
+ALTER TABLE sessions DROP COLUMN token_hash;
+
+CREATE TABLE refresh_tokens (
+    token_id UUID PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES sessions (session_id),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX refresh_tokens_session_id_idx ON refresh_tokens (session_id);
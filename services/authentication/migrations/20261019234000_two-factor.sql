@@ -0,0 +1,31 @@
+-- authenticator app of a user, enabled once a first code has been verified
+CREATE TABLE totp_factors (
+    user_id UUID PRIMARY KEY REFERENCES users (user_id),
+    -- the authenticator needs the secret itself, it can't be hashed
+    secret VARCHAR(40) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    enabled_at TIMESTAMPTZ,
+    -- time step of the last accepted code, a code is never accepted twice
+    last_used_step BIGINT
+);
+
+CREATE TABLE recovery_codes (
+    code_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (user_id),
+    code_hash VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX recovery_codes_user_id_idx ON recovery_codes (user_id);
+
+-- password checked, waiting for the second factor
+CREATE TABLE login_challenges (
+    challenge_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (user_id),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    failed_attempts INT NOT NULL DEFAULT 0,
+    used_at TIMESTAMPTZ
+);
@@ -0,0 +1,16 @@
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+CREATE TYPE email_token_purpose AS ENUM ('VERIFY_EMAIL', 'RESET_PASSWORD');
+
+-- single use tokens mailed to the users, only their hash is stored
+CREATE TABLE email_tokens (
+    token_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (user_id),
+    purpose email_token_purpose NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX email_tokens_user_id_idx ON email_tokens (user_id);
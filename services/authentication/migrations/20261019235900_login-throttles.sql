@@ -0,0 +1,20 @@
+-- failed logins of an email or of an ip address, forgotten once they are old enough
+CREATE TABLE login_throttles (
+    throttle_key VARCHAR(330) PRIMARY KEY,
+    failures INTEGER NOT NULL,
+    last_failure_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX login_throttles_last_failure_at_idx ON login_throttles (last_failure_at);
+
+-- accounts locked after too many failed logins, kept for their owner to review
+CREATE TABLE lockout_events (
+    event_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (user_id),
+    -- address of the failed login that locked the account
+    ip_address VARCHAR(45) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    locked_until TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX lockout_events_user_id_idx ON lockout_events (user_id);
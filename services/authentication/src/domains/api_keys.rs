@@ -0,0 +1,150 @@
+use auth_layer::API_KEY_PREFIX;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    errors::{Error, Result},
+    sessions::{hash_token, new_token},
+};
+
+/// scopes a key can be limited to, keys have no access beyond them
+pub const API_KEY_SCOPES: [&str; 3] = ["budget:read", "budget:write", "work_notes:write"];
+
+/// keys created without an expiry stop working after this long
+pub const DEFAULT_API_KEY_DURATION: Duration = Duration::days(90);
+
+pub const MAX_API_KEY_NAME_LENGTH: usize = 100;
+
+/// length of the start of the key kept to tell the keys apart, the prefix and 8 characters
+const KEY_HINT_LENGTH: usize = API_KEY_PREFIX.len() + 8;
+
+/// Named key a script uses instead of a login, only its hash is stored
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub key_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(skip)]
+    pub key_hash: String,
+    pub key_hint: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Key just created, the only time its value is handed to the user
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+impl ApiKey {
+    /// new key of the user and the value handed to them
+    pub fn new(user_id: Uuid, payload: CreateApiKey) -> Result<(Self, String)> {
+        let now = Utc::now();
+
+        let name = payload.name.trim().to_string();
+
+        if name.is_empty() || name.chars().count() > MAX_API_KEY_NAME_LENGTH {
+            return Err(Error::InvalidApiKey(format!(
+                "name must have between 1 and {MAX_API_KEY_NAME_LENGTH} characters"
+            )));
+        }
+
+        let mut scopes = payload.scopes;
+        scopes.sort();
+        scopes.dedup();
+
+        if scopes.is_empty() {
+            return Err(Error::InvalidApiKey(String::from("scopes are required")));
+        }
+
+        if let Some(scope) = scopes
+            .iter()
+            .find(|s| !API_KEY_SCOPES.contains(&s.as_str()))
+        {
+            return Err(Error::InvalidApiKey(format!("unknown scope '{scope}'")));
+        }
+
+        let expires_at = payload.expires_at.unwrap_or(now + DEFAULT_API_KEY_DURATION);
+
+        if expires_at <= now {
+            return Err(Error::InvalidApiKey(String::from(
+                "expiry must be in the future",
+            )));
+        }
+
+        let key = format!("{API_KEY_PREFIX}{}", new_token());
+
+        let api_key = ApiKey {
+            key_id: Uuid::new_v4(),
+            user_id,
+            name,
+            key_hash: hash_token(&key),
+            key_hint: key[..KEY_HINT_LENGTH].to_string(),
+            scopes,
+            created_at: now,
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+        };
+
+        Ok((api_key, key))
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(scopes: &[&str], expires_at: Option<DateTime<Utc>>) -> Result<(ApiKey, String)> {
+        ApiKey::new(
+            Uuid::new_v4(),
+            CreateApiKey {
+                name: String::from(" home assistant "),
+                scopes: scopes.iter().map(|s| s.to_string()).collect(),
+                expires_at,
+            },
+        )
+    }
+
+    #[test]
+    fn should_keep_only_the_hash_of_a_scoped_key() {
+        let (api_key, key) =
+            create(&["budget:write", "budget:read", "budget:write"], None).unwrap();
+
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert!(key.starts_with(&api_key.key_hint));
+        assert_eq!(api_key.key_hash, hash_token(&key));
+        assert_eq!(api_key.name, "home assistant");
+        assert_eq!(api_key.scopes, ["budget:read", "budget:write"]);
+        assert!(api_key.is_active());
+
+        for invalid in [
+            create(&[], None),
+            create(&["budget:admin"], None),
+            create(&["budget:read"], Some(Utc::now() - Duration::days(1))),
+        ] {
+            assert!(matches!(invalid, Err(Error::InvalidApiKey(_))));
+        }
+    }
+}
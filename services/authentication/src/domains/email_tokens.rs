@@ -0,0 +1,117 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::mail::Mail;
+
+use super::{
+    sessions::{hash_token, new_token},
+    users::User,
+};
+
+pub const VERIFY_EMAIL_DURATION: Duration = Duration::days(2);
+
+/// a reset link is worth as much as the password, it only works briefly
+pub const RESET_PASSWORD_DURATION: Duration = Duration::hours(1);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "email_token_purpose", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EmailTokenPurpose {
+    VerifyEmail,
+    ResetPassword,
+}
+
+/// Single use token mailed to a user, only its hash is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailToken {
+    pub token_id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: EmailTokenPurpose,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmail {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPassword {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPassword {
+    pub token: String,
+    pub password: String,
+}
+
+impl EmailToken {
+    /// new token of the user and the value mailed to them
+    pub fn new(user_id: Uuid, purpose: EmailTokenPurpose) -> (Self, String) {
+        let token = new_token();
+        let now = Utc::now();
+
+        let duration = match purpose {
+            EmailTokenPurpose::VerifyEmail => VERIFY_EMAIL_DURATION,
+            EmailTokenPurpose::ResetPassword => RESET_PASSWORD_DURATION,
+        };
+
+        let email_token = EmailToken {
+            token_id: Uuid::new_v4(),
+            user_id,
+            purpose,
+            token_hash: hash_token(&token),
+            created_at: now,
+            expires_at: now + duration,
+            used_at: None,
+        };
+
+        (email_token, token)
+    }
+
+    pub fn is_active(&self, purpose: EmailTokenPurpose) -> bool {
+        self.purpose == purpose && self.used_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
+/// mail with the link of the token, the web app at `app_url` takes it from there
+pub fn token_mail(user: &User, purpose: EmailTokenPurpose, token: &str, app_url: &str) -> Mail {
+    let app_url = app_url.trim_end_matches('/');
+
+    let (subject, body) = match purpose {
+        EmailTokenPurpose::VerifyEmail => (
+            "Confirm your email",
+            format!(
+                "Hi {},\n\nconfirm the email of your Home App account by opening\n\n\
+                 {app_url}/verify-email?token={token}\n\n\
+                 The link works for {} days.",
+                user.name,
+                VERIFY_EMAIL_DURATION.num_days()
+            ),
+        ),
+        EmailTokenPurpose::ResetPassword => (
+            "Reset your password",
+            format!(
+                "Hi {},\n\nchoose a new password for your Home App account by opening\n\n\
+                 {app_url}/reset-password?token={token}\n\n\
+                 The link works for {} minutes, ignore this mail if you did not ask for it.",
+                user.name,
+                RESET_PASSWORD_DURATION.num_minutes()
+            ),
+        ),
+    };
+
+    Mail {
+        to: user.email.clone(),
+        subject: subject.to_string(),
+        body,
+    }
+}
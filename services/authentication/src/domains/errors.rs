@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use sqlx::error::ErrorKind;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum Error {
+    #[error("Database error")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Invalid email")]
+    InvalidEmail,
+    #[error("Password is too weak")]
+    WeakPassword,
+    #[error("Email is already registered")]
+    EmailAlreadyRegistered,
+    #[error("Email is already verified")]
+    EmailAlreadyVerified,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Too many failed logins, blocked until {0}")]
+    TooManyAttempts(DateTime<Utc>),
+    #[error("Missing bearer token")]
+    MissingToken,
+    #[error("Invalid or expired token")]
+    InvalidToken,
+    #[error("Refresh token has been already used")]
+    RefreshTokenReused,
+    #[error("Invalid api key: {0}")]
+    InvalidApiKey(String),
+    #[error("Api key not found")]
+    ApiKeyNotFound(Uuid),
+    #[error("Two factor authentication is already enabled")]
+    TwoFactorAlreadyEnabled,
+    #[error("Two factor authentication is not being enrolled")]
+    TwoFactorNotEnrolled,
+    #[error("Two factor authentication is not enabled")]
+    TwoFactorNotEnabled,
+    #[error("Invalid two factor code")]
+    InvalidTwoFactorCode,
+    #[error("Password hashing failed: {0}")]
+    PasswordHashing(String),
+    #[error("Token signing failed: {0}")]
+    TokenSigning(String),
+    #[error("Mail could not be sent: {0}")]
+    MailFailed(String),
+}
+
+impl Error {
+    /// Stable machine readable code, clients should rely on it instead of the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DatabaseError(_) => "DATABASE_ERROR",
+            Self::InvalidEmail => "INVALID_EMAIL",
+            Self::WeakPassword => "WEAK_PASSWORD",
+            Self::EmailAlreadyRegistered => "EMAIL_ALREADY_REGISTERED",
+            Self::EmailAlreadyVerified => "EMAIL_ALREADY_VERIFIED",
+            Self::InvalidCredentials => "INVALID_CREDENTIALS",
+            Self::TooManyAttempts(_) => "TOO_MANY_ATTEMPTS",
+            Self::MissingToken => "MISSING_TOKEN",
+            Self::InvalidToken => "INVALID_TOKEN",
+            Self::RefreshTokenReused => "REFRESH_TOKEN_REUSED",
+            Self::InvalidApiKey(_) => "INVALID_API_KEY",
+            Self::ApiKeyNotFound(_) => "API_KEY_NOT_FOUND",
+            Self::TwoFactorAlreadyEnabled => "TWO_FACTOR_ALREADY_ENABLED",
+            Self::TwoFactorNotEnrolled => "TWO_FACTOR_NOT_ENROLLED",
+            Self::TwoFactorNotEnabled => "TWO_FACTOR_NOT_ENABLED",
+            Self::InvalidTwoFactorCode => "INVALID_TWO_FACTOR_CODE",
+            Self::PasswordHashing(_) => "PASSWORD_HASHING_FAILED",
+            Self::TokenSigning(_) => "TOKEN_SIGNING_FAILED",
+            Self::MailFailed(_) => "MAIL_FAILED",
+        }
+    }
+}
+
+/// Kind of the constraint violated by a database error, if any
+pub fn database_error_kind(err: &sqlx::Error) -> Option<ErrorKind> {
+    match err {
+        sqlx::Error::Database(db_err) => Some(db_err.kind()),
+        _ => None,
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,182 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// failures older than this are forgotten, a lockout lasts until then
+pub const FAILURE_WINDOW: Duration = Duration::minutes(15);
+
+/// the failed logins of an email only slow down the logins to it, whoever makes them
+pub const ACCOUNT_POLICY: ThrottlePolicy = ThrottlePolicy {
+    free_failures: 3,
+    max_delay: Duration::minutes(1),
+    lockout_failures: 10,
+};
+
+/// an address can be shared by a whole household or office, it gets more leeway
+pub const IP_POLICY: ThrottlePolicy = ThrottlePolicy {
+    free_failures: 20,
+    max_delay: Duration::minutes(1),
+    lockout_failures: 100,
+};
+
+/// mails asked for an email count whoever asks for them, a few are enough to get one through
+pub const MAIL_POLICY: ThrottlePolicy = ThrottlePolicy {
+    free_failures: 3,
+    max_delay: Duration::minutes(1),
+    lockout_failures: 5,
+};
+
+/// every registration or reset mail asked from an address counts, each one may probe an email or
+/// send a mail
+pub const REQUEST_POLICY: ThrottlePolicy = ThrottlePolicy {
+    free_failures: 10,
+    max_delay: Duration::minutes(1),
+    lockout_failures: 30,
+};
+
+/// How many failed logins are allowed before the next ones have to wait
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrottlePolicy {
+    /// failures without any wait
+    pub free_failures: i32,
+    /// the wait doubles with each further failure up to this long
+    pub max_delay: Duration,
+    /// failures locking the logins out for the whole window
+    pub lockout_failures: i32,
+}
+
+/// Failed logins of an email or an address within the window
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginThrottle {
+    pub throttle_key: String,
+    pub failures: i32,
+    pub last_failure_at: DateTime<Utc>,
+}
+
+/// Account locked by failed logins, kept for the user to review
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockoutEvent {
+    pub event_id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    /// address of the failed login that locked the account
+    pub ip_address: String,
+    pub created_at: DateTime<Utc>,
+    pub locked_until: DateTime<Utc>,
+}
+
+impl ThrottlePolicy {
+    /// wait after the last of the failures
+    pub fn delay(&self, failures: i32) -> Duration {
+        if failures >= self.lockout_failures {
+            return FAILURE_WINDOW;
+        }
+
+        if failures <= self.free_failures {
+            return Duration::zero();
+        }
+
+        let doublings = (failures - self.free_failures - 1).min(30);
+
+        Duration::seconds(1 << doublings).min(self.max_delay)
+    }
+
+    /// none when the next login can be tried right away
+    pub fn blocked_until(&self, throttle: &LoginThrottle) -> Option<DateTime<Utc>> {
+        let until = throttle.last_failure_at + self.delay(throttle.failures);
+
+        (until > Utc::now()).then_some(until)
+    }
+
+    /// true for the failure starting the lockout, the blocked logins are not counted
+    pub fn locks_out(&self, failures: i32) -> bool {
+        failures == self.lockout_failures
+    }
+}
+
+impl LoginThrottle {
+    /// key of the email as typed, the unknown ones are throttled like the registered ones
+    pub fn account_key(email: &str) -> String {
+        format!("account:{}", throttled_email(email))
+    }
+
+    /// key of the mails asked for the email, apart from its logins
+    pub fn mail_key(email: &str) -> String {
+        format!("mail:{}", throttled_email(email))
+    }
+
+    /// key of the address for the requests of an action other than the logins
+    pub fn request_key(action: &str, ip_address: IpAddr) -> String {
+        format!("{action}:{}", Self::ip_key(ip_address))
+    }
+
+    /// key of the address, an IPv6 client usually holds the whole /64 so it is counted as one
+    pub fn ip_key(ip_address: IpAddr) -> String {
+        match ip_address {
+            IpAddr::V6(ip) if ip.to_ipv4_mapped().is_none() => {
+                let [a, b, c, d, ..] = ip.segments();
+                format!("ip:{a:x}:{b:x}:{c:x}:{d:x}::/64")
+            }
+            ip => format!("ip:{}", ip.to_canonical()),
+        }
+    }
+}
+
+fn throttled_email(email: &str) -> String {
+    email.trim().to_lowercase().chars().take(320).collect()
+}
+
+impl LockoutEvent {
+    pub fn new(user_id: Uuid, ip_address: IpAddr, locked_until: DateTime<Utc>) -> Self {
+        LockoutEvent {
+            event_id: Uuid::new_v4(),
+            user_id,
+            ip_address: ip_address.to_canonical().to_string(),
+            created_at: Utc::now(),
+            locked_until,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_double_the_wait_until_the_lockout() {
+        let delays: Vec<i64> = (1..=10)
+            .map(|failures| ACCOUNT_POLICY.delay(failures).num_seconds())
+            .collect();
+
+        assert_eq!(delays, [0, 0, 0, 1, 2, 4, 8, 16, 32, 15 * 60]);
+        assert_eq!(IP_POLICY.delay(40), Duration::minutes(1));
+
+        let throttle = LoginThrottle {
+            throttle_key: LoginThrottle::account_key(" Ana@Example.com"),
+            failures: 5,
+            last_failure_at: Utc::now() - Duration::seconds(3),
+        };
+
+        assert_eq!(throttle.throttle_key, "account:ana@example.com");
+        assert_eq!(ACCOUNT_POLICY.blocked_until(&throttle), None);
+        assert!(ACCOUNT_POLICY
+            .blocked_until(&LoginThrottle {
+                failures: 6,
+                ..throttle
+            })
+            .is_some());
+    }
+
+    #[test]
+    fn should_count_an_ipv6_network_as_one_address() {
+        let ip = |ip: &str| LoginThrottle::ip_key(ip.parse().unwrap());
+
+        assert_eq!(ip("2001:db8:1:2:3:4:5:6"), "ip:2001:db8:1:2::/64");
+        assert_eq!(ip("2001:db8:1:2::7"), "ip:2001:db8:1:2::/64");
+        assert_eq!(ip("::ffff:192.0.2.1"), "ip:192.0.2.1");
+        assert_eq!(ip("192.0.2.1"), "ip:192.0.2.1");
+    }
+}
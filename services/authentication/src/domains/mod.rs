@@ -0,0 +1,9 @@
+pub mod api_keys;
+pub mod email_tokens;
+pub mod errors;
+pub mod login_throttles;
+pub mod passwords;
+pub mod sessions;
+pub mod tokens;
+pub mod two_factor;
+pub mod users;
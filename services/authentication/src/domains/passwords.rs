@@ -0,0 +1,64 @@
+use std::sync::OnceLock;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+use super::errors::{Error, Result};
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+/// hashing cost grows with the password, longer ones are refused before hashing
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// Argon2id with the recommended parameters, they are stored in every hash so they can change
+/// without invalidating the existing passwords
+fn argon2() -> Argon2<'static> {
+    Argon2::default()
+}
+
+pub fn validate_password(password: &str) -> Result<()> {
+    let length = password.chars().count();
+
+    if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&length) {
+        return Err(Error::WeakPassword);
+    }
+
+    Ok(())
+}
+
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    argon2()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|err| Error::PasswordHashing(err.to_string()))
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .is_ok_and(|hash| argon2().verify_password(password.as_bytes(), &hash).is_ok())
+}
+
+/// hash checked when the email is unknown, so the answer takes as long as for a wrong password
+pub fn dummy_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+    DUMMY_HASH.get_or_init(|| hash_password("dummy password").unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_verify_only_the_hashed_password() {
+        let hash = hash_password("correct horse").unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("wrong horse", &hash));
+        assert!(!verify_password("correct horse", "not a hash"));
+    }
+}
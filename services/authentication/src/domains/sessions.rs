@@ -0,0 +1,111 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{two_factor::TwoFactorChallenge, users::User};
+
+/// a login has to be repeated after this long, however often its tokens are refreshed
+pub const SESSION_DURATION: Duration = Duration::days(30);
+
+/// Login of a user, it is the family of every refresh token issued since the login
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Single use token exchanged for new tokens, only its hash is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshToken {
+    pub token_id: Uuid,
+    pub session_id: Uuid,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    /// set when the token is exchanged, a used token presented again revokes the session
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokens {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_at: DateTime<Utc>,
+    pub refresh_token: String,
+    pub refresh_expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResponse {
+    #[serde(flatten)]
+    pub tokens: TokenResponse,
+    pub user: User,
+}
+
+/// Answer of the password step of a login, users with a second factor still have to give it
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResult {
+    Authenticated(LoginResponse),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+impl Session {
+    pub fn new(user_id: Uuid) -> Self {
+        let now = Utc::now();
+
+        Session {
+            session_id: Uuid::new_v4(),
+            user_id,
+            created_at: now,
+            expires_at: now + SESSION_DURATION,
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
+impl RefreshToken {
+    /// new token of the session and the value handed to the user
+    pub fn new(session_id: Uuid) -> (Self, String) {
+        let token = new_token();
+
+        let refresh_token = RefreshToken {
+            token_id: Uuid::new_v4(),
+            session_id,
+            token_hash: hash_token(&token),
+            created_at: Utc::now(),
+            used_at: None,
+        };
+
+        (refresh_token, token)
+    }
+}
+
+/// random token handed to the user, 256 bits as hex
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    hex::encode(bytes)
+}
+
+/// tokens are random, a plain hash is enough to keep them useless if the table leaks
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use auth_layer::Claims;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, Jwk, JwkSet, KeyAlgorithm, PublicKeyUse,
+        RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey,
+    pkcs8::{DecodePrivateKey, DecodePublicKey},
+    traits::PublicKeyParts,
+    RsaPrivateKey, RsaPublicKey,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::errors::{Error, Result};
+
+/// access tokens can't be revoked once issued, so they are short lived
+pub const ACCESS_TOKEN_DURATION: Duration = Duration::minutes(15);
+
+/// Keys signing the access tokens and the public keys other services verify them with
+pub struct JwtKeys {
+    issuer: String,
+    key_id: String,
+    encoding_key: EncodingKey,
+    decoding_keys: HashMap<String, DecodingKey>,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    /// signing key as a PKCS#8 or PKCS#1 PEM, the public keys of the previous signing keys stay
+    /// published so the tokens they signed remain valid until they expire
+    pub fn from_pem(
+        issuer: &str,
+        private_key_pem: &str,
+        previous_public_key_pems: &[String],
+    ) -> std::result::Result<Self, String> {
+        let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(private_key_pem))
+            .map_err(|err| format!("invalid signing key: {err}"))?;
+        let encoding_key = EncodingKey::from_rsa_pem(private_key_pem.as_bytes())
+            .map_err(|err| format!("invalid signing key: {err}"))?;
+
+        let mut public_keys = vec![private_key.to_public_key()];
+
+        for pem in previous_public_key_pems {
+            public_keys.push(
+                RsaPublicKey::from_public_key_pem(pem)
+                    .map_err(|err| format!("invalid previous public key: {err}"))?,
+            );
+        }
+
+        let jwks = JwkSet {
+            keys: public_keys.iter().map(jwk).collect(),
+        };
+
+        let mut decoding_keys = HashMap::new();
+
+        for jwk in &jwks.keys {
+            let key = DecodingKey::from_jwk(jwk).map_err(|err| err.to_string())?;
+            decoding_keys.insert(key_id(jwk), key);
+        }
+
+        Ok(JwtKeys {
+            issuer: issuer.to_string(),
+            key_id: key_id(&jwks.keys[0]),
+            encoding_key,
+            decoding_keys,
+            jwks,
+        })
+    }
+
+    /// access token of the session and the time it expires
+    pub fn sign(&self, user_id: Uuid, session_id: Uuid) -> Result<(String, DateTime<Utc>)> {
+        let now = Utc::now();
+        let expires_at = now + ACCESS_TOKEN_DURATION;
+
+        let claims = Claims {
+            sub: user_id,
+            sid: session_id,
+            iss: self.issuer.clone(),
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+            scope: None,
+        };
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(self.key_id.clone());
+
+        let token = jsonwebtoken::encode(&header, &claims, &self.encoding_key)
+            .map_err(|err| Error::TokenSigning(err.to_string()))?;
+
+        Ok((token, expires_at))
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Claims> {
+        let header = jsonwebtoken::decode_header(token).map_err(|_| Error::InvalidToken)?;
+        let key = header
+            .kid
+            .and_then(|kid| self.decoding_keys.get(&kid))
+            .ok_or(Error::InvalidToken)?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+
+        jsonwebtoken::decode::<Claims>(token, key, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| Error::InvalidToken)
+    }
+
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn jwk(public_key: &RsaPublicKey) -> Jwk {
+    let n = URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be());
+    let e = URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be());
+
+    // RFC 7638 thumbprint, the same key always gets the same id
+    let thumbprint = format!(r#"{{"e":"{e}","kty":"RSA","n":"{n}"}}"#);
+    let kid = URL_SAFE_NO_PAD.encode(Sha256::digest(thumbprint.as_bytes()));
+
+    Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(KeyAlgorithm::RS256),
+            key_id: Some(kid),
+            ..Default::default()
+        },
+        algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n,
+            e,
+        }),
+    }
+}
+
+fn key_id(jwk: &Jwk) -> String {
+    jwk.common.key_id.clone().unwrap_or_default()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn test_keys() -> JwtKeys {
+        JwtKeys::from_pem(
+            "home_app",
+            include_str!("../../testdata/jwt.pem"),
+            &[include_str!("../../testdata/jwt_previous.pub.pem").to_string()],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_verify_only_the_tokens_it_signed() {
+        let keys = test_keys();
+        let (user_id, session_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let (token, _) = keys.sign(user_id, session_id).unwrap();
+        let claims = keys.verify(&token).unwrap();
+
+        assert_eq!((claims.sub, claims.sid), (user_id, session_id));
+        assert_eq!(keys.jwks().keys.len(), 2);
+
+        let mut tampered = token.clone();
+        tampered.insert(tampered.rfind('.').unwrap() + 1, 'A');
+
+        assert!(matches!(keys.verify(&tampered), Err(Error::InvalidToken)));
+
+        let other_issuer = JwtKeys::from_pem("other", include_str!("../../testdata/jwt.pem"), &[])
+            .unwrap()
+            .sign(user_id, session_id)
+            .unwrap()
+            .0;
+
+        assert!(matches!(
+            keys.verify(&other_issuer),
+            Err(Error::InvalidToken)
+        ));
+    }
+}
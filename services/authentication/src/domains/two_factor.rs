@@ -0,0 +1,299 @@
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use uuid::Uuid;
+
+use super::sessions::{hash_token, new_token};
+
+/// name the authenticator apps show next to the codes
+pub const TOTP_ISSUER: &str = "Home App";
+
+/// codes change every 30 seconds, the defaults of RFC 6238 every authenticator supports
+pub const TOTP_PERIOD: i64 = 30;
+pub const TOTP_DIGITS: u32 = 6;
+
+/// codes of the previous and the next step are accepted too, clocks drift
+const TOTP_SKEW: i64 = 1;
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// the second factor has to be given this soon after the password
+pub const LOGIN_CHALLENGE_DURATION: Duration = Duration::minutes(5);
+
+/// wrong codes a login challenge takes before it stops working
+pub const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+/// Authenticator app of a user, it protects the logins once enabled
+#[derive(Debug, Clone, PartialEq)]
+pub struct TotpFactor {
+    pub user_id: Uuid,
+    /// 160 bits as hex
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+}
+
+/// Single use code replacing the authenticator when it is lost, only its hash is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveryCode {
+    pub code_id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// Login whose password was right, finished by giving the second factor
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginChallenge {
+    pub challenge_id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub failed_attempts: i32,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpEnrollment {
+    /// base32, for the authenticators that can't scan the uri
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+/// code of the authenticator, or a recovery code
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCode {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisableTwoFactor {
+    pub password: String,
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginTwoFactor {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorChallenge {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TotpFactor {
+    pub fn new(user_id: Uuid) -> Self {
+        let mut secret = [0u8; 20];
+        rand::thread_rng().fill_bytes(&mut secret);
+
+        TotpFactor {
+            user_id,
+            secret: hex::encode(secret),
+            created_at: Utc::now(),
+            enabled_at: None,
+            last_used_step: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    pub fn enrollment(&self, email: &str) -> TotpEnrollment {
+        let secret = base32(&hex::decode(&self.secret).unwrap_or_default());
+        let issuer = percent_encode(TOTP_ISSUER);
+
+        TotpEnrollment {
+            provisioning_uri: format!(
+                "otpauth://totp/{issuer}:{}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD}",
+                percent_encode(email)
+            ),
+            secret,
+        }
+    }
+
+    /// time step the code belongs to, none when it matches no step close to now
+    pub fn verify(&self, code: &str, now: DateTime<Utc>) -> Option<i64> {
+        let secret = hex::decode(&self.secret).ok()?;
+        let current = now.timestamp().div_euclid(TOTP_PERIOD);
+
+        (current - TOTP_SKEW..=current + TOTP_SKEW).find(|step| totp(&secret, *step) == code.trim())
+    }
+}
+
+impl RecoveryCode {
+    /// new codes of the user and the values handed to them
+    pub fn generate(user_id: Uuid) -> (Vec<Self>, Vec<String>) {
+        let now = Utc::now();
+
+        (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let mut bytes = [0u8; 5];
+                rand::thread_rng().fill_bytes(&mut bytes);
+                let code = hex::encode(bytes);
+                let code = format!("{}-{}", &code[..5], &code[5..]);
+
+                let recovery_code = RecoveryCode {
+                    code_id: Uuid::new_v4(),
+                    user_id,
+                    code_hash: hash_recovery_code(&code),
+                    created_at: now,
+                    used_at: None,
+                };
+
+                (recovery_code, code)
+            })
+            .unzip()
+    }
+}
+
+impl LoginChallenge {
+    /// new challenge of the user and the token handed to them
+    pub fn new(user_id: Uuid) -> (Self, String) {
+        let token = new_token();
+        let now = Utc::now();
+
+        let challenge = LoginChallenge {
+            challenge_id: Uuid::new_v4(),
+            user_id,
+            token_hash: hash_token(&token),
+            created_at: now,
+            expires_at: now + LOGIN_CHALLENGE_DURATION,
+            failed_attempts: 0,
+            used_at: None,
+        };
+
+        (challenge, token)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.used_at.is_none()
+            && self.expires_at > Utc::now()
+            && self.failed_attempts < MAX_CHALLENGE_ATTEMPTS
+    }
+}
+
+/// codes are typed by hand, case and separators don't matter
+pub fn hash_recovery_code(code: &str) -> String {
+    let code: String = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    hash_token(&code)
+}
+
+/// the authenticator codes are digits, anything else is taken for a recovery code
+pub fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+
+    code.len() == TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// RFC 6238 code of the time step, HMAC-SHA1 truncated as in RFC 4226
+fn totp(secret: &[u8], step: i64) -> String {
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(secret) else {
+        return String::new();
+    };
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// RFC 4648 base32 without padding, the encoding of the secrets in the provisioning uris
+fn base32(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    let mut encoded = String::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        encoded.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    encoded
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn should_generate_the_codes_of_rfc_6238() {
+        let factor = TotpFactor {
+            secret: hex::encode(b"12345678901234567890"),
+            ..TotpFactor::new(Uuid::new_v4())
+        };
+        let at = |timestamp| Utc.timestamp_opt(timestamp, 0).unwrap();
+
+        assert_eq!(totp(b"12345678901234567890", 1), "287082");
+        assert_eq!(factor.verify("081804", at(1111111109)), Some(37037036));
+        assert_eq!(factor.verify("081804", at(1111111109 + 30)), Some(37037036));
+        assert_eq!(factor.verify("081804", at(1111111109 + 90)), None);
+
+        let enrollment = factor.enrollment("ana+budget@example.com");
+
+        assert_eq!(enrollment.secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert!(enrollment
+            .provisioning_uri
+            .starts_with("otpauth://totp/Home%20App:ana%2Bbudget@example.com?secret=GEZD"));
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::errors::{Error, Result};
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: String,
+    #[serde(skip)]
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// set once the user opened the link mailed on registration
+    pub email_verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterUser {
+    pub email: String,
+    pub name: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginUser {
+    pub email: String,
+    pub password: String,
+}
+
+impl User {
+    pub fn new(email: String, name: String, password_hash: String) -> Self {
+        User {
+            user_id: Uuid::new_v4(),
+            email,
+            name,
+            password_hash,
+            created_at: Utc::now(),
+            updated_at: None,
+            email_verified_at: None,
+        }
+    }
+}
+
+/// trimmed and lowercase, the form emails are stored and looked up in
+pub fn normalize_email(email: &str) -> Result<String> {
+    let email = email.trim().to_lowercase();
+
+    let valid = email.len() <= 320
+        && email.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && domain.contains('.') && !domain.contains('@')
+        })
+        && !email.contains(char::is_whitespace);
+
+    if !valid {
+        return Err(Error::InvalidEmail);
+    }
+
+    Ok(email)
+}
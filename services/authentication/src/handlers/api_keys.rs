@@ -0,0 +1,127 @@
+use auth_layer::VerifiedApiKey;
+use uuid::Uuid;
+
+use crate::domains::{
+    api_keys::{ApiKey, CreateApiKey, CreatedApiKey},
+    errors::{Error, Result},
+    sessions::hash_token,
+};
+
+use super::Handler;
+
+impl Handler {
+    pub async fn list_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        self.api_key_repository.list_api_keys(user_id).await
+    }
+
+    pub async fn create_api_key(
+        &self,
+        user_id: Uuid,
+        payload: CreateApiKey,
+    ) -> Result<CreatedApiKey> {
+        let (api_key, key) = ApiKey::new(user_id, payload)?;
+
+        let api_key = self.api_key_repository.create_api_key(api_key).await?;
+
+        Ok(CreatedApiKey { api_key, key })
+    }
+
+    pub async fn revoke_api_key(&self, user_id: Uuid, key_id: Uuid) -> Result<ApiKey> {
+        self.api_key_repository
+            .revoke_api_key(user_id, key_id)
+            .await?
+            .ok_or(Error::ApiKeyNotFound(key_id))
+    }
+
+    /// owner and scopes of a key neither revoked nor expired, the services ask on every new key
+    pub async fn verify_api_key(&self, key: &str) -> Result<VerifiedApiKey> {
+        let api_key = self
+            .api_key_repository
+            .get_api_key_by_hash(&hash_token(key))
+            .await?
+            .filter(ApiKey::is_active)
+            .ok_or(Error::InvalidToken)?;
+
+        self.api_key_repository.use_api_key(api_key.key_id).await?;
+
+        Ok(VerifiedApiKey {
+            key_id: api_key.key_id,
+            user_id: api_key.user_id,
+            scopes: api_key.scopes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::{
+        domains::{api_keys::CreateApiKey, tokens::tests::test_keys},
+        mail::MockMailSender,
+        repositories::{
+            api_keys::MockApiKeyRepository, email_tokens::MockEmailTokenRepository,
+            login_throttles::MockLoginThrottleRepository, sessions::MockSessionRepository,
+            two_factor::MockTwoFactorRepository, users::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_verify_only_active_api_keys() {
+        let (api_key, key) = ApiKey::new(
+            Uuid::new_v4(),
+            CreateApiKey {
+                name: String::from("home assistant"),
+                scopes: vec![String::from("budget:write")],
+                expires_at: None,
+            },
+        )
+        .unwrap();
+        let revoked = ApiKey {
+            revoked_at: Some(Utc::now()),
+            ..api_key.clone()
+        };
+        let key_id = api_key.key_id;
+
+        let mut api_key_repository = MockApiKeyRepository::new();
+
+        let stored = api_key.clone();
+        api_key_repository
+            .expect_get_api_key_by_hash()
+            .times(1)
+            .returning(move |hash| Ok((hash == stored.key_hash).then(|| stored.clone())));
+        api_key_repository
+            .expect_get_api_key_by_hash()
+            .returning(move |_| Ok(Some(revoked.clone())));
+        api_key_repository
+            .expect_use_api_key()
+            .withf(move |id| *id == key_id)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let handler = Handler::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(api_key_repository),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(MockLoginThrottleRepository::new()),
+            Arc::new(MockMailSender::new()),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let verified = handler.verify_api_key(&key).await.unwrap();
+
+        assert_eq!(verified.user_id, api_key.user_id);
+        assert_eq!(verified.scopes, ["budget:write"]);
+
+        let revoked = handler.verify_api_key(&key).await;
+
+        assert!(matches!(revoked, Err(Error::InvalidToken)));
+    }
+}
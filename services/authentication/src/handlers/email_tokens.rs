@@ -0,0 +1,257 @@
+use std::net::IpAddr;
+
+use crate::domains::{
+    email_tokens::{
+        token_mail, EmailToken, EmailTokenPurpose, ForgotPassword, ResetPassword, VerifyEmail,
+    },
+    errors::{Error, Result},
+    login_throttles::{LoginThrottle, MAIL_POLICY, REQUEST_POLICY},
+    passwords::{hash_password, validate_password},
+    sessions::hash_token,
+    users::{normalize_email, User},
+};
+
+use super::Handler;
+
+impl Handler {
+    pub async fn verify_email(&self, payload: VerifyEmail) -> Result<User> {
+        let email_token = self
+            .use_email_token(&payload.token, EmailTokenPurpose::VerifyEmail)
+            .await?;
+
+        self.user_repository
+            .verify_email(email_token.user_id)
+            .await?
+            .ok_or(Error::InvalidToken)
+    }
+
+    /// new verification link, the previous ones stop working
+    pub async fn resend_verification_email(&self, user: &User) -> Result<()> {
+        if user.email_verified_at.is_some() {
+            return Err(Error::EmailAlreadyVerified);
+        }
+
+        self.mail_token(user, EmailTokenPurpose::VerifyEmail).await
+    }
+
+    /// answers the same whether the email is registered or not, the mail is sent in the
+    /// background so not even the time taken tells them apart, and the mails of an email and
+    /// the requests of an address are throttled
+    pub async fn forgot_password(&self, payload: ForgotPassword, ip_address: IpAddr) -> Result<()> {
+        let throttles = [
+            (LoginThrottle::mail_key(&payload.email), MAIL_POLICY),
+            (
+                LoginThrottle::request_key("reset", ip_address),
+                REQUEST_POLICY,
+            ),
+        ];
+
+        self.check_throttles(&throttles).await?;
+        self.record_requests(&throttles.map(|(key, _)| key)).await?;
+
+        let user = match normalize_email(&payload.email) {
+            Ok(email) => self.user_repository.get_user_by_email(&email).await?,
+            Err(_) => None,
+        };
+
+        if let Some(user) = user {
+            self.mail_token_in_background(user, EmailTokenPurpose::ResetPassword);
+        }
+
+        Ok(())
+    }
+
+    /// new password from a reset link, every login of the user is revoked
+    pub async fn reset_password(&self, payload: ResetPassword) -> Result<()> {
+        validate_password(&payload.password)?;
+        let password_hash = hash_password(&payload.password)?;
+
+        let email_token = self
+            .use_email_token(&payload.token, EmailTokenPurpose::ResetPassword)
+            .await?;
+
+        self.user_repository
+            .update_password(email_token.user_id, &password_hash)
+            .await?;
+
+        self.session_repository
+            .revoke_user_sessions(email_token.user_id)
+            .await
+    }
+
+    pub(super) fn mail_token_in_background(&self, user: User, purpose: EmailTokenPurpose) {
+        let handler = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(err) = handler.mail_token(&user, purpose).await {
+                log::error!(
+                    "Could not mail the {purpose:?} link of {}: {err}",
+                    user.user_id
+                );
+            }
+        });
+    }
+
+    async fn mail_token(&self, user: &User, purpose: EmailTokenPurpose) -> Result<()> {
+        let (email_token, token) = EmailToken::new(user.user_id, purpose);
+
+        self.email_token_repository
+            .create_email_token(email_token)
+            .await?;
+
+        self.mail_sender
+            .send(token_mail(user, purpose, &token, &self.app_url))
+            .await
+    }
+
+    async fn use_email_token(&self, token: &str, purpose: EmailTokenPurpose) -> Result<EmailToken> {
+        let email_token = self
+            .email_token_repository
+            .get_email_token_by_hash(&hash_token(token))
+            .await?
+            .filter(|email_token| email_token.is_active(purpose))
+            .ok_or(Error::InvalidToken)?;
+
+        if !self
+            .email_token_repository
+            .use_email_token(email_token.token_id)
+            .await?
+        {
+            return Err(Error::InvalidToken);
+        }
+
+        Ok(email_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        domains::{passwords::verify_password, tokens::tests::test_keys},
+        mail::MockMailSender,
+        repositories::{
+            api_keys::MockApiKeyRepository, email_tokens::MockEmailTokenRepository,
+            login_throttles::MockLoginThrottleRepository, sessions::MockSessionRepository,
+            two_factor::MockTwoFactorRepository, users::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reset_the_password_once_and_revoke_the_logins() {
+        let user_id = Uuid::new_v4();
+        let (email_token, token) = EmailToken::new(user_id, EmailTokenPurpose::ResetPassword);
+        let token_id = email_token.token_id;
+
+        let mut email_token_repository = MockEmailTokenRepository::new();
+        email_token_repository
+            .expect_get_email_token_by_hash()
+            .returning(move |_| Ok(Some(email_token.clone())));
+        email_token_repository
+            .expect_use_email_token()
+            .withf(move |id| *id == token_id)
+            .times(2)
+            .returning({
+                let mut used = false;
+                move |_| Ok(!std::mem::replace(&mut used, true))
+            });
+
+        let mut user_repository = MockUserRepository::new();
+        user_repository
+            .expect_update_password()
+            .withf(move |id, hash| *id == user_id && verify_password("new horse battery", hash))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut session_repository = MockSessionRepository::new();
+        session_repository
+            .expect_revoke_user_sessions()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mail_sender = MockMailSender::new();
+        mail_sender.expect_send().never();
+
+        let handler = Handler::new(
+            Arc::new(user_repository),
+            Arc::new(session_repository),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(email_token_repository),
+            Arc::new(MockLoginThrottleRepository::new()),
+            Arc::new(mail_sender),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let reset = |password: &str| ResetPassword {
+            token: token.clone(),
+            password: String::from(password),
+        };
+
+        let weak = handler.reset_password(reset("short")).await;
+        assert!(matches!(weak, Err(Error::WeakPassword)));
+
+        handler
+            .reset_password(reset("new horse battery"))
+            .await
+            .unwrap();
+
+        let reused = handler.reset_password(reset("other horse battery")).await;
+        assert!(matches!(reused, Err(Error::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn should_stop_mailing_a_throttled_email() {
+        let mut user_repository = MockUserRepository::new();
+        user_repository.expect_get_user_by_email().never();
+
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .withf(|throttle_keys| throttle_keys == ["mail:ana@example.com", "reset:ip:192.0.2.1"])
+            .returning(|throttle_keys| {
+                Ok(vec![LoginThrottle {
+                    throttle_key: throttle_keys[0].clone(),
+                    failures: MAIL_POLICY.lockout_failures,
+                    last_failure_at: Utc::now(),
+                }])
+            });
+        login_throttle_repository
+            .expect_record_login_failure()
+            .never();
+
+        let mut mail_sender = MockMailSender::new();
+        mail_sender.expect_send().never();
+
+        let handler = Handler::new(
+            Arc::new(user_repository),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(login_throttle_repository),
+            Arc::new(mail_sender),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let forgot = handler
+            .forgot_password(
+                ForgotPassword {
+                    email: String::from(" Ana@Example.com "),
+                },
+                IpAddr::from([192, 0, 2, 1]),
+            )
+            .await;
+
+        assert!(matches!(forgot, Err(Error::TooManyAttempts(_))));
+    }
+}
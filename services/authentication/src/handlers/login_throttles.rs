@@ -0,0 +1,204 @@
+use std::net::IpAddr;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::{Error, Result},
+    login_throttles::{LockoutEvent, ThrottlePolicy, ACCOUNT_POLICY, FAILURE_WINDOW, IP_POLICY},
+    users::User,
+};
+
+use super::Handler;
+
+impl Handler {
+    pub async fn get_lockout_events(&self, user: &User) -> Result<Vec<LockoutEvent>> {
+        self.login_throttle_repository
+            .get_lockout_events(user.user_id)
+            .await
+    }
+
+    /// refuses the login while the email or the address has to wait, before the password is
+    /// even checked
+    pub(super) async fn check_login_throttles(
+        &self,
+        account_key: &str,
+        ip_key: &str,
+    ) -> Result<()> {
+        self.check_throttles(&[
+            (account_key.to_string(), ACCOUNT_POLICY),
+            (ip_key.to_string(), IP_POLICY),
+        ])
+        .await
+    }
+
+    /// refuses the request while one of its keys has to wait
+    pub(super) async fn check_throttles(
+        &self,
+        throttles: &[(String, ThrottlePolicy)],
+    ) -> Result<()> {
+        let throttle_keys: Vec<String> = throttles.iter().map(|(key, _)| key.clone()).collect();
+
+        let login_throttles = self
+            .login_throttle_repository
+            .get_login_throttles(&throttle_keys)
+            .await?;
+
+        let blocked_until = login_throttles
+            .iter()
+            .filter_map(|login_throttle| {
+                let (_, policy) = throttles
+                    .iter()
+                    .find(|(key, _)| *key == login_throttle.throttle_key)?;
+
+                policy.blocked_until(login_throttle)
+            })
+            .max();
+
+        match blocked_until {
+            Some(until) => Err(Error::TooManyAttempts(until)),
+            None => Ok(()),
+        }
+    }
+
+    /// counts a request throttled whatever its outcome, like a failed login
+    pub(super) async fn record_requests(&self, throttle_keys: &[String]) -> Result<()> {
+        let forget_before = Utc::now() - FAILURE_WINDOW;
+
+        for throttle_key in throttle_keys {
+            self.login_throttle_repository
+                .record_login_failure(throttle_key, forget_before)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// counts the failure for the email and the address, locking out a registered user records
+    /// the lockout for them
+    pub(super) async fn record_login_failure(
+        &self,
+        account_key: &str,
+        ip_key: &str,
+        user_id: Option<Uuid>,
+        ip_address: IpAddr,
+    ) -> Result<()> {
+        let forget_before = Utc::now() - FAILURE_WINDOW;
+
+        let account = self
+            .login_throttle_repository
+            .record_login_failure(account_key, forget_before)
+            .await?;
+        self.login_throttle_repository
+            .record_login_failure(ip_key, forget_before)
+            .await?;
+
+        if let Some(user_id) = user_id.filter(|_| ACCOUNT_POLICY.locks_out(account.failures)) {
+            let locked_until = account.last_failure_at + ACCOUNT_POLICY.delay(account.failures);
+
+            self.login_throttle_repository
+                .create_lockout_event(LockoutEvent::new(user_id, ip_address, locked_until))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Duration;
+
+    use crate::{
+        domains::{
+            login_throttles::LoginThrottle, passwords::hash_password, tokens::tests::test_keys,
+            users::LoginUser,
+        },
+        mail::MockMailSender,
+        repositories::{
+            api_keys::MockApiKeyRepository, email_tokens::MockEmailTokenRepository,
+            login_throttles::MockLoginThrottleRepository, sessions::MockSessionRepository,
+            two_factor::MockTwoFactorRepository, users::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_lock_out_an_email_whether_it_is_registered_or_not() {
+        let user = User::new(
+            String::from("ana@example.com"),
+            String::from("Ana"),
+            hash_password("correct horse").unwrap(),
+        );
+        let user_id = user.user_id;
+
+        let mut user_repository = MockUserRepository::new();
+
+        user_repository
+            .expect_get_user_by_email()
+            .returning(move |email| Ok((email == "ana@example.com").then(|| user.clone())));
+
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+
+        // every failure reaches the lockout, only the registered email records one
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .returning(|throttle_keys| {
+                Ok(throttle_keys
+                    .iter()
+                    .filter(|throttle_key| *throttle_key == "account:locked@example.com")
+                    .map(|throttle_key| LoginThrottle {
+                        throttle_key: throttle_key.clone(),
+                        failures: ACCOUNT_POLICY.lockout_failures,
+                        last_failure_at: Utc::now() - Duration::minutes(1),
+                    })
+                    .collect())
+            });
+        login_throttle_repository
+            .expect_record_login_failure()
+            .returning(|throttle_key, _| {
+                Ok(LoginThrottle {
+                    throttle_key: throttle_key.to_string(),
+                    failures: ACCOUNT_POLICY.lockout_failures,
+                    last_failure_at: Utc::now(),
+                })
+            });
+        login_throttle_repository
+            .expect_create_lockout_event()
+            .withf(move |event| event.user_id == user_id && event.ip_address == "192.0.2.1")
+            .times(1)
+            .returning(Ok);
+
+        let handler = Handler::new(
+            Arc::new(user_repository),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(login_throttle_repository),
+            Arc::new(MockMailSender::new()),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let login = |email: &str| LoginUser {
+            email: String::from(email),
+            password: String::from("wrong horse"),
+        };
+        let ip_address = IpAddr::from([192, 0, 2, 1]);
+
+        for email in ["ana@example.com", "bob@example.com"] {
+            let result = handler.login(login(email), ip_address).await;
+            assert!(matches!(result, Err(Error::InvalidCredentials)));
+        }
+
+        for email in ["locked@example.com", "Unlocked@Example.com "] {
+            let result = handler.login(login(email), ip_address).await;
+            let locked = matches!(result, Err(Error::TooManyAttempts(_)));
+            assert_eq!(locked, email == "locked@example.com");
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::{
+    domains::tokens::JwtKeys,
+    mail::MailSender,
+    repositories::{
+        api_keys::ApiKeyRepository, email_tokens::EmailTokenRepository,
+        login_throttles::LoginThrottleRepository, sessions::SessionRepository,
+        two_factor::TwoFactorRepository, users::UserRepository,
+    },
+};
+
+pub mod api_keys;
+pub mod email_tokens;
+pub mod login_throttles;
+pub mod sessions;
+pub mod two_factor;
+pub mod users;
+
+#[derive(Clone)]
+pub struct Handler {
+    user_repository: Arc<dyn UserRepository + Send + Sync>,
+    session_repository: Arc<dyn SessionRepository + Send + Sync>,
+    api_key_repository: Arc<dyn ApiKeyRepository + Send + Sync>,
+    two_factor_repository: Arc<dyn TwoFactorRepository + Send + Sync>,
+    email_token_repository: Arc<dyn EmailTokenRepository + Send + Sync>,
+    login_throttle_repository: Arc<dyn LoginThrottleRepository + Send + Sync>,
+    mail_sender: Arc<dyn MailSender + Send + Sync>,
+    /// web app the mailed links open
+    app_url: String,
+    keys: Arc<JwtKeys>,
+}
+
+impl Handler {
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        user_repository: Arc<dyn UserRepository + Send + Sync>,
+        session_repository: Arc<dyn SessionRepository + Send + Sync>,
+        api_key_repository: Arc<dyn ApiKeyRepository + Send + Sync>,
+        two_factor_repository: Arc<dyn TwoFactorRepository + Send + Sync>,
+        email_token_repository: Arc<dyn EmailTokenRepository + Send + Sync>,
+        login_throttle_repository: Arc<dyn LoginThrottleRepository + Send + Sync>,
+        mail_sender: Arc<dyn MailSender + Send + Sync>,
+        app_url: String,
+        keys: Arc<JwtKeys>,
+    ) -> Self {
+        Self {
+            user_repository,
+            session_repository,
+            api_key_repository,
+            two_factor_repository,
+            email_token_repository,
+            login_throttle_repository,
+            mail_sender,
+            app_url,
+            keys,
+        }
+    }
+}
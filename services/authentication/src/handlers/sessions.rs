@@ -0,0 +1,197 @@
+use jsonwebtoken::jwk::JwkSet;
+
+use crate::domains::{
+    errors::{Error, Result},
+    sessions::{hash_token, RefreshToken, Session, TokenResponse},
+    users::User,
+};
+
+use super::Handler;
+
+impl Handler {
+    pub async fn start_session(&self, user: &User) -> Result<TokenResponse> {
+        let session = self
+            .session_repository
+            .create_session(Session::new(user.user_id))
+            .await?;
+
+        self.issue_tokens(&session).await
+    }
+
+    /// access token and the next refresh token of the session
+    async fn issue_tokens(&self, session: &Session) -> Result<TokenResponse> {
+        let (refresh_token, token) = RefreshToken::new(session.session_id);
+        self.session_repository
+            .create_refresh_token(refresh_token)
+            .await?;
+
+        let (access_token, expires_at) = self.keys.sign(session.user_id, session.session_id)?;
+
+        Ok(TokenResponse {
+            access_token,
+            token_type: "Bearer",
+            expires_at,
+            refresh_token: token,
+            refresh_expires_at: session.expires_at,
+        })
+    }
+
+    /// exchange a refresh token for new tokens, a token used twice was stolen from one of its
+    /// holders, so the whole session is revoked
+    pub async fn refresh(&self, token: &str) -> Result<TokenResponse> {
+        let refresh_token = self
+            .session_repository
+            .get_refresh_token_by_hash(&hash_token(token))
+            .await?
+            .ok_or(Error::InvalidToken)?;
+
+        let session = self
+            .session_repository
+            .get_session_by_id(refresh_token.session_id)
+            .await?
+            .filter(Session::is_active)
+            .ok_or(Error::InvalidToken)?;
+
+        let first_use = refresh_token.used_at.is_none()
+            && self
+                .session_repository
+                .use_refresh_token(refresh_token.token_id)
+                .await?;
+
+        if !first_use {
+            self.session_repository
+                .revoke_session(session.session_id)
+                .await?;
+
+            return Err(Error::RefreshTokenReused);
+        }
+
+        self.issue_tokens(&session).await
+    }
+
+    /// revoke the session of the access token, its refresh tokens stop working
+    pub async fn logout(&self, access_token: &str) -> Result<()> {
+        let claims = self.keys.verify(access_token)?;
+
+        self.session_repository.revoke_session(claims.sid).await
+    }
+
+    /// user of an access token whose session is still active
+    pub async fn authenticate(&self, access_token: &str) -> Result<User> {
+        let claims = self.keys.verify(access_token)?;
+
+        self.session_repository
+            .get_session_by_id(claims.sid)
+            .await?
+            .filter(Session::is_active)
+            .ok_or(Error::InvalidToken)?;
+
+        self.user_repository
+            .get_user_by_id(claims.sub)
+            .await?
+            .ok_or(Error::InvalidToken)
+    }
+
+    pub fn jwks(&self) -> &JwkSet {
+        self.keys.jwks()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        domains::tokens::tests::test_keys,
+        mail::MockMailSender,
+        repositories::{
+            api_keys::MockApiKeyRepository, email_tokens::MockEmailTokenRepository,
+            login_throttles::MockLoginThrottleRepository, sessions::MockSessionRepository,
+            two_factor::MockTwoFactorRepository, users::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    fn handler(session_repository: MockSessionRepository) -> Handler {
+        Handler::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(session_repository),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(MockLoginThrottleRepository::new()),
+            Arc::new(MockMailSender::new()),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        )
+    }
+
+    fn new_refresh_token(session: &Session, used: bool) -> RefreshToken {
+        RefreshToken {
+            used_at: used.then(Utc::now),
+            ..RefreshToken::new(session.session_id).0
+        }
+    }
+
+    #[tokio::test]
+    async fn should_rotate_the_refresh_token() {
+        let session = Session::new(Uuid::new_v4());
+        let refresh_token = new_refresh_token(&session, false);
+        let mut session_repository = MockSessionRepository::new();
+
+        session_repository
+            .expect_get_refresh_token_by_hash()
+            .returning(move |_| Ok(Some(refresh_token.clone())));
+        let active = session.clone();
+        session_repository
+            .expect_get_session_by_id()
+            .returning(move |_| Ok(Some(active.clone())));
+        session_repository
+            .expect_use_refresh_token()
+            .times(1)
+            .returning(|_| Ok(true));
+        session_repository
+            .expect_create_refresh_token()
+            .times(1)
+            .returning(Ok);
+        session_repository.expect_revoke_session().never();
+
+        let handler = handler(session_repository);
+        let tokens = handler.refresh("refresh token").await.unwrap();
+
+        assert_eq!(tokens.refresh_expires_at, session.expires_at);
+        assert_eq!(
+            handler.keys.verify(&tokens.access_token).unwrap().sid,
+            session.session_id
+        );
+    }
+
+    #[tokio::test]
+    async fn should_revoke_the_session_when_a_refresh_token_is_reused() {
+        let session = Session::new(Uuid::new_v4());
+        let refresh_token = new_refresh_token(&session, true);
+        let session_id = session.session_id;
+        let mut session_repository = MockSessionRepository::new();
+
+        session_repository
+            .expect_get_refresh_token_by_hash()
+            .returning(move |_| Ok(Some(refresh_token.clone())));
+        session_repository
+            .expect_get_session_by_id()
+            .returning(move |_| Ok(Some(session.clone())));
+        session_repository
+            .expect_revoke_session()
+            .withf(move |id| *id == session_id)
+            .times(1)
+            .returning(|_| Ok(()));
+        session_repository.expect_create_refresh_token().never();
+
+        let result = handler(session_repository).refresh("refresh token").await;
+
+        assert!(matches!(result, Err(Error::RefreshTokenReused)));
+    }
+}
@@ -0,0 +1,451 @@
+use std::net::IpAddr;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::{Error, Result},
+    login_throttles::LoginThrottle,
+    passwords::verify_password,
+    sessions::{hash_token, LoginResponse},
+    two_factor::{
+        hash_recovery_code, is_totp_code, DisableTwoFactor, LoginChallenge, LoginTwoFactor,
+        RecoveryCode, RecoveryCodes, TotpEnrollment, TotpFactor, TwoFactorChallenge, TwoFactorCode,
+    },
+    users::User,
+};
+
+use super::Handler;
+
+impl Handler {
+    /// new secret for the authenticator, replacing the one the user was enrolling
+    pub async fn enroll_totp(&self, user: &User) -> Result<TotpEnrollment> {
+        if self.enabled_totp_factor(user.user_id).await?.is_some() {
+            return Err(Error::TwoFactorAlreadyEnabled);
+        }
+
+        let factor = self
+            .two_factor_repository
+            .save_totp_factor(TotpFactor::new(user.user_id))
+            .await?;
+
+        Ok(factor.enrollment(&user.email))
+    }
+
+    /// enable the factor being enrolled with a first code of the authenticator
+    pub async fn enable_totp(
+        &self,
+        user_id: Uuid,
+        payload: TwoFactorCode,
+    ) -> Result<RecoveryCodes> {
+        let factor = self
+            .two_factor_repository
+            .get_totp_factor(user_id)
+            .await?
+            .ok_or(Error::TwoFactorNotEnrolled)?;
+
+        if factor.is_enabled() {
+            return Err(Error::TwoFactorAlreadyEnabled);
+        }
+
+        if !is_totp_code(&payload.code) || !self.check_second_factor(&factor, &payload.code).await?
+        {
+            return Err(Error::InvalidTwoFactorCode);
+        }
+
+        self.replace_recovery_codes(user_id).await
+    }
+
+    /// new recovery codes, the previous ones stop working
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+        payload: TwoFactorCode,
+    ) -> Result<RecoveryCodes> {
+        let factor = self
+            .enabled_totp_factor(user_id)
+            .await?
+            .ok_or(Error::TwoFactorNotEnabled)?;
+
+        if !self.check_second_factor(&factor, &payload.code).await? {
+            return Err(Error::InvalidTwoFactorCode);
+        }
+
+        self.replace_recovery_codes(user_id).await
+    }
+
+    /// the user logs in again, with the password and a code, to turn the second factor off,
+    /// the failures are throttled like the ones of the login
+    pub async fn disable_totp(
+        &self,
+        user: &User,
+        payload: DisableTwoFactor,
+        ip_address: IpAddr,
+    ) -> Result<()> {
+        let account_key = LoginThrottle::account_key(&user.email);
+        let ip_key = LoginThrottle::ip_key(ip_address);
+
+        self.check_login_throttles(&account_key, &ip_key).await?;
+
+        if !verify_password(&payload.password, &user.password_hash) {
+            self.record_login_failure(&account_key, &ip_key, Some(user.user_id), ip_address)
+                .await?;
+
+            return Err(Error::InvalidCredentials);
+        }
+
+        let factor = self
+            .enabled_totp_factor(user.user_id)
+            .await?
+            .ok_or(Error::TwoFactorNotEnabled)?;
+
+        if !self.check_second_factor(&factor, &payload.code).await? {
+            self.record_login_failure(&account_key, &ip_key, Some(user.user_id), ip_address)
+                .await?;
+
+            return Err(Error::InvalidTwoFactorCode);
+        }
+
+        self.login_throttle_repository
+            .clear_login_throttle(&account_key)
+            .await?;
+
+        self.two_factor_repository
+            .delete_totp_factor(user.user_id)
+            .await
+    }
+
+    /// challenge of a login whose password was right, when the user has a second factor
+    pub(super) async fn login_challenge(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<TwoFactorChallenge>> {
+        if self.enabled_totp_factor(user_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let (challenge, token) = LoginChallenge::new(user_id);
+        let challenge = self
+            .two_factor_repository
+            .create_login_challenge(challenge)
+            .await?;
+
+        Ok(Some(TwoFactorChallenge {
+            two_factor_required: true,
+            challenge_token: token,
+            expires_at: challenge.expires_at,
+        }))
+    }
+
+    /// second step of the login, every wrong code counts against the challenge and is
+    /// throttled like a wrong password
+    pub async fn login_two_factor(
+        &self,
+        payload: LoginTwoFactor,
+        ip_address: IpAddr,
+    ) -> Result<LoginResponse> {
+        let challenge = self
+            .two_factor_repository
+            .get_login_challenge_by_hash(&hash_token(&payload.challenge_token))
+            .await?
+            .filter(LoginChallenge::is_active)
+            .ok_or(Error::InvalidToken)?;
+
+        let user = self
+            .user_repository
+            .get_user_by_id(challenge.user_id)
+            .await?
+            .ok_or(Error::InvalidToken)?;
+
+        let account_key = LoginThrottle::account_key(&user.email);
+        let ip_key = LoginThrottle::ip_key(ip_address);
+
+        self.check_login_throttles(&account_key, &ip_key).await?;
+
+        let factor = self
+            .enabled_totp_factor(challenge.user_id)
+            .await?
+            .ok_or(Error::InvalidToken)?;
+
+        if !self.check_second_factor(&factor, &payload.code).await? {
+            self.two_factor_repository
+                .fail_login_challenge(challenge.challenge_id)
+                .await?;
+            self.record_login_failure(&account_key, &ip_key, Some(user.user_id), ip_address)
+                .await?;
+
+            return Err(Error::InvalidTwoFactorCode);
+        }
+
+        if !self
+            .two_factor_repository
+            .use_login_challenge(challenge.challenge_id)
+            .await?
+        {
+            return Err(Error::InvalidToken);
+        }
+
+        self.login_throttle_repository
+            .clear_login_throttle(&account_key)
+            .await?;
+
+        Ok(LoginResponse {
+            tokens: self.start_session(&user).await?,
+            user,
+        })
+    }
+
+    async fn enabled_totp_factor(&self, user_id: Uuid) -> Result<Option<TotpFactor>> {
+        Ok(self
+            .two_factor_repository
+            .get_totp_factor(user_id)
+            .await?
+            .filter(TotpFactor::is_enabled))
+    }
+
+    /// a code of the authenticator not used yet, or one of the recovery codes left
+    async fn check_second_factor(&self, factor: &TotpFactor, code: &str) -> Result<bool> {
+        if !is_totp_code(code) {
+            return self
+                .two_factor_repository
+                .use_recovery_code(factor.user_id, &hash_recovery_code(code))
+                .await;
+        }
+
+        let Some(step) = factor.verify(code, Utc::now()) else {
+            return Ok(false);
+        };
+
+        self.two_factor_repository
+            .use_totp_step(factor.user_id, step)
+            .await
+    }
+
+    async fn replace_recovery_codes(&self, user_id: Uuid) -> Result<RecoveryCodes> {
+        let (codes, recovery_codes) = RecoveryCode::generate(user_id);
+
+        self.two_factor_repository
+            .enable_totp_factor(user_id, codes)
+            .await?;
+
+        Ok(RecoveryCodes { recovery_codes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Duration;
+
+    use crate::{
+        domains::{
+            login_throttles::ACCOUNT_POLICY, passwords::hash_password, tokens::tests::test_keys,
+        },
+        mail::MockMailSender,
+        repositories::{
+            api_keys::MockApiKeyRepository, email_tokens::MockEmailTokenRepository,
+            login_throttles::MockLoginThrottleRepository, sessions::MockSessionRepository,
+            two_factor::MockTwoFactorRepository, users::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    fn new_user() -> User {
+        User::new(
+            String::from("ana@example.com"),
+            String::from("Ana"),
+            hash_password("correct horse").unwrap(),
+        )
+    }
+
+    fn enabled_factor(user_id: Uuid) -> TotpFactor {
+        TotpFactor {
+            enabled_at: Some(Utc::now()),
+            ..TotpFactor::new(user_id)
+        }
+    }
+
+    fn new_handler(
+        user_repository: MockUserRepository,
+        session_repository: MockSessionRepository,
+        two_factor_repository: MockTwoFactorRepository,
+        login_throttle_repository: MockLoginThrottleRepository,
+    ) -> Handler {
+        Handler::new(
+            Arc::new(user_repository),
+            Arc::new(session_repository),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(two_factor_repository),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(login_throttle_repository),
+            Arc::new(MockMailSender::new()),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        )
+    }
+
+    #[tokio::test]
+    async fn should_count_the_wrong_codes_of_a_login_challenge() {
+        let user = new_user();
+        let (challenge, token) = LoginChallenge::new(user.user_id);
+        let challenge_id = challenge.challenge_id;
+        let factor = enabled_factor(user.user_id);
+        let (_, recovery_codes) = RecoveryCode::generate(user.user_id);
+        let recovery_hash = hash_recovery_code(&recovery_codes[0]);
+
+        let mut two_factor_repository = MockTwoFactorRepository::new();
+
+        two_factor_repository
+            .expect_get_login_challenge_by_hash()
+            .returning(move |_| Ok(Some(challenge.clone())));
+        two_factor_repository
+            .expect_get_totp_factor()
+            .returning(move |_| Ok(Some(factor.clone())));
+        two_factor_repository
+            .expect_use_recovery_code()
+            .returning(move |_, hash| Ok(hash == recovery_hash));
+        two_factor_repository
+            .expect_fail_login_challenge()
+            .withf(move |id| *id == challenge_id)
+            .times(1)
+            .returning(|_| Ok(()));
+        two_factor_repository
+            .expect_use_login_challenge()
+            .times(1)
+            .returning(|_| Ok(true));
+
+        let mut user_repository = MockUserRepository::new();
+        let stored = user.clone();
+        user_repository
+            .expect_get_user_by_id()
+            .returning(move |_| Ok(Some(stored.clone())));
+
+        let mut session_repository = MockSessionRepository::new();
+        session_repository.expect_create_session().returning(Ok);
+        session_repository
+            .expect_create_refresh_token()
+            .returning(Ok);
+
+        // the wrong code counts for the email and the address, the right one clears the email
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .returning(|_| Ok(vec![]));
+        login_throttle_repository
+            .expect_record_login_failure()
+            .times(2)
+            .returning(|throttle_key, _| {
+                Ok(LoginThrottle {
+                    throttle_key: throttle_key.to_string(),
+                    failures: 1,
+                    last_failure_at: Utc::now(),
+                })
+            });
+        login_throttle_repository
+            .expect_clear_login_throttle()
+            .withf(|throttle_key| throttle_key == "account:ana@example.com")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let handler = new_handler(
+            user_repository,
+            session_repository,
+            two_factor_repository,
+            login_throttle_repository,
+        );
+
+        let login = |code: &str| LoginTwoFactor {
+            challenge_token: token.clone(),
+            code: String::from(code),
+        };
+        let ip_address = IpAddr::from([192, 0, 2, 1]);
+
+        let wrong = handler
+            .login_two_factor(login("00000-00000"), ip_address)
+            .await;
+
+        assert!(matches!(wrong, Err(Error::InvalidTwoFactorCode)));
+
+        let response = handler
+            .login_two_factor(login(&recovery_codes[0].to_uppercase()), ip_address)
+            .await
+            .unwrap();
+
+        assert_eq!(response.user.user_id, user.user_id);
+    }
+
+    #[tokio::test]
+    async fn should_lock_out_the_codes_like_the_passwords() {
+        let user = new_user();
+        let (challenge, token) = LoginChallenge::new(user.user_id);
+        let (_, recovery_codes) = RecoveryCode::generate(user.user_id);
+
+        // the codes are never checked while the email is locked out
+        let mut two_factor_repository = MockTwoFactorRepository::new();
+
+        two_factor_repository
+            .expect_get_login_challenge_by_hash()
+            .returning(move |_| Ok(Some(challenge.clone())));
+        two_factor_repository.expect_use_recovery_code().never();
+        two_factor_repository.expect_use_totp_step().never();
+        two_factor_repository.expect_delete_totp_factor().never();
+
+        let mut user_repository = MockUserRepository::new();
+        let stored = user.clone();
+        user_repository
+            .expect_get_user_by_id()
+            .returning(move |_| Ok(Some(stored.clone())));
+
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .returning(|throttle_keys| {
+                Ok(throttle_keys
+                    .iter()
+                    .filter(|throttle_key| *throttle_key == "account:ana@example.com")
+                    .map(|throttle_key| LoginThrottle {
+                        throttle_key: throttle_key.clone(),
+                        failures: ACCOUNT_POLICY.lockout_failures,
+                        last_failure_at: Utc::now() - Duration::minutes(1),
+                    })
+                    .collect())
+            });
+        login_throttle_repository.expect_record_login_failure().never();
+
+        let handler = new_handler(
+            user_repository,
+            MockSessionRepository::new(),
+            two_factor_repository,
+            login_throttle_repository,
+        );
+
+        let ip_address = IpAddr::from([192, 0, 2, 1]);
+
+        let login = handler
+            .login_two_factor(
+                LoginTwoFactor {
+                    challenge_token: token,
+                    code: recovery_codes[0].clone(),
+                },
+                ip_address,
+            )
+            .await;
+
+        assert!(matches!(login, Err(Error::TooManyAttempts(_))));
+
+        let disable = handler
+            .disable_totp(
+                &user,
+                DisableTwoFactor {
+                    password: String::from("correct horse"),
+                    code: recovery_codes[0].clone(),
+                },
+                ip_address,
+            )
+            .await;
+
+        assert!(matches!(disable, Err(Error::TooManyAttempts(_))));
+    }
+}
@@ -0,0 +1,332 @@
+use std::net::IpAddr;
+
+use sqlx::error::ErrorKind;
+
+use crate::domains::{
+    email_tokens::EmailTokenPurpose,
+    errors::{database_error_kind, Error, Result},
+    login_throttles::{LoginThrottle, REQUEST_POLICY},
+    passwords::{dummy_hash, hash_password, validate_password, verify_password},
+    sessions::{LoginResponse, LoginResult},
+    users::{normalize_email, LoginUser, RegisterUser, User},
+};
+
+use super::Handler;
+
+impl Handler {
+    /// the verification mail is sent in the background, a failed one can be sent again later,
+    /// every registration of an address counts so the taken emails can't be looked up in bulk
+    pub async fn register(&self, payload: RegisterUser, ip_address: IpAddr) -> Result<User> {
+        let request_key = LoginThrottle::request_key("register", ip_address);
+
+        self.check_throttles(&[(request_key.clone(), REQUEST_POLICY)])
+            .await?;
+        self.record_requests(&[request_key]).await?;
+
+        let email = normalize_email(&payload.email)?;
+        validate_password(&payload.password)?;
+
+        let password_hash = hash_password(&payload.password)?;
+
+        let user = self
+            .user_repository
+            .create_user(User::new(email, payload.name.trim().into(), password_hash))
+            .await
+            .map_err(|err| match err {
+                Error::DatabaseError(ref db_err)
+                    if database_error_kind(db_err) == Some(ErrorKind::UniqueViolation) =>
+                {
+                    Error::EmailAlreadyRegistered
+                }
+                err => err,
+            })?;
+
+        self.mail_token_in_background(user.clone(), EmailTokenPurpose::VerifyEmail);
+
+        Ok(user)
+    }
+
+    /// unknown emails and wrong passwords fail the same way, and are throttled the same way
+    pub async fn login(&self, payload: LoginUser, ip_address: IpAddr) -> Result<LoginResult> {
+        let account_key = LoginThrottle::account_key(&payload.email);
+        let ip_key = LoginThrottle::ip_key(ip_address);
+
+        self.check_login_throttles(&account_key, &ip_key).await?;
+
+        let user = match normalize_email(&payload.email) {
+            Ok(email) => self.user_repository.get_user_by_email(&email).await?,
+            Err(_) => None,
+        };
+
+        let password_hash = user
+            .as_ref()
+            .map_or(dummy_hash(), |user| user.password_hash.as_str());
+        let valid = verify_password(&payload.password, password_hash);
+
+        let user_id = user.as_ref().map(|user| user.user_id);
+
+        let Some(user) = user.filter(|_| valid) else {
+            self.record_login_failure(&account_key, &ip_key, user_id, ip_address)
+                .await?;
+
+            return Err(Error::InvalidCredentials);
+        };
+
+        // the failures are only forgotten once the second factor is right too, a known
+        // password must not reset the throttle of the codes
+        if let Some(challenge) = self.login_challenge(user.user_id).await? {
+            return Ok(LoginResult::TwoFactorRequired(challenge));
+        }
+
+        self.login_throttle_repository
+            .clear_login_throttle(&account_key)
+            .await?;
+
+        Ok(LoginResult::Authenticated(LoginResponse {
+            tokens: self.start_session(&user).await?,
+            user,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::{
+        domains::tokens::tests::test_keys,
+        mail::MockMailSender,
+        repositories::{
+            api_keys::MockApiKeyRepository, email_tokens::MockEmailTokenRepository,
+            login_throttles::MockLoginThrottleRepository, sessions::MockSessionRepository,
+            two_factor::MockTwoFactorRepository, users::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    fn new_user(password: &str) -> User {
+        User::new(
+            String::from("ana@example.com"),
+            String::from("Ana"),
+            hash_password(password).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn should_store_a_normalized_email_and_a_hashed_password() {
+        let mut user_repository = MockUserRepository::new();
+
+        user_repository
+            .expect_create_user()
+            .withf(|user| {
+                user.email == "ana@example.com"
+                    && verify_password("correct horse", &user.password_hash)
+            })
+            .returning(Ok);
+
+        let mut email_token_repository = MockEmailTokenRepository::new();
+
+        email_token_repository
+            .expect_create_email_token()
+            .returning(Ok);
+
+        let mut mail_sender = MockMailSender::new();
+
+        mail_sender.expect_send().returning(|_| Ok(()));
+
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .returning(|_| Ok(vec![]));
+        login_throttle_repository
+            .expect_record_login_failure()
+            .withf(|throttle_key, _| throttle_key == "register:ip:192.0.2.1")
+            .times(2)
+            .returning(|throttle_key, _| {
+                Ok(LoginThrottle {
+                    throttle_key: throttle_key.to_string(),
+                    failures: 1,
+                    last_failure_at: Utc::now(),
+                })
+            });
+
+        let handler = Handler::new(
+            Arc::new(user_repository),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(email_token_repository),
+            Arc::new(login_throttle_repository),
+            Arc::new(mail_sender),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let ip_address = IpAddr::from([192, 0, 2, 1]);
+
+        let user = handler
+            .register(
+                RegisterUser {
+                    email: String::from(" Ana@Example.com "),
+                    name: String::from("Ana"),
+                    password: String::from("correct horse"),
+                },
+                ip_address,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(user.email, "ana@example.com");
+
+        let weak = handler
+            .register(
+                RegisterUser {
+                    email: String::from("ana@example.com"),
+                    name: String::from("Ana"),
+                    password: String::from("short"),
+                },
+                ip_address,
+            )
+            .await;
+
+        assert!(matches!(weak, Err(Error::WeakPassword)));
+    }
+
+    #[tokio::test]
+    async fn should_refuse_the_registrations_of_a_throttled_address() {
+        let mut user_repository = MockUserRepository::new();
+
+        user_repository.expect_create_user().never();
+
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .returning(|throttle_keys| {
+                Ok(vec![LoginThrottle {
+                    throttle_key: throttle_keys[0].clone(),
+                    failures: REQUEST_POLICY.lockout_failures,
+                    last_failure_at: Utc::now(),
+                }])
+            });
+        login_throttle_repository
+            .expect_record_login_failure()
+            .never();
+
+        let handler = Handler::new(
+            Arc::new(user_repository),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(MockTwoFactorRepository::new()),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(login_throttle_repository),
+            Arc::new(MockMailSender::new()),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let registered = handler
+            .register(
+                RegisterUser {
+                    email: String::from("ana@example.com"),
+                    name: String::from("Ana"),
+                    password: String::from("correct horse"),
+                },
+                IpAddr::from([192, 0, 2, 1]),
+            )
+            .await;
+
+        assert!(matches!(registered, Err(Error::TooManyAttempts(_))));
+    }
+
+    #[tokio::test]
+    async fn should_refuse_a_wrong_password_or_an_unknown_email() {
+        let mut user_repository = MockUserRepository::new();
+        let user = new_user("correct horse");
+
+        user_repository
+            .expect_get_user_by_email()
+            .returning(move |email| Ok((email == "ana@example.com").then(|| user.clone())));
+
+        let mut session_repository = MockSessionRepository::new();
+
+        session_repository
+            .expect_create_session()
+            .times(1)
+            .returning(Ok);
+        session_repository
+            .expect_create_refresh_token()
+            .times(1)
+            .returning(Ok);
+
+        let mut two_factor_repository = MockTwoFactorRepository::new();
+
+        two_factor_repository
+            .expect_get_totp_factor()
+            .returning(|_| Ok(None));
+
+        let mut login_throttle_repository = MockLoginThrottleRepository::new();
+
+        login_throttle_repository
+            .expect_get_login_throttles()
+            .returning(|_| Ok(vec![]));
+        login_throttle_repository
+            .expect_record_login_failure()
+            .times(4)
+            .returning(|throttle_key, _| {
+                Ok(LoginThrottle {
+                    throttle_key: throttle_key.to_string(),
+                    failures: 1,
+                    last_failure_at: Utc::now(),
+                })
+            });
+        login_throttle_repository
+            .expect_clear_login_throttle()
+            .withf(|throttle_key| throttle_key == "account:ana@example.com")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let handler = Handler::new(
+            Arc::new(user_repository),
+            Arc::new(session_repository),
+            Arc::new(MockApiKeyRepository::new()),
+            Arc::new(two_factor_repository),
+            Arc::new(MockEmailTokenRepository::new()),
+            Arc::new(login_throttle_repository),
+            Arc::new(MockMailSender::new()),
+            String::from("https://home.example.com"),
+            Arc::new(test_keys()),
+        );
+
+        let login = |email: &str, password: &str| LoginUser {
+            email: String::from(email),
+            password: String::from(password),
+        };
+
+        let ip_address = IpAddr::from([192, 0, 2, 1]);
+
+        let wrong_password = handler
+            .login(login("ana@example.com", "wrong horse"), ip_address)
+            .await;
+        let unknown_email = handler
+            .login(login("bob@example.com", "correct horse"), ip_address)
+            .await;
+
+        assert!(matches!(wrong_password, Err(Error::InvalidCredentials)));
+        assert!(matches!(unknown_email, Err(Error::InvalidCredentials)));
+
+        let Ok(LoginResult::Authenticated(response)) = handler
+            .login(login("ANA@example.com", "correct horse"), ip_address)
+            .await
+        else {
+            panic!("login without a second factor should be authenticated");
+        };
+
+        assert_eq!(response.user.email, "ana@example.com");
+        assert_eq!(response.tokens.refresh_token.len(), 64);
+    }
+}
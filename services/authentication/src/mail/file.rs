@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+use crate::domains::errors::{Error, Result};
+
+use super::{Mail, MailSender};
+
+/// Keeps the mails instead of sending them, appended to a file or printed, for local runs
+pub struct FileMailSender {
+    path: Option<PathBuf>,
+}
+
+impl FileMailSender {
+    /// mails appended to the file, or printed to the standard output without one
+    pub fn new(path: Option<PathBuf>) -> Self {
+        FileMailSender { path }
+    }
+}
+
+#[async_trait::async_trait]
+impl MailSender for FileMailSender {
+    async fn send(&self, mail: Mail) -> Result<()> {
+        let entry = format!(
+            "Date: {}\nTo: {}\nSubject: {}\n\n{}\n\n",
+            Utc::now().to_rfc2822(),
+            mail.to,
+            mail.subject,
+            mail.body
+        );
+
+        let Some(path) = &self.path else {
+            print!("{entry}");
+            return Ok(());
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|err| Error::MailFailed(err.to_string()))?;
+
+        file.write_all(entry.as_bytes())
+            .await
+            .map_err(|err| Error::MailFailed(err.to_string()))?;
+
+        // tokio writes in the background, the mail is only kept once flushed
+        file.flush()
+            .await
+            .map_err(|err| Error::MailFailed(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_append_the_mails_to_the_file() {
+        let path = std::env::temp_dir().join(format!("mails-{}.txt", uuid::Uuid::new_v4()));
+        let sender = FileMailSender::new(Some(path.clone()));
+
+        for subject in ["first", "second"] {
+            sender
+                .send(Mail {
+                    to: String::from("ana@example.com"),
+                    subject: String::from(subject),
+                    body: String::from("body"),
+                })
+                .await
+                .unwrap();
+        }
+
+        let mails = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mails.matches("To: ana@example.com").count(), 2);
+        assert!(mails.find("Subject: first") < mails.find("Subject: second"));
+    }
+}
@@ -0,0 +1,20 @@
+use mockall::automock;
+
+use crate::domains::errors::Result;
+
+pub mod file;
+pub mod smtp;
+
+/// Plain text mail to a single recipient
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[automock]
+#[async_trait::async_trait]
+pub trait MailSender {
+    async fn send(&self, mail: Mail) -> Result<()>;
+}
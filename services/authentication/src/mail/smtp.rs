@@ -0,0 +1,327 @@
+use std::{str::FromStr, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_native_tls::{native_tls, TlsConnector, TlsStream};
+use uuid::Uuid;
+
+use crate::domains::errors::{Error, Result};
+
+use super::{Mail, MailSender};
+
+/// a whole delivery, from connecting to quitting, gives up after this long
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How the connection to the server is encrypted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmtpTls {
+    /// upgraded after the greeting, usually on port 587
+    StartTls,
+    /// encrypted from the start, usually on port 465
+    Implicit,
+    /// plain text, only for relays on the same host or network
+    None,
+}
+
+impl FromStr for SmtpTls {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "starttls" => Ok(Self::StartTls),
+            "implicit" => Ok(Self::Implicit),
+            "none" => Ok(Self::None),
+            other => Err(format!(
+                "unknown SMTP TLS mode {other}, expected starttls, implicit or none"
+            )),
+        }
+    }
+}
+
+/// Sends the mails through an SMTP server, a connection per mail
+pub struct SmtpMailSender {
+    host: String,
+    port: u16,
+    tls: SmtpTls,
+    credentials: Option<(String, String)>,
+    /// mailbox of the From header, like `Home App <noreply@example.com>`
+    from: String,
+}
+
+impl SmtpMailSender {
+    pub fn new(host: &str, port: u16, tls: SmtpTls, from: &str) -> Self {
+        SmtpMailSender {
+            host: host.to_string(),
+            port,
+            tls,
+            credentials: None,
+            from: from.to_string(),
+        }
+    }
+
+    /// authenticate with AUTH PLAIN, only sent once the connection is encrypted or on a relay
+    /// trusted with plain text
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    async fn deliver(&self, mail: &Mail) -> Result<()> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(failed)?;
+
+        match self.tls {
+            SmtpTls::None => self.session(Connection::new(stream), mail, true).await,
+            SmtpTls::Implicit => {
+                let stream = self.encrypt(stream).await?;
+                self.session(Connection::new(stream), mail, true).await
+            }
+            SmtpTls::StartTls => {
+                let mut connection = Connection::new(stream);
+                connection.expect(220).await?;
+                connection
+                    .command(&format!("EHLO {}", self.hello_name()), 250)
+                    .await?;
+                connection.command("STARTTLS", 220).await?;
+
+                let stream = self.encrypt(connection.into_inner()).await?;
+                self.session(Connection::new(stream), mail, false).await
+            }
+        }
+    }
+
+    async fn encrypt(&self, stream: TcpStream) -> Result<TlsStream<TcpStream>> {
+        let connector = native_tls::TlsConnector::new().map_err(failed)?;
+
+        TlsConnector::from(connector)
+            .connect(&self.host, stream)
+            .await
+            .map_err(failed)
+    }
+
+    async fn session<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        mut connection: Connection<S>,
+        mail: &Mail,
+        greeting: bool,
+    ) -> Result<()> {
+        if greeting {
+            connection.expect(220).await?;
+        }
+
+        connection
+            .command(&format!("EHLO {}", self.hello_name()), 250)
+            .await?;
+
+        if let Some((username, password)) = &self.credentials {
+            let token = STANDARD.encode(format!("\0{username}\0{password}"));
+            connection
+                .command(&format!("AUTH PLAIN {token}"), 235)
+                .await?;
+        }
+
+        connection
+            .command(&format!("MAIL FROM:<{}>", address(&self.from)), 250)
+            .await?;
+        connection
+            .command(&format!("RCPT TO:<{}>", mail.to), 250)
+            .await?;
+        connection.command("DATA", 354).await?;
+        connection.command(&self.message(mail), 250).await?;
+
+        // the mail is accepted, a failed goodbye changes nothing
+        connection.command("QUIT", 221).await.ok();
+
+        Ok(())
+    }
+
+    /// headers and body, every line ending in CRLF and the lines starting with a dot doubled so
+    /// none ends the data early, followed by the terminating dot
+    fn message(&self, mail: &Mail) -> String {
+        let body: String = mail
+            .body
+            .lines()
+            .map(|line| {
+                if line.starts_with('.') {
+                    format!(".{line}\r\n")
+                } else {
+                    format!("{line}\r\n")
+                }
+            })
+            .collect();
+
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@{}>\r\n\
+             MIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\
+             Content-Transfer-Encoding: 8bit\r\n\r\n{body}.",
+            self.from,
+            mail.to,
+            mail.subject,
+            Utc::now().to_rfc2822(),
+            Uuid::new_v4(),
+            self.hello_name(),
+        )
+    }
+
+    /// domain of the sender, the name the client greets the server with
+    fn hello_name(&self) -> &str {
+        address(&self.from)
+            .rsplit_once('@')
+            .map_or("localhost", |(_, domain)| domain)
+    }
+}
+
+#[async_trait::async_trait]
+impl MailSender for SmtpMailSender {
+    async fn send(&self, mail: Mail) -> Result<()> {
+        tokio::time::timeout(SMTP_TIMEOUT, self.deliver(&mail))
+            .await
+            .map_err(|_| Error::MailFailed(String::from("SMTP server timed out")))?
+    }
+}
+
+/// Connection to the server, a command at a time
+struct Connection<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Connection<S> {
+    fn new(stream: S) -> Self {
+        Connection {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    async fn command(&mut self, command: &str, expected: u16) -> Result<()> {
+        self.stream
+            .write_all(format!("{command}\r\n").as_bytes())
+            .await
+            .map_err(failed)?;
+        self.stream.flush().await.map_err(failed)?;
+
+        self.expect(expected).await
+    }
+
+    /// reply of the server, the lines of a multiline reply all start with the code and a dash
+    async fn expect(&mut self, expected: u16) -> Result<()> {
+        let mut reply = String::new();
+
+        loop {
+            let mut line = String::new();
+
+            if self.stream.read_line(&mut line).await.map_err(failed)? == 0 {
+                return Err(Error::MailFailed(String::from(
+                    "SMTP server closed the connection",
+                )));
+            }
+
+            reply.push_str(&line);
+
+            if line.as_bytes().get(3) != Some(&b'-') {
+                break;
+            }
+        }
+
+        let code = reply.get(..3).and_then(|code| code.parse::<u16>().ok());
+
+        if code != Some(expected) {
+            return Err(Error::MailFailed(format!(
+                "SMTP server answered {}",
+                reply.trim_end()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+/// address of a mailbox written as `Name <address>` or as the bare address
+fn address(mailbox: &str) -> &str {
+    mailbox
+        .rsplit_once('<')
+        .and_then(|(_, address)| address.strip_suffix('>'))
+        .unwrap_or(mailbox)
+        .trim()
+}
+
+fn failed(err: impl ToString) -> Error {
+    Error::MailFailed(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// answers every command like a server accepting the mail, and returns what it received
+    async fn fake_server(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        let mut received = String::new();
+
+        stream.write_all(b"220 ready\r\n").await.unwrap();
+
+        loop {
+            let mut line = String::new();
+            stream.read_line(&mut line).await.unwrap();
+            received.push_str(&line);
+
+            let reply: &[u8] = match line.trim_end() {
+                command if command.starts_with("EHLO") => b"250-hello\r\n250 AUTH PLAIN\r\n",
+                command if command.starts_with("AUTH") => b"235 authenticated\r\n",
+                "DATA" => b"354 go ahead\r\n",
+                "." => b"250 queued\r\n",
+                "QUIT" => {
+                    stream.write_all(b"221 bye\r\n").await.unwrap();
+                    return received;
+                }
+                command if command.starts_with("MAIL") || command.starts_with("RCPT") => {
+                    b"250 ok\r\n"
+                }
+                _ => continue,
+            };
+
+            stream.write_all(reply).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn should_deliver_the_mail_to_the_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(fake_server(listener));
+
+        SmtpMailSender::new(
+            "127.0.0.1",
+            port,
+            SmtpTls::None,
+            "Home App <noreply@example.com>",
+        )
+        .credentials("ana", "secret")
+        .send(Mail {
+            to: String::from("ana@example.com"),
+            subject: String::from("Reset your password"),
+            body: String::from("first line\n.hidden line"),
+        })
+        .await
+        .unwrap();
+
+        let received = server.await.unwrap();
+
+        assert!(received.starts_with("EHLO example.com\r\nAUTH PLAIN AGFuYQBzZWNyZXQ=\r\n"));
+        assert!(received.contains("MAIL FROM:<noreply@example.com>\r\n"));
+        assert!(received.contains("RCPT TO:<ana@example.com>\r\n"));
+        assert!(received.contains("Subject: Reset your password\r\n"));
+        assert!(received.contains("\r\n\r\nfirst line\r\n..hidden line\r\n.\r\nQUIT\r\n"));
+    }
+}
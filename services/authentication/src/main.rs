@@ -1,14 +1,31 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
+use axum::Extension;
+
+use domains::tokens::JwtKeys;
+use handlers::Handler;
+use mail::{
+    file::FileMailSender,
+    smtp::{SmtpMailSender, SmtpTls},
+    MailSender,
+};
 use repositories::SqlxRepository;
-use sqlx::{migrate::MigrateError, postgres::PgPoolOptions, Pool, Postgres};
+use routes::TrustForwardedFor;
+use sqlx::postgres::PgPoolOptions;
 
+mod domains;
+mod handlers;
+mod logger;
+mod mail;
 mod repositories;
+mod routes;
 
 #[tokio::main]
 async fn main() {
     dotenv::dotenv().ok();
 
+    logger::init().expect("Failed to initialize logger");
+
     let conn_str =
         std::env::var("LEGALENTITY_DATABASE_URL").expect("Could not fetch connection string.");
 
@@ -18,12 +35,103 @@ async fn main() {
         .await
         .expect("Couldn't connect to the database");
 
-    let tes = execute_migration(&pool).await;
-    println!("{tes:?}");
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("Couldn't run the database migrations");
+
+    // deploys run the migrations once before starting the new instances
+    if std::env::args().any(|arg| arg == "--migrate-only") {
+        log::info!("Database migrations applied");
+        return;
+    }
 
+    let keys = Arc::new(load_keys());
+    let app_url = std::env::var("APP_URL").expect("Could not fetch the web app url.");
     let sqlx_repository = Arc::new(SqlxRepository::new(pool));
+    let handler = Handler::new(
+        sqlx_repository.clone(),
+        sqlx_repository.clone(),
+        sqlx_repository.clone(),
+        sqlx_repository.clone(),
+        sqlx_repository.clone(),
+        sqlx_repository,
+        mail_sender(),
+        app_url,
+        keys,
+    );
+
+    let mut app = routes::configure_routes().with_state(handler);
+
+    // behind a proxy every login would come from its address and be throttled together
+    if std::env::var("TRUST_FORWARDED_FOR").is_ok_and(|value| value == "true") {
+        app = app.layer(Extension(TrustForwardedFor));
+    }
+
+    let port = std::env::var("PORT").expect("Could not fetch port data.");
+    let url = format!("0.0.0.0:{}", port);
+
+    let listener = tokio::net::TcpListener::bind(url).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
-async fn execute_migration(pool: &Pool<Postgres>) -> Result<(), MigrateError> {
-    sqlx::migrate!().run(pool).await
+/// MAIL_SENDER picks smtp or file, the file sender keeps the mails of local runs in MAIL_FILE or
+/// prints them
+fn mail_sender() -> Arc<dyn MailSender + Send + Sync> {
+    let env = |name: &str| std::env::var(name).ok();
+
+    match env("MAIL_SENDER").as_deref() {
+        None | Some("file") => Arc::new(FileMailSender::new(env("MAIL_FILE").map(PathBuf::from))),
+        Some("smtp") => {
+            let host = env("SMTP_HOST").expect("Could not fetch the SMTP host.");
+            let port = env("SMTP_PORT").map_or(587, |port| {
+                port.parse()
+                    .unwrap_or_else(|err| panic!("Invalid SMTP port {port}: {err}"))
+            });
+            let tls: SmtpTls = env("SMTP_TLS")
+                .as_deref()
+                .unwrap_or("starttls")
+                .parse()
+                .unwrap_or_else(|err| panic!("{err}"));
+            let from = env("MAIL_FROM").expect("Could not fetch the mail sender address.");
+
+            let sender = SmtpMailSender::new(&host, port, tls, &from);
+
+            match (env("SMTP_USERNAME"), env("SMTP_PASSWORD")) {
+                (Some(username), Some(password)) => {
+                    Arc::new(sender.credentials(&username, &password))
+                }
+                _ => Arc::new(sender),
+            }
+        }
+        Some(other) => panic!("Unknown mail sender {other}, expected smtp or file"),
+    }
+}
+
+/// signing key of the access tokens, the public keys of the previous ones are kept in the JWKS
+/// for a rotation
+fn load_keys() -> JwtKeys {
+    let read = |path: &str| {
+        std::fs::read_to_string(path).unwrap_or_else(|err| panic!("Could not read {path}: {err}"))
+    };
+
+    let private_key = read(
+        &std::env::var("JWT_PRIVATE_KEY_FILE").expect("Could not fetch the JWT signing key file."),
+    );
+    let previous_public_keys: Vec<String> = std::env::var("JWT_PREVIOUS_PUBLIC_KEY_FILES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(read)
+        .collect();
+    let issuer = std::env::var("JWT_ISSUER").unwrap_or_else(|_| String::from("home_app"));
+
+    JwtKeys::from_pem(&issuer, &private_key, &previous_public_keys)
+        .unwrap_or_else(|err| panic!("Could not load the JWT keys: {err}"))
 }
@@ -0,0 +1,110 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{api_keys::ApiKey, errors::Result};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait ApiKeyRepository {
+    async fn create_api_key(&self, api_key: ApiKey) -> Result<ApiKey>;
+    async fn list_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>>;
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>>;
+    /// none when the user has no such key, revoking twice keeps the first time
+    async fn revoke_api_key(&self, user_id: Uuid, key_id: Uuid) -> Result<Option<ApiKey>>;
+    async fn use_api_key(&self, key_id: Uuid) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl ApiKeyRepository for SqlxRepository {
+    async fn create_api_key(&self, api_key: ApiKey) -> Result<ApiKey> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            INSERT INTO api_keys (
+                key_id,
+                user_id,
+                name,
+                key_hash,
+                key_hint,
+                scopes,
+                created_at,
+                expires_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8
+            ) RETURNING *
+            "#,
+            api_key.key_id,
+            api_key.user_id,
+            api_key.name,
+            api_key.key_hash,
+            api_key.key_hint,
+            &api_key.scopes,
+            api_key.created_at,
+            api_key.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    async fn list_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        let api_keys = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at, key_id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(api_keys)
+    }
+
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT * FROM api_keys WHERE key_hash = $1
+            "#,
+            key_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    async fn revoke_api_key(&self, user_id: Uuid, key_id: Uuid) -> Result<Option<ApiKey>> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            UPDATE api_keys SET revoked_at = COALESCE(revoked_at, now())
+            WHERE key_id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+            key_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    async fn use_api_key(&self, key_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE api_keys SET last_used_at = now() WHERE key_id = $1
+            "#,
+            key_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
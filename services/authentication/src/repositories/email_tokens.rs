@@ -0,0 +1,109 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    email_tokens::{EmailToken, EmailTokenPurpose},
+    errors::Result,
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait EmailTokenRepository {
+    /// the unused tokens of the user with the same purpose stop working
+    async fn create_email_token(&self, email_token: EmailToken) -> Result<EmailToken>;
+    async fn get_email_token_by_hash(&self, token_hash: &str) -> Result<Option<EmailToken>>;
+    /// false when the token was already used, even by a concurrent request
+    async fn use_email_token(&self, token_id: Uuid) -> Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl EmailTokenRepository for SqlxRepository {
+    async fn create_email_token(&self, email_token: EmailToken) -> Result<EmailToken> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE email_tokens SET used_at = now()
+            WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL
+            "#,
+            email_token.user_id,
+            email_token.purpose as EmailTokenPurpose
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let email_token = sqlx::query_as!(
+            EmailToken,
+            r#"
+            INSERT INTO email_tokens (
+                token_id,
+                user_id,
+                purpose,
+                token_hash,
+                created_at,
+                expires_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6
+            ) RETURNING
+                token_id,
+                user_id,
+                purpose as "purpose: EmailTokenPurpose",
+                token_hash,
+                created_at,
+                expires_at,
+                used_at
+            "#,
+            email_token.token_id,
+            email_token.user_id,
+            email_token.purpose as EmailTokenPurpose,
+            email_token.token_hash,
+            email_token.created_at,
+            email_token.expires_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(email_token)
+    }
+
+    async fn get_email_token_by_hash(&self, token_hash: &str) -> Result<Option<EmailToken>> {
+        let email_token = sqlx::query_as!(
+            EmailToken,
+            r#"
+            SELECT
+                token_id,
+                user_id,
+                purpose as "purpose: EmailTokenPurpose",
+                token_hash,
+                created_at,
+                expires_at,
+                used_at
+            FROM email_tokens
+            WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(email_token)
+    }
+
+    async fn use_email_token(&self, token_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE email_tokens SET used_at = now()
+            WHERE token_id = $1 AND used_at IS NULL
+            "#,
+            token_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
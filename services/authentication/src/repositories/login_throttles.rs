@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::Result,
+    login_throttles::{LockoutEvent, LoginThrottle},
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait LoginThrottleRepository {
+    async fn get_login_throttles(&self, throttle_keys: &[String]) -> Result<Vec<LoginThrottle>>;
+    /// counts one more failure, starting over when the last one is older than `forget_before`
+    async fn record_login_failure(
+        &self,
+        throttle_key: &str,
+        forget_before: DateTime<Utc>,
+    ) -> Result<LoginThrottle>;
+    async fn clear_login_throttle(&self, throttle_key: &str) -> Result<()>;
+    async fn create_lockout_event(&self, lockout_event: LockoutEvent) -> Result<LockoutEvent>;
+    /// newest first
+    async fn get_lockout_events(&self, user_id: Uuid) -> Result<Vec<LockoutEvent>>;
+}
+
+#[async_trait::async_trait]
+impl LoginThrottleRepository for SqlxRepository {
+    async fn get_login_throttles(&self, throttle_keys: &[String]) -> Result<Vec<LoginThrottle>> {
+        let login_throttles = sqlx::query_as!(
+            LoginThrottle,
+            r#"
+            SELECT throttle_key, failures, last_failure_at
+            FROM login_throttles
+            WHERE throttle_key = ANY($1)
+            "#,
+            throttle_keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(login_throttles)
+    }
+
+    async fn record_login_failure(
+        &self,
+        throttle_key: &str,
+        forget_before: DateTime<Utc>,
+    ) -> Result<LoginThrottle> {
+        let mut tx = self.pool.begin().await?;
+
+        // the forgotten failures of every key go at once, the table only holds the recent ones
+        sqlx::query!(
+            r#"
+            DELETE FROM login_throttles
+            WHERE last_failure_at < $1 AND throttle_key <> $2
+            "#,
+            forget_before,
+            throttle_key
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let login_throttle = sqlx::query_as!(
+            LoginThrottle,
+            r#"
+            INSERT INTO login_throttles (throttle_key, failures, last_failure_at)
+            VALUES ($1, 1, now())
+            ON CONFLICT (throttle_key) DO UPDATE SET
+                failures = CASE
+                    WHEN login_throttles.last_failure_at < $2 THEN 1
+                    ELSE login_throttles.failures + 1
+                END,
+                last_failure_at = now()
+            RETURNING throttle_key, failures, last_failure_at
+            "#,
+            throttle_key,
+            forget_before
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(login_throttle)
+    }
+
+    async fn clear_login_throttle(&self, throttle_key: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM login_throttles WHERE throttle_key = $1
+            "#,
+            throttle_key
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_lockout_event(&self, lockout_event: LockoutEvent) -> Result<LockoutEvent> {
+        let lockout_event = sqlx::query_as!(
+            LockoutEvent,
+            r#"
+            INSERT INTO lockout_events (
+                event_id,
+                user_id,
+                ip_address,
+                created_at,
+                locked_until
+            ) VALUES (
+                $1, $2, $3, $4, $5
+            ) RETURNING
+                event_id,
+                user_id,
+                ip_address,
+                created_at,
+                locked_until
+            "#,
+            lockout_event.event_id,
+            lockout_event.user_id,
+            lockout_event.ip_address,
+            lockout_event.created_at,
+            lockout_event.locked_until
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(lockout_event)
+    }
+
+    async fn get_lockout_events(&self, user_id: Uuid) -> Result<Vec<LockoutEvent>> {
+        let lockout_events = sqlx::query_as!(
+            LockoutEvent,
+            r#"
+            SELECT
+                event_id,
+                user_id,
+                ip_address,
+                created_at,
+                locked_until
+            FROM lockout_events
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lockout_events)
+    }
+}
@@ -1,5 +1,13 @@
 use sqlx::PgPool;
 
+pub mod api_keys;
+pub mod email_tokens;
+pub mod login_throttles;
+pub mod sessions;
+pub mod two_factor;
+pub mod users;
+
+#[derive(Clone)]
 pub struct SqlxRepository {
     pool: PgPool,
 }
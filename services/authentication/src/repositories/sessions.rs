@@ -0,0 +1,143 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::Result,
+    sessions::{RefreshToken, Session},
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait SessionRepository {
+    async fn create_session(&self, session: Session) -> Result<Session>;
+    async fn get_session_by_id(&self, session_id: Uuid) -> Result<Option<Session>>;
+    async fn revoke_session(&self, session_id: Uuid) -> Result<()>;
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> Result<()>;
+    async fn create_refresh_token(&self, refresh_token: RefreshToken) -> Result<RefreshToken>;
+    async fn get_refresh_token_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>>;
+    /// false when the token was already used, even by a concurrent request
+    async fn use_refresh_token(&self, token_id: Uuid) -> Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl SessionRepository for SqlxRepository {
+    async fn create_session(&self, session: Session) -> Result<Session> {
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            INSERT INTO sessions (
+                session_id,
+                user_id,
+                created_at,
+                expires_at
+            ) VALUES (
+                $1, $2, $3, $4
+            ) RETURNING *
+            "#,
+            session.session_id,
+            session.user_id,
+            session.created_at,
+            session.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    async fn get_session_by_id(&self, session_id: Uuid) -> Result<Option<Session>> {
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            SELECT * FROM sessions WHERE session_id = $1
+            "#,
+            session_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    async fn revoke_session(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE sessions SET revoked_at = now()
+            WHERE session_id = $1 AND revoked_at IS NULL
+            "#,
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE sessions SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_refresh_token(&self, refresh_token: RefreshToken) -> Result<RefreshToken> {
+        let refresh_token = sqlx::query_as!(
+            RefreshToken,
+            r#"
+            INSERT INTO refresh_tokens (
+                token_id,
+                session_id,
+                token_hash,
+                created_at
+            ) VALUES (
+                $1, $2, $3, $4
+            ) RETURNING *
+            "#,
+            refresh_token.token_id,
+            refresh_token.session_id,
+            refresh_token.token_hash,
+            refresh_token.created_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(refresh_token)
+    }
+
+    async fn get_refresh_token_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>> {
+        let refresh_token = sqlx::query_as!(
+            RefreshToken,
+            r#"
+            SELECT * FROM refresh_tokens WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(refresh_token)
+    }
+
+    async fn use_refresh_token(&self, token_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE refresh_tokens SET used_at = now()
+            WHERE token_id = $1 AND used_at IS NULL
+            "#,
+            token_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,251 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::Result,
+    two_factor::{LoginChallenge, RecoveryCode, TotpFactor},
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait TwoFactorRepository {
+    async fn get_totp_factor(&self, user_id: Uuid) -> Result<Option<TotpFactor>>;
+    /// replaces the factor the user was enrolling
+    async fn save_totp_factor(&self, factor: TotpFactor) -> Result<TotpFactor>;
+    /// false when a code of the step or of a later one was already accepted
+    async fn use_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool>;
+    /// enables the factor and replaces the recovery codes of the user
+    async fn enable_totp_factor(&self, user_id: Uuid, codes: Vec<RecoveryCode>) -> Result<()>;
+    /// removes the factor and the recovery codes of the user
+    async fn delete_totp_factor(&self, user_id: Uuid) -> Result<()>;
+    /// false when the user has no such code left
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool>;
+    async fn create_login_challenge(&self, challenge: LoginChallenge) -> Result<LoginChallenge>;
+    async fn get_login_challenge_by_hash(&self, token_hash: &str)
+        -> Result<Option<LoginChallenge>>;
+    async fn fail_login_challenge(&self, challenge_id: Uuid) -> Result<()>;
+    /// false when the challenge was already used, even by a concurrent request
+    async fn use_login_challenge(&self, challenge_id: Uuid) -> Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl TwoFactorRepository for SqlxRepository {
+    async fn get_totp_factor(&self, user_id: Uuid) -> Result<Option<TotpFactor>> {
+        let factor = sqlx::query_as!(
+            TotpFactor,
+            r#"
+            SELECT * FROM totp_factors WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(factor)
+    }
+
+    async fn save_totp_factor(&self, factor: TotpFactor) -> Result<TotpFactor> {
+        let factor = sqlx::query_as!(
+            TotpFactor,
+            r#"
+            INSERT INTO totp_factors (
+                user_id,
+                secret,
+                created_at
+            ) VALUES (
+                $1, $2, $3
+            )
+            ON CONFLICT (user_id) DO UPDATE SET
+                secret = EXCLUDED.secret,
+                created_at = EXCLUDED.created_at,
+                enabled_at = NULL,
+                last_used_step = NULL
+            RETURNING *
+            "#,
+            factor.user_id,
+            factor.secret,
+            factor.created_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(factor)
+    }
+
+    async fn use_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE totp_factors SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+            user_id,
+            step
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn enable_totp_factor(&self, user_id: Uuid, codes: Vec<RecoveryCode>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE totp_factors SET enabled_at = COALESCE(enabled_at, now())
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM recovery_codes WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for code in codes {
+            sqlx::query!(
+                r#"
+                INSERT INTO recovery_codes (
+                    code_id,
+                    user_id,
+                    code_hash,
+                    created_at
+                ) VALUES (
+                    $1, $2, $3, $4
+                )
+                "#,
+                code.code_id,
+                code.user_id,
+                code.code_hash,
+                code.created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_totp_factor(&self, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM recovery_codes WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM totp_factors WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE recovery_codes SET used_at = now()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            code_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() >= 1)
+    }
+
+    async fn create_login_challenge(&self, challenge: LoginChallenge) -> Result<LoginChallenge> {
+        let challenge = sqlx::query_as!(
+            LoginChallenge,
+            r#"
+            INSERT INTO login_challenges (
+                challenge_id,
+                user_id,
+                token_hash,
+                created_at,
+                expires_at
+            ) VALUES (
+                $1, $2, $3, $4, $5
+            ) RETURNING *
+            "#,
+            challenge.challenge_id,
+            challenge.user_id,
+            challenge.token_hash,
+            challenge.created_at,
+            challenge.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    async fn get_login_challenge_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<LoginChallenge>> {
+        let challenge = sqlx::query_as!(
+            LoginChallenge,
+            r#"
+            SELECT * FROM login_challenges WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    async fn fail_login_challenge(&self, challenge_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE login_challenges SET failed_attempts = failed_attempts + 1
+            WHERE challenge_id = $1
+            "#,
+            challenge_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn use_login_challenge(&self, challenge_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE login_challenges SET used_at = now()
+            WHERE challenge_id = $1 AND used_at IS NULL
+            "#,
+            challenge_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,136 @@
+use mockall::automock;
+
+use crate::domains::{errors::Result, users::User};
+
+use super::SqlxRepository;
+use uuid::Uuid;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait UserRepository {
+    async fn create_user(&self, user: User) -> Result<User>;
+    async fn get_user_by_id(&self, user_id: Uuid) -> Result<Option<User>>;
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>>;
+    /// none when there is no such user, verifying twice keeps the first time
+    async fn verify_email(&self, user_id: Uuid) -> Result<Option<User>>;
+    async fn update_password(&self, user_id: Uuid, password_hash: &str) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl UserRepository for SqlxRepository {
+    async fn create_user(&self, user: User) -> Result<User> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            INSERT INTO users (
+                user_id,
+                email,
+                name,
+                password_hash,
+                created_at
+            ) VALUES (
+                $1, $2, $3, $4, $5
+            ) RETURNING
+                user_id,
+                email,
+                name,
+                password_hash,
+                created_at,
+                updated_at,
+                email_verified_at
+            "#,
+            user.user_id,
+            user.email,
+            user.name,
+            user.password_hash,
+            user.created_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn get_user_by_id(&self, user_id: Uuid) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                user_id,
+                email,
+                name,
+                password_hash,
+                created_at,
+                updated_at,
+                email_verified_at
+            FROM users
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                user_id,
+                email,
+                name,
+                password_hash,
+                created_at,
+                updated_at,
+                email_verified_at
+            FROM users
+            WHERE email = $1
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn verify_email(&self, user_id: Uuid) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET email_verified_at = COALESCE(email_verified_at, now())
+            WHERE user_id = $1
+            RETURNING
+                user_id,
+                email,
+                name,
+                password_hash,
+                created_at,
+                updated_at,
+                email_verified_at
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn update_password(&self, user_id: Uuid, password_hash: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE users SET password_hash = $2, updated_at = now() WHERE user_id = $1
+            "#,
+            user_id,
+            password_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use auth_layer::VerifyApiKey;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{api_keys::CreateApiKey, errors::Result},
+    handlers::Handler,
+};
+
+use super::BearerToken;
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/auth/api-keys",
+        Router::new()
+            .route("/", get(list_api_keys))
+            .route("/", post(create_api_key))
+            .route("/verify", post(verify_api_key))
+            .route("/:key_id", delete(revoke_api_key)),
+    )
+}
+
+async fn list_api_keys(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let api_keys = handler.list_api_keys(user.user_id).await?;
+
+    Ok(Json(api_keys))
+}
+
+async fn create_api_key(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+    Json(payload): Json<CreateApiKey>,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let api_key = handler.create_api_key(user.user_id, payload).await?;
+
+    Ok((StatusCode::CREATED, Json(api_key)))
+}
+
+async fn revoke_api_key(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+    Path(key_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let api_key = handler.revoke_api_key(user.user_id, key_id).await?;
+
+    Ok(Json(api_key))
+}
+
+/// owner and scopes of a key, asked by the services the key is presented to
+async fn verify_api_key(
+    State(handler): State<Handler>,
+    Json(payload): Json<VerifyApiKey>,
+) -> Result<impl IntoResponse> {
+    let api_key = handler.verify_api_key(&payload.api_key).await?;
+
+    Ok(Json(api_key))
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+
+use crate::{
+    domains::{
+        errors::Result,
+        sessions::RefreshTokens,
+        two_factor::LoginTwoFactor,
+        users::{LoginUser, RegisterUser},
+    },
+    handlers::Handler,
+};
+
+use super::{BearerToken, ClientIp};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/auth",
+        Router::new()
+            .route("/register", post(register))
+            .route("/login", post(login))
+            .route("/login/two-factor", post(login_two_factor))
+            .route("/refresh", post(refresh))
+            .route("/logout", post(logout))
+            .route("/me", get(me))
+            .route("/jwks", get(jwks)),
+    )
+}
+
+async fn register(
+    State(handler): State<Handler>,
+    ClientIp(ip_address): ClientIp,
+    Json(payload): Json<RegisterUser>,
+) -> Result<impl IntoResponse> {
+    let user = handler.register(payload, ip_address).await?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+async fn login(
+    State(handler): State<Handler>,
+    ClientIp(ip_address): ClientIp,
+    Json(payload): Json<LoginUser>,
+) -> Result<impl IntoResponse> {
+    let response = handler.login(payload, ip_address).await?;
+
+    Ok(Json(response))
+}
+
+async fn login_two_factor(
+    State(handler): State<Handler>,
+    ClientIp(ip_address): ClientIp,
+    Json(payload): Json<LoginTwoFactor>,
+) -> Result<impl IntoResponse> {
+    let response = handler.login_two_factor(payload, ip_address).await?;
+
+    Ok(Json(response))
+}
+
+async fn me(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    Ok(Json(user))
+}
+
+async fn refresh(
+    State(handler): State<Handler>,
+    Json(payload): Json<RefreshTokens>,
+) -> Result<impl IntoResponse> {
+    let tokens = handler.refresh(&payload.refresh_token).await?;
+
+    Ok(Json(tokens))
+}
+
+async fn logout(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+) -> Result<impl IntoResponse> {
+    handler.logout(&token).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// public keys of the access tokens, services cache them to verify tokens on their own
+async fn jwks(State(handler): State<Handler>) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(handler.jwks().clone()),
+    )
+}
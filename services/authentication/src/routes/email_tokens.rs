@@ -0,0 +1,62 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+
+use crate::{
+    domains::{
+        email_tokens::{ForgotPassword, ResetPassword, VerifyEmail},
+        errors::Result,
+    },
+    handlers::Handler,
+};
+
+use super::{BearerToken, ClientIp};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/auth",
+        Router::new()
+            .route("/verify-email", post(verify_email))
+            .route("/verify-email/resend", post(resend_verification_email))
+            .route("/password/forgot", post(forgot_password))
+            .route("/password/reset", post(reset_password)),
+    )
+}
+
+async fn verify_email(
+    State(handler): State<Handler>,
+    Json(payload): Json<VerifyEmail>,
+) -> Result<impl IntoResponse> {
+    let user = handler.verify_email(payload).await?;
+
+    Ok(Json(user))
+}
+
+async fn resend_verification_email(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    handler.resend_verification_email(&user).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// accepted for any email, registered or not
+async fn forgot_password(
+    State(handler): State<Handler>,
+    ClientIp(ip_address): ClientIp,
+    Json(payload): Json<ForgotPassword>,
+) -> Result<impl IntoResponse> {
+    handler.forgot_password(payload, ip_address).await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn reset_password(
+    State(handler): State<Handler>,
+    Json(payload): Json<ResetPassword>,
+) -> Result<impl IntoResponse> {
+    handler.reset_password(payload).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,21 @@
+use axum::{extract::State, response::IntoResponse, routing::get, Json, Router};
+
+use crate::{domains::errors::Result, handlers::Handler};
+
+use super::BearerToken;
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().route("/auth/lockouts", get(get_lockout_events))
+}
+
+/// lockouts of the account by failed logins, newest first
+async fn get_lockout_events(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let lockout_events = handler.get_lockout_events(&user).await?;
+
+    Ok(Json(lockout_events))
+}
@@ -0,0 +1,179 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{domains::errors::Error, handlers::Handler};
+
+pub mod api_keys;
+pub mod auth;
+pub mod email_tokens;
+pub mod login_throttles;
+pub mod two_factor;
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new()
+        .merge(auth::configure_routes())
+        .merge(email_tokens::configure_routes())
+        .merge(login_throttles::configure_routes())
+        .merge(api_keys::configure_routes())
+        .merge(two_factor::configure_routes())
+}
+
+/// Envelope returned by every failed request
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorResponse {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::DatabaseError(_) | Self::PasswordHashing(_) | Self::TokenSigning(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            Self::InvalidEmail | Self::WeakPassword | Self::InvalidApiKey(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::EmailAlreadyRegistered
+            | Self::EmailAlreadyVerified
+            | Self::TwoFactorAlreadyEnabled
+            | Self::TwoFactorNotEnrolled
+            | Self::TwoFactorNotEnabled => StatusCode::CONFLICT,
+            Self::ApiKeyNotFound(_) => StatusCode::NOT_FOUND,
+            Self::TooManyAttempts(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::MailFailed(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::InvalidCredentials
+            | Self::MissingToken
+            | Self::InvalidToken
+            | Self::RefreshTokenReused
+            | Self::InvalidTwoFactorCode => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::DatabaseError(_) | Self::PasswordHashing(_) | Self::TokenSigning(_) => {
+                "Internal server error.".into()
+            }
+            Self::InvalidEmail => "Email address is not valid.".into(),
+            Self::WeakPassword => "Password must have between 8 and 128 characters.".into(),
+            Self::EmailAlreadyRegistered => "Email is already registered.".into(),
+            Self::EmailAlreadyVerified => "Email is already verified.".into(),
+            Self::InvalidCredentials => "Email or password is incorrect.".into(),
+            Self::TooManyAttempts(_) => "Too many failed logins, try again later.".into(),
+            Self::MissingToken => "Authorization header with a bearer token is required.".into(),
+            Self::InvalidToken => "Token is invalid or has expired.".into(),
+            Self::RefreshTokenReused => {
+                "Refresh token has been already used, the session has been revoked.".into()
+            }
+            Self::InvalidApiKey(reason) => format!("Api key is not valid: {reason}."),
+            Self::ApiKeyNotFound(_) => "Api key not found.".into(),
+            Self::TwoFactorAlreadyEnabled => "Two factor authentication is already enabled.".into(),
+            Self::TwoFactorNotEnrolled => {
+                "Two factor authentication must be enrolled before it is enabled.".into()
+            }
+            Self::TwoFactorNotEnabled => "Two factor authentication is not enabled.".into(),
+            Self::InvalidTwoFactorCode => "Two factor code is incorrect or already used.".into(),
+            Self::MailFailed(_) => "Mail could not be sent, try again later.".into(),
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+
+        // the body only tells an internal error happened, its cause goes to the logs
+        if status.is_server_error() {
+            match std::error::Error::source(&self) {
+                Some(source) => log::error!("{status} {}: {self}: {source}", self.code()),
+                None => log::error!("{status} {}: {self}", self.code()),
+            }
+        }
+
+        let body = ErrorResponse {
+            code: self.code(),
+            message: self.message(),
+        };
+
+        let mut response = (status, Json(body)).into_response();
+
+        if let Self::TooManyAttempts(until) = self {
+            let seconds = (until - Utc::now()).num_seconds().max(1);
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, seconds.into());
+        }
+
+        if status == StatusCode::UNAUTHORIZED {
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                header::HeaderValue::from_static("Bearer"),
+            );
+        }
+
+        response
+    }
+}
+
+/// Token of the `Authorization: Bearer` header
+pub struct BearerToken(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for BearerToken {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| BearerToken(token.trim().to_string()))
+            .ok_or(Error::MissingToken)
+    }
+}
+
+/// Present when the service runs behind a proxy appending the client to `X-Forwarded-For`
+#[derive(Debug, Clone, Copy)]
+pub struct TrustForwardedFor;
+
+/// Address of the client, the last one appended to `X-Forwarded-For` when the proxy is trusted,
+/// a client can send the header with any addresses it likes
+pub struct ClientIp(pub IpAddr);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    /// only without the connection info, which the server always adds
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let forwarded = parts
+            .extensions
+            .get::<TrustForwardedFor>()
+            .and_then(|_| parts.headers.get_all("x-forwarded-for").iter().next_back())
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok());
+
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        forwarded
+            .or(peer)
+            .map(ClientIp)
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
@@ -0,0 +1,72 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+
+use crate::{
+    domains::{
+        errors::Result,
+        two_factor::{DisableTwoFactor, TwoFactorCode},
+    },
+    handlers::Handler,
+};
+
+use super::{BearerToken, ClientIp};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/auth/two-factor",
+        Router::new()
+            .route("/totp", post(enroll_totp))
+            .route("/enable", post(enable_totp))
+            .route("/disable", post(disable_totp))
+            .route("/recovery-codes", post(regenerate_recovery_codes)),
+    )
+}
+
+async fn enroll_totp(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let enrollment = handler.enroll_totp(&user).await?;
+
+    Ok(Json(enrollment))
+}
+
+async fn enable_totp(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+    Json(payload): Json<TwoFactorCode>,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let recovery_codes = handler.enable_totp(user.user_id, payload).await?;
+
+    Ok(Json(recovery_codes))
+}
+
+async fn disable_totp(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+    ClientIp(ip_address): ClientIp,
+    Json(payload): Json<DisableTwoFactor>,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    handler.disable_totp(&user, payload, ip_address).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn regenerate_recovery_codes(
+    State(handler): State<Handler>,
+    BearerToken(token): BearerToken,
+    Json(payload): Json<TwoFactorCode>,
+) -> Result<impl IntoResponse> {
+    let user = handler.authenticate(&token).await?;
+
+    let recovery_codes = handler
+        .regenerate_recovery_codes(user.user_id, payload)
+        .await?;
+
+    Ok(Json(recovery_codes))
+}
@@ -1,9 +1,18 @@
+use auth_layer::BearerToken;
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use uuid::Uuid;
 
+/// User the budget is called on behalf of, with the workspace they picked
+#[derive(Debug, Clone)]
+pub struct BudgetCaller {
+    pub token: BearerToken,
+    /// none lets the budget service use the oldest workspace of the user
+    pub workspace_id: Option<Uuid>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
@@ -12,7 +21,7 @@ pub struct Transaction {
     pub description: String,
     pub value: BigDecimal,
     pub due_date: NaiveDate,
-    pub category: Category,
+    pub category_id: Uuid,
     pub account_id: Uuid,
     pub status: TransactionStatus,
     pub created_at: DateTime<Utc>,
@@ -22,6 +31,40 @@ pub struct Transaction {
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub account_id: Uuid,
+    pub institution_id: Uuid,
+    /// filled by the bff from the institutions list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub institution: Option<Institution>,
+    pub owner: String,
+    pub account_type: AccountType,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Institution {
+    pub institution_id: Uuid,
+    pub name: String,
+    pub code: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountType {
+    Debit,
+    Credit,
+    Hybrid,
+}
+
 #[derive(Debug, Serialize, Deserialize, Type, Clone, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[sqlx(type_name = "movement_type", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -38,26 +81,3 @@ pub enum TransactionStatus {
     Canceled,
     Completed,
 }
-
-#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-#[sqlx(type_name = "category", rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Category {
-    Food,
-    Home,
-    Education,
-    Entertainment,
-    Transport,
-    Healthy,
-    Salary,
-    Utilities,
-    Insurance,
-    Savings,
-    DebtPayments,
-    ChildCare,
-    Gifts,
-    Subscriptions,
-    Travel,
-    Clothing,
-    Maintenance,
-}
@@ -4,6 +4,18 @@ use thiserror::Error;
 pub enum Error {
     #[error("Reqwest error")]
     ReqwestError(#[from] reqwest::Error),
+    #[error("gRPC error")]
+    GrpcError(Box<tonic::Status>),
+    #[error("Invalid budget response: {0}")]
+    InvalidBudgetResponse(String),
+    #[error("Invalid workspace header {0:?}")]
+    InvalidWorkspaceHeader(String),
+}
+
+impl From<tonic::Status> for Error {
+    fn from(status: tonic::Status) -> Self {
+        Self::GrpcError(Box::new(status))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
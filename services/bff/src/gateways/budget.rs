@@ -1,4 +1,32 @@
-use crate::domains::{budget::Transaction, errors::Result};
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use tonic::{
+    transport::{Channel, Endpoint},
+    Request,
+};
+use uuid::Uuid;
+
+use crate::domains::{
+    budget::{
+        Account, AccountType, BudgetCaller, Institution, MovementType, Transaction,
+        TransactionStatus,
+    },
+    errors::{Error, Result},
+};
+
+use proto::{
+    accounts_client::AccountsClient, institutions_client::InstitutionsClient,
+    transactions_client::TransactionsClient,
+};
+
+pub mod proto {
+    tonic::include_proto!("budget.v1");
+}
+
+/// header and metadata entry selecting the workspace in the budget service
+const WORKSPACE_HEADER: &str = "x-workspace-id";
 
 pub struct ApiBudgetGateway {
     http_client: reqwest::Client,
@@ -12,22 +40,411 @@ impl Default for ApiBudgetGateway {
     }
 }
 
+impl ApiBudgetGateway {
+    /// request on behalf of the caller, in the workspace they picked
+    fn get(&self, url: &str, caller: &BudgetCaller) -> reqwest::RequestBuilder {
+        let request = self.http_client.get(url).bearer_auth(&caller.token.0);
+
+        match caller.workspace_id {
+            Some(workspace_id) => request.header(WORKSPACE_HEADER, workspace_id.to_string()),
+            None => request,
+        }
+    }
+}
+
+/// Budget service, called on behalf of the user in the workspace they picked
 #[async_trait::async_trait]
 pub trait BudgetGateway {
-    async fn transactions(&self) -> Result<Vec<Transaction>>;
+    async fn transactions(&self, caller: &BudgetCaller) -> Result<Vec<Transaction>>;
+    async fn accounts(&self, caller: &BudgetCaller) -> Result<Vec<Account>>;
+    async fn institutions(&self, caller: &BudgetCaller) -> Result<Vec<Institution>>;
 }
 
 #[async_trait::async_trait]
 impl BudgetGateway for ApiBudgetGateway {
-    async fn transactions(&self) -> Result<Vec<Transaction>> {
+    async fn transactions(&self, caller: &BudgetCaller) -> Result<Vec<Transaction>> {
         let transactions = self
-            .http_client
-            .get("http://localhost:8000/transactions")
+            .get("http://localhost:8000/transactions", caller)
             .send()
             .await?
+            .error_for_status()?
             .json::<Vec<Transaction>>()
             .await?;
 
         Ok(transactions)
     }
+
+    async fn accounts(&self, caller: &BudgetCaller) -> Result<Vec<Account>> {
+        let accounts = self
+            .get("http://localhost:8000/accounts", caller)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<Account>>()
+            .await?;
+
+        Ok(accounts)
+    }
+
+    async fn institutions(&self, caller: &BudgetCaller) -> Result<Vec<Institution>> {
+        let institutions = self
+            .get("http://localhost:8000/institutions", caller)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<Institution>>()
+            .await?;
+
+        Ok(institutions)
+    }
+}
+
+/// Budget service over gRPC, the channel connects on the first call
+#[derive(Clone)]
+pub struct GrpcBudgetGateway {
+    channel: Channel,
+}
+
+impl GrpcBudgetGateway {
+    pub fn new(url: &str) -> std::result::Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(url.to_string())?.connect_lazy();
+
+        Ok(Self { channel })
+    }
+}
+
+/// request carrying the token and workspace of the caller, like the headers of the HTTP gateway
+fn authorized<T>(message: T, caller: &BudgetCaller) -> Result<Request<T>> {
+    let mut request = Request::new(message);
+    let value = format!("Bearer {}", caller.token.0)
+        .parse()
+        .map_err(|_| tonic::Status::unauthenticated("Token is not a valid metadata value"))?;
+
+    request.metadata_mut().insert("authorization", value);
+
+    if let Some(workspace_id) = caller.workspace_id {
+        let value = workspace_id.to_string().parse().map_err(|_| {
+            tonic::Status::invalid_argument("Workspace is not a valid metadata value")
+        })?;
+
+        request.metadata_mut().insert(WORKSPACE_HEADER, value);
+    }
+
+    Ok(request)
+}
+
+fn invalid(field: &str, value: &str) -> Error {
+    Error::InvalidBudgetResponse(format!("{field} has an unexpected value {value:?}"))
+}
+
+fn parse_id(field: &str, value: &str) -> Result<Uuid> {
+    value.parse().map_err(|_| invalid(field, value))
+}
+
+fn parse_timestamp(field: &str, value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| invalid(field, value))
+}
+
+fn parse_optional_timestamp(field: &str, value: Option<String>) -> Result<Option<DateTime<Utc>>> {
+    value
+        .map(|value| parse_timestamp(field, &value))
+        .transpose()
+}
+
+impl TryFrom<proto::Transaction> for Transaction {
+    type Error = Error;
+
+    fn try_from(value: proto::Transaction) -> Result<Self> {
+        let movement_type = match value.movement_type() {
+            proto::MovementType::Income => MovementType::Income,
+            proto::MovementType::Expense => MovementType::Expense,
+            proto::MovementType::Unspecified => {
+                return Err(invalid("movement_type", &value.movement_type.to_string()))
+            }
+        };
+        let status = match value.status() {
+            proto::TransactionStatus::Pending => TransactionStatus::Pending,
+            proto::TransactionStatus::Canceled => TransactionStatus::Canceled,
+            proto::TransactionStatus::Completed => TransactionStatus::Completed,
+            proto::TransactionStatus::Unspecified => {
+                return Err(invalid("status", &value.status.to_string()))
+            }
+        };
+
+        Ok(Transaction {
+            transaction_id: parse_id("transaction_id", &value.transaction_id)?,
+            movement_type,
+            description: value.description,
+            value: BigDecimal::from_str(&value.value)
+                .map_err(|_| invalid("value", &value.value))?,
+            due_date: NaiveDate::parse_from_str(&value.due_date, "%Y-%m-%d")
+                .map_err(|_| invalid("due_date", &value.due_date))?,
+            category_id: parse_id("category_id", &value.category_id)?,
+            account_id: parse_id("account_id", &value.account_id)?,
+            status,
+            created_at: parse_timestamp("created_at", &value.created_at)?,
+            updated_at: parse_optional_timestamp("updated_at", value.updated_at)?,
+            deleted_at: parse_optional_timestamp("deleted_at", value.deleted_at)?,
+        })
+    }
+}
+
+impl TryFrom<proto::Account> for Account {
+    type Error = Error;
+
+    fn try_from(value: proto::Account) -> Result<Self> {
+        let account_type = match value.account_type() {
+            proto::AccountType::Debit => AccountType::Debit,
+            proto::AccountType::Credit => AccountType::Credit,
+            proto::AccountType::Hybrid => AccountType::Hybrid,
+            proto::AccountType::Unspecified => {
+                return Err(invalid("account_type", &value.account_type.to_string()))
+            }
+        };
+
+        Ok(Account {
+            account_id: parse_id("account_id", &value.account_id)?,
+            institution_id: parse_id("institution_id", &value.institution_id)?,
+            institution: None,
+            owner: value.owner,
+            account_type,
+            created_at: parse_timestamp("created_at", &value.created_at)?,
+            updated_at: parse_optional_timestamp("updated_at", value.updated_at)?,
+            deleted_at: parse_optional_timestamp("deleted_at", value.deleted_at)?,
+        })
+    }
+}
+
+impl TryFrom<proto::Institution> for Institution {
+    type Error = Error;
+
+    fn try_from(value: proto::Institution) -> Result<Self> {
+        Ok(Institution {
+            institution_id: parse_id("institution_id", &value.institution_id)?,
+            name: value.name,
+            code: value.code,
+            logo_url: value.logo_url,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl BudgetGateway for GrpcBudgetGateway {
+    async fn transactions(&self, caller: &BudgetCaller) -> Result<Vec<Transaction>> {
+        let request = authorized(proto::ListTransactionsRequest::default(), caller)?;
+
+        TransactionsClient::new(self.channel.clone())
+            .list_transactions(request)
+            .await?
+            .into_inner()
+            .transactions
+            .into_iter()
+            .map(Transaction::try_from)
+            .collect()
+    }
+
+    async fn accounts(&self, caller: &BudgetCaller) -> Result<Vec<Account>> {
+        let request = authorized(proto::ListAccountsRequest {}, caller)?;
+
+        AccountsClient::new(self.channel.clone())
+            .list_accounts(request)
+            .await?
+            .into_inner()
+            .accounts
+            .into_iter()
+            .map(Account::try_from)
+            .collect()
+    }
+
+    async fn institutions(&self, caller: &BudgetCaller) -> Result<Vec<Institution>> {
+        let request = authorized(proto::ListInstitutionsRequest {}, caller)?;
+
+        InstitutionsClient::new(self.channel.clone())
+            .list_institutions(request)
+            .await?
+            .into_inner()
+            .institutions
+            .into_iter()
+            .map(Institution::try_from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use auth_layer::BearerToken;
+
+    use super::*;
+
+    /// transaction written the way the budget service converts its records
+    fn proto_transaction(created_at: DateTime<Utc>) -> proto::Transaction {
+        proto::Transaction {
+            transaction_id: Uuid::new_v4().to_string(),
+            financial_plan_id: Uuid::new_v4().to_string(),
+            movement_type: proto::MovementType::Expense as i32,
+            description: String::from("Groceries"),
+            value: BigDecimal::from_str("-42.90").unwrap().to_string(),
+            due_date: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap().to_string(),
+            category_id: Uuid::new_v4().to_string(),
+            account_id: Uuid::new_v4().to_string(),
+            status: proto::TransactionStatus::Completed as i32,
+            created_at: created_at.to_rfc3339(),
+            updated_at: Some(created_at.to_rfc3339()),
+            deleted_at: None,
+        }
+    }
+
+    fn proto_account() -> proto::Account {
+        proto::Account {
+            account_id: Uuid::new_v4().to_string(),
+            institution_id: Uuid::new_v4().to_string(),
+            owner: String::from("Ana"),
+            account_type: proto::AccountType::Hybrid as i32,
+            created_at: Utc::now().to_rfc3339(),
+            updated_at: None,
+            deleted_at: Some(Utc::now().to_rfc3339()),
+        }
+    }
+
+    fn is_invalid(result: Result<impl std::fmt::Debug>, field: &str) -> bool {
+        matches!(result, Err(Error::InvalidBudgetResponse(message)) if message.starts_with(field))
+    }
+
+    #[test]
+    fn should_read_the_transactions_of_the_budget() {
+        let created_at = Utc::now();
+        let value = proto_transaction(created_at);
+
+        let transaction = Transaction::try_from(value.clone()).unwrap();
+
+        assert_eq!(transaction.transaction_id.to_string(), value.transaction_id);
+        assert!(matches!(transaction.movement_type, MovementType::Expense));
+        assert_eq!(transaction.value, BigDecimal::from_str("-42.90").unwrap());
+        assert_eq!(
+            transaction.due_date,
+            NaiveDate::from_ymd_opt(2026, 3, 10).unwrap()
+        );
+        assert_eq!(transaction.status, TransactionStatus::Completed);
+        assert_eq!(transaction.created_at, created_at);
+        assert_eq!(transaction.updated_at, Some(created_at));
+        assert_eq!(transaction.deleted_at, None);
+    }
+
+    #[test]
+    fn should_refuse_transactions_it_cannot_read() {
+        let value = || proto_transaction(Utc::now());
+
+        let cases = [
+            (
+                "movement_type",
+                proto::Transaction {
+                    movement_type: proto::MovementType::Unspecified as i32,
+                    ..value()
+                },
+            ),
+            (
+                "status",
+                proto::Transaction {
+                    status: 42,
+                    ..value()
+                },
+            ),
+            (
+                "value",
+                proto::Transaction {
+                    value: String::from("12,50"),
+                    ..value()
+                },
+            ),
+            (
+                "due_date",
+                proto::Transaction {
+                    due_date: String::from("10/03/2026"),
+                    ..value()
+                },
+            ),
+            (
+                "category_id",
+                proto::Transaction {
+                    category_id: String::from("food"),
+                    ..value()
+                },
+            ),
+            (
+                "updated_at",
+                proto::Transaction {
+                    updated_at: Some(String::from("2026-03-10")),
+                    ..value()
+                },
+            ),
+        ];
+
+        for (field, value) in cases {
+            assert!(is_invalid(Transaction::try_from(value), field), "{field}");
+        }
+    }
+
+    #[test]
+    fn should_read_the_accounts_and_institutions_of_the_budget() {
+        let value = proto_account();
+
+        let account = Account::try_from(value.clone()).unwrap();
+
+        assert_eq!(account.institution_id.to_string(), value.institution_id);
+        assert!(matches!(account.account_type, AccountType::Hybrid));
+        assert!(account.institution.is_none());
+        assert!(account.deleted_at.is_some());
+
+        let unspecified = proto::Account {
+            account_type: proto::AccountType::Unspecified as i32,
+            ..proto_account()
+        };
+        assert!(is_invalid(Account::try_from(unspecified), "account_type"));
+
+        let institution = proto::Institution {
+            institution_id: Uuid::new_v4().to_string(),
+            name: String::from("Nubank"),
+            code: Some(String::from("260")),
+            logo_url: None,
+            created_at: Utc::now().to_rfc3339(),
+            updated_at: None,
+            deleted_at: None,
+        };
+
+        let read = Institution::try_from(institution.clone()).unwrap();
+        assert_eq!(read.institution_id.to_string(), institution.institution_id);
+        assert_eq!(read.code.as_deref(), Some("260"));
+
+        let invalid = proto::Institution {
+            institution_id: String::new(),
+            ..institution
+        };
+        assert!(is_invalid(Institution::try_from(invalid), "institution_id"));
+    }
+
+    fn caller(workspace_id: Option<Uuid>) -> BudgetCaller {
+        BudgetCaller {
+            token: BearerToken(String::from("token")),
+            workspace_id,
+        }
+    }
+
+    #[test]
+    fn should_forward_the_workspace_of_the_caller() {
+        let workspace_id = Uuid::new_v4();
+
+        let request = authorized((), &caller(Some(workspace_id))).unwrap();
+        let metadata = request.metadata();
+
+        assert_eq!(metadata.get("authorization").unwrap(), "Bearer token");
+        assert_eq!(
+            metadata.get(WORKSPACE_HEADER).unwrap(),
+            workspace_id.to_string().as_str()
+        );
+
+        let request = authorized((), &caller(None)).unwrap();
+        assert!(request.metadata().get(WORKSPACE_HEADER).is_none());
+    }
 }
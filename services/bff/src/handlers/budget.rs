@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use crate::{
-    domains::{budget::Transaction, errors::Result},
+    domains::{
+        budget::{Account, BudgetCaller, Institution, Transaction},
+        errors::Result,
+    },
     gateways::budget::BudgetGateway,
 };
 
@@ -13,14 +16,36 @@ pub struct BudgetHandlerImpl {
 
 #[async_trait::async_trait]
 pub trait BudgetHandler {
-    async fn get_transactions(&self) -> Result<Vec<Transaction>>;
+    async fn get_transactions(&self, caller: &BudgetCaller) -> Result<Vec<Transaction>>;
+    async fn get_accounts(&self, caller: &BudgetCaller) -> Result<Vec<Account>>;
+    async fn get_institutions(&self, caller: &BudgetCaller) -> Result<Vec<Institution>>;
 }
 
 #[async_trait::async_trait]
 impl BudgetHandler for BudgetHandlerImpl {
-    async fn get_transactions(&self) -> Result<Vec<Transaction>> {
-        let transaction = self.budget_gateway.transactions().await?;
+    async fn get_transactions(&self, caller: &BudgetCaller) -> Result<Vec<Transaction>> {
+        let transaction = self.budget_gateway.transactions(caller).await?;
 
         Ok(transaction)
     }
+
+    async fn get_accounts(&self, caller: &BudgetCaller) -> Result<Vec<Account>> {
+        let mut accounts = self.budget_gateway.accounts(caller).await?;
+        let institutions = self.budget_gateway.institutions(caller).await?;
+
+        for account in accounts.iter_mut() {
+            account.institution = institutions
+                .iter()
+                .find(|i| i.institution_id == account.institution_id)
+                .cloned();
+        }
+
+        Ok(accounts)
+    }
+
+    async fn get_institutions(&self, caller: &BudgetCaller) -> Result<Vec<Institution>> {
+        let institutions = self.budget_gateway.institutions(caller).await?;
+
+        Ok(institutions)
+    }
 }
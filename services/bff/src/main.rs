@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use gateways::budget::ApiBudgetGateway;
+use auth_layer::{ApiKeyVerifier, AuthLayer, JwksVerifier};
+use gateways::budget::{ApiBudgetGateway, BudgetGateway, GrpcBudgetGateway};
 use handlers::budget::BudgetHandlerImpl;
 use routes::AppState;
 pub mod domains;
@@ -8,8 +9,19 @@ pub mod gateways;
 pub mod handlers;
 pub mod routes;
 
+/// BUDGET_GATEWAY picks how the budget is called, http by default or grpc at BUDGET_GRPC_URL
 fn create_budget_handler() -> BudgetHandlerImpl {
-    let budget_gateway = Arc::new(ApiBudgetGateway::default());
+    let budget_gateway: Arc<dyn BudgetGateway + Send + Sync> =
+        match std::env::var("BUDGET_GATEWAY").as_deref() {
+            Ok("grpc") => {
+                let url = std::env::var("BUDGET_GRPC_URL")
+                    .unwrap_or_else(|_| String::from("http://localhost:50051"));
+
+                Arc::new(GrpcBudgetGateway::new(&url).expect("Invalid BUDGET_GRPC_URL"))
+            }
+            Ok("http") | Err(_) => Arc::new(ApiBudgetGateway::default()),
+            Ok(gateway) => panic!("Unknown budget gateway {gateway}, expected http or grpc"),
+        };
 
     BudgetHandlerImpl { budget_gateway }
 }
@@ -24,7 +36,13 @@ async fn main() {
         budget_handler: Arc::new(budget),
     };
 
-    let router = routes::configure_services().with_state(app_state);
+    let auth = AuthLayer::new(Arc::new(JwksVerifier::from_env()))
+        .api_keys(Arc::new(ApiKeyVerifier::from_env()))
+        .public_path("/health");
+
+    let router = routes::configure_services()
+        .with_state(app_state)
+        .layer(auth);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3001").await.unwrap();
 
@@ -1,15 +1,84 @@
-use axum::{extract::State, response::IntoResponse, routing::get, Json, Router};
+use auth_layer::BearerToken;
+use axum::{
+    extract::{FromRequestParts, State},
+    http::request::Parts,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
 
-use crate::domains::errors::Result;
+use crate::domains::{
+    budget::BudgetCaller,
+    errors::{Error, Result},
+};
 
 use super::AppState;
 
+/// header selecting the workspace, forwarded to the budget service
+const WORKSPACE_HEADER: &str = "x-workspace-id";
+
 pub(super) fn configure_router() -> Router<AppState> {
-    Router::new().route("/budget/transactions", get(get_transactions))
+    Router::new()
+        .route("/budget/transactions", get(get_transactions))
+        .route("/budget/accounts", get(get_accounts))
+        .route("/budget/institutions", get(get_institutions))
 }
 
-async fn get_transactions(State(handler): State<AppState>) -> Result<impl IntoResponse> {
-    let transactions = handler.budget_handler.get_transactions().await?;
+async fn get_transactions(
+    State(handler): State<AppState>,
+    caller: BudgetCaller,
+) -> Result<impl IntoResponse> {
+    let transactions = handler.budget_handler.get_transactions(&caller).await?;
 
     Ok(Json(transactions))
 }
+
+async fn get_accounts(
+    State(handler): State<AppState>,
+    caller: BudgetCaller,
+) -> Result<impl IntoResponse> {
+    let accounts = handler.budget_handler.get_accounts(&caller).await?;
+
+    Ok(Json(accounts))
+}
+
+async fn get_institutions(
+    State(handler): State<AppState>,
+    caller: BudgetCaller,
+) -> Result<impl IntoResponse> {
+    let institutions = handler.budget_handler.get_institutions(&caller).await?;
+
+    Ok(Json(institutions))
+}
+
+#[async_trait::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for BudgetCaller {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let token = BearerToken::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let workspace_id = parts
+            .headers
+            .get(WORKSPACE_HEADER)
+            .map(|value| {
+                let value = String::from_utf8_lossy(value.as_bytes());
+
+                value
+                    .parse()
+                    .map_err(|_| Error::InvalidWorkspaceHeader(value.into_owned()))
+            })
+            .transpose()
+            .map_err(IntoResponse::into_response)?;
+
+        Ok(BudgetCaller {
+            token,
+            workspace_id,
+        })
+    }
+}
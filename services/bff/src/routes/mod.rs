@@ -1,12 +1,19 @@
 use std::sync::Arc;
 pub mod budget;
 
-use axum::{http::StatusCode, response::IntoResponse, Router};
+use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+use tonic::Code;
 
 use crate::{domains::errors::Error, handlers::budget::DynBudgetHandler};
 
 pub(crate) fn configure_services() -> Router<AppState> {
-    Router::new().nest("/bff", Router::new().merge(budget::configure_router()))
+    Router::new()
+        .route("/health", get(health))
+        .nest("/bff", Router::new().merge(budget::configure_router()))
+}
+
+async fn health() -> &'static str {
+    "ok"
 }
 
 #[derive(Clone)]
@@ -17,8 +24,72 @@ pub struct AppState {
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
         match self {
-            Self::ReqwestError(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}")),
+            // the budget service refusing the token of the caller is answered as is
+            Self::ReqwestError(err) => match err.status() {
+                Some(status) if status.is_client_error() => {
+                    (status, String::from("Budget service refused the request"))
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}")),
+            },
+            Self::GrpcError(status) => match status.code() {
+                Code::Unauthenticated => (
+                    StatusCode::UNAUTHORIZED,
+                    String::from("Budget service refused the request"),
+                ),
+                Code::PermissionDenied => (
+                    StatusCode::FORBIDDEN,
+                    String::from("Budget service refused the request"),
+                ),
+                Code::NotFound => (StatusCode::NOT_FOUND, String::from(status.message())),
+                // the budget answers the requests it can't process with this code
+                Code::InvalidArgument => (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    String::from(status.message()),
+                ),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, format!("{status:?}")),
+            },
+            err @ Self::InvalidBudgetResponse(_) => (StatusCode::BAD_GATEWAY, err.to_string()),
+            err @ Self::InvalidWorkspaceHeader(_) => (StatusCode::BAD_REQUEST, err.to_string()),
         }
         .into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tonic::Status;
+
+    use super::*;
+
+    #[test]
+    fn should_answer_the_budget_failures_with_their_status() {
+        let cases = [
+            (
+                Status::not_found("Account not found"),
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                Status::permission_denied("Workspace role does not allow the operation"),
+                StatusCode::FORBIDDEN,
+            ),
+            (
+                Status::invalid_argument("value must be a decimal number"),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                Status::unauthenticated("Missing token"),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                Status::internal("Database error"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for (status, expected) in cases {
+            let response = Error::from(status).into_response();
+
+            assert_eq!(response.status(), expected);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+/// compiles the gRPC services of the budget, protoc comes with the build so no install is needed
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["../../proto/budget/v1/budget.proto"], &["../../proto"])?;
+
+    Ok(())
+}
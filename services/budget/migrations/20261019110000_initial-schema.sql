@@ -0,0 +1,124 @@
+-- schema the budget service was running on before the versioned migrations, every
+-- statement is skipped when the object exists so databases created before them can
+-- apply it as their baseline
+DO $$ BEGIN
+    CREATE TYPE bank_name AS ENUM ('NUBANK', 'INTER', 'SANTANDER', 'ITAU', 'BRADESCO', 'BANCO_DO_BRASIL', 'SWILE');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+DO $$ BEGIN
+    CREATE TYPE account_type AS ENUM ('DEBIT', 'CREDIT', 'HYBRID');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+DO $$ BEGIN
+    CREATE TYPE month_reference AS ENUM ('JANUARY','FEBRUARY','MARCH','APRIL','MAY','JUNE','JULY','AUGUST','SEPTEMBER','OCTOBER','NOVEMBER','DECEMBER');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+DO $$ BEGIN
+    CREATE TYPE movement_type AS ENUM ('INCOME', 'EXPENSE');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+DO $$ BEGIN
+    CREATE TYPE status AS ENUM ('PENDING', 'CANCELED', 'COMPLETED');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+DO $$ BEGIN
+    CREATE TYPE category AS ENUM ('FOOD','HOME','EDUCATION','ENTERTAINMENT','TRANSPORT','HEALTHY','SALARY','UTILITIES','INSURANCE','SAVINGS','DEBT_PAYMENTS','CHILD_CARE','GIFTS','SUBSCRIPTIONS','TRAVEL','CLOTHING','MAINTENANCE');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+DO $$ BEGIN
+    CREATE TYPE frequency AS ENUM ('MONTHLY', 'WEEKLY', 'ANNUALLY');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS accounts (
+    account_id UUID PRIMARY KEY,
+    bank_name bank_name NOT NULL,
+    owner VARCHAR NOT NULL,
+    account_type account_type NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS financial_plans (
+    financial_plan_id UUID PRIMARY KEY,
+    title VARCHAR,
+    month month_reference NOT NULL,
+    year SMALLINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    transaction_id UUID PRIMARY KEY,
+    financial_plan_id UUID NOT NULL REFERENCES financial_plans (financial_plan_id),
+    movement_type movement_type NOT NULL,
+    description VARCHAR NOT NULL,
+    value NUMERIC NOT NULL,
+    due_date DATE NOT NULL,
+    category category NOT NULL,
+    account_id UUID NOT NULL REFERENCES accounts (account_id),
+    status status NOT NULL DEFAULT 'PENDING',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS installments (
+    installment_id UUID PRIMARY KEY,
+    transaction_id UUID NOT NULL REFERENCES transactions (transaction_id),
+    installment_number SMALLINT NOT NULL,
+    total_installment SMALLINT NOT NULL,
+    due_date DATE NOT NULL,
+    value NUMERIC NOT NULL,
+    status status NOT NULL DEFAULT 'PENDING',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS settlements (
+    settlement_id UUID PRIMARY KEY,
+    transaction_id UUID NOT NULL REFERENCES transactions (transaction_id),
+    installment_id UUID REFERENCES installments (installment_id),
+    paid_date DATE NOT NULL,
+    paid_value NUMERIC NOT NULL,
+    discount NUMERIC,
+    fees NUMERIC,
+    attachment BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS recurrences (
+    recurrence_id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts (account_id),
+    title VARCHAR NOT NULL,
+    frequency frequency NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT false,
+    category category NOT NULL,
+    start_date DATE NOT NULL,
+    value NUMERIC NOT NULL,
+    movement_type movement_type NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS transaction_recurrence_links (
+    transaction_id UUID NOT NULL REFERENCES transactions (transaction_id),
+    recurrence_id UUID NOT NULL REFERENCES recurrences (recurrence_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (transaction_id, recurrence_id)
+);
+
+CREATE INDEX IF NOT EXISTS financial_plans_year_month_idx ON financial_plans (year, month) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS transactions_financial_plan_id_idx ON transactions (financial_plan_id);
+CREATE INDEX IF NOT EXISTS transactions_account_id_idx ON transactions (account_id);
+CREATE INDEX IF NOT EXISTS installments_transaction_id_idx ON installments (transaction_id);
+CREATE INDEX IF NOT EXISTS settlements_transaction_id_idx ON settlements (transaction_id);
+CREATE INDEX IF NOT EXISTS settlements_installment_id_idx ON settlements (installment_id);
+CREATE INDEX IF NOT EXISTS recurrences_account_id_idx ON recurrences (account_id);
+CREATE INDEX IF NOT EXISTS transaction_recurrence_links_recurrence_id_idx ON transaction_recurrence_links (recurrence_id);
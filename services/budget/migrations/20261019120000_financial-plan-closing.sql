@@ -0,0 +1 @@
+ALTER TABLE financial_plans ADD COLUMN IF NOT EXISTS closed_at TIMESTAMPTZ;
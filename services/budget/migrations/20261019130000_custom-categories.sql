@@ -0,0 +1,41 @@
+CREATE TYPE category_kind AS ENUM ('INCOME', 'EXPENSE');
+
+CREATE TABLE IF NOT EXISTS categories (
+    category_id UUID PRIMARY KEY,
+    parent_id UUID REFERENCES categories (category_id),
+    name VARCHAR NOT NULL,
+    icon VARCHAR,
+    color VARCHAR,
+    kind category_kind NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS categories_parent_name_idx
+    ON categories (COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'), lower(name))
+    WHERE deleted_at IS NULL;
+
+-- seed the user managed categories from the old hardcoded enum
+INSERT INTO categories (category_id, name, kind)
+SELECT
+    gen_random_uuid(),
+    initcap(replace(lower(value::text), '_', ' ')),
+    CASE WHEN value = 'SALARY' THEN 'INCOME'::category_kind ELSE 'EXPENSE'::category_kind END
+FROM unnest(enum_range(NULL::category)) AS value;
+
+ALTER TABLE transactions ADD COLUMN category_id UUID REFERENCES categories (category_id);
+ALTER TABLE recurrences ADD COLUMN category_id UUID REFERENCES categories (category_id);
+
+UPDATE transactions tr SET category_id = c.category_id
+FROM categories c
+WHERE c.parent_id IS NULL AND c.name = initcap(replace(lower(tr.category::text), '_', ' '));
+
+UPDATE recurrences rc SET category_id = c.category_id
+FROM categories c
+WHERE c.parent_id IS NULL AND c.name = initcap(replace(lower(rc.category::text), '_', ' '));
+
+ALTER TABLE transactions ALTER COLUMN category_id SET NOT NULL, DROP COLUMN category;
+ALTER TABLE recurrences ALTER COLUMN category_id SET NOT NULL, DROP COLUMN category;
+
+DROP TYPE category;
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS tags (
+    tag_id UUID PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    color VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS tags_name_idx ON tags (lower(name)) WHERE deleted_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS transaction_tags (
+    transaction_id UUID NOT NULL REFERENCES transactions (transaction_id),
+    tag_id UUID NOT NULL REFERENCES tags (tag_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (transaction_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS transaction_tags_tag_id_idx ON transaction_tags (tag_id);
+
+CREATE TABLE IF NOT EXISTS recurrence_tags (
+    recurrence_id UUID NOT NULL REFERENCES recurrences (recurrence_id),
+    tag_id UUID NOT NULL REFERENCES tags (tag_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (recurrence_id, tag_id)
+);
@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS institutions (
+    institution_id UUID PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    code VARCHAR,
+    logo_url VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS institutions_name_idx ON institutions (lower(name)) WHERE deleted_at IS NULL;
+
+-- seed the institutions from the old hardcoded enum, code is the COMPE number when there is one
+CREATE TEMPORARY TABLE legacy_banks (bank bank_name, name VARCHAR, code VARCHAR);
+
+INSERT INTO legacy_banks (bank, name, code) VALUES
+    ('NUBANK', 'Nubank', '260'),
+    ('INTER', 'Inter', '077'),
+    ('SANTANDER', 'Santander', '033'),
+    ('ITAU', 'Itaú', '341'),
+    ('BRADESCO', 'Bradesco', '237'),
+    ('BANCO_DO_BRASIL', 'Banco do Brasil', '001'),
+    ('SWILE', 'Swile', NULL);
+
+INSERT INTO institutions (institution_id, name, code)
+SELECT gen_random_uuid(), name, code FROM legacy_banks;
+
+ALTER TABLE accounts ADD COLUMN institution_id UUID REFERENCES institutions (institution_id);
+
+UPDATE accounts a SET institution_id = i.institution_id
+FROM legacy_banks lb
+JOIN institutions i ON i.name = lb.name
+WHERE lb.bank = a.bank_name;
+
+ALTER TABLE accounts ALTER COLUMN institution_id SET NOT NULL, DROP COLUMN bank_name;
+
+DROP TABLE legacy_banks;
+
+DROP TYPE bank_name;
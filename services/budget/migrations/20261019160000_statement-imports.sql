@@ -0,0 +1,31 @@
+CREATE TYPE import_format AS ENUM ('OFX', 'CSV');
+CREATE TYPE import_status AS ENUM ('PREVIEW', 'COMMITTED');
+CREATE TYPE import_line_status AS ENUM ('NEW', 'DUPLICATE', 'IMPORTED');
+
+CREATE TABLE IF NOT EXISTS statement_imports (
+    import_id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts (account_id),
+    format import_format NOT NULL,
+    status import_status NOT NULL DEFAULT 'PREVIEW',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    committed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS statement_imports_account_id_idx ON statement_imports (account_id);
+
+-- external_id is the OFX FITID or a hash of the line content, used to skip lines already imported
+CREATE TABLE IF NOT EXISTS statement_import_lines (
+    line_id UUID PRIMARY KEY,
+    import_id UUID NOT NULL REFERENCES statement_imports (import_id),
+    external_id VARCHAR NOT NULL,
+    due_date DATE NOT NULL,
+    description VARCHAR NOT NULL,
+    value NUMERIC NOT NULL,
+    movement_type movement_type NOT NULL,
+    status import_line_status NOT NULL,
+    transaction_id UUID REFERENCES transactions (transaction_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS statement_import_lines_import_id_idx ON statement_import_lines (import_id);
+CREATE INDEX IF NOT EXISTS statement_import_lines_external_id_idx ON statement_import_lines (external_id);
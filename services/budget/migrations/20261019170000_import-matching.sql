@@ -0,0 +1,7 @@
+ALTER TYPE import_line_status ADD VALUE IF NOT EXISTS 'MATCHED';
+
+-- match proposed by the matching engine when the preview was made
+ALTER TABLE statement_import_lines
+    ADD COLUMN IF NOT EXISTS match_transaction_id UUID REFERENCES transactions (transaction_id),
+    ADD COLUMN IF NOT EXISTS match_installment_id UUID REFERENCES installments (installment_id),
+    ADD COLUMN IF NOT EXISTS match_score DOUBLE PRECISION;
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS categorization_rules (
+    rule_id UUID PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    pattern VARCHAR NOT NULL,
+    movement_type movement_type NOT NULL,
+    category_id UUID NOT NULL REFERENCES categories (category_id),
+    priority INTEGER NOT NULL DEFAULT 0,
+    hits BIGINT NOT NULL DEFAULT 0,
+    last_hit_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS categorization_rules_priority_idx ON categorization_rules (priority) WHERE deleted_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS categorization_rule_tags (
+    rule_id UUID NOT NULL REFERENCES categorization_rules (rule_id),
+    tag_id UUID NOT NULL REFERENCES tags (tag_id),
+    PRIMARY KEY (rule_id, tag_id)
+);
@@ -0,0 +1,19 @@
+CREATE TYPE audit_entity AS ENUM (
+    'TRANSACTION', 'ACCOUNT', 'INSTALLMENT', 'SETTLEMENT', 'RECURRENCE', 'FINANCIAL_PLAN',
+    'CATEGORY', 'TAG', 'INSTITUTION', 'IMPORT', 'RULE'
+);
+CREATE TYPE audit_action AS ENUM ('CREATE', 'UPDATE', 'DELETE', 'STATUS_CHANGE', 'TAG_ATTACH', 'TAG_DETACH');
+
+-- append only, entity_id has no foreign key so the history outlives the records
+CREATE TABLE IF NOT EXISTS audit_log (
+    audit_id UUID PRIMARY KEY,
+    entity_type audit_entity NOT NULL,
+    entity_id UUID NOT NULL,
+    action audit_action NOT NULL,
+    changes JSONB NOT NULL,
+    actor VARCHAR,
+    request_id VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_entity_idx ON audit_log (entity_type, entity_id, created_at);
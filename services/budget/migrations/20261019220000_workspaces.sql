@@ -0,0 +1,75 @@
+CREATE TYPE workspace_role AS ENUM ('OWNER', 'EDITOR', 'VIEWER');
+
+CREATE TABLE IF NOT EXISTS workspaces (
+    workspace_id UUID PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ
+);
+
+-- user ids come from the access tokens, the users live in the authentication service
+CREATE TABLE IF NOT EXISTS workspace_members (
+    workspace_id UUID NOT NULL REFERENCES workspaces (workspace_id),
+    user_id UUID NOT NULL,
+    role workspace_role NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (workspace_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS workspace_members_user_id_idx ON workspace_members (user_id);
+
+-- records made before the workspaces were shared by everyone, they move to a workspace
+-- without members until the deploy names its owner with LEGACY_WORKSPACE_OWNER
+INSERT INTO workspaces (workspace_id, name) VALUES ('00000000-0000-0000-0000-000000000001', 'Household');
+
+ALTER TABLE institutions ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE accounts ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE categories ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE tags ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE financial_plans ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE transactions ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE installments ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE settlements ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE recurrences ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE categorization_rules ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE statement_imports ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+ALTER TABLE audit_log ADD COLUMN workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces (workspace_id);
+
+ALTER TABLE institutions ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE accounts ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE categories ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE tags ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE financial_plans ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE transactions ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE installments ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE settlements ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE recurrences ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE categorization_rules ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE statement_imports ALTER COLUMN workspace_id DROP DEFAULT;
+ALTER TABLE audit_log ALTER COLUMN workspace_id DROP DEFAULT;
+
+CREATE INDEX IF NOT EXISTS institutions_workspace_id_idx ON institutions (workspace_id);
+CREATE INDEX IF NOT EXISTS accounts_workspace_id_idx ON accounts (workspace_id);
+CREATE INDEX IF NOT EXISTS categories_workspace_id_idx ON categories (workspace_id);
+CREATE INDEX IF NOT EXISTS tags_workspace_id_idx ON tags (workspace_id);
+CREATE INDEX IF NOT EXISTS financial_plans_workspace_id_idx ON financial_plans (workspace_id);
+CREATE INDEX IF NOT EXISTS transactions_workspace_id_idx ON transactions (workspace_id);
+CREATE INDEX IF NOT EXISTS installments_workspace_id_idx ON installments (workspace_id);
+CREATE INDEX IF NOT EXISTS settlements_workspace_id_idx ON settlements (workspace_id);
+CREATE INDEX IF NOT EXISTS recurrences_workspace_id_idx ON recurrences (workspace_id);
+CREATE INDEX IF NOT EXISTS categorization_rules_workspace_id_idx ON categorization_rules (workspace_id);
+CREATE INDEX IF NOT EXISTS statement_imports_workspace_id_idx ON statement_imports (workspace_id);
+
+-- names are unique inside a workspace only
+DROP INDEX IF EXISTS categories_parent_name_idx;
+DROP INDEX IF EXISTS tags_name_idx;
+DROP INDEX IF EXISTS institutions_name_idx;
+
+CREATE UNIQUE INDEX IF NOT EXISTS categories_parent_name_idx
+    ON categories (workspace_id, COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'), lower(name))
+    WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS tags_name_idx ON tags (workspace_id, lower(name)) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS institutions_name_idx ON institutions (workspace_id, lower(name)) WHERE deleted_at IS NULL;
+
+DROP INDEX IF EXISTS audit_log_entity_idx;
+CREATE INDEX IF NOT EXISTS audit_log_entity_idx ON audit_log (workspace_id, entity_type, entity_id, created_at);
@@ -0,0 +1,16 @@
+CREATE TYPE account_permission AS ENUM ('VIEW', 'RECORD', 'MANAGE');
+
+-- accounts without grants are shared with every member of their workspace,
+-- once an account has grants only the users holding one reach it
+CREATE TABLE IF NOT EXISTS account_grants (
+    account_id UUID NOT NULL REFERENCES accounts (account_id),
+    user_id UUID NOT NULL,
+    permission account_permission NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (account_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS account_grants_user_id_idx ON account_grants (user_id);
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'GRANT';
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'REVOKE';
@@ -0,0 +1,5 @@
+-- a user gets a single personal workspace even when their first requests race to create it,
+-- the workspaces made before only matter to their members and are left without an owner here
+ALTER TABLE workspaces ADD COLUMN IF NOT EXISTS personal_owner_id UUID;
+
+CREATE UNIQUE INDEX IF NOT EXISTS workspaces_personal_owner_id_idx ON workspaces (personal_owner_id);
@@ -0,0 +1,3 @@
+-- the audit log outlives the records it tells about, forced restores keep it while they replace
+-- the workspaces
+ALTER TABLE audit_log DROP CONSTRAINT IF EXISTS audit_log_workspace_id_fkey;
@@ -3,11 +3,16 @@ use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
+use super::{
+    errors::{Error, Result},
+    workspaces::WorkspaceMember,
+};
+
+#[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     pub account_id: Uuid,
-    pub bank_name: Bank,
+    pub institution_id: Uuid,
     pub owner: String,
     pub account_type: AccountType,
     pub created_at: DateTime<Utc>,
@@ -17,10 +22,16 @@ pub struct Account {
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+impl Account {
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateAccount {
-    pub bank_name: Bank,
+    pub institution_id: Uuid,
     pub owner: String,
     pub account_type: AccountType,
 }
@@ -28,25 +39,12 @@ pub struct CreateAccount {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateAccount {
-    pub bank_name: Option<Bank>,
+    pub institution_id: Option<Uuid>,
     pub owner: Option<String>,
     pub account_type: Option<AccountType>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Type)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-#[sqlx(type_name = "bank_name", rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Bank {
-    Nubank,
-    Inter,
-    Santander,
-    Itau,
-    Bradesco,
-    BancoDoBrasil,
-    Swile
-}
-
-#[derive(Debug, Serialize, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[sqlx(type_name = "account_type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountType {
@@ -54,3 +52,144 @@ pub enum AccountType {
     Credit,
     Hybrid,
 }
+
+/// Access of a user to an account, each permission includes the ones before it
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "account_permission", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountPermission {
+    /// sees the account and the records touching it
+    View,
+    /// records transactions, settlements and recurrences in the account
+    Record,
+    /// changes the account and its grants
+    Manage,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountGrant {
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    pub permission: AccountPermission,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantAccount {
+    pub user_id: Uuid,
+    pub permission: AccountPermission,
+}
+
+impl AccountGrant {
+    pub fn new(account_id: Uuid, user_id: Uuid, permission: AccountPermission) -> Self {
+        AccountGrant {
+            account_id,
+            user_id,
+            permission,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// accounts without grants are shared, the others need someone to manage them
+    pub fn keeps_a_manager(grants: &[AccountGrant]) -> bool {
+        grants.is_empty()
+            || grants
+                .iter()
+                .any(|g| g.permission == AccountPermission::Manage)
+    }
+}
+
+/// Permissions of the caller over the accounts of the workspace
+#[derive(Debug)]
+pub struct AccountAccess {
+    /// user id and the most their role allows
+    member: (Uuid, AccountPermission),
+    grants: Vec<AccountGrant>,
+}
+
+impl AccountAccess {
+    pub fn new(member: &WorkspaceMember, grants: Vec<AccountGrant>) -> Self {
+        AccountAccess {
+            member: (member.user_id, member.role.account_permission()),
+            grants,
+        }
+    }
+
+    /// accounts without grants follow the workspace role, the others only reach
+    /// the users holding a grant
+    pub fn permission(&self, account_id: Uuid) -> Option<AccountPermission> {
+        let (user_id, most) = self.member;
+
+        let mut grants = self
+            .grants
+            .iter()
+            .filter(|g| g.account_id == account_id)
+            .peekable();
+
+        if grants.peek().is_none() {
+            return Some(most);
+        }
+
+        grants
+            .find(|g| g.user_id == user_id)
+            .map(|g| g.permission.min(most))
+    }
+
+    pub fn allows(&self, account_id: Uuid, permission: AccountPermission) -> bool {
+        self.permission(account_id)
+            .is_some_and(|granted| granted >= permission)
+    }
+
+    /// accounts the caller can't see are not found
+    pub fn check(&self, account_id: Uuid, permission: AccountPermission) -> Result<()> {
+        match self.permission(account_id) {
+            None => Err(Error::AccountNotFound(account_id)),
+            Some(granted) if granted < permission => {
+                Err(Error::AccountPermissionDenied(account_id))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domains::workspaces::WorkspaceRole;
+
+    use super::*;
+
+    #[test]
+    fn should_restrict_accounts_with_grants_to_their_holders() {
+        let user_id = Uuid::new_v4();
+        let shared = Uuid::new_v4();
+        let private = Uuid::new_v4();
+        let viewed = Uuid::new_v4();
+
+        let grants = vec![
+            AccountGrant::new(private, Uuid::new_v4(), AccountPermission::Manage),
+            AccountGrant::new(viewed, Uuid::new_v4(), AccountPermission::Manage),
+            AccountGrant::new(viewed, user_id, AccountPermission::Record),
+        ];
+        let editor = WorkspaceMember::new(Uuid::new_v4(), user_id, WorkspaceRole::Editor);
+        let access = AccountAccess::new(&editor, grants.clone());
+
+        assert_eq!(access.permission(shared), Some(AccountPermission::Manage));
+        assert_eq!(access.permission(private), None);
+        assert_eq!(access.permission(viewed), Some(AccountPermission::Record));
+        assert!(matches!(
+            access.check(viewed, AccountPermission::Manage),
+            Err(Error::AccountPermissionDenied(id)) if id == viewed
+        ));
+        assert!(matches!(
+            access.check(private, AccountPermission::View),
+            Err(Error::AccountNotFound(id)) if id == private
+        ));
+
+        let viewer = WorkspaceMember::new(Uuid::new_v4(), user_id, WorkspaceRole::Viewer);
+        let access = AccountAccess::new(&viewer, grants);
+
+        assert_eq!(access.permission(viewed), Some(AccountPermission::View));
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+/// Change made to a budget record, entries are never updated nor deleted
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub audit_id: Uuid,
+    pub entity_type: AuditEntity,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    /// changed fields with their value before and after the change
+    pub changes: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "audit_entity", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditEntity {
+    Transaction,
+    Account,
+    Installment,
+    Settlement,
+    Recurrence,
+    FinancialPlan,
+    Category,
+    Tag,
+    Institution,
+    Import,
+    Rule,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "audit_action", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    StatusChange,
+    TagAttach,
+    TagDetach,
+    Grant,
+    Revoke,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditParams {
+    pub entity: AuditEntity,
+    pub id: Uuid,
+}
+
+/// Who made the request being handled, requests without it are made by the service itself
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub actor: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// fields left out of the changes, the update time changes every time and
+/// attachments are binary files
+const IGNORED_FIELDS: [&str; 2] = ["updatedAt", "attachment"];
+
+impl AuditEntry {
+    pub fn new(
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+        action: AuditAction,
+        changes: Value,
+        context: AuditContext,
+    ) -> Self {
+        AuditEntry {
+            audit_id: Uuid::new_v4(),
+            entity_type,
+            entity_id,
+            action,
+            changes,
+            actor: context.actor,
+            request_id: context.request_id,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// fields whose value differ between the two versions of the record, as
+    /// `{ field: { before, after } }`, a missing version has every field null
+    pub fn diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+        let empty = Map::new();
+        let before = before.and_then(Value::as_object).unwrap_or(&empty);
+        let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+        let mut changes = Map::new();
+
+        for field in before.keys().chain(after.keys()) {
+            if IGNORED_FIELDS.contains(&field.as_str()) || changes.contains_key(field) {
+                continue;
+            }
+
+            let old = before.get(field).unwrap_or(&Value::Null);
+            let new = after.get(field).unwrap_or(&Value::Null);
+
+            if old != new {
+                changes.insert(
+                    field.clone(),
+                    serde_json::json!({ "before": old, "after": new }),
+                );
+            }
+        }
+
+        Value::Object(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_keep_only_the_changed_fields() {
+        let before = json!({ "value": "10", "description": "rent", "updatedAt": null });
+        let after = json!({ "value": "12", "description": "rent", "updatedAt": "2024-05-10" });
+
+        assert_eq!(
+            AuditEntry::diff(Some(&before), Some(&after)),
+            json!({ "value": { "before": "10", "after": "12" } })
+        );
+
+        assert_eq!(
+            AuditEntry::diff(None, Some(&json!({ "value": "10" }))),
+            json!({ "value": { "before": null, "after": "10" } })
+        );
+    }
+}
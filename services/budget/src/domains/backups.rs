@@ -0,0 +1,311 @@
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use super::errors::{Error, Result};
+
+pub const BACKUP_FORMAT: &str = "home-app-budget-backup";
+
+/// Bumped whenever a migration changes the tables of the backup
+pub const BACKUP_VERSION: u32 = 4;
+
+/// Tables of the backup, parents come before the tables referencing them
+pub const BACKUP_TABLES: [&str; 20] = [
+    "workspaces",
+    "workspace_members",
+    "institutions",
+    "accounts",
+    "account_grants",
+    "categories",
+    "tags",
+    "financial_plans",
+    "transactions",
+    "installments",
+    "settlements",
+    "recurrences",
+    "transaction_recurrence_links",
+    "transaction_tags",
+    "recurrence_tags",
+    "categorization_rules",
+    "categorization_rule_tags",
+    "statement_imports",
+    "statement_import_lines",
+    "audit_log",
+];
+
+/// Users running the deployment, the only ones reaching the backups since they hold every
+/// workspace
+#[derive(Debug, Clone, Default)]
+pub struct Operators(Arc<HashSet<Uuid>>);
+
+impl Operators {
+    pub fn new(user_ids: impl IntoIterator<Item = Uuid>) -> Self {
+        Operators(Arc::new(user_ids.into_iter().collect()))
+    }
+
+    pub fn contains(&self, user_id: Uuid) -> bool {
+        self.0.contains(&user_id)
+    }
+}
+
+/// Rows buffered between the database and the client, the slower side holds the other back
+pub const BACKUP_BUFFER: usize = 256;
+
+/// Line of a backup, sent as newline delimited json so neither side holds it whole: the header
+/// first, then the rows of every table in `BACKUP_TABLES` order and an end line counting them
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BackupLine {
+    #[serde(rename_all = "camelCase")]
+    Header {
+        format: String,
+        version: u32,
+        created_at: DateTime<Utc>,
+    },
+    Row {
+        table: String,
+        row: serde_json::Value,
+    },
+    End {
+        rows: usize,
+    },
+}
+
+/// Record of a table, with its database columns
+#[derive(Debug)]
+pub struct BackupRow {
+    pub table: &'static str,
+    pub row: serde_json::Value,
+}
+
+/// Backup being read from the database
+#[derive(Debug)]
+pub struct Backup {
+    pub created_at: DateTime<Utc>,
+    pub lines: mpsc::Receiver<Result<BackupLine>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreParams {
+    /// replace the existing records instead of refusing the restore
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredTable {
+    pub name: String,
+    pub rows: usize,
+}
+
+/// Checks the lines of a backup as they arrive, a truncated backup misses its end line
+#[derive(Debug, Default)]
+pub struct RestoreProgress {
+    started: bool,
+    ended: bool,
+    /// position in `BACKUP_TABLES` of the table being restored
+    position: usize,
+    tables: Vec<RestoredTable>,
+}
+
+impl BackupLine {
+    pub fn header(created_at: DateTime<Utc>) -> Self {
+        BackupLine::Header {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at,
+        }
+    }
+
+    pub fn parse(line: &[u8]) -> Result<Self> {
+        serde_json::from_slice(line)
+            .map_err(|error| Error::InvalidBackup(format!("unreadable line: {error}")))
+    }
+
+    /// the line with its newline
+    pub fn to_ndjson(&self) -> Vec<u8> {
+        let mut line = serde_json::to_vec(self).expect("backup lines are plain json");
+        line.push(b'\n');
+        line
+    }
+}
+
+impl From<BackupRow> for BackupLine {
+    fn from(row: BackupRow) -> Self {
+        BackupLine::Row {
+            table: row.table.to_string(),
+            row: row.row,
+        }
+    }
+}
+
+impl RestoreProgress {
+    /// check the next line of the backup, giving back the row it holds
+    pub fn accept(&mut self, line: BackupLine) -> Result<Option<BackupRow>> {
+        if self.ended {
+            return Err(Error::InvalidBackup("lines after the end".to_string()));
+        }
+
+        match line {
+            BackupLine::Header {
+                format, version, ..
+            } => {
+                if self.started {
+                    return Err(Error::InvalidBackup("repeated header".to_string()));
+                }
+
+                if format != BACKUP_FORMAT {
+                    return Err(Error::InvalidBackup(format!("unknown format '{format}'")));
+                }
+
+                if version != BACKUP_VERSION {
+                    return Err(Error::InvalidBackup(format!(
+                        "version {version} is not supported, expected {BACKUP_VERSION}"
+                    )));
+                }
+
+                self.started = true;
+
+                Ok(None)
+            }
+            _ if !self.started => Err(Error::InvalidBackup(
+                "the header must come first".to_string(),
+            )),
+            BackupLine::Row { table, row } => {
+                let position = BACKUP_TABLES
+                    .iter()
+                    .position(|name| *name == table)
+                    .ok_or_else(|| Error::InvalidBackup(format!("unknown table '{table}'")))?;
+
+                // parents are restored before the tables referencing them
+                if position < self.position {
+                    return Err(Error::InvalidBackup(format!(
+                        "table '{table}' is out of order"
+                    )));
+                }
+
+                if !row.is_object() {
+                    return Err(Error::InvalidBackup(format!(
+                        "rows of table '{table}' must be objects"
+                    )));
+                }
+
+                self.position = position;
+
+                match self.tables.last_mut() {
+                    Some(restored) if restored.name == table => restored.rows += 1,
+                    _ => self.tables.push(RestoredTable {
+                        name: table,
+                        rows: 1,
+                    }),
+                }
+
+                Ok(Some(BackupRow {
+                    table: BACKUP_TABLES[position],
+                    row,
+                }))
+            }
+            BackupLine::End { rows } => {
+                let read: usize = self.tables.iter().map(|table| table.rows).sum();
+
+                if rows != read {
+                    return Err(Error::InvalidBackup(format!(
+                        "the end counts {rows} rows, {read} were read"
+                    )));
+                }
+
+                self.ended = true;
+
+                Ok(None)
+            }
+        }
+    }
+
+    /// tables restored, once the whole backup was read
+    pub fn finish(self) -> Result<Vec<RestoredTable>> {
+        if !self.ended {
+            return Err(Error::InvalidBackup(
+                "the backup is truncated, its end line is missing".to_string(),
+            ));
+        }
+
+        Ok(self.tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_row(table: &str) -> BackupLine {
+        BackupLine::Row {
+            table: table.to_string(),
+            row: serde_json::json!({ "id": 1 }),
+        }
+    }
+
+    #[test]
+    fn should_accept_the_tables_in_restore_order_and_check_the_version() {
+        let mut progress = RestoreProgress::default();
+
+        assert!(matches!(
+            progress.accept(new_row("accounts")),
+            Err(Error::InvalidBackup(_))
+        ));
+
+        progress.accept(BackupLine::header(Utc::now())).unwrap();
+        progress.accept(new_row("accounts")).unwrap();
+        let row = progress.accept(new_row("transactions")).unwrap().unwrap();
+        assert_eq!(row.table, "transactions");
+
+        assert!(matches!(
+            progress.accept(new_row("accounts")),
+            Err(Error::InvalidBackup(_))
+        ));
+
+        progress.accept(BackupLine::End { rows: 2 }).unwrap();
+
+        assert_eq!(
+            progress.finish().unwrap(),
+            vec![
+                RestoredTable {
+                    name: "accounts".to_string(),
+                    rows: 1
+                },
+                RestoredTable {
+                    name: "transactions".to_string(),
+                    rows: 1
+                },
+            ]
+        );
+
+        let mut progress = RestoreProgress::default();
+        assert!(matches!(
+            progress.accept(BackupLine::Header {
+                format: BACKUP_FORMAT.to_string(),
+                version: BACKUP_VERSION - 1,
+                created_at: Utc::now(),
+            }),
+            Err(Error::InvalidBackup(_))
+        ));
+    }
+
+    #[test]
+    fn should_refuse_a_truncated_backup() {
+        let mut progress = RestoreProgress::default();
+
+        progress.accept(BackupLine::header(Utc::now())).unwrap();
+        progress.accept(new_row("accounts")).unwrap();
+
+        assert!(matches!(
+            progress.accept(BackupLine::End { rows: 2 }),
+            Err(Error::InvalidBackup(_))
+        ));
+        assert!(matches!(progress.finish(), Err(Error::InvalidBackup(_))));
+    }
+}
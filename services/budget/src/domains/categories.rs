@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::Type;
+use uuid::Uuid;
+
+use crate::update_fields;
+
+use super::transactions::MovementType;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Category {
+    pub category_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub kind: CategoryKind,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Categories every workspace starts with, the ones the legacy workspace got from the old enum
+pub const DEFAULT_CATEGORIES: [(&str, CategoryKind); 17] = [
+    ("Food", CategoryKind::Expense),
+    ("Home", CategoryKind::Expense),
+    ("Education", CategoryKind::Expense),
+    ("Entertainment", CategoryKind::Expense),
+    ("Transport", CategoryKind::Expense),
+    ("Healthy", CategoryKind::Expense),
+    ("Salary", CategoryKind::Income),
+    ("Utilities", CategoryKind::Expense),
+    ("Insurance", CategoryKind::Expense),
+    ("Savings", CategoryKind::Expense),
+    ("Debt Payments", CategoryKind::Expense),
+    ("Child Care", CategoryKind::Expense),
+    ("Gifts", CategoryKind::Expense),
+    ("Subscriptions", CategoryKind::Expense),
+    ("Travel", CategoryKind::Expense),
+    ("Clothing", CategoryKind::Expense),
+    ("Maintenance", CategoryKind::Expense),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCategory {
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub kind: CategoryKind,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCategory {
+    pub name: Option<String>,
+    /// missing keeps the icon, null clears it
+    #[serde(default, deserialize_with = "nullable")]
+    pub icon: Option<Option<String>>,
+    /// missing keeps the color, null clears it
+    #[serde(default, deserialize_with = "nullable")]
+    pub color: Option<Option<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "category_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CategoryKind {
+    Income,
+    Expense,
+}
+
+/// tell a null field, which clears the value, apart from a missing one
+fn nullable<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+impl From<MovementType> for CategoryKind {
+    fn from(value: MovementType) -> Self {
+        match value {
+            MovementType::Income => CategoryKind::Income,
+            MovementType::Expense => CategoryKind::Expense,
+        }
+    }
+}
+
+impl Category {
+    pub fn new_from_payload(payload: CreateCategory) -> Self {
+        Category {
+            category_id: Uuid::new_v4(),
+            parent_id: payload.parent_id,
+            name: payload.name,
+            icon: payload.icon,
+            color: payload.color,
+            kind: payload.kind,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    /// categories of a new workspace
+    pub fn defaults() -> Vec<Self> {
+        DEFAULT_CATEGORIES
+            .into_iter()
+            .map(|(name, kind)| {
+                Category::new_from_payload(CreateCategory {
+                    parent_id: None,
+                    name: name.to_string(),
+                    icon: None,
+                    color: None,
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// ROOT categories are the ones without parent, subcategories roll up into them
+    pub fn is_root(&self) -> bool {
+        self.parent_id.is_none()
+    }
+
+    /// prepare a category to be updated
+    pub fn update(&mut self, data: UpdateCategory) {
+        update_fields!(self, data, name, icon, color);
+        self.updated_at = Some(Utc::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_missing_fields_and_clear_null_ones() {
+        let mut category = Category::new_from_payload(CreateCategory {
+            parent_id: None,
+            name: String::from("Pets"),
+            icon: Some(String::from("paw")),
+            color: Some(String::from("#ff8800")),
+            kind: CategoryKind::Expense,
+        });
+
+        let payload: UpdateCategory = serde_json::from_str(r#"{ "icon": null }"#).unwrap();
+        category.update(payload);
+
+        assert_eq!(category.name, "Pets");
+        assert_eq!(category.icon, None);
+        assert_eq!(category.color.as_deref(), Some("#ff8800"));
+    }
+}
@@ -1,9 +1,11 @@
+use sqlx::error::ErrorKind;
 use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
-    #[error("Databse error")]
+    #[error("Database error")]
     DatabaseError(#[from] sqlx::Error),
     #[error("Transaction not found")]
     TransactionNotFound(Uuid),
@@ -11,16 +13,218 @@ pub enum Error {
     AccountNotFound(Uuid),
     #[error("Account has been already deleted")]
     AccountAlreadyDeleted(Uuid),
+    #[error("Account permission does not allow the operation")]
+    AccountPermissionDenied(Uuid),
+    #[error("Account must keep a manager")]
+    LastAccountManager(Uuid),
+    #[error("Account grant not found")]
+    AccountGrantNotFound(Uuid),
     #[error("Transaction has been already finished")]
     TransactionFinished(Uuid),
-    #[error("Account not found")]
+    #[error("Installment not found")]
     InstallmentNotFound(Uuid),
     #[error("Installment has been already finished")]
     InstallmentFinished(Uuid),
+    #[error("Settlement not found")]
+    SettlementNotFound(Uuid),
     #[error("Recurrence not found")]
     RecurrenceNotFound(Uuid),
     #[error("Financial plan not found")]
     FinancialPlanNotFound(Uuid),
+    #[error("Referenced account has been deleted")]
+    AccountDeleted(Uuid),
+    #[error("Referenced transaction has been deleted")]
+    TransactionDeleted(Uuid),
+    #[error("Referenced installment has been deleted")]
+    InstallmentDeleted(Uuid),
+    #[error("Installment does not belong to the transaction")]
+    InstallmentTransactionMismatch(Uuid),
+    #[error("Referenced financial plan has been deleted")]
+    FinancialPlanDeleted(Uuid),
+    #[error("Financial plan has been already closed")]
+    FinancialPlanClosed(Uuid),
+    #[error("Category not found")]
+    CategoryNotFound(Uuid),
+    #[error("Referenced category has been deleted")]
+    CategoryDeleted(Uuid),
+    #[error("Category kind does not match the movement type")]
+    CategoryKindMismatch(Uuid),
+    #[error("Category can't be used as parent")]
+    InvalidCategoryParent(Uuid),
+    #[error("Category still has subcategories")]
+    CategoryHasSubcategories(Uuid),
+    #[error("Tag not found")]
+    TagNotFound(Uuid),
+    #[error("Tag has been deleted")]
+    TagDeleted(Uuid),
+    #[error("Institution not found")]
+    InstitutionNotFound(Uuid),
+    #[error("Institution has been deleted")]
+    InstitutionDeleted(Uuid),
+    #[error("Institution still has accounts")]
+    InstitutionHasAccounts(Uuid),
+    #[error("Statement import not found")]
+    ImportNotFound(Uuid),
+    #[error("Statement import has been already committed")]
+    ImportAlreadyCommitted(Uuid),
+    #[error("Invalid statement file: {0}")]
+    InvalidStatementFile(String),
+    #[error("Categorization rule not found")]
+    RuleNotFound(Uuid),
+    #[error("Categorization rule has been deleted")]
+    RuleDeleted(Uuid),
+    #[error("Category is required when no rule matches")]
+    CategoryRequired,
+    #[error("Invalid export parameters: {0}")]
+    InvalidExportParams(String),
+    #[error("Export failed: {0}")]
+    ExportFailed(String),
+    #[error("Invalid backup: {0}")]
+    InvalidBackup(String),
+    #[error("Restore target already has records")]
+    RestoreTargetNotEmpty,
+    #[error("Operation is not supported by the {0} storage")]
+    UnsupportedByStorage(&'static str),
+    #[error("Request has no authenticated user")]
+    Unauthenticated,
+    #[error("Request is not scoped to a workspace")]
+    WorkspaceRequired,
+    #[error("Invalid workspace header: {0}")]
+    InvalidWorkspaceHeader(String),
+    #[error("Workspace not found")]
+    WorkspaceNotFound(Uuid),
+    #[error("Workspace role does not allow the operation")]
+    InsufficientRole(Uuid),
+    #[error("Workspace member not found")]
+    MemberNotFound(Uuid),
+    #[error("Workspace must keep an owner")]
+    LastWorkspaceOwner(Uuid),
+    #[error("Only operators of the deployment can do this")]
+    OperatorRequired,
+}
+
+impl Error {
+    /// Stable machine readable code, clients should rely on it instead of the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DatabaseError(err) => match database_error_kind(err) {
+                Some(ErrorKind::UniqueViolation) => "UNIQUE_VIOLATION",
+                Some(ErrorKind::ForeignKeyViolation) => "FOREIGN_KEY_VIOLATION",
+                Some(ErrorKind::NotNullViolation) => "NOT_NULL_VIOLATION",
+                Some(ErrorKind::CheckViolation) => "CHECK_VIOLATION",
+                _ => "DATABASE_ERROR",
+            },
+            Self::TransactionNotFound(_) => "TRANSACTION_NOT_FOUND",
+            Self::AccountNotFound(_) => "ACCOUNT_NOT_FOUND",
+            Self::AccountAlreadyDeleted(_) => "ACCOUNT_ALREADY_DELETED",
+            Self::AccountPermissionDenied(_) => "ACCOUNT_PERMISSION_DENIED",
+            Self::LastAccountManager(_) => "LAST_ACCOUNT_MANAGER",
+            Self::AccountGrantNotFound(_) => "ACCOUNT_GRANT_NOT_FOUND",
+            Self::TransactionFinished(_) => "TRANSACTION_FINISHED",
+            Self::InstallmentNotFound(_) => "INSTALLMENT_NOT_FOUND",
+            Self::InstallmentFinished(_) => "INSTALLMENT_FINISHED",
+            Self::SettlementNotFound(_) => "SETTLEMENT_NOT_FOUND",
+            Self::RecurrenceNotFound(_) => "RECURRENCE_NOT_FOUND",
+            Self::FinancialPlanNotFound(_) => "FINANCIAL_PLAN_NOT_FOUND",
+            Self::AccountDeleted(_) => "ACCOUNT_DELETED",
+            Self::TransactionDeleted(_) => "TRANSACTION_DELETED",
+            Self::InstallmentDeleted(_) => "INSTALLMENT_DELETED",
+            Self::InstallmentTransactionMismatch(_) => "INSTALLMENT_TRANSACTION_MISMATCH",
+            Self::FinancialPlanDeleted(_) => "FINANCIAL_PLAN_DELETED",
+            Self::FinancialPlanClosed(_) => "FINANCIAL_PLAN_CLOSED",
+            Self::CategoryNotFound(_) => "CATEGORY_NOT_FOUND",
+            Self::CategoryDeleted(_) => "CATEGORY_DELETED",
+            Self::CategoryKindMismatch(_) => "CATEGORY_KIND_MISMATCH",
+            Self::InvalidCategoryParent(_) => "INVALID_CATEGORY_PARENT",
+            Self::CategoryHasSubcategories(_) => "CATEGORY_HAS_SUBCATEGORIES",
+            Self::TagNotFound(_) => "TAG_NOT_FOUND",
+            Self::TagDeleted(_) => "TAG_DELETED",
+            Self::InstitutionNotFound(_) => "INSTITUTION_NOT_FOUND",
+            Self::InstitutionDeleted(_) => "INSTITUTION_DELETED",
+            Self::InstitutionHasAccounts(_) => "INSTITUTION_HAS_ACCOUNTS",
+            Self::ImportNotFound(_) => "IMPORT_NOT_FOUND",
+            Self::ImportAlreadyCommitted(_) => "IMPORT_ALREADY_COMMITTED",
+            Self::InvalidStatementFile(_) => "INVALID_STATEMENT_FILE",
+            Self::RuleNotFound(_) => "RULE_NOT_FOUND",
+            Self::RuleDeleted(_) => "RULE_DELETED",
+            Self::CategoryRequired => "CATEGORY_REQUIRED",
+            Self::InvalidExportParams(_) => "INVALID_EXPORT_PARAMS",
+            Self::ExportFailed(_) => "EXPORT_FAILED",
+            Self::InvalidBackup(_) => "INVALID_BACKUP",
+            Self::RestoreTargetNotEmpty => "RESTORE_TARGET_NOT_EMPTY",
+            Self::UnsupportedByStorage(_) => "UNSUPPORTED_BY_STORAGE",
+            Self::Unauthenticated => "UNAUTHENTICATED",
+            Self::WorkspaceRequired => "WORKSPACE_REQUIRED",
+            Self::InvalidWorkspaceHeader(_) => "INVALID_WORKSPACE_HEADER",
+            Self::WorkspaceNotFound(_) => "WORKSPACE_NOT_FOUND",
+            Self::InsufficientRole(_) => "INSUFFICIENT_ROLE",
+            Self::MemberNotFound(_) => "MEMBER_NOT_FOUND",
+            Self::LastWorkspaceOwner(_) => "LAST_WORKSPACE_OWNER",
+            Self::OperatorRequired => "OPERATOR_REQUIRED",
+        }
+    }
+
+    /// Id of the record that caused the error, when there is one
+    pub fn entity_id(&self) -> Option<Uuid> {
+        match self {
+            Self::DatabaseError(_)
+            | Self::InvalidStatementFile(_)
+            | Self::CategoryRequired
+            | Self::InvalidExportParams(_)
+            | Self::ExportFailed(_)
+            | Self::InvalidBackup(_)
+            | Self::RestoreTargetNotEmpty
+            | Self::UnsupportedByStorage(_)
+            | Self::Unauthenticated
+            | Self::WorkspaceRequired
+            | Self::InvalidWorkspaceHeader(_)
+            | Self::OperatorRequired => None,
+            Self::TransactionNotFound(id)
+            | Self::AccountNotFound(id)
+            | Self::AccountAlreadyDeleted(id)
+            | Self::AccountPermissionDenied(id)
+            | Self::LastAccountManager(id)
+            | Self::AccountGrantNotFound(id)
+            | Self::TransactionFinished(id)
+            | Self::InstallmentNotFound(id)
+            | Self::InstallmentFinished(id)
+            | Self::SettlementNotFound(id)
+            | Self::RecurrenceNotFound(id)
+            | Self::FinancialPlanNotFound(id)
+            | Self::AccountDeleted(id)
+            | Self::TransactionDeleted(id)
+            | Self::InstallmentDeleted(id)
+            | Self::InstallmentTransactionMismatch(id)
+            | Self::FinancialPlanDeleted(id)
+            | Self::FinancialPlanClosed(id)
+            | Self::CategoryNotFound(id)
+            | Self::CategoryDeleted(id)
+            | Self::CategoryKindMismatch(id)
+            | Self::InvalidCategoryParent(id)
+            | Self::CategoryHasSubcategories(id)
+            | Self::TagNotFound(id)
+            | Self::TagDeleted(id)
+            | Self::InstitutionNotFound(id)
+            | Self::InstitutionDeleted(id)
+            | Self::InstitutionHasAccounts(id)
+            | Self::ImportNotFound(id)
+            | Self::ImportAlreadyCommitted(id)
+            | Self::RuleNotFound(id)
+            | Self::RuleDeleted(id)
+            | Self::WorkspaceNotFound(id)
+            | Self::InsufficientRole(id)
+            | Self::MemberNotFound(id)
+            | Self::LastWorkspaceOwner(id) => Some(*id),
+        }
+    }
+}
+
+/// Kind of the constraint violated by a database error, if any
+pub fn database_error_kind(err: &sqlx::Error) -> Option<ErrorKind> {
+    match err {
+        sqlx::Error::Database(db_err) => Some(db_err.kind()),
+        _ => None,
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
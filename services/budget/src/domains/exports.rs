@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{
+    format::{Item, StrftimeItems},
+    NaiveDate,
+};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    accounts::{Account, AccountType},
+    categories::Category,
+    errors::{Error, Result},
+    installments::Installment,
+    institutions::Institution,
+    settlements::Settlement,
+    transactions::{MovementType, Transaction, TransactionStatus},
+};
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+    Json,
+}
+
+/// Default number and date formatting of the spreadsheet
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en-US")]
+    EnUs,
+    #[serde(rename = "pt-BR")]
+    PtBr,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub locale: Locale,
+    /// `.` or `,`, overrides the one of the locale
+    pub decimal_separator: Option<char>,
+    /// chrono format string such as `%d/%m/%Y`, overrides the one of the locale
+    pub date_format: Option<String>,
+}
+
+/// A transaction, or one of its installments, with the joined account and settlement columns
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRow {
+    pub transaction_id: Uuid,
+    pub financial_plan_id: Uuid,
+    pub due_date: NaiveDate,
+    pub description: String,
+    pub movement_type: MovementType,
+    pub status: TransactionStatus,
+    pub value: BigDecimal,
+    pub category: Option<String>,
+    pub account_id: Uuid,
+    pub account_owner: Option<String>,
+    pub account_type: Option<AccountType>,
+    pub institution: Option<String>,
+    pub installment_id: Option<Uuid>,
+    pub installment_number: Option<i16>,
+    pub total_installments: Option<i16>,
+    pub installment_due_date: Option<NaiveDate>,
+    pub installment_value: Option<BigDecimal>,
+    pub installment_status: Option<TransactionStatus>,
+    pub paid_date: Option<NaiveDate>,
+    pub paid_value: Option<BigDecimal>,
+    pub discount: Option<BigDecimal>,
+    pub fees: Option<BigDecimal>,
+}
+
+/// Records joined into the exported rows
+pub struct ExportSources<'a> {
+    pub accounts: &'a [Account],
+    pub institutions: &'a [Institution],
+    pub categories: &'a [Category],
+    pub installments: &'a [Installment],
+    pub settlements: &'a [Settlement],
+}
+
+pub struct ExportFile {
+    pub content_type: &'static str,
+    pub file_name: String,
+    pub body: Vec<u8>,
+}
+
+struct ExportOptions {
+    decimal_separator: char,
+    date_format: String,
+}
+
+enum Cell {
+    Text(String),
+    Integer(i16),
+    Number(BigDecimal),
+    Date(NaiveDate),
+    Empty,
+}
+
+impl ExportRow {
+    const HEADERS: [&'static str; 22] = [
+        "Transaction id",
+        "Financial plan id",
+        "Due date",
+        "Description",
+        "Movement type",
+        "Status",
+        "Value",
+        "Category",
+        "Account id",
+        "Account owner",
+        "Account type",
+        "Institution",
+        "Installment id",
+        "Installment number",
+        "Total installments",
+        "Installment due date",
+        "Installment value",
+        "Installment status",
+        "Paid date",
+        "Paid value",
+        "Discount",
+        "Fees",
+    ];
+
+    /// one row per transaction, or per installment for transactions split in installments
+    pub fn collect(transactions: &[Transaction], sources: &ExportSources) -> Vec<ExportRow> {
+        let accounts: HashMap<Uuid, &Account> =
+            sources.accounts.iter().map(|a| (a.account_id, a)).collect();
+        let institutions: HashMap<Uuid, &Institution> = sources
+            .institutions
+            .iter()
+            .map(|i| (i.institution_id, i))
+            .collect();
+        let categories: HashMap<Uuid, &Category> = sources
+            .categories
+            .iter()
+            .map(|c| (c.category_id, c))
+            .collect();
+        let settlements: HashMap<(Uuid, Option<Uuid>), &Settlement> = sources
+            .settlements
+            .iter()
+            .filter(|s| s.deleted_at.is_none())
+            .map(|s| ((s.transaction_id, s.installment_id), s))
+            .collect();
+
+        let mut installments: HashMap<Uuid, Vec<&Installment>> = HashMap::new();
+
+        for installment in sources.installments.iter().filter(|i| !i.is_deleted()) {
+            installments
+                .entry(installment.transaction_id)
+                .or_default()
+                .push(installment);
+        }
+
+        let mut transactions: Vec<&Transaction> =
+            transactions.iter().filter(|t| !t.is_deleted()).collect();
+        transactions
+            .sort_by(|a, b| (a.due_date, &a.description).cmp(&(b.due_date, &b.description)));
+
+        let mut rows = vec![];
+
+        for transaction in transactions {
+            let account = accounts.get(&transaction.account_id);
+
+            let row = ExportRow {
+                transaction_id: transaction.transaction_id,
+                financial_plan_id: transaction.financial_plan_id,
+                due_date: transaction.due_date,
+                description: transaction.description.clone(),
+                movement_type: transaction.movement_type,
+                status: transaction.status,
+                value: transaction.value.clone(),
+                category: categories
+                    .get(&transaction.category_id)
+                    .map(|c| c.name.clone()),
+                account_id: transaction.account_id,
+                account_owner: account.map(|a| a.owner.clone()),
+                account_type: account.map(|a| a.account_type),
+                institution: account
+                    .and_then(|a| institutions.get(&a.institution_id))
+                    .map(|i| i.name.clone()),
+                installment_id: None,
+                installment_number: None,
+                total_installments: None,
+                installment_due_date: None,
+                installment_value: None,
+                installment_status: None,
+                paid_date: None,
+                paid_value: None,
+                discount: None,
+                fees: None,
+            };
+
+            let Some(transaction_installments) = installments.get_mut(&transaction.transaction_id)
+            else {
+                rows.push(
+                    row.with_settlement(
+                        settlements
+                            .get(&(transaction.transaction_id, None))
+                            .copied(),
+                    ),
+                );
+                continue;
+            };
+
+            transaction_installments.sort_by_key(|i| i.installment_number);
+
+            for installment in transaction_installments.iter() {
+                let settlement = settlements
+                    .get(&(transaction.transaction_id, Some(installment.installment_id)))
+                    .copied();
+
+                rows.push(
+                    ExportRow {
+                        installment_id: Some(installment.installment_id),
+                        installment_number: Some(installment.installment_number),
+                        total_installments: Some(installment.total_installment),
+                        installment_due_date: Some(installment.due_date),
+                        installment_value: Some(installment.value.clone()),
+                        installment_status: Some(installment.status),
+                        ..row.clone()
+                    }
+                    .with_settlement(settlement),
+                );
+            }
+        }
+
+        rows
+    }
+
+    fn with_settlement(mut self, settlement: Option<&Settlement>) -> Self {
+        if let Some(settlement) = settlement {
+            self.paid_date = Some(settlement.paid_date);
+            self.paid_value = Some(settlement.paid_value.clone());
+            self.discount = settlement.discount.clone();
+            self.fees = settlement.fees.clone();
+        }
+
+        self
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        let text = |value: String| Cell::Text(value);
+        let number = |value: &Option<BigDecimal>| value.clone().map_or(Cell::Empty, Cell::Number);
+        let date = |value: Option<NaiveDate>| value.map_or(Cell::Empty, Cell::Date);
+
+        vec![
+            text(self.transaction_id.to_string()),
+            text(self.financial_plan_id.to_string()),
+            Cell::Date(self.due_date),
+            text(self.description.clone()),
+            text(label(&self.movement_type)),
+            text(label(&self.status)),
+            Cell::Number(self.value.clone()),
+            self.category.clone().map_or(Cell::Empty, text),
+            text(self.account_id.to_string()),
+            self.account_owner.clone().map_or(Cell::Empty, text),
+            self.account_type
+                .map_or(Cell::Empty, |value| text(label(&value))),
+            self.institution.clone().map_or(Cell::Empty, text),
+            self.installment_id
+                .map_or(Cell::Empty, |value| text(value.to_string())),
+            self.installment_number.map_or(Cell::Empty, Cell::Integer),
+            self.total_installments.map_or(Cell::Empty, Cell::Integer),
+            date(self.installment_due_date),
+            number(&self.installment_value),
+            self.installment_status
+                .map_or(Cell::Empty, |value| text(label(&value))),
+            date(self.paid_date),
+            number(&self.paid_value),
+            number(&self.discount),
+            number(&self.fees),
+        ]
+    }
+}
+
+impl ExportParams {
+    fn options(&self) -> Result<ExportOptions> {
+        let (decimal_separator, date_format) = match self.locale {
+            Locale::EnUs => ('.', "%Y-%m-%d"),
+            Locale::PtBr => (',', "%d/%m/%Y"),
+        };
+
+        let decimal_separator = self.decimal_separator.unwrap_or(decimal_separator);
+        let date_format = self.date_format.as_deref().unwrap_or(date_format);
+
+        if !matches!(decimal_separator, '.' | ',') {
+            return Err(Error::InvalidExportParams(format!(
+                "decimal separator must be '.' or ',', got '{decimal_separator}'"
+            )));
+        }
+
+        if StrftimeItems::new(date_format).any(|item| matches!(item, Item::Error)) {
+            return Err(Error::InvalidExportParams(format!(
+                "invalid date format '{date_format}'"
+            )));
+        }
+
+        Ok(ExportOptions {
+            decimal_separator,
+            date_format: date_format.to_string(),
+        })
+    }
+
+    /// write the rows in the requested format, the name is used for the file name
+    pub fn render(&self, name: &str, rows: &[ExportRow]) -> Result<ExportFile> {
+        let options = self.options()?;
+
+        let (content_type, extension, body) = match self.format {
+            ExportFormat::Csv => (
+                "text/csv; charset=utf-8",
+                "csv",
+                render_csv(rows, &options)?,
+            ),
+            ExportFormat::Xlsx => (
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                "xlsx",
+                render_xlsx(rows, &options).map_err(|err| Error::ExportFailed(err.to_string()))?,
+            ),
+            ExportFormat::Json => (
+                "application/json",
+                "json",
+                serde_json::to_vec_pretty(rows)
+                    .map_err(|err| Error::ExportFailed(err.to_string()))?,
+            ),
+        };
+
+        Ok(ExportFile {
+            content_type,
+            file_name: format!("{name}.{extension}"),
+            body,
+        })
+    }
+}
+
+fn render_csv(rows: &[ExportRow], options: &ExportOptions) -> Result<Vec<u8>> {
+    // a comma separated file can't use comma as decimal separator
+    let delimiter = match options.decimal_separator {
+        ',' => b';',
+        _ => b',',
+    };
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(vec![]);
+
+    let failed = |err: csv::Error| Error::ExportFailed(err.to_string());
+
+    writer.write_record(ExportRow::HEADERS).map_err(failed)?;
+
+    for row in rows {
+        let record: Vec<String> = row
+            .cells()
+            .into_iter()
+            .map(|cell| match cell {
+                Cell::Text(value) => value,
+                Cell::Integer(value) => value.to_string(),
+                Cell::Number(value) => format_number(&value, options.decimal_separator),
+                Cell::Date(value) => value.format(&options.date_format).to_string(),
+                Cell::Empty => String::new(),
+            })
+            .collect();
+
+        writer.write_record(record).map_err(failed)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|err| Error::ExportFailed(err.to_string()))
+}
+
+fn render_xlsx(
+    rows: &[ExportRow],
+    options: &ExportOptions,
+) -> std::result::Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("Transactions")?;
+
+    let header = Format::new().set_bold();
+    let money = Format::new().set_num_format("0.00");
+    let date = Format::new().set_num_format(excel_date_format(&options.date_format));
+
+    for (col, title) in ExportRow::HEADERS.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *title, &header)?;
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let line = index as u32 + 1;
+
+        for (col, cell) in row.cells().into_iter().enumerate() {
+            let col = col as u16;
+
+            match cell {
+                Cell::Text(value) => worksheet.write_string(line, col, value)?,
+                Cell::Integer(value) => worksheet.write_number(line, col, value)?,
+                Cell::Number(value) => worksheet.write_number_with_format(
+                    line,
+                    col,
+                    value.to_f64().unwrap_or_default(),
+                    &money,
+                )?,
+                Cell::Date(value) => {
+                    worksheet.write_datetime_with_format(line, col, value, &date)?
+                }
+                Cell::Empty => worksheet,
+            };
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofit();
+
+    workbook.save_to_buffer()
+}
+
+fn format_number(value: &BigDecimal, decimal_separator: char) -> String {
+    value
+        .with_scale(2)
+        .to_string()
+        .replace('.', &decimal_separator.to_string())
+}
+
+/// translate the day, month and year fields of a chrono format to the excel ones
+fn excel_date_format(date_format: &str) -> String {
+    let mut format = String::new();
+    let mut chars = date_format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            format.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('d') => format.push_str("dd"),
+            Some('e') => format.push('d'),
+            Some('m') => format.push_str("mm"),
+            Some('Y') => format.push_str("yyyy"),
+            Some('y') => format.push_str("yy"),
+            Some('b') => format.push_str("mmm"),
+            Some('B') => format.push_str("mmmm"),
+            _ => return String::from("yyyy-mm-dd"),
+        }
+    }
+
+    format
+}
+
+/// name of the enum variant as it is serialized in the api
+fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(label)) => label,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_csv_with_pt_br_locale() {
+        let transaction = Transaction {
+            description: String::from("Groceries"),
+            value: BigDecimal::from(1234) / BigDecimal::from(10),
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+            ..Default::default()
+        };
+
+        let rows = ExportRow::collect(
+            &[transaction],
+            &ExportSources {
+                accounts: &[],
+                institutions: &[],
+                categories: &[],
+                installments: &[],
+                settlements: &[],
+            },
+        );
+
+        let params = ExportParams {
+            locale: Locale::PtBr,
+            ..Default::default()
+        };
+
+        let file = params.render("transactions", &rows).unwrap();
+        let content = String::from_utf8(file.body).unwrap();
+        let line = content.lines().nth(1).unwrap();
+
+        assert_eq!(file.file_name, "transactions.csv");
+        assert!(content.starts_with("Transaction id;Financial plan id;Due date;"));
+        assert!(line.contains(";05/03/2024;Groceries;"));
+        assert!(line.contains(";123,40;"));
+    }
+}
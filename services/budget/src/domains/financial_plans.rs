@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -9,6 +9,8 @@ pub struct FinancialPlan {
     pub title: Option<String>,
     pub month: MonthReference,
     pub year: i16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<DateTime<Utc>>,
@@ -25,11 +27,21 @@ impl FinancialPlan {
             })),
             month: payload.month,
             year: payload.year,
+            closed_at: None,
             created_at: Utc::now(),
             updated_at: None,
             deleted_at: None,
         }
     }
+
+    /// CLOSED plans don't accept new transactions anymore
+    pub fn is_closed(&self) -> bool {
+        self.closed_at.is_some()
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -57,3 +69,22 @@ pub enum MonthReference {
     November = 11,
     December = 12,
 }
+
+impl MonthReference {
+    pub fn from_date(date: NaiveDate) -> Self {
+        match date.month() {
+            1 => MonthReference::January,
+            2 => MonthReference::February,
+            3 => MonthReference::March,
+            4 => MonthReference::April,
+            5 => MonthReference::May,
+            6 => MonthReference::June,
+            7 => MonthReference::July,
+            8 => MonthReference::August,
+            9 => MonthReference::September,
+            10 => MonthReference::October,
+            11 => MonthReference::November,
+            _ => MonthReference::December,
+        }
+    }
+}
@@ -0,0 +1,603 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::Type;
+use uuid::Uuid;
+
+use super::{
+    errors::{Error, Result},
+    matching::{MatchParams, ProposedMatch},
+    settlements::CreateSettlement,
+    transactions::{CreateTransaction, MovementType},
+};
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementImport {
+    pub import_id: Uuid,
+    pub account_id: Uuid,
+    pub format: ImportFormat,
+    pub status: ImportStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLine {
+    pub line_id: Uuid,
+    pub import_id: Uuid,
+    /// OFX FITID or a hash of the line content
+    pub external_id: String,
+    pub due_date: NaiveDate,
+    pub description: String,
+    pub value: BigDecimal,
+    pub movement_type: MovementType,
+    pub status: ImportLineStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<Uuid>,
+    /// pending transaction proposed as the match of the line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_transaction_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_installment_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_score: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "import_format", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportFormat {
+    Ofx,
+    Csv,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "import_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportStatus {
+    Preview,
+    Committed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "import_line_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportLineStatus {
+    /// will be created on commit
+    New,
+    /// already imported before, skipped on commit
+    Duplicate,
+    /// created on commit
+    Imported,
+    /// settled an existing transaction on commit
+    Matched,
+}
+
+/// Known CSV exports
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CsvPreset {
+    /// NuConta statement: Data,Valor,Identificador,Descrição
+    Nubank,
+    /// Nubank credit card bill: date,title,amount
+    NubankCard,
+    /// Inter checking account statement
+    Inter,
+}
+
+/// Describes where each field lives in a CSV export, columns are zero based
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvLayout {
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// lines to skip before the header
+    #[serde(default)]
+    pub skip_lines: usize,
+    #[serde(default = "default_has_headers")]
+    pub has_headers: bool,
+    pub date_column: usize,
+    pub date_format: String,
+    pub description_column: usize,
+    pub value_column: usize,
+    pub id_column: Option<usize>,
+    /// values written as 1.234,56
+    #[serde(default)]
+    pub decimal_comma: bool,
+    /// positive values are expenses, as in credit card bills
+    #[serde(default)]
+    pub invert_sign: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateImport {
+    pub format: ImportFormat,
+    pub preset: Option<CsvPreset>,
+    pub layout: Option<CsvLayout>,
+    /// raw content of the statement file
+    pub content: String,
+    #[serde(default)]
+    pub matching: MatchParams,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitImport {
+    /// category of the created income transactions
+    pub income_category_id: Uuid,
+    /// category of the created expense transactions
+    pub expense_category_id: Uuid,
+    /// lines whose proposed match is refused, they become new transactions
+    #[serde(default)]
+    pub rejected_matches: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    #[serde(flatten)]
+    pub import: StatementImport,
+    pub new_lines: usize,
+    pub duplicates: usize,
+    pub matches: usize,
+    pub lines: Vec<ImportLine>,
+}
+
+/// A line read from a statement file, before deduplication
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    pub fitid: Option<String>,
+    pub date: NaiveDate,
+    pub description: String,
+    /// negative values are expenses
+    pub amount: BigDecimal,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_has_headers() -> bool {
+    true
+}
+
+impl CsvPreset {
+    pub fn layout(&self) -> CsvLayout {
+        match self {
+            CsvPreset::Nubank => CsvLayout {
+                delimiter: ',',
+                skip_lines: 0,
+                has_headers: true,
+                date_column: 0,
+                date_format: String::from("%d/%m/%Y"),
+                description_column: 3,
+                value_column: 1,
+                id_column: Some(2),
+                decimal_comma: false,
+                invert_sign: false,
+            },
+            CsvPreset::NubankCard => CsvLayout {
+                delimiter: ',',
+                skip_lines: 0,
+                has_headers: true,
+                date_column: 0,
+                date_format: String::from("%Y-%m-%d"),
+                description_column: 1,
+                value_column: 2,
+                id_column: None,
+                decimal_comma: false,
+                invert_sign: true,
+            },
+            CsvPreset::Inter => CsvLayout {
+                delimiter: ';',
+                skip_lines: 5,
+                has_headers: true,
+                date_column: 0,
+                date_format: String::from("%d/%m/%Y"),
+                description_column: 1,
+                value_column: 2,
+                id_column: None,
+                decimal_comma: true,
+                invert_sign: false,
+            },
+        }
+    }
+}
+
+impl CreateImport {
+    /// read the statement lines from the uploaded content
+    pub fn parse(&self) -> Result<Vec<StatementLine>> {
+        match self.format {
+            ImportFormat::Ofx => parse_ofx(&self.content),
+            ImportFormat::Csv => {
+                let layout = match (&self.layout, self.preset) {
+                    (Some(layout), _) => layout.clone(),
+                    (None, Some(preset)) => preset.layout(),
+                    (None, None) => {
+                        return Err(invalid_file("a CSV import needs a preset or a layout"))
+                    }
+                };
+
+                parse_csv(&self.content, &layout)
+            }
+        }
+    }
+}
+
+impl StatementImport {
+    pub fn new(account_id: Uuid, format: ImportFormat) -> Self {
+        StatementImport {
+            import_id: Uuid::new_v4(),
+            account_id,
+            format,
+            status: ImportStatus::Preview,
+            created_at: Utc::now(),
+            committed_at: None,
+        }
+    }
+
+    pub fn is_committed(&self) -> bool {
+        self.status == ImportStatus::Committed
+    }
+}
+
+impl ImportLine {
+    /// build the lines of an import, flagging the ones whose external id was already imported
+    /// or repeats inside the same file
+    pub fn from_statement(
+        import_id: Uuid,
+        statement: Vec<StatementLine>,
+        imported: &HashSet<String>,
+    ) -> Vec<ImportLine> {
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        statement
+            .into_iter()
+            .map(|line| {
+                let external_id = match &line.fitid {
+                    Some(fitid) => format!("fitid:{fitid}"),
+                    None => {
+                        // identical lines in the same file are told apart by their position
+                        let content = line.content_key();
+                        let occurrence = occurrences.entry(content.clone()).or_default();
+                        *occurrence += 1;
+
+                        let digest = Sha256::digest(format!("{content}|{occurrence}"));
+                        format!("hash:{}", hex::encode(digest))
+                    }
+                };
+
+                let status = if imported.contains(&external_id) || !seen.insert(external_id.clone())
+                {
+                    ImportLineStatus::Duplicate
+                } else {
+                    ImportLineStatus::New
+                };
+
+                let movement_type = if line.amount < BigDecimal::zero() {
+                    MovementType::Expense
+                } else {
+                    MovementType::Income
+                };
+
+                ImportLine {
+                    line_id: Uuid::new_v4(),
+                    import_id,
+                    external_id,
+                    due_date: line.date,
+                    description: line.description,
+                    value: line.amount.abs().normalized(),
+                    movement_type,
+                    status,
+                    transaction_id: None,
+                    match_transaction_id: None,
+                    match_installment_id: None,
+                    match_score: None,
+                }
+            })
+            .collect()
+    }
+
+    pub fn new_transaction(
+        &self,
+        account_id: Uuid,
+        financial_plan_id: Uuid,
+        category_id: Option<Uuid>,
+    ) -> CreateTransaction {
+        CreateTransaction {
+            financial_plan_id,
+            movement_type: self.movement_type,
+            description: self.description.clone(),
+            value: self.value.clone(),
+            due_date: self.due_date,
+            category_id,
+            account_id,
+            installments: 0,
+        }
+    }
+
+    pub fn propose_match(&mut self, proposal: &ProposedMatch) {
+        self.match_transaction_id = Some(proposal.transaction_id);
+        self.match_installment_id = proposal.installment_id;
+        self.match_score = Some(proposal.score);
+    }
+
+    /// settlement of the line, paying less than expected is a discount and more is fees
+    pub fn new_settlement(&self, expected: &BigDecimal) -> CreateSettlement {
+        let difference = &self.value - expected;
+
+        CreateSettlement {
+            paid_date: self.due_date,
+            paid_value: self.value.clone(),
+            discount: (difference < BigDecimal::zero()).then(|| difference.abs().normalized()),
+            fees: (difference > BigDecimal::zero()).then(|| difference.normalized()),
+            attachment: None,
+        }
+    }
+}
+
+impl ImportPreview {
+    pub fn new(import: StatementImport, lines: Vec<ImportLine>) -> Self {
+        ImportPreview {
+            new_lines: lines
+                .iter()
+                .filter(|l| l.status == ImportLineStatus::New)
+                .count(),
+            duplicates: lines
+                .iter()
+                .filter(|l| l.status == ImportLineStatus::Duplicate)
+                .count(),
+            matches: lines
+                .iter()
+                .filter(|l| l.match_transaction_id.is_some())
+                .count(),
+            import,
+            lines,
+        }
+    }
+}
+
+impl StatementLine {
+    fn content_key(&self) -> String {
+        format!(
+            "{}|{}|{}",
+            self.date,
+            self.amount.normalized(),
+            self.description.trim().to_lowercase()
+        )
+    }
+}
+
+fn invalid_file(reason: impl Into<String>) -> Error {
+    Error::InvalidStatementFile(reason.into())
+}
+
+/// OFX 1.x files are SGML, leaf tags are usually not closed so values run until the next tag
+fn parse_ofx(content: &str) -> Result<Vec<StatementLine>> {
+    let blocks: Vec<&str> = content
+        .split("<STMTTRN>")
+        .skip(1)
+        .map(|block| block.split("</STMTTRN>").next().unwrap_or(block))
+        .collect();
+
+    if blocks.is_empty() && !content.contains("<OFX>") {
+        return Err(invalid_file("not an OFX file"));
+    }
+
+    blocks
+        .into_iter()
+        .enumerate()
+        .map(|(index, block)| {
+            let field = |tag: &str| {
+                ofx_value(block, tag).ok_or_else(|| {
+                    invalid_file(format!("transaction {}: missing {tag}", index + 1))
+                })
+            };
+
+            let posted = field("DTPOSTED")?;
+            let date = posted
+                .get(..8)
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+                .ok_or_else(|| {
+                    invalid_file(format!("transaction {}: invalid date {posted}", index + 1))
+                })?;
+
+            let amount = field("TRNAMT")?;
+            let amount = parse_amount(amount, false).ok_or_else(|| {
+                invalid_file(format!(
+                    "transaction {}: invalid amount {amount}",
+                    index + 1
+                ))
+            })?;
+
+            let description = ofx_value(block, "MEMO")
+                .or_else(|| ofx_value(block, "NAME"))
+                .unwrap_or_default()
+                .to_string();
+
+            Ok(StatementLine {
+                fitid: ofx_value(block, "FITID").map(String::from),
+                date,
+                description,
+                amount,
+            })
+        })
+        .collect()
+}
+
+fn ofx_value<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+    let start = block.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let value = block[start..].split('<').next()?.trim();
+
+    (!value.is_empty()).then_some(value)
+}
+
+fn parse_csv(content: &str, layout: &CsvLayout) -> Result<Vec<StatementLine>> {
+    // the reader splits on a single byte, a wider char would be cut to another one
+    let delimiter = Some(layout.delimiter)
+        .filter(char::is_ascii)
+        .and_then(|delimiter| u8::try_from(delimiter).ok())
+        .ok_or_else(|| invalid_file(format!("the delimiter {} is not ASCII", layout.delimiter)))?;
+
+    let content: String = content
+        .lines()
+        .skip(layout.skip_lines)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(layout.has_headers)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    reader
+        .records()
+        .enumerate()
+        .filter(|(_, record)| !matches!(record, Ok(r) if r.iter().all(|f| f.trim().is_empty())))
+        .map(|(index, record)| {
+            let row = index + 1;
+            let record = record.map_err(|err| invalid_file(format!("row {row}: {err}")))?;
+
+            let column = |position: usize| {
+                record
+                    .get(position)
+                    .map(str::trim)
+                    .ok_or_else(|| invalid_file(format!("row {row}: missing column {position}")))
+            };
+
+            let date = column(layout.date_column)?;
+            let date = NaiveDate::parse_from_str(date, &layout.date_format)
+                .map_err(|_| invalid_file(format!("row {row}: invalid date {date}")))?;
+
+            let value = column(layout.value_column)?;
+            let mut amount = parse_amount(value, layout.decimal_comma)
+                .ok_or_else(|| invalid_file(format!("row {row}: invalid value {value}")))?;
+
+            if layout.invert_sign {
+                amount = -amount;
+            }
+
+            let fitid = match layout.id_column {
+                Some(position) => Some(column(position)?.to_string()).filter(|id| !id.is_empty()),
+                None => None,
+            };
+
+            Ok(StatementLine {
+                fitid,
+                date,
+                description: column(layout.description_column)?.to_string(),
+                amount,
+            })
+        })
+        .collect()
+}
+
+fn parse_amount(value: &str, decimal_comma: bool) -> Option<BigDecimal> {
+    let value: String = value
+        .trim()
+        .trim_start_matches("R$")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    let value = if decimal_comma {
+        value.replace('.', "").replace(',', ".")
+    } else {
+        value.replace(',', "")
+    };
+
+    BigDecimal::from_str(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_ofx_transactions() {
+        let content = r#"
+OFXHEADER:100
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20260310000000[-3:BRT]
+<TRNAMT>-42.90
+<FITID>abc-1
+<MEMO>UBER *TRIP
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20260305
+<TRNAMT>5000.00
+<FITID>abc-2
+<NAME>SALARIO
+</STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>
+"#;
+
+        let lines = parse_ofx(content).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].fitid.as_deref(), Some("abc-1"));
+        assert_eq!(lines[0].date, NaiveDate::from_ymd_opt(2026, 3, 10).unwrap());
+        assert_eq!(lines[0].amount, BigDecimal::from_str("-42.90").unwrap());
+        assert_eq!(lines[0].description, "UBER *TRIP");
+        assert_eq!(lines[1].description, "SALARIO");
+    }
+
+    #[test]
+    fn should_refuse_a_delimiter_that_is_not_ascii() {
+        let layout = CsvLayout {
+            delimiter: '¬',
+            ..CsvPreset::Inter.layout()
+        };
+
+        let lines = parse_csv("10/03/2026¬Padaria¬-12,50\n", &layout);
+
+        assert!(matches!(lines, Err(Error::InvalidStatementFile(_))));
+    }
+
+    #[test]
+    fn should_parse_inter_csv_with_decimal_comma() {
+        let content = "Extrato Conta Corrente\nConta ;123\nPeríodo ;01/03/2026 a 31/03/2026\nSaldo ;1.000,00\n\nData Lançamento;Descrição;Valor;Saldo\n10/03/2026;Pix enviado: Padaria;-1.234,56;100,00\n11/03/2026;Pix recebido: Ana;50,00;150,00\n";
+
+        let lines = parse_csv(content, &CsvPreset::Inter.layout()).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].amount, BigDecimal::from_str("-1234.56").unwrap());
+        assert_eq!(lines[1].description, "Pix recebido: Ana");
+    }
+
+    #[test]
+    fn should_flag_lines_already_imported() {
+        let line = StatementLine {
+            fitid: None,
+            date: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+            description: String::from("Coffee"),
+            amount: BigDecimal::from(-5),
+        };
+
+        let first = ImportLine::from_statement(Uuid::new_v4(), vec![line.clone()], &HashSet::new());
+        let imported = HashSet::from([first[0].external_id.clone()]);
+
+        // the same coffee bought twice on the same day only has its first purchase imported
+        let lines = ImportLine::from_statement(Uuid::new_v4(), vec![line.clone(), line], &imported);
+
+        assert_eq!(lines[0].status, ImportLineStatus::Duplicate);
+        assert_eq!(lines[1].status, ImportLineStatus::New);
+        assert_eq!(lines[1].movement_type, MovementType::Expense);
+        assert_eq!(lines[1].value, BigDecimal::from(5));
+    }
+}
@@ -37,15 +37,6 @@ pub struct InstallmentParams {
     pub total_installment: i16,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateInstallment {
-    pub transaction_id: Uuid,
-    pub installment_number: i16,
-    pub due_date: NaiveDate,
-    pub value: BigDecimal,
-    pub status: TransactionStatus,
-}
-
 impl InstallmentParams {
     pub fn new(number: i16, total: i16) -> Self {
         InstallmentParams {
@@ -69,9 +60,13 @@ impl PartialInstallment {
 
 impl Installment {
     pub fn is_finished(&self) -> bool {
-        match self.status {
-            TransactionStatus::Completed | TransactionStatus::Canceled => true,
-            _ => false,
-        }
+        matches!(
+            self.status,
+            TransactionStatus::Completed | TransactionStatus::Canceled
+        )
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
     }
 }
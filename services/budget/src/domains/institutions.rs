@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::update_fields;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Institution {
+    pub institution_id: Uuid,
+    pub name: String,
+    /// COMPE or ISPB code, empty for wallets and benefit cards
+    pub code: Option<String>,
+    pub logo_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Institutions every workspace starts with, the ones the legacy workspace got from the old enum
+pub const DEFAULT_INSTITUTIONS: [(&str, Option<&str>); 7] = [
+    ("Nubank", Some("260")),
+    ("Inter", Some("077")),
+    ("Santander", Some("033")),
+    ("Itaú", Some("341")),
+    ("Bradesco", Some("237")),
+    ("Banco do Brasil", Some("001")),
+    ("Swile", None),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInstitution {
+    pub name: String,
+    pub code: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInstitution {
+    pub name: Option<String>,
+    pub code: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+impl Institution {
+    pub fn new_from_payload(payload: CreateInstitution) -> Self {
+        Institution {
+            institution_id: Uuid::new_v4(),
+            name: payload.name,
+            code: payload.code,
+            logo_url: payload.logo_url,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    /// institutions of a new workspace
+    pub fn defaults() -> Vec<Self> {
+        DEFAULT_INSTITUTIONS
+            .into_iter()
+            .map(|(name, code)| {
+                Institution::new_from_payload(CreateInstitution {
+                    name: name.to_string(),
+                    code: code.map(str::to_string),
+                    logo_url: None,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// prepare an institution to be updated
+    pub fn update(&mut self, data: UpdateInstitution) {
+        if data.code.is_some() {
+            self.code = data.code;
+        }
+        if data.logo_url.is_some() {
+            self.logo_url = data.logo_url;
+        }
+        update_fields!(self, data, name);
+        self.updated_at = Some(Utc::now());
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::{HashMap, HashSet};
+
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{
+    imports::{ImportLine, ImportLineStatus},
+    installments::Installment,
+    transactions::{MovementType, Transaction, TransactionStatus},
+};
+
+/// How close an imported line must be to a pending transaction to be proposed as its match
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchParams {
+    /// accepted difference between the values, as a fraction of the expected value
+    pub amount_tolerance: BigDecimal,
+    /// accepted distance in days between the due date and the statement date
+    pub date_window_days: i64,
+    /// minimum description similarity, from 0 to 1
+    pub min_similarity: f64,
+}
+
+/// A pending transaction, or one of its pending installments, that can be settled by a line
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchCandidate {
+    pub transaction_id: Uuid,
+    pub installment_id: Option<Uuid>,
+    pub movement_type: MovementType,
+    pub due_date: NaiveDate,
+    pub value: BigDecimal,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposedMatch {
+    pub transaction_id: Uuid,
+    pub installment_id: Option<Uuid>,
+    pub score: f64,
+}
+
+impl Default for MatchParams {
+    fn default() -> Self {
+        MatchParams {
+            amount_tolerance: BigDecimal::new(1.into(), 2),
+            date_window_days: 5,
+            min_similarity: 0.0,
+        }
+    }
+}
+
+impl MatchCandidate {
+    /// pending transactions of the account, transactions split in installments are matched
+    /// by their pending installments instead
+    pub fn collect(
+        account_id: Uuid,
+        transactions: &[Transaction],
+        installments: &[Installment],
+    ) -> Vec<MatchCandidate> {
+        let mut by_transaction: HashMap<Uuid, Vec<&Installment>> = HashMap::new();
+
+        for installment in installments.iter().filter(|i| !i.is_deleted()) {
+            by_transaction
+                .entry(installment.transaction_id)
+                .or_default()
+                .push(installment);
+        }
+
+        transactions
+            .iter()
+            .filter(|t| t.account_id == account_id)
+            .filter(|t| t.status == TransactionStatus::Pending && !t.is_deleted())
+            .flat_map(|t| match by_transaction.get(&t.transaction_id) {
+                None => vec![MatchCandidate {
+                    transaction_id: t.transaction_id,
+                    installment_id: None,
+                    movement_type: t.movement_type,
+                    due_date: t.due_date,
+                    value: t.value.clone(),
+                    description: t.description.clone(),
+                }],
+                Some(installments) => installments
+                    .iter()
+                    .filter(|i| !i.is_finished())
+                    .map(|i| MatchCandidate {
+                        transaction_id: t.transaction_id,
+                        installment_id: Some(i.installment_id),
+                        movement_type: t.movement_type,
+                        due_date: i.due_date,
+                        value: i.value.clone(),
+                        description: t.description.clone(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// from 0 to 1, none when the line is out of the accepted range
+    fn score(&self, line: &ImportLine, params: &MatchParams) -> Option<f64> {
+        if line.movement_type != self.movement_type {
+            return None;
+        }
+
+        let days = (line.due_date - self.due_date).num_days().abs();
+        if days > params.date_window_days {
+            return None;
+        }
+
+        let difference = (&line.value - &self.value).abs();
+        let allowed = (&self.value * &params.amount_tolerance).abs();
+        if difference > allowed {
+            return None;
+        }
+
+        let similarity = similarity(&line.description, &self.description);
+        if similarity < params.min_similarity {
+            return None;
+        }
+
+        let amount_score = if allowed.is_zero() {
+            1.0
+        } else {
+            1.0 - (difference / allowed).to_f64().unwrap_or(1.0)
+        };
+        let date_score = 1.0 - days as f64 / (params.date_window_days + 1) as f64;
+
+        Some(0.5 * amount_score + 0.3 * date_score + 0.2 * similarity)
+    }
+}
+
+/// propose at most one candidate for each new line, best scores are assigned first
+pub fn propose_matches(
+    lines: &[ImportLine],
+    candidates: &[MatchCandidate],
+    params: &MatchParams,
+) -> HashMap<Uuid, ProposedMatch> {
+    let mut pairs: Vec<(f64, &ImportLine, &MatchCandidate)> = lines
+        .iter()
+        .filter(|line| line.status == ImportLineStatus::New)
+        .flat_map(|line| {
+            candidates
+                .iter()
+                .filter_map(move |c| c.score(line, params).map(|score| (score, line, c)))
+        })
+        .collect();
+
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut matched_lines = HashSet::new();
+    let mut matched_candidates = HashSet::new();
+    let mut proposals = HashMap::new();
+
+    for (score, line, candidate) in pairs {
+        let candidate_key = (candidate.transaction_id, candidate.installment_id);
+
+        if matched_lines.contains(&line.line_id) || matched_candidates.contains(&candidate_key) {
+            continue;
+        }
+
+        matched_lines.insert(line.line_id);
+        matched_candidates.insert(candidate_key);
+        proposals.insert(
+            line.line_id,
+            ProposedMatch {
+                transaction_id: candidate.transaction_id,
+                installment_id: candidate.installment_id,
+                score,
+            },
+        );
+    }
+
+    proposals
+}
+
+/// share of words in common between two descriptions, ignoring case and punctuation
+fn similarity(left: &str, right: &str) -> f64 {
+    let words = |text: &str| -> HashSet<String> {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| word.len() > 1)
+            .map(String::from)
+            .collect()
+    };
+
+    let (left, right) = (words(left), words(right));
+
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+
+    left.intersection(&right).count() as f64 / left.union(&right).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn new_line(description: &str, value: &str, day: u32) -> ImportLine {
+        ImportLine {
+            line_id: Uuid::new_v4(),
+            import_id: Uuid::new_v4(),
+            external_id: Uuid::new_v4().to_string(),
+            due_date: NaiveDate::from_ymd_opt(2026, 3, day).unwrap(),
+            description: description.to_string(),
+            value: BigDecimal::from_str(value).unwrap(),
+            movement_type: MovementType::Expense,
+            status: ImportLineStatus::New,
+            transaction_id: None,
+            match_transaction_id: None,
+            match_installment_id: None,
+            match_score: None,
+        }
+    }
+
+    fn new_candidate(description: &str, value: &str, day: u32) -> MatchCandidate {
+        MatchCandidate {
+            transaction_id: Uuid::new_v4(),
+            installment_id: None,
+            movement_type: MovementType::Expense,
+            due_date: NaiveDate::from_ymd_opt(2026, 3, day).unwrap(),
+            value: BigDecimal::from_str(value).unwrap(),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn should_propose_the_closest_pending_transaction() {
+        let rent = new_candidate("Rent", "1500", 10);
+        let energy = new_candidate("Energy bill", "230", 12);
+
+        let lines = vec![
+            new_line("PIX RENT MARCH", "1500", 11),
+            new_line("ENERGY BILL", "231.50", 14),
+            new_line("ENERGY BILL", "300", 12),
+            new_line("COFFEE", "1500", 30),
+        ];
+
+        let proposals = propose_matches(
+            &lines,
+            &[rent.clone(), energy.clone()],
+            &MatchParams::default(),
+        );
+
+        assert_eq!(proposals.len(), 2);
+        assert_eq!(
+            proposals[&lines[0].line_id].transaction_id,
+            rent.transaction_id
+        );
+        assert_eq!(
+            proposals[&lines[1].line_id].transaction_id,
+            energy.transaction_id
+        );
+    }
+}
@@ -1,8 +1,19 @@
 pub mod accounts;
+pub mod audit;
+pub mod backups;
+pub mod categories;
 pub mod errors;
+pub mod exports;
+pub mod imports;
 pub mod installments;
+pub mod institutions;
+pub mod matching;
 pub mod recurrences;
+pub mod reports;
+pub mod rules;
 pub mod settlements;
+pub mod tags;
 pub mod transactions;
 pub mod views;
 pub mod financial_plans;
+pub mod workspaces;
@@ -6,7 +6,7 @@ use uuid::Uuid;
 
 use crate::update_fields;
 
-use super::transactions::{Category, CreateTransaction, MovementType};
+use super::transactions::{CreateTransaction, MovementType};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct Recurrence {
@@ -14,7 +14,7 @@ pub struct Recurrence {
     pub account_id: Uuid,
     pub title: String,
     pub frequency: Frequency,
-    pub category: Category,
+    pub category_id: Uuid,
     pub is_active: bool,
     pub start_date: NaiveDate,
     pub value: BigDecimal,
@@ -40,11 +40,12 @@ pub struct CreateRecurrenceLink {
 }
 
 #[derive(Debug, Deserialize, Serialize, Type, Clone)]
-#[sqlx(type_name = "frequency")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "frequency", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Frequency {
-    MONTHLY,
-    WEEKLY,
-    ANNUALLY,
+    Monthly,
+    Weekly,
+    Annually,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,7 +54,8 @@ pub struct CreateRecurrence {
     pub account_id: Uuid,
     pub title: String,
     pub frequency: Frequency,
-    pub category: Category,
+    /// when missing the category comes from the first matching rule
+    pub category_id: Option<Uuid>,
     #[serde(default)]
     pub is_active: bool,
     pub start_date: NaiveDate,
@@ -67,7 +69,7 @@ pub struct UpdateRecurrence {
     pub account_id: Option<Uuid>,
     pub title: Option<String>,
     pub frequency: Option<Frequency>,
-    pub category: Option<Category>,
+    pub category_id: Option<Uuid>,
     pub is_active: Option<bool>,
     pub start_date: Option<NaiveDate>,
     pub value: Option<BigDecimal>,
@@ -75,13 +77,13 @@ pub struct UpdateRecurrence {
 }
 
 impl Recurrence {
-    pub fn new_from_payload(payload: CreateRecurrence) -> Self {
+    pub fn new_from_payload(payload: CreateRecurrence, category_id: Uuid) -> Self {
         Recurrence {
             recurrence_id: Uuid::new_v4(),
             account_id: payload.account_id,
             title: payload.title,
             frequency: payload.frequency,
-            category: payload.category,
+            category_id,
             is_active: payload.is_active,
             start_date: payload.start_date,
             value: payload.value,
@@ -95,9 +97,9 @@ impl Recurrence {
     pub fn new_recurrency_transaction(&self, next_due_date: NaiveDate, financial_plan_id: Uuid) -> CreateTransaction {
         CreateTransaction {
             account_id: self.account_id,
-            financial_plan_id: financial_plan_id,
+            financial_plan_id,
             description: self.title.clone(),
-            category: self.category,
+            category_id: Some(self.category_id),
             due_date: next_due_date,
             installments: 0,
             movement_type: self.movement_type,
@@ -107,9 +109,9 @@ impl Recurrence {
 
     pub fn get_next_date_from_frequency(&self, target_date: NaiveDate) -> NaiveDate {
         match self.frequency {
-            Frequency::WEEKLY => target_date + Duration::days(7),
-            Frequency::MONTHLY => target_date + Duration::days(30),
-            Frequency::ANNUALLY => target_date + Duration::days(365),
+            Frequency::Weekly => target_date + Duration::days(7),
+            Frequency::Monthly => target_date + Duration::days(30),
+            Frequency::Annually => target_date + Duration::days(365),
         }
     }
 
@@ -121,7 +123,7 @@ impl Recurrence {
             account_id,
             title,
             frequency,
-            category,
+            category_id,
             is_active,
             start_date,
             value,
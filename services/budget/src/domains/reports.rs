@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    categories::{Category, CategoryKind},
+    tags::{Tag, TransactionTag},
+    transactions::{MovementType, Transaction, TransactionStatus},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportParams {
+    pub financial_plan_id: Option<Uuid>,
+    /// first due date counted
+    pub from: Option<NaiveDate>,
+    /// last due date counted
+    pub to: Option<NaiveDate>,
+    /// only count a category and its subcategories
+    pub category_id: Option<Uuid>,
+}
+
+/// The transactions counted by a report, selected by the repository
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportFilter {
+    pub financial_plan_id: Option<Uuid>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// every category when missing
+    pub category_ids: Option<Vec<Uuid>>,
+}
+
+impl ReportFilter {
+    pub fn new(params: &ReportParams, category_ids: Option<Vec<Uuid>>) -> Self {
+        ReportFilter {
+            financial_plan_id: params.financial_plan_id,
+            from: params.from,
+            to: params.to,
+            category_ids,
+        }
+    }
+
+    /// canceled and deleted transactions are never counted
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        !transaction.is_deleted()
+            && transaction.status != TransactionStatus::Canceled
+            && self
+                .financial_plan_id
+                .is_none_or(|id| transaction.financial_plan_id == id)
+            && self.from.is_none_or(|from| transaction.due_date >= from)
+            && self.to.is_none_or(|to| transaction.due_date <= to)
+            && self
+                .category_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&transaction.category_id))
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTotal {
+    pub category_id: Uuid,
+    pub name: String,
+    pub kind: CategoryKind,
+    pub total: BigDecimal,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subcategories: Vec<CategoryTotal>,
+}
+
+impl CategoryTotal {
+    /// sum the transactions by root category, subcategories are rolled up into their parents,
+    /// deleted categories are left out
+    pub fn rollup(categories: &[Category], transactions: &[Transaction]) -> Vec<CategoryTotal> {
+        let mut totals: HashMap<Uuid, BigDecimal> = HashMap::new();
+
+        for transaction in transactions {
+            if transaction.is_deleted() || transaction.status == TransactionStatus::Canceled {
+                continue;
+            }
+
+            *totals.entry(transaction.category_id).or_default() += &transaction.value;
+        }
+
+        let categories: Vec<&Category> = categories
+            .iter()
+            .filter(|category| !category.is_deleted())
+            .collect();
+
+        categories
+            .iter()
+            .filter(|category| category.is_root())
+            .filter_map(|root| {
+                let subcategories: Vec<CategoryTotal> = categories
+                    .iter()
+                    .filter(|category| category.parent_id == Some(root.category_id))
+                    .filter_map(|category| {
+                        totals
+                            .get(&category.category_id)
+                            .map(|total| CategoryTotal::new(category, total.clone(), vec![]))
+                    })
+                    .collect();
+
+                let own_total = totals.get(&root.category_id);
+
+                if own_total.is_none() && subcategories.is_empty() {
+                    return None;
+                }
+
+                let total = subcategories
+                    .iter()
+                    .fold(own_total.cloned().unwrap_or_default(), |acc, sub| {
+                        acc + &sub.total
+                    });
+
+                Some(CategoryTotal::new(root, total, subcategories))
+            })
+            .collect()
+    }
+
+    fn new(category: &Category, total: BigDecimal, subcategories: Vec<CategoryTotal>) -> Self {
+        CategoryTotal {
+            category_id: category.category_id,
+            name: category.name.clone(),
+            kind: category.kind,
+            total: total.normalized(),
+            subcategories,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagTotal {
+    pub tag_id: Uuid,
+    pub name: String,
+    pub income: BigDecimal,
+    pub expense: BigDecimal,
+}
+
+impl TagTotal {
+    /// sum income and expenses of the transactions carrying each tag
+    pub fn summarize(
+        tags: &[Tag],
+        transactions: &[Transaction],
+        links: &[TransactionTag],
+    ) -> Vec<TagTotal> {
+        let counted: HashSet<Uuid> = transactions
+            .iter()
+            .filter(|t| !t.is_deleted() && t.status != TransactionStatus::Canceled)
+            .map(|t| t.transaction_id)
+            .collect();
+
+        let by_transaction: HashMap<Uuid, &Transaction> =
+            transactions.iter().map(|t| (t.transaction_id, t)).collect();
+
+        tags.iter()
+            .filter(|tag| !tag.is_deleted())
+            .filter_map(|tag| {
+                let tagged: Vec<&Transaction> = links
+                    .iter()
+                    .filter(|link| link.tag_id == tag.tag_id)
+                    .filter(|link| counted.contains(&link.transaction_id))
+                    .filter_map(|link| by_transaction.get(&link.transaction_id).copied())
+                    .collect();
+
+                if tagged.is_empty() {
+                    return None;
+                }
+
+                let (income, expense) = tagged.iter().fold(
+                    (BigDecimal::default(), BigDecimal::default()),
+                    |(income, expense), t| match t.movement_type {
+                        MovementType::Income => (income + &t.value, expense),
+                        MovementType::Expense => (income, expense + &t.value),
+                    },
+                );
+
+                Some(TagTotal {
+                    tag_id: tag.tag_id,
+                    name: tag.name.clone(),
+                    income: income.normalized(),
+                    expense: expense.normalized(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn new_category(name: &str, parent_id: Option<Uuid>) -> Category {
+        Category {
+            category_id: Uuid::new_v4(),
+            parent_id,
+            name: name.to_string(),
+            icon: None,
+            color: None,
+            kind: CategoryKind::Expense,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    fn new_transaction(category_id: Uuid, value: i32) -> Transaction {
+        Transaction {
+            category_id,
+            value: BigDecimal::from(value),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_roll_subcategories_up_into_parents() {
+        let home = new_category("Home", None);
+        let pets = new_category("Pets", None);
+        let rent = new_category("Rent", Some(home.category_id));
+        let energy = new_category("Energy", Some(home.category_id));
+
+        let mut canceled = new_transaction(energy.category_id, 1000);
+        canceled.status = TransactionStatus::Canceled;
+
+        let transactions = vec![
+            new_transaction(home.category_id, 10),
+            new_transaction(rent.category_id, 100),
+            new_transaction(energy.category_id, 50),
+            canceled,
+        ];
+
+        let report = CategoryTotal::rollup(&[home, pets, rent, energy], &transactions);
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].name, "Home");
+        assert_eq!(report[0].total, BigDecimal::from(160));
+        assert_eq!(report[0].subcategories.len(), 2);
+    }
+
+    #[test]
+    fn should_leave_deleted_categories_out_of_the_rollup() {
+        let home = new_category("Home", None);
+        let mut rent = new_category("Rent", Some(home.category_id));
+        rent.deleted_at = Some(Utc::now());
+        let mut pets = new_category("Pets", None);
+        pets.deleted_at = Some(Utc::now());
+
+        let transactions = vec![
+            new_transaction(home.category_id, 10),
+            new_transaction(rent.category_id, 100),
+            new_transaction(pets.category_id, 50),
+        ];
+
+        let report = CategoryTotal::rollup(&[home, rent, pets], &transactions);
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].total, BigDecimal::from(10));
+        assert!(report[0].subcategories.is_empty());
+    }
+}
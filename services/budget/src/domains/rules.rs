@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::update_fields;
+
+use super::transactions::MovementType;
+
+/// Sets the category and tags of records whose description contains the pattern
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub rule_id: Uuid,
+    pub name: String,
+    /// case insensitive text searched in the description
+    pub pattern: String,
+    pub movement_type: MovementType,
+    pub category_id: Uuid,
+    pub tag_ids: Vec<Uuid>,
+    /// rules with lower priority run first, the first matching rule wins
+    pub priority: i32,
+    pub hits: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_hit_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRule {
+    pub name: String,
+    pub pattern: String,
+    pub movement_type: MovementType,
+    pub category_id: Uuid,
+    #[serde(default)]
+    pub tag_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRule {
+    pub name: Option<String>,
+    pub pattern: Option<String>,
+    pub movement_type: Option<MovementType>,
+    pub category_id: Option<Uuid>,
+    pub tag_ids: Option<Vec<Uuid>>,
+    pub priority: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RuleTarget {
+    Transaction,
+    Recurrence,
+}
+
+/// What applying a rule changes, or would change, on an existing record
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleChange {
+    pub rule_id: Uuid,
+    pub target: RuleTarget,
+    pub record_id: Uuid,
+    pub description: String,
+    pub previous_category_id: Uuid,
+    pub category_id: Uuid,
+    pub added_tag_ids: Vec<Uuid>,
+}
+
+impl Rule {
+    pub fn new_from_payload(payload: CreateRule) -> Self {
+        Rule {
+            rule_id: Uuid::new_v4(),
+            name: payload.name,
+            pattern: payload.pattern,
+            movement_type: payload.movement_type,
+            category_id: payload.category_id,
+            tag_ids: payload.tag_ids,
+            priority: payload.priority,
+            hits: 0,
+            last_hit_at: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// prepare a rule to be updated
+    pub fn update(&mut self, data: UpdateRule) {
+        update_fields!(
+            self,
+            data,
+            name,
+            pattern,
+            movement_type,
+            category_id,
+            tag_ids,
+            priority
+        );
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn matches(&self, description: &str, movement_type: MovementType) -> bool {
+        !self.is_deleted()
+            && self.movement_type == movement_type
+            && description
+                .to_lowercase()
+                .contains(&self.pattern.trim().to_lowercase())
+    }
+
+    /// first rule by priority matching the record, ties are broken by the oldest rule
+    pub fn find<'a>(
+        rules: &'a [Rule],
+        description: &str,
+        movement_type: MovementType,
+    ) -> Option<&'a Rule> {
+        rules
+            .iter()
+            .filter(|rule| rule.matches(description, movement_type))
+            .min_by_key(|rule| (rule.priority, rule.created_at))
+    }
+}
+
+impl RuleChange {
+    /// none when the record already has the category and tags of the rule
+    pub fn new(
+        rule: &Rule,
+        target: RuleTarget,
+        record_id: Uuid,
+        description: &str,
+        category_id: Uuid,
+        tags: Option<&HashSet<Uuid>>,
+    ) -> Option<RuleChange> {
+        let added_tag_ids: Vec<Uuid> = rule
+            .tag_ids
+            .iter()
+            .filter(|tag_id| tags.is_none_or(|tags| !tags.contains(tag_id)))
+            .copied()
+            .collect();
+
+        if rule.category_id == category_id && added_tag_ids.is_empty() {
+            return None;
+        }
+
+        Some(RuleChange {
+            rule_id: rule.rule_id,
+            target,
+            record_id,
+            description: description.to_string(),
+            previous_category_id: category_id,
+            category_id: rule.category_id,
+            added_tag_ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_rule(pattern: &str, priority: i32) -> Rule {
+        Rule::new_from_payload(CreateRule {
+            name: pattern.to_string(),
+            pattern: pattern.to_string(),
+            movement_type: MovementType::Expense,
+            category_id: Uuid::new_v4(),
+            tag_ids: vec![],
+            priority,
+        })
+    }
+
+    #[test]
+    fn should_find_the_matching_rule_with_lowest_priority() {
+        let uber = new_rule("uber", 10);
+        let uber_eats = new_rule("UBER EATS", 1);
+        let rules = vec![uber.clone(), uber_eats.clone()];
+
+        let found =
+            |description| Rule::find(&rules, description, MovementType::Expense).map(|r| r.rule_id);
+
+        assert_eq!(found("Uber *Trip"), Some(uber.rule_id));
+        assert_eq!(found("UBER EATS SAO PAULO"), Some(uber_eats.rule_id));
+        assert_eq!(found("Bakery"), None);
+        assert_eq!(
+            Rule::find(&rules, "Uber refund", MovementType::Income),
+            None
+        );
+    }
+}
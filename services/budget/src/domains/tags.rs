@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::update_fields;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub tag_id: Uuid,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTag {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTag {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTag {
+    pub transaction_id: Uuid,
+    pub tag_id: Uuid,
+}
+
+/// How a listing filtered by many tags should match them
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TagMatch {
+    /// at least one of the tags
+    #[default]
+    Any,
+    /// every one of the tags
+    All,
+}
+
+impl Tag {
+    pub fn new_from_payload(payload: CreateTag) -> Self {
+        Tag {
+            tag_id: Uuid::new_v4(),
+            name: payload.name,
+            color: payload.color,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// prepare a tag to be updated
+    pub fn update(&mut self, data: UpdateTag) {
+        if data.color.is_some() {
+            self.color = data.color;
+        }
+        update_fields!(self, data, name);
+        self.updated_at = Some(Utc::now());
+    }
+}
+
+impl TransactionTag {
+    /// index the tag ids attached to each transaction
+    pub fn group_by_transaction(links: &[TransactionTag]) -> HashMap<Uuid, HashSet<Uuid>> {
+        let mut groups: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+
+        for link in links {
+            groups
+                .entry(link.transaction_id)
+                .or_default()
+                .insert(link.tag_id);
+        }
+
+        groups
+    }
+}
+
+impl TagMatch {
+    pub fn matches(&self, wanted: &[Uuid], tags: Option<&HashSet<Uuid>>) -> bool {
+        let Some(tags) = tags else {
+            return wanted.is_empty();
+        };
+
+        match self {
+            TagMatch::Any => wanted.iter().any(|tag_id| tags.contains(tag_id)),
+            TagMatch::All => wanted.iter().all(|tag_id| tags.contains(tag_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_match_any_or_all_tags() {
+        let (vacation, reimbursable, work) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let tags = HashSet::from([vacation, reimbursable]);
+
+        assert!(TagMatch::Any.matches(&[vacation, work], Some(&tags)));
+        assert!(!TagMatch::All.matches(&[vacation, work], Some(&tags)));
+        assert!(TagMatch::All.matches(&[vacation, reimbursable], Some(&tags)));
+        assert!(!TagMatch::Any.matches(&[work], None));
+    }
+}
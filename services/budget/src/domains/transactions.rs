@@ -15,7 +15,7 @@ pub struct Transaction {
     pub description: String,
     pub value: BigDecimal,
     pub due_date: NaiveDate,
-    pub category: Category,
+    pub category_id: Uuid,
     pub account_id: Uuid,
     pub status: TransactionStatus,
     pub created_at: DateTime<Utc>,
@@ -33,7 +33,8 @@ pub struct CreateTransaction {
     pub description: String,
     pub value: BigDecimal,
     pub due_date: NaiveDate,
-    pub category: Category,
+    /// when missing the category comes from the first matching rule
+    pub category_id: Option<Uuid>,
     pub account_id: Uuid,
     pub installments: i16,
 }
@@ -45,8 +46,9 @@ pub struct UpdateTransaction {
     pub description: Option<String>,
     pub value: Option<BigDecimal>,
     pub due_date: Option<NaiveDate>,
-    pub category: Option<Category>,
+    pub category_id: Option<Uuid>,
     pub account_id: Option<Uuid>,
+    pub financial_plan_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq)]
@@ -66,36 +68,13 @@ pub enum TransactionStatus {
     Completed,
 }
 
-#[derive(Debug, Serialize, Deserialize, Type, Clone, Copy, PartialEq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-#[sqlx(type_name = "category", rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Category {
-    Food,
-    Home,
-    Education,
-    Entertainment,
-    Transport,
-    Healthy,
-    Salary,
-    Utilities,
-    Insurance,
-    Savings,
-    DebtPayments,
-    ChildCare,
-    Gifts,
-    Subscriptions,
-    Travel,
-    Clothing,
-    Maintenance,
-}
-
 impl Default for Transaction {
     fn default() -> Self {
         Transaction {
             transaction_id: Uuid::new_v4(),
             financial_plan_id: Uuid::new_v4(),
             account_id: Uuid::new_v4(),
-            category: Category::Food,
+            category_id: Uuid::new_v4(),
             description: String::from("default value"),
             movement_type: MovementType::Expense,
             due_date: NaiveDate::default(),
@@ -111,10 +90,14 @@ impl Default for Transaction {
 impl Transaction {
     /// FINISHED transaction is when the status equals to COMPLETED or CANCELED
     pub fn is_finished(&self) -> bool {
-        match self.status {
-            TransactionStatus::Completed | TransactionStatus::Canceled => true,
-            _ => false,
-        }
+        matches!(
+            self.status,
+            TransactionStatus::Completed | TransactionStatus::Canceled
+        )
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
     }
 
     /// prepare an transaction to be updated
@@ -126,20 +109,21 @@ impl Transaction {
             description,
             value,
             due_date,
-            category,
-            account_id
+            category_id,
+            account_id,
+            financial_plan_id
         );
         self.updated_at = Some(Utc::now());
     }
 
-    pub fn from_payload(payload: CreateTransaction) -> Self {
+    pub fn from_payload(payload: CreateTransaction, category_id: Uuid) -> Self {
         Transaction {
             transaction_id: Uuid::new_v4(),
             financial_plan_id: payload.financial_plan_id,
             account_id: payload.account_id,
             description: payload.description,
             value: payload.value,
-            category: payload.category,
+            category_id,
             status: TransactionStatus::Pending,
             due_date: payload.due_date,
             movement_type: payload.movement_type,
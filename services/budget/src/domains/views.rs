@@ -1,16 +1,23 @@
 use std::num::NonZeroU16;
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
 
-use super::transactions::TransactionStatus;
+use super::{tags::TagMatch, transactions::TransactionStatus};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct TransactionFilterParams {
-    pub status: TransactionStatus,
-    pub pagination: Option<PaginationParameters>,
+    pub status: Option<TransactionStatus>,
+    /// comma separated list of tag ids
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub tags: Vec<Uuid>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 pub struct PaginationParameters {
     #[serde(default = "default_page")]
     pub page: NonZeroU16,
@@ -18,10 +25,26 @@ pub struct PaginationParameters {
     pub size: NonZeroU16,
 }
 
+#[allow(dead_code)]
 fn default_page() -> NonZeroU16 {
     NonZeroU16::new(1).unwrap()
 }
 
+#[allow(dead_code)]
 fn default_size() -> NonZeroU16 {
     NonZeroU16::new(30).unwrap()
 }
+
+fn comma_separated<'de, D>(deserializer: D) -> Result<Vec<Uuid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| Uuid::parse_str(id).map_err(serde::de::Error::custom))
+        .collect()
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::update_fields;
+
+use super::accounts::AccountPermission;
+
+/// Owner of the budget records, a single user or a household sharing them
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "workspace_role", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkspaceRole {
+    /// manages the workspace and its members
+    Owner,
+    /// changes the records
+    Editor,
+    /// only reads the records
+    Viewer,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    pub workspace_id: Uuid,
+    pub user_id: Uuid,
+    pub role: WorkspaceRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Workspace with the role of the user listing it
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMembership {
+    #[serde(flatten)]
+    pub workspace: Workspace,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkspace {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspace {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMember {
+    pub user_id: Uuid,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMember {
+    pub role: WorkspaceRole,
+}
+
+/// name of the workspace made for users without one
+pub const PERSONAL_WORKSPACE: &str = "Personal";
+
+/// workspace the migration moved the records from before workspaces to, it has no members
+/// until the deploy names its owner
+pub const LEGACY_WORKSPACE: Uuid = Uuid::from_u128(1);
+
+impl Workspace {
+    pub fn new(name: String) -> Self {
+        Workspace {
+            workspace_id: Uuid::new_v4(),
+            name,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    /// prepare a workspace to be updated
+    pub fn update(&mut self, data: UpdateWorkspace) {
+        update_fields!(self, data, name);
+        self.updated_at = Some(Utc::now());
+    }
+}
+
+impl WorkspaceRole {
+    /// viewers only read the records
+    pub fn can_write(&self) -> bool {
+        !matches!(self, Self::Viewer)
+    }
+
+    /// most the role allows over an account, whatever it was granted
+    pub fn account_permission(&self) -> AccountPermission {
+        match self {
+            Self::Owner | Self::Editor => AccountPermission::Manage,
+            Self::Viewer => AccountPermission::View,
+        }
+    }
+}
+
+impl WorkspaceMember {
+    pub fn new(workspace_id: Uuid, user_id: Uuid, role: WorkspaceRole) -> Self {
+        WorkspaceMember {
+            workspace_id,
+            user_id,
+            role,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,328 @@
+// the parsers fail with the status of the call, the error type tonic itself answers with
+#![allow(clippy::result_large_err)]
+
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use tonic::Status;
+use uuid::Uuid;
+
+use crate::domains::{
+    accounts::{Account, AccountType, CreateAccount, UpdateAccount},
+    financial_plans::{CreateFinancialPlan, FinancialPlan, MonthReference},
+    institutions::Institution,
+    settlements::{CreateSettlement, Settlement, SettlementParams},
+    tags::TagMatch,
+    transactions::{
+        CreateTransaction, MovementType, Transaction, TransactionStatus, UpdateTransaction,
+    },
+    views::TransactionFilterParams,
+};
+
+use super::{invalid_argument, proto};
+
+fn timestamp(value: DateTime<Utc>) -> String {
+    value.to_rfc3339()
+}
+
+pub(super) fn parse_id(field: &str, value: &str) -> Result<Uuid, Status> {
+    value
+        .parse()
+        .map_err(|_| invalid_argument(format!("{field} must be a UUID")))
+}
+
+fn parse_decimal(field: &str, value: &str) -> Result<BigDecimal, Status> {
+    BigDecimal::from_str(value)
+        .map_err(|_| invalid_argument(format!("{field} must be a decimal number")))
+}
+
+fn parse_date(field: &str, value: &str) -> Result<NaiveDate, Status> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| invalid_argument(format!("{field} must be a YYYY-MM-DD date")))
+}
+
+fn parse_account_type(value: i32) -> Result<AccountType, Status> {
+    match proto::AccountType::try_from(value) {
+        Ok(proto::AccountType::Debit) => Ok(AccountType::Debit),
+        Ok(proto::AccountType::Credit) => Ok(AccountType::Credit),
+        Ok(proto::AccountType::Hybrid) => Ok(AccountType::Hybrid),
+        _ => Err(invalid_argument("account_type is required")),
+    }
+}
+
+fn parse_movement_type(value: i32) -> Result<MovementType, Status> {
+    match proto::MovementType::try_from(value) {
+        Ok(proto::MovementType::Income) => Ok(MovementType::Income),
+        Ok(proto::MovementType::Expense) => Ok(MovementType::Expense),
+        _ => Err(invalid_argument("movement_type is required")),
+    }
+}
+
+pub(super) fn parse_status(value: i32) -> Result<TransactionStatus, Status> {
+    match proto::TransactionStatus::try_from(value) {
+        Ok(proto::TransactionStatus::Pending) => Ok(TransactionStatus::Pending),
+        Ok(proto::TransactionStatus::Canceled) => Ok(TransactionStatus::Canceled),
+        Ok(proto::TransactionStatus::Completed) => Ok(TransactionStatus::Completed),
+        _ => Err(invalid_argument("status is required")),
+    }
+}
+
+fn parse_month(value: i32) -> Result<MonthReference, Status> {
+    u32::try_from(value)
+        .ok()
+        .and_then(|month| NaiveDate::from_ymd_opt(2000, month, 1))
+        .map(MonthReference::from_date)
+        .ok_or_else(|| invalid_argument("month must be between 1 and 12"))
+}
+
+impl From<AccountType> for proto::AccountType {
+    fn from(value: AccountType) -> Self {
+        match value {
+            AccountType::Debit => proto::AccountType::Debit,
+            AccountType::Credit => proto::AccountType::Credit,
+            AccountType::Hybrid => proto::AccountType::Hybrid,
+        }
+    }
+}
+
+impl From<MovementType> for proto::MovementType {
+    fn from(value: MovementType) -> Self {
+        match value {
+            MovementType::Income => proto::MovementType::Income,
+            MovementType::Expense => proto::MovementType::Expense,
+        }
+    }
+}
+
+impl From<TransactionStatus> for proto::TransactionStatus {
+    fn from(value: TransactionStatus) -> Self {
+        match value {
+            TransactionStatus::Pending => proto::TransactionStatus::Pending,
+            TransactionStatus::Canceled => proto::TransactionStatus::Canceled,
+            TransactionStatus::Completed => proto::TransactionStatus::Completed,
+        }
+    }
+}
+
+impl From<Account> for proto::Account {
+    fn from(value: Account) -> Self {
+        proto::Account {
+            account_id: value.account_id.to_string(),
+            institution_id: value.institution_id.to_string(),
+            owner: value.owner,
+            account_type: proto::AccountType::from(value.account_type) as i32,
+            created_at: timestamp(value.created_at),
+            updated_at: value.updated_at.map(timestamp),
+            deleted_at: value.deleted_at.map(timestamp),
+        }
+    }
+}
+
+impl From<Institution> for proto::Institution {
+    fn from(value: Institution) -> Self {
+        proto::Institution {
+            institution_id: value.institution_id.to_string(),
+            name: value.name,
+            code: value.code,
+            logo_url: value.logo_url,
+            created_at: timestamp(value.created_at),
+            updated_at: value.updated_at.map(timestamp),
+            deleted_at: value.deleted_at.map(timestamp),
+        }
+    }
+}
+
+impl From<Transaction> for proto::Transaction {
+    fn from(value: Transaction) -> Self {
+        proto::Transaction {
+            transaction_id: value.transaction_id.to_string(),
+            financial_plan_id: value.financial_plan_id.to_string(),
+            movement_type: proto::MovementType::from(value.movement_type) as i32,
+            description: value.description,
+            value: value.value.to_string(),
+            due_date: value.due_date.to_string(),
+            category_id: value.category_id.to_string(),
+            account_id: value.account_id.to_string(),
+            status: proto::TransactionStatus::from(value.status) as i32,
+            created_at: timestamp(value.created_at),
+            updated_at: value.updated_at.map(timestamp),
+            deleted_at: value.deleted_at.map(timestamp),
+        }
+    }
+}
+
+impl From<FinancialPlan> for proto::FinancialPlan {
+    fn from(value: FinancialPlan) -> Self {
+        proto::FinancialPlan {
+            financial_plan_id: value.financial_plan_id.to_string(),
+            title: value.title,
+            month: value.month as i32,
+            year: value.year.into(),
+            closed_at: value.closed_at.map(timestamp),
+            created_at: timestamp(value.created_at),
+            updated_at: value.updated_at.map(timestamp),
+            deleted_at: value.deleted_at.map(timestamp),
+        }
+    }
+}
+
+impl From<Settlement> for proto::Settlement {
+    fn from(value: Settlement) -> Self {
+        proto::Settlement {
+            settlement_id: value.settlement_id.to_string(),
+            transaction_id: value.transaction_id.to_string(),
+            installment_id: value.installment_id.map(|id| id.to_string()),
+            paid_date: value.paid_date.to_string(),
+            paid_value: value.paid_value.to_string(),
+            discount: value.discount.map(|discount| discount.to_string()),
+            fees: value.fees.map(|fees| fees.to_string()),
+            attachment: value.attachment,
+            created_at: timestamp(value.created_at),
+            updated_at: value.updated_at.map(timestamp),
+            deleted_at: value.deleted_at.map(timestamp),
+        }
+    }
+}
+
+impl TryFrom<proto::CreateAccountRequest> for CreateAccount {
+    type Error = Status;
+
+    fn try_from(value: proto::CreateAccountRequest) -> Result<Self, Status> {
+        Ok(CreateAccount {
+            institution_id: parse_id("institution_id", &value.institution_id)?,
+            owner: value.owner,
+            account_type: parse_account_type(value.account_type)?,
+        })
+    }
+}
+
+impl TryFrom<proto::UpdateAccountRequest> for UpdateAccount {
+    type Error = Status;
+
+    fn try_from(value: proto::UpdateAccountRequest) -> Result<Self, Status> {
+        Ok(UpdateAccount {
+            institution_id: value
+                .institution_id
+                .map(|id| parse_id("institution_id", &id))
+                .transpose()?,
+            owner: value.owner,
+            account_type: value.account_type.map(parse_account_type).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::ListTransactionsRequest> for TransactionFilterParams {
+    type Error = Status;
+
+    fn try_from(value: proto::ListTransactionsRequest) -> Result<Self, Status> {
+        let tag_match = match value.tag_match() {
+            proto::TagMatch::Any => TagMatch::Any,
+            proto::TagMatch::All => TagMatch::All,
+        };
+
+        Ok(TransactionFilterParams {
+            status: value.status.map(parse_status).transpose()?,
+            tags: value
+                .tag_ids
+                .iter()
+                .map(|id| parse_id("tag_ids", id))
+                .collect::<Result<_, _>>()?,
+            tag_match,
+        })
+    }
+}
+
+impl TryFrom<proto::CreateTransactionRequest> for CreateTransaction {
+    type Error = Status;
+
+    fn try_from(value: proto::CreateTransactionRequest) -> Result<Self, Status> {
+        Ok(CreateTransaction {
+            financial_plan_id: parse_id("financial_plan_id", &value.financial_plan_id)?,
+            movement_type: parse_movement_type(value.movement_type)?,
+            description: value.description,
+            value: parse_decimal("value", &value.value)?,
+            due_date: parse_date("due_date", &value.due_date)?,
+            category_id: value
+                .category_id
+                .map(|id| parse_id("category_id", &id))
+                .transpose()?,
+            account_id: parse_id("account_id", &value.account_id)?,
+            installments: i16::try_from(value.installments)
+                .map_err(|_| invalid_argument("installments is out of range"))?,
+        })
+    }
+}
+
+impl TryFrom<proto::UpdateTransactionRequest> for UpdateTransaction {
+    type Error = Status;
+
+    fn try_from(value: proto::UpdateTransactionRequest) -> Result<Self, Status> {
+        Ok(UpdateTransaction {
+            movement_type: value.movement_type.map(parse_movement_type).transpose()?,
+            description: value.description,
+            value: value
+                .value
+                .map(|value| parse_decimal("value", &value))
+                .transpose()?,
+            due_date: value
+                .due_date
+                .map(|date| parse_date("due_date", &date))
+                .transpose()?,
+            category_id: value
+                .category_id
+                .map(|id| parse_id("category_id", &id))
+                .transpose()?,
+            account_id: value
+                .account_id
+                .map(|id| parse_id("account_id", &id))
+                .transpose()?,
+            financial_plan_id: value
+                .financial_plan_id
+                .map(|id| parse_id("financial_plan_id", &id))
+                .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::CreateFinancialPlanRequest> for CreateFinancialPlan {
+    type Error = Status;
+
+    fn try_from(value: proto::CreateFinancialPlanRequest) -> Result<Self, Status> {
+        Ok(CreateFinancialPlan {
+            title: value.title,
+            month: parse_month(value.month)?,
+            year: i16::try_from(value.year)
+                .map_err(|_| invalid_argument("year is out of range"))?,
+        })
+    }
+}
+
+impl TryFrom<proto::CreateSettlementRequest> for (CreateSettlement, SettlementParams) {
+    type Error = Status;
+
+    fn try_from(value: proto::CreateSettlementRequest) -> Result<Self, Status> {
+        let payload = CreateSettlement {
+            paid_date: parse_date("paid_date", &value.paid_date)?,
+            paid_value: parse_decimal("paid_value", &value.paid_value)?,
+            discount: value
+                .discount
+                .map(|discount| parse_decimal("discount", &discount))
+                .transpose()?,
+            fees: value
+                .fees
+                .map(|fees| parse_decimal("fees", &fees))
+                .transpose()?,
+            attachment: value.attachment,
+        };
+        let params = SettlementParams {
+            transaction_id: parse_id("transaction_id", &value.transaction_id)?,
+            installment_id: value
+                .installment_id
+                .map(|id| parse_id("installment_id", &id))
+                .transpose()?,
+        };
+
+        Ok((payload, params))
+    }
+}
@@ -0,0 +1,302 @@
+use std::{future::Future, net::SocketAddr};
+
+use auth_layer::{AuthError, AuthLayer};
+use axum::http::StatusCode;
+use tonic::{
+    metadata::{MetadataMap, MetadataValue},
+    transport::Server,
+    Code, Request, Response, Status,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{audit::AuditContext, errors::Error},
+    handlers::{
+        audit::AUDIT_CONTEXT,
+        workspaces::{MEMBER, WORKSPACE},
+        Handler,
+    },
+};
+
+use proto::{
+    accounts_server::AccountsServer, financial_plans_server::FinancialPlansServer,
+    institutions_server::InstitutionsServer, settlements_server::SettlementsServer,
+    transactions_server::TransactionsServer,
+};
+
+mod convert;
+mod services;
+
+pub mod proto {
+    tonic::include_proto!("budget.v1");
+}
+
+/// metadata entry selecting the workspace, like the header of the HTTP API
+const WORKSPACE_METADATA: &str = "x-workspace-id";
+
+/// metadata entry of a failure with the code of the HTTP error envelope
+const ERROR_CODE_METADATA: &str = "x-error-code";
+
+/// gRPC services of the budget, the same handler as the HTTP routes behind them
+#[derive(Clone)]
+pub struct BudgetGrpc {
+    handler: Handler,
+    auth: AuthLayer,
+}
+
+impl BudgetGrpc {
+    pub fn new(handler: Handler, auth: AuthLayer) -> Self {
+        BudgetGrpc { handler, auth }
+    }
+
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+        Server::builder()
+            .add_service(AccountsServer::new(self.clone()))
+            .add_service(InstitutionsServer::new(self.clone()))
+            .add_service(TransactionsServer::new(self.clone()))
+            .add_service(FinancialPlansServer::new(self.clone()))
+            .add_service(SettlementsServer::new(self))
+            .serve(addr)
+            .await
+    }
+
+    /// runs the call the way the HTTP middlewares run a request: authenticated, in a workspace
+    /// of the caller that lets them write when the call does, and audited as them in the same
+    /// unit of work as its changes
+    async fn call<T, R, F, Fut>(
+        &self,
+        request: Request<T>,
+        write: bool,
+        call: F,
+    ) -> Result<Response<R>, Status>
+    where
+        F: FnOnce(Handler, T) -> Fut,
+        Fut: Future<Output = Result<R, Status>>,
+    {
+        let metadata = request.metadata();
+        let text = |key: &str| metadata.get(key).and_then(|value| value.to_str().ok());
+
+        let (user, _) = self
+            .auth
+            .authorize(text("authorization"), write)
+            .await
+            .map_err(auth_status)?;
+
+        let requested = text(WORKSPACE_METADATA)
+            .map(|value| {
+                value
+                    .parse::<Uuid>()
+                    .map_err(|_| Error::InvalidWorkspaceHeader(value.to_string()))
+            })
+            .transpose()?;
+
+        let member = self
+            .handler
+            .resolve_workspace(user.user_id, requested)
+            .await?;
+
+        if write && !member.role.can_write() {
+            return Err(Error::InsufficientRole(member.workspace_id).into());
+        }
+
+        let context = AuditContext {
+            actor: Some(user.user_id.to_string()),
+            request_id: text("x-request-id").map(String::from),
+        };
+        let workspace_id = member.workspace_id;
+
+        let scoped = AUDIT_CONTEXT.scope(
+            context,
+            WORKSPACE.scope(
+                workspace_id,
+                MEMBER.scope(member, call(self.handler.clone(), request.into_inner())),
+            ),
+        );
+
+        let response = if write {
+            self.handler
+                .in_unit_of_work(scoped, Result::is_ok)
+                .await??
+        } else {
+            scoped.await?
+        };
+
+        Ok(Response::new(response))
+    }
+}
+
+/// failure with the gRPC code closest to the HTTP status of the error
+impl From<Error> for Status {
+    fn from(err: Error) -> Self {
+        let status = err.status_code();
+
+        if status.is_server_error() {
+            log::error!("gRPC call failed with {}: {err:?}", err.code());
+        }
+
+        let code = match status {
+            StatusCode::BAD_REQUEST | StatusCode::CONFLICT => Code::FailedPrecondition,
+            StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+            StatusCode::FORBIDDEN => Code::PermissionDenied,
+            StatusCode::NOT_IMPLEMENTED => Code::Unimplemented,
+            _ => Code::Internal,
+        };
+
+        with_error_code(code, err.message(), err.code())
+    }
+}
+
+fn auth_status(err: AuthError) -> Status {
+    let code = match err {
+        AuthError::MissingToken | AuthError::InvalidToken => Code::Unauthenticated,
+        AuthError::InsufficientScope(_) => Code::PermissionDenied,
+        AuthError::KeysUnavailable(ref reason) => {
+            log::error!("Could not fetch the signing keys: {reason}");
+            Code::Unavailable
+        }
+    };
+
+    with_error_code(code, err.message(), err.code())
+}
+
+/// request field the call can't use, before it reaches the handler
+fn invalid_argument(message: impl Into<String>) -> Status {
+    with_error_code(Code::InvalidArgument, message.into(), "INVALID_ARGUMENT")
+}
+
+fn with_error_code(code: Code, message: String, error_code: &'static str) -> Status {
+    let mut metadata = MetadataMap::new();
+    metadata.insert(ERROR_CODE_METADATA, MetadataValue::from_static(error_code));
+
+    Status::with_metadata(code, message, metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use auth_layer::{CurrentUser, TokenVerifier};
+
+    use crate::{domains::institutions::CreateInstitution, repositories::memory::MemoryRepository};
+
+    use super::{
+        proto::{accounts_server::Accounts, institutions_server::Institutions},
+        *,
+    };
+
+    const ANA: Uuid = Uuid::from_u128(1);
+    const BOB: Uuid = Uuid::from_u128(2);
+
+    /// `ana` and `bob` are their users, `read` is ana limited to reading the budget
+    struct TestVerifier;
+
+    #[async_trait::async_trait]
+    impl TokenVerifier for TestVerifier {
+        async fn verify(&self, token: &str) -> Result<CurrentUser, AuthError> {
+            let user_id = match token {
+                "ana" | "read" => ANA,
+                "bob" => BOB,
+                _ => return Err(AuthError::InvalidToken),
+            };
+
+            Ok(CurrentUser {
+                user_id,
+                session_id: Uuid::new_v4(),
+                scopes: (token == "read").then(|| vec![String::from("budget:read")]),
+            })
+        }
+    }
+
+    fn request<T>(message: T, token: Option<&str>) -> Request<T> {
+        let mut request = Request::new(message);
+
+        if let Some(token) = token {
+            let value = format!("Bearer {token}").parse().unwrap();
+            request.metadata_mut().insert("authorization", value);
+        }
+
+        request
+    }
+
+    #[tokio::test]
+    async fn should_call_the_handler_within_the_workspace_of_the_caller() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let auth = AuthLayer::new(Arc::new(TestVerifier)).scoped("budget");
+        let grpc = BudgetGrpc::new(handler.clone(), auth);
+
+        let workspace_id = handler
+            .resolve_workspace(ANA, None)
+            .await
+            .unwrap()
+            .workspace_id;
+        let institution = WORKSPACE
+            .scope(
+                workspace_id,
+                handler.create_institution(CreateInstitution {
+                    name: String::from("C6 Bank"),
+                    code: None,
+                    logo_url: None,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let create = |token| {
+            request(
+                proto::CreateAccountRequest {
+                    institution_id: institution.institution_id.to_string(),
+                    owner: String::from("Ana"),
+                    account_type: proto::AccountType::Debit as i32,
+                },
+                token,
+            )
+        };
+
+        let missing = grpc.create_account(create(None)).await.unwrap_err();
+        assert_eq!(missing.code(), Code::Unauthenticated);
+        assert_eq!(
+            missing.metadata().get(ERROR_CODE_METADATA).unwrap(),
+            "MISSING_TOKEN"
+        );
+
+        let read_only = grpc.create_account(create(Some("read"))).await.unwrap_err();
+        assert_eq!(read_only.code(), Code::PermissionDenied);
+
+        let account = grpc
+            .create_account(create(Some("ana")))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(account.account_type(), proto::AccountType::Debit);
+
+        let accounts = grpc
+            .list_accounts(request(proto::ListAccountsRequest {}, Some("read")))
+            .await
+            .unwrap()
+            .into_inner()
+            .accounts;
+        assert_eq!(accounts, [account]);
+
+        // bob gets a workspace of his own, with only the default institutions
+        let institutions = grpc
+            .list_institutions(request(proto::ListInstitutionsRequest {}, Some("bob")))
+            .await
+            .unwrap()
+            .into_inner()
+            .institutions;
+        assert!(institutions.iter().all(|i| i.name != "C6 Bank"));
+
+        let invalid = grpc
+            .get_account(request(
+                proto::GetAccountRequest {
+                    account_id: String::from("not an id"),
+                },
+                Some("ana"),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(invalid.code(), Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,267 @@
+use tonic::{Request, Response, Status};
+
+use super::{
+    convert::{parse_id, parse_status},
+    proto::{
+        self, accounts_server::Accounts, financial_plans_server::FinancialPlans,
+        institutions_server::Institutions, settlements_server::Settlements,
+        transactions_server::Transactions,
+    },
+    BudgetGrpc,
+};
+
+#[tonic::async_trait]
+impl Accounts for BudgetGrpc {
+    async fn list_accounts(
+        &self,
+        request: Request<proto::ListAccountsRequest>,
+    ) -> Result<Response<proto::ListAccountsResponse>, Status> {
+        self.call(request, false, |handler, _| async move {
+            let accounts = handler.list_accounts().await?;
+
+            Ok(proto::ListAccountsResponse {
+                accounts: accounts.into_iter().map(Into::into).collect(),
+            })
+        })
+        .await
+    }
+
+    async fn get_account(
+        &self,
+        request: Request<proto::GetAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        self.call(request, false, |handler, message| async move {
+            let account_id = parse_id("account_id", &message.account_id)?;
+
+            Ok(handler.get_account_by_id(account_id).await?.into())
+        })
+        .await
+    }
+
+    async fn create_account(
+        &self,
+        request: Request<proto::CreateAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        self.call(request, true, |handler, message| async move {
+            Ok(handler.create_account(message.try_into()?).await?.into())
+        })
+        .await
+    }
+
+    async fn update_account(
+        &self,
+        request: Request<proto::UpdateAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let account_id = parse_id("account_id", &message.account_id)?;
+            let payload = message.try_into()?;
+
+            Ok(handler
+                .update_account_by_id(account_id, payload)
+                .await?
+                .into())
+        })
+        .await
+    }
+
+    async fn delete_account(
+        &self,
+        request: Request<proto::DeleteAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let account_id = parse_id("account_id", &message.account_id)?;
+
+            Ok(handler.delete_account_by_id(account_id).await?.into())
+        })
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl Institutions for BudgetGrpc {
+    async fn list_institutions(
+        &self,
+        request: Request<proto::ListInstitutionsRequest>,
+    ) -> Result<Response<proto::ListInstitutionsResponse>, Status> {
+        self.call(request, false, |handler, _| async move {
+            let institutions = handler.list_institutions().await?;
+
+            Ok(proto::ListInstitutionsResponse {
+                institutions: institutions.into_iter().map(Into::into).collect(),
+            })
+        })
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl Transactions for BudgetGrpc {
+    async fn list_transactions(
+        &self,
+        request: Request<proto::ListTransactionsRequest>,
+    ) -> Result<Response<proto::ListTransactionsResponse>, Status> {
+        self.call(request, false, |handler, message| async move {
+            let transactions = handler.list_transactions(message.try_into()?).await?;
+
+            Ok(proto::ListTransactionsResponse {
+                transactions: transactions.into_iter().map(Into::into).collect(),
+            })
+        })
+        .await
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<proto::GetTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.call(request, false, |handler, message| async move {
+            let transaction_id = parse_id("transaction_id", &message.transaction_id)?;
+
+            Ok(handler.get_transaction_by_id(transaction_id).await?.into())
+        })
+        .await
+    }
+
+    async fn create_transaction(
+        &self,
+        request: Request<proto::CreateTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.call(request, true, |handler, message| async move {
+            Ok(handler.create_transaction(message.try_into()?).await?.into())
+        })
+        .await
+    }
+
+    async fn update_transaction(
+        &self,
+        request: Request<proto::UpdateTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let transaction_id = parse_id("transaction_id", &message.transaction_id)?;
+            let payload = message.try_into()?;
+
+            Ok(handler
+                .update_transaction_by_id(transaction_id, payload)
+                .await?
+                .into())
+        })
+        .await
+    }
+
+    async fn delete_transaction(
+        &self,
+        request: Request<proto::DeleteTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let transaction_id = parse_id("transaction_id", &message.transaction_id)?;
+
+            Ok(handler.delete_transaction_by_id(transaction_id).await?.into())
+        })
+        .await
+    }
+
+    async fn finish_transaction(
+        &self,
+        request: Request<proto::FinishTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let transaction_id = parse_id("transaction_id", &message.transaction_id)?;
+            let status = parse_status(message.status)?;
+
+            Ok(handler
+                .finish_transaction(transaction_id, status)
+                .await?
+                .into())
+        })
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl FinancialPlans for BudgetGrpc {
+    async fn list_financial_plans(
+        &self,
+        request: Request<proto::ListFinancialPlansRequest>,
+    ) -> Result<Response<proto::ListFinancialPlansResponse>, Status> {
+        self.call(request, false, |handler, _| async move {
+            let financial_plans = handler.list_financial_plans().await?;
+
+            Ok(proto::ListFinancialPlansResponse {
+                financial_plans: financial_plans.into_iter().map(Into::into).collect(),
+            })
+        })
+        .await
+    }
+
+    async fn get_financial_plan(
+        &self,
+        request: Request<proto::GetFinancialPlanRequest>,
+    ) -> Result<Response<proto::FinancialPlan>, Status> {
+        self.call(request, false, |handler, message| async move {
+            let financial_plan_id = parse_id("financial_plan_id", &message.financial_plan_id)?;
+
+            Ok(handler
+                .get_financial_plan_by_id(financial_plan_id)
+                .await?
+                .into())
+        })
+        .await
+    }
+
+    async fn create_financial_plan(
+        &self,
+        request: Request<proto::CreateFinancialPlanRequest>,
+    ) -> Result<Response<proto::FinancialPlan>, Status> {
+        self.call(request, true, |handler, message| async move {
+            Ok(handler
+                .create_financial_plan(message.try_into()?)
+                .await?
+                .into())
+        })
+        .await
+    }
+
+    async fn close_financial_plan(
+        &self,
+        request: Request<proto::CloseFinancialPlanRequest>,
+    ) -> Result<Response<proto::FinancialPlan>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let financial_plan_id = parse_id("financial_plan_id", &message.financial_plan_id)?;
+
+            Ok(handler
+                .close_financial_plan(financial_plan_id)
+                .await?
+                .into())
+        })
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl Settlements for BudgetGrpc {
+    async fn list_settlements(
+        &self,
+        request: Request<proto::ListSettlementsRequest>,
+    ) -> Result<Response<proto::ListSettlementsResponse>, Status> {
+        self.call(request, false, |handler, _| async move {
+            let settlements = handler.list_settlements().await?;
+
+            Ok(proto::ListSettlementsResponse {
+                settlements: settlements.into_iter().map(Into::into).collect(),
+            })
+        })
+        .await
+    }
+
+    async fn create_settlement(
+        &self,
+        request: Request<proto::CreateSettlementRequest>,
+    ) -> Result<Response<proto::Settlement>, Status> {
+        self.call(request, true, |handler, message| async move {
+            let (payload, params) = message.try_into()?;
+
+            Ok(handler.create_settlement(payload, params).await?.into())
+        })
+        .await
+    }
+}
@@ -1,35 +1,129 @@
 use uuid::Uuid;
 
 use crate::domains::{
-    accounts::{Account, CreateAccount, UpdateAccount},
+    accounts::{
+        Account, AccountAccess, AccountGrant, AccountPermission, CreateAccount, GrantAccount,
+        UpdateAccount,
+    },
+    audit::{AuditAction, AuditEntity},
     errors::{Error, Result},
 };
 
-use super::Handler;
+use super::{
+    workspaces::{current_member, current_workspace},
+    Handler,
+};
 
 impl Handler {
     pub async fn list_accounts(&self) -> Result<Vec<Account>> {
-        self.account_repository.list_accounts().await
+        let access = self.account_access().await?;
+
+        Ok(self
+            .account_repository
+            .list_accounts(current_workspace()?)
+            .await?
+            .into_iter()
+            .filter(|a| access.allows(a.account_id, AccountPermission::View))
+            .collect())
     }
 
     pub async fn create_account(&self, payload: CreateAccount) -> Result<Account> {
-        self.account_repository.create_account(payload).await
+        self.get_active_institution(payload.institution_id).await?;
+
+        let account = self
+            .account_repository
+            .create_account(current_workspace()?, payload)
+            .await?;
+
+        self.audit(
+            AuditEntity::Account,
+            account.account_id,
+            AuditAction::Create,
+            None,
+            Some(&account),
+        )
+        .await?;
+
+        Ok(account)
     }
 
     pub async fn get_account_by_id(&self, account_id: Uuid) -> Result<Account> {
-        self.account_repository
-            .get_account_by_id(account_id)
+        let account = self
+            .account_repository
+            .get_account_by_id(current_workspace()?, account_id)
             .await?
-            .ok_or(Error::AccountNotFound(account_id))
+            .ok_or(Error::AccountNotFound(account_id))?;
+
+        self.check_account_permission(account_id, AccountPermission::View)
+            .await?;
+
+        Ok(account)
+    }
+
+    /// fetch an account that can still be referenced by new records
+    pub async fn get_active_account(&self, account_id: Uuid) -> Result<Account> {
+        let account = self.get_account_by_id(account_id).await?;
+
+        if account.is_deleted() {
+            return Err(Error::AccountDeleted(account_id));
+        }
+
+        Ok(account)
+    }
+
+    /// fetch an active account the caller can record transactions in
+    pub async fn get_recordable_account(&self, account_id: Uuid) -> Result<Account> {
+        let account = self.get_active_account(account_id).await?;
+
+        self.check_account_permission(account_id, AccountPermission::Record)
+            .await?;
+
+        Ok(account)
+    }
+
+    /// permissions of the caller over the accounts of the workspace, work
+    /// without a member in scope reaches no account
+    pub async fn account_access(&self) -> Result<AccountAccess> {
+        let member = current_member().ok_or(Error::Unauthenticated)?;
+
+        let grants = self
+            .account_repository
+            .list_account_grants(current_workspace()?)
+            .await?;
+
+        Ok(AccountAccess::new(&member, grants))
+    }
+
+    pub async fn check_account_permission(
+        &self,
+        account_id: Uuid,
+        permission: AccountPermission,
+    ) -> Result<()> {
+        self.account_access().await?.check(account_id, permission)
     }
 
     pub async fn delete_account_by_id(&self, account_id: Uuid) -> Result<Account> {
-        self.get_account_by_id(account_id).await?;
+        let before = self.get_account_by_id(account_id).await?;
 
-        self.account_repository
-            .delete_account_by_id(account_id)
+        self.check_account_permission(account_id, AccountPermission::Manage)
+            .await?;
+
+        let account = self
+            .account_repository
+            .delete_account_by_id(current_workspace()?, account_id)
             .await?
-            .ok_or(Error::AccountAlreadyDeleted(account_id))
+            .ok_or(Error::AccountAlreadyDeleted(account_id))?;
+
+        self.audit(
+            AuditEntity::Account,
+            account_id,
+            AuditAction::Delete,
+            Some(&before),
+            Some(&account),
+        )
+        .await?;
+
+        Ok(account)
     }
 
     pub async fn update_account_by_id(
@@ -40,9 +134,185 @@ impl Handler {
         // REFAC this route when the rules is defined
         let result = self.get_account_by_id(account_id).await?;
 
-        self.account_repository
-            .update_account_by_id(result, payload)
+        self.check_account_permission(account_id, AccountPermission::Manage)
+            .await?;
+
+        if let Some(institution_id) = payload.institution_id {
+            self.get_active_institution(institution_id).await?;
+        }
+
+        let account = self
+            .account_repository
+            .update_account_by_id(current_workspace()?, result.clone(), payload)
             .await?
-            .ok_or(Error::AccountAlreadyDeleted(account_id))
+            .ok_or(Error::AccountAlreadyDeleted(account_id))?;
+
+        self.audit(
+            AuditEntity::Account,
+            account_id,
+            AuditAction::Update,
+            Some(&result),
+            Some(&account),
+        )
+        .await?;
+
+        Ok(account)
+    }
+
+    pub async fn list_account_grants(&self, account_id: Uuid) -> Result<Vec<AccountGrant>> {
+        self.get_account_by_id(account_id).await?;
+
+        self.account_grants(account_id).await
+    }
+
+    /// grant the user access to the account, the first grant makes the account
+    /// private to the caller and the user
+    pub async fn grant_account(
+        &self,
+        account_id: Uuid,
+        payload: GrantAccount,
+    ) -> Result<AccountGrant> {
+        let member = current_member().ok_or(Error::Unauthenticated)?;
+
+        self.get_active_account(account_id).await?;
+        self.check_account_permission(account_id, AccountPermission::Manage)
+            .await?;
+        self.get_member(member.workspace_id, payload.user_id)
+            .await?;
+
+        let grants = self.account_grants(account_id).await?;
+
+        let mut saved = grants.clone();
+        saved.retain(|g| g.user_id != payload.user_id);
+        saved.push(AccountGrant::new(
+            account_id,
+            payload.user_id,
+            payload.permission,
+        ));
+
+        let grant_caller = grants.is_empty() && payload.user_id != member.user_id;
+
+        if grant_caller {
+            saved.push(AccountGrant::new(
+                account_id,
+                member.user_id,
+                AccountPermission::Manage,
+            ));
+        }
+
+        if !AccountGrant::keeps_a_manager(&saved) {
+            return Err(Error::LastAccountManager(account_id));
+        }
+
+        if grant_caller {
+            self.save_account_grant(
+                None,
+                AccountGrant::new(account_id, member.user_id, AccountPermission::Manage),
+            )
+            .await?;
+        }
+
+        let before = grants.into_iter().find(|g| g.user_id == payload.user_id);
+
+        self.save_account_grant(
+            before,
+            AccountGrant::new(account_id, payload.user_id, payload.permission),
+        )
+        .await
+    }
+
+    /// revoke the grant of the user, an account left without grants is shared again
+    pub async fn revoke_account_grant(
+        &self,
+        account_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<AccountGrant> {
+        self.get_account_by_id(account_id).await?;
+        self.check_account_permission(account_id, AccountPermission::Manage)
+            .await?;
+
+        let mut grants = self.account_grants(account_id).await?;
+        grants.retain(|g| g.user_id != user_id);
+
+        if !AccountGrant::keeps_a_manager(&grants) {
+            return Err(Error::LastAccountManager(account_id));
+        }
+
+        let grant = self
+            .account_repository
+            .delete_account_grant(current_workspace()?, account_id, user_id)
+            .await?
+            .ok_or(Error::AccountGrantNotFound(user_id))?;
+
+        self.audit(
+            AuditEntity::Account,
+            account_id,
+            AuditAction::Revoke,
+            Some(&grant),
+            None,
+        )
+        .await?;
+
+        Ok(grant)
+    }
+
+    async fn account_grants(&self, account_id: Uuid) -> Result<Vec<AccountGrant>> {
+        Ok(self
+            .account_repository
+            .list_account_grants(current_workspace()?)
+            .await?
+            .into_iter()
+            .filter(|g| g.account_id == account_id)
+            .collect())
+    }
+
+    async fn save_account_grant(
+        &self,
+        before: Option<AccountGrant>,
+        grant: AccountGrant,
+    ) -> Result<AccountGrant> {
+        let account_id = grant.account_id;
+
+        let grant = self
+            .account_repository
+            .save_account_grant(current_workspace()?, grant)
+            .await?
+            .ok_or(Error::AccountNotFound(account_id))?;
+
+        self.audit(
+            AuditEntity::Account,
+            account_id,
+            AuditAction::Grant,
+            before.as_ref(),
+            Some(&grant),
+        )
+        .await?;
+
+        Ok(grant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::handlers::{workspaces::WORKSPACE, MockRepositories};
+
+    #[tokio::test]
+    async fn should_not_reach_accounts_without_a_member() {
+        let mut mocks = MockRepositories::default();
+
+        mocks
+            .account_repository
+            .expect_list_accounts()
+            .returning(|_| Ok(vec![]));
+
+        let handler = mocks.into_handler();
+
+        let result = WORKSPACE
+            .scope(Uuid::new_v4(), handler.list_accounts())
+            .await;
+
+        assert!(matches!(result, Err(Error::Unauthenticated)));
     }
 }
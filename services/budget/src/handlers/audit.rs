@@ -0,0 +1,132 @@
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::domains::{
+    accounts::AccountPermission,
+    audit::{AuditAction, AuditContext, AuditEntity, AuditEntry, AuditParams},
+    errors::{Error, Result},
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+tokio::task_local! {
+    /// actor and request id of the request being handled, set by the routes
+    pub static AUDIT_CONTEXT: AuditContext;
+}
+
+impl Handler {
+    /// the changes of records in accounts the caller can't see are not found, like the records
+    pub async fn list_audit(&self, params: AuditParams) -> Result<Vec<AuditEntry>> {
+        self.check_audited_record(params.entity, params.id).await?;
+
+        self.audit_repository
+            .list_audit(current_workspace()?, params.entity, params.id)
+            .await
+    }
+
+    async fn check_audited_record(&self, entity: AuditEntity, id: Uuid) -> Result<()> {
+        match entity {
+            AuditEntity::Transaction => {
+                self.get_transaction_by_id(id).await?;
+            }
+            AuditEntity::Account => match self.get_account_by_id(id).await {
+                Err(Error::AccountPermissionDenied(_)) => return Err(Error::AccountNotFound(id)),
+                result => {
+                    result?;
+                }
+            },
+            AuditEntity::Installment => {
+                self.get_installment_by_id(id).await?;
+            }
+            AuditEntity::Recurrence => {
+                self.get_recurrence_by_id(id).await?;
+            }
+            // listed settlements are already limited to the accounts the caller can see
+            AuditEntity::Settlement => {
+                let settlements = self.list_settlements().await?;
+
+                if !settlements.iter().any(|s| s.settlement_id == id) {
+                    return Err(Error::SettlementNotFound(id));
+                }
+            }
+            AuditEntity::Import => {
+                let import = self
+                    .import_repository
+                    .get_import_by_id(current_workspace()?, id)
+                    .await?
+                    .ok_or(Error::ImportNotFound(id))?;
+
+                if !self
+                    .account_access()
+                    .await?
+                    .allows(import.account_id, AccountPermission::View)
+                {
+                    return Err(Error::ImportNotFound(id));
+                }
+            }
+            // the other records are shared by the whole workspace
+            AuditEntity::FinancialPlan
+            | AuditEntity::Category
+            | AuditEntity::Tag
+            | AuditEntity::Institution
+            | AuditEntity::Rule => {}
+        }
+
+        Ok(())
+    }
+
+    /// record a change of the record, before is none when it was created and
+    /// updates that changed nothing are not recorded, the entry shares the unit
+    /// of work of the request so it is only kept along with the change
+    pub async fn audit<T: Serialize>(
+        &self,
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<()> {
+        let to_value = |record: &T| serde_json::to_value(record).ok();
+
+        let changes = AuditEntry::diff(
+            before.and_then(to_value).as_ref(),
+            after.and_then(to_value).as_ref(),
+        );
+
+        if changes
+            .as_object()
+            .is_some_and(|changes| changes.is_empty())
+        {
+            return Ok(());
+        }
+
+        // work started by the service itself, like the recurrence scheduler, has no context
+        let context = AUDIT_CONTEXT.try_with(Clone::clone).unwrap_or_default();
+
+        self.audit_repository
+            .record_audit(
+                current_workspace()?,
+                AuditEntry::new(entity_type, entity_id, action, changes, context),
+            )
+            .await
+    }
+
+    /// record a tag attached to or detached from the record
+    pub async fn audit_tag(
+        &self,
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+        action: AuditAction,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        self.audit(
+            entity_type,
+            entity_id,
+            action,
+            None,
+            Some(&json!({ "tagId": tag_id })),
+        )
+        .await
+    }
+}
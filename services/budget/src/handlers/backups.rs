@@ -0,0 +1,106 @@
+use chrono::Utc;
+use tokio::sync::mpsc;
+
+use crate::domains::{
+    backups::{Backup, BackupLine, RestoreParams, RestoreProgress, RestoredTable, BACKUP_BUFFER},
+    errors::{Error, Result},
+};
+
+use super::Handler;
+
+/// rows inserted by a single statement of a restore
+const RESTORE_BATCH_SIZE: usize = 1000;
+
+impl Handler {
+    /// start reading the backup, its lines are sent as the tables are read
+    pub async fn create_backup(&self) -> Result<Backup> {
+        let mut rows = self.backup_repository.read_tables().await?;
+        let created_at = Utc::now();
+        let (sender, lines) = mpsc::channel(BACKUP_BUFFER);
+
+        tokio::spawn(async move {
+            if sender
+                .send(Ok(BackupLine::header(created_at)))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let mut count = 0;
+
+            while let Some(row) = rows.recv().await {
+                let failed = row.is_err();
+                count += 1;
+
+                // without its end line the backup is known to be truncated
+                if sender.send(row.map(BackupLine::from)).await.is_err() || failed {
+                    return;
+                }
+            }
+
+            let _ = sender.send(Ok(BackupLine::End { rows: count })).await;
+        });
+
+        Ok(Backup { created_at, lines })
+    }
+
+    /// load a backup as its lines arrive, in a single unit of work, existing records are only
+    /// replaced when forced and the audit log is always kept
+    pub async fn restore_backup(
+        &self,
+        mut lines: mpsc::Receiver<Result<BackupLine>>,
+        params: RestoreParams,
+    ) -> Result<Vec<RestoredTable>> {
+        let mut progress = RestoreProgress::default();
+
+        // an unreadable backup is refused before touching the records
+        let header = lines
+            .recv()
+            .await
+            .ok_or_else(|| Error::InvalidBackup("the backup is empty".to_string()))??;
+        progress.accept(header)?;
+
+        self.atomically(async {
+            if params.force {
+                self.backup_repository.clear_tables().await?;
+            } else if self.backup_repository.has_records().await? {
+                return Err(Error::RestoreTargetNotEmpty);
+            }
+
+            let mut table = "";
+            let mut batch = Vec::with_capacity(RESTORE_BATCH_SIZE);
+
+            while let Some(line) = lines.recv().await {
+                let Some(row) = progress.accept(line?)? else {
+                    continue;
+                };
+
+                if row.table != table || batch.len() == RESTORE_BATCH_SIZE {
+                    self.insert_batch(table, &mut batch).await?;
+                    table = row.table;
+                }
+
+                batch.push(row.row);
+            }
+
+            self.insert_batch(table, &mut batch).await?;
+
+            progress.finish()
+        })
+        .await
+    }
+
+    async fn insert_batch(
+        &self,
+        table: &'static str,
+        batch: &mut Vec<serde_json::Value>,
+    ) -> Result<()> {
+        if !batch.is_empty() {
+            self.backup_repository.insert_rows(table, batch).await?;
+            batch.clear();
+        }
+
+        Ok(())
+    }
+}
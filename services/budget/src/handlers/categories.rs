@@ -0,0 +1,141 @@
+use uuid::Uuid;
+
+use crate::domains::{
+    audit::{AuditAction, AuditEntity},
+    categories::{Category, CategoryKind, CreateCategory, UpdateCategory},
+    errors::{Error, Result},
+    transactions::MovementType,
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    pub async fn list_categories(&self) -> Result<Vec<Category>> {
+        self.category_repository
+            .list_categories(current_workspace()?)
+            .await
+    }
+
+    pub async fn create_category(&self, payload: CreateCategory) -> Result<Category> {
+        if let Some(parent_id) = payload.parent_id {
+            let parent = self.get_active_category(parent_id).await?;
+
+            // only two levels are supported: categories and their subcategories
+            if !parent.is_root() {
+                return Err(Error::InvalidCategoryParent(parent_id));
+            }
+
+            if parent.kind != payload.kind {
+                return Err(Error::CategoryKindMismatch(parent_id));
+            }
+        }
+
+        let category = self
+            .category_repository
+            .create_category(current_workspace()?, Category::new_from_payload(payload))
+            .await?;
+
+        self.audit(
+            AuditEntity::Category,
+            category.category_id,
+            AuditAction::Create,
+            None,
+            Some(&category),
+        )
+        .await?;
+
+        Ok(category)
+    }
+
+    pub async fn get_category_by_id(&self, category_id: Uuid) -> Result<Category> {
+        self.category_repository
+            .get_category_by_id(current_workspace()?, category_id)
+            .await?
+            .ok_or(Error::CategoryNotFound(category_id))
+    }
+
+    /// fetch a category that can still be referenced by new records
+    pub async fn get_active_category(&self, category_id: Uuid) -> Result<Category> {
+        let category = self.get_category_by_id(category_id).await?;
+
+        if category.is_deleted() {
+            return Err(Error::CategoryDeleted(category_id));
+        }
+
+        Ok(category)
+    }
+
+    /// check the category can classify a movement of the given type
+    pub async fn validate_category(
+        &self,
+        category_id: Uuid,
+        movement_type: MovementType,
+    ) -> Result<Category> {
+        let category = self.get_active_category(category_id).await?;
+
+        if category.kind != CategoryKind::from(movement_type) {
+            return Err(Error::CategoryKindMismatch(category_id));
+        }
+
+        Ok(category)
+    }
+
+    pub async fn update_category_by_id(
+        &self,
+        category_id: Uuid,
+        payload: UpdateCategory,
+    ) -> Result<Category> {
+        let before = self.get_active_category(category_id).await?;
+
+        let mut category = before.clone();
+        category.update(payload);
+
+        let category = self
+            .category_repository
+            .update_category(current_workspace()?, category)
+            .await?
+            .ok_or(Error::CategoryDeleted(category_id))?;
+
+        self.audit(
+            AuditEntity::Category,
+            category_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&category),
+        )
+        .await?;
+
+        Ok(category)
+    }
+
+    pub async fn delete_category_by_id(&self, category_id: Uuid) -> Result<Category> {
+        let before = self.get_active_category(category_id).await?;
+
+        let has_subcategories = self
+            .list_categories()
+            .await?
+            .iter()
+            .any(|c| c.parent_id == Some(category_id) && !c.is_deleted());
+
+        if has_subcategories {
+            return Err(Error::CategoryHasSubcategories(category_id));
+        }
+
+        let category = self
+            .category_repository
+            .delete_category_by_id(current_workspace()?, category_id)
+            .await?
+            .ok_or(Error::CategoryDeleted(category_id))?;
+
+        self.audit(
+            AuditEntity::Category,
+            category_id,
+            AuditAction::Delete,
+            Some(&before),
+            Some(&category),
+        )
+        .await?;
+
+        Ok(category)
+    }
+}
@@ -0,0 +1,88 @@
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::Result,
+    exports::{ExportFile, ExportParams, ExportRow, ExportSources},
+    transactions::Transaction,
+    views::TransactionFilterParams,
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    /// export the transactions matching the listing filters
+    pub async fn export_transactions(
+        &self,
+        filters: TransactionFilterParams,
+        params: ExportParams,
+    ) -> Result<ExportFile> {
+        let transactions = self.list_transactions(filters).await?;
+
+        self.export(&transactions, "transactions", &params).await
+    }
+
+    /// export the transactions of a financial plan matching the listing filters
+    pub async fn export_financial_plan(
+        &self,
+        financial_plan_id: Uuid,
+        filters: TransactionFilterParams,
+        params: ExportParams,
+    ) -> Result<ExportFile> {
+        let financial_plan = self.get_financial_plan_by_id(financial_plan_id).await?;
+
+        let transactions: Vec<Transaction> = self
+            .list_transactions(filters)
+            .await?
+            .into_iter()
+            .filter(|t| t.financial_plan_id == financial_plan_id)
+            .collect();
+
+        let name = format!(
+            "financial-plan-{}-{:02}",
+            financial_plan.year, financial_plan.month as u8
+        );
+
+        self.export(&transactions, &name, &params).await
+    }
+
+    async fn export(
+        &self,
+        transactions: &[Transaction],
+        name: &str,
+        params: &ExportParams,
+    ) -> Result<ExportFile> {
+        let accounts = self
+            .account_repository
+            .list_accounts(current_workspace()?)
+            .await?;
+        let institutions = self
+            .institution_repository
+            .list_institutions(current_workspace()?)
+            .await?;
+        let categories = self
+            .category_repository
+            .list_categories(current_workspace()?)
+            .await?;
+        let installments = self
+            .installment_repository
+            .list_installments(current_workspace()?)
+            .await?;
+        let settlements = self
+            .settlement_repository
+            .list_settlements(current_workspace()?)
+            .await?;
+
+        let rows = ExportRow::collect(
+            transactions,
+            &ExportSources {
+                accounts: &accounts,
+                institutions: &institutions,
+                categories: &categories,
+                installments: &installments,
+                settlements: &settlements,
+            },
+        );
+
+        params.render(name, &rows)
+    }
+}
@@ -1,11 +1,13 @@
+use chrono::{Datelike, NaiveDate};
 use uuid::Uuid;
 
 use crate::domains::{
+    audit::{AuditAction, AuditEntity},
     errors::{Error, Result},
-    financial_plans::{CreateFinancialPlan, FinancialPlan},
+    financial_plans::{CreateFinancialPlan, FinancialPlan, MonthReference},
 };
 
-use super::Handler;
+use super::{workspaces::current_workspace, Handler};
 
 impl Handler {
     pub async fn create_financial_plan(
@@ -16,16 +18,25 @@ impl Handler {
 
         let financial_plan = self
             .financial_plan_repository
-            .create_financial_plan(payload)
+            .create_financial_plan(current_workspace()?, payload)
             .await?;
 
+        self.audit(
+            AuditEntity::FinancialPlan,
+            financial_plan.financial_plan_id,
+            AuditAction::Create,
+            None,
+            Some(&financial_plan),
+        )
+        .await?;
+
         Ok(financial_plan)
     }
 
     pub async fn list_financial_plans(&self) -> Result<Vec<FinancialPlan>> {
         let financial_plans = self
             .financial_plan_repository
-            .list_financial_plans()
+            .list_financial_plans(current_workspace()?)
             .await?;
 
         Ok(financial_plans)
@@ -33,8 +44,68 @@ impl Handler {
 
     pub async fn get_financial_plan_by_id(&self, financial_plan_id: Uuid) -> Result<FinancialPlan> {
         self.financial_plan_repository
-            .get_financial_plan_by_id(financial_plan_id)
+            .get_financial_plan_by_id(current_workspace()?, financial_plan_id)
             .await?
             .ok_or(Error::FinancialPlanNotFound(financial_plan_id))
     }
+
+    /// fetch a financial plan that still accepts new transactions
+    pub async fn get_open_financial_plan(&self, financial_plan_id: Uuid) -> Result<FinancialPlan> {
+        let financial_plan = self.get_financial_plan_by_id(financial_plan_id).await?;
+
+        if financial_plan.is_deleted() {
+            return Err(Error::FinancialPlanDeleted(financial_plan_id));
+        }
+
+        if financial_plan.is_closed() {
+            return Err(Error::FinancialPlanClosed(financial_plan_id));
+        }
+
+        Ok(financial_plan)
+    }
+
+    pub async fn close_financial_plan(&self, financial_plan_id: Uuid) -> Result<FinancialPlan> {
+        let before = self.get_open_financial_plan(financial_plan_id).await?;
+
+        let financial_plan = self
+            .financial_plan_repository
+            .close_financial_plan(current_workspace()?, financial_plan_id)
+            .await?
+            .ok_or(Error::FinancialPlanClosed(financial_plan_id))?;
+
+        self.audit(
+            AuditEntity::FinancialPlan,
+            financial_plan_id,
+            AuditAction::StatusChange,
+            Some(&before),
+            Some(&financial_plan),
+        )
+        .await?;
+
+        Ok(financial_plan)
+    }
+
+    /// fetch the plan of the month the date belongs to, creating it when missing
+    pub async fn get_or_create_financial_plan(&self, date: NaiveDate) -> Result<FinancialPlan> {
+        let month = MonthReference::from_date(date);
+        let year = date.year() as i16;
+
+        let financial_plan = self
+            .list_financial_plans()
+            .await?
+            .into_iter()
+            .find(|p| p.month == month && p.year == year && !p.is_deleted());
+
+        match financial_plan {
+            Some(financial_plan) => Ok(financial_plan),
+            None => {
+                self.create_financial_plan(CreateFinancialPlan {
+                    title: None,
+                    month,
+                    year,
+                })
+                .await
+            }
+        }
+    }
 }
@@ -0,0 +1,399 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::domains::{
+    audit::{AuditAction, AuditEntity},
+    errors::{Error, Result},
+    imports::{
+        CommitImport, CreateImport, ImportLine, ImportLineStatus, ImportPreview, StatementImport,
+    },
+    matching::{propose_matches, MatchCandidate},
+    settlements::SettlementParams,
+    transactions::MovementType,
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    pub async fn list_imports(&self, account_id: Uuid) -> Result<Vec<StatementImport>> {
+        self.get_account_by_id(account_id).await?;
+
+        self.import_repository
+            .list_imports(current_workspace()?, account_id)
+            .await
+    }
+
+    /// parse a statement file and store its lines as a preview, nothing is created until commit
+    pub async fn create_import(
+        &self,
+        account_id: Uuid,
+        payload: CreateImport,
+    ) -> Result<ImportPreview> {
+        self.get_recordable_account(account_id).await?;
+
+        let statement = payload.parse()?;
+        let imported = self.list_imported_external_ids(account_id).await?;
+
+        let import = StatementImport::new(account_id, payload.format);
+        let mut lines = ImportLine::from_statement(import.import_id, statement, &imported);
+
+        let transactions = self
+            .transaction_repository
+            .list_transactions(current_workspace()?)
+            .await?;
+        let installments = self
+            .installment_repository
+            .list_installments(current_workspace()?)
+            .await?;
+        let candidates = MatchCandidate::collect(account_id, &transactions, &installments);
+
+        let proposals = propose_matches(&lines, &candidates, &payload.matching);
+
+        for line in lines.iter_mut() {
+            if let Some(proposal) = proposals.get(&line.line_id) {
+                line.propose_match(proposal);
+            }
+        }
+
+        let import = self
+            .import_repository
+            .create_import(current_workspace()?, import, lines)
+            .await?;
+
+        self.audit(
+            AuditEntity::Import,
+            import.import_id,
+            AuditAction::Create,
+            None,
+            Some(&import),
+        )
+        .await?;
+
+        self.get_import_preview(account_id, import.import_id).await
+    }
+
+    pub async fn get_import(&self, account_id: Uuid, import_id: Uuid) -> Result<StatementImport> {
+        self.import_repository
+            .get_import_by_id(current_workspace()?, import_id)
+            .await?
+            .filter(|import| import.account_id == account_id)
+            .ok_or(Error::ImportNotFound(import_id))
+    }
+
+    pub async fn get_import_preview(
+        &self,
+        account_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<ImportPreview> {
+        let import = self.get_import(account_id, import_id).await?;
+        let lines = self
+            .import_repository
+            .list_import_lines(current_workspace()?, import_id)
+            .await?;
+
+        Ok(ImportPreview::new(import, lines))
+    }
+
+    /// settle the matched transactions and create a settled transaction for each other new line
+    pub async fn commit_import(
+        &self,
+        account_id: Uuid,
+        import_id: Uuid,
+        payload: CommitImport,
+    ) -> Result<ImportPreview> {
+        self.atomically(self.commit_import_lines(account_id, import_id, payload))
+            .await?;
+
+        self.get_import_preview(account_id, import_id).await
+    }
+
+    /// claim the import before creating anything, a concurrent commit of the same
+    /// import waits on the claim and then finds it already committed
+    async fn commit_import_lines(
+        &self,
+        account_id: Uuid,
+        import_id: Uuid,
+        payload: CommitImport,
+    ) -> Result<()> {
+        let import = self.get_import(account_id, import_id).await?;
+
+        if import.is_committed() {
+            return Err(Error::ImportAlreadyCommitted(import_id));
+        }
+
+        self.get_recordable_account(account_id).await?;
+        self.validate_category(payload.income_category_id, MovementType::Income)
+            .await?;
+        self.validate_category(payload.expense_category_id, MovementType::Expense)
+            .await?;
+
+        let committed = self
+            .import_repository
+            .commit_import(current_workspace()?, import_id)
+            .await?
+            .ok_or(Error::ImportAlreadyCommitted(import_id))?;
+
+        self.audit(
+            AuditEntity::Import,
+            import_id,
+            AuditAction::StatusChange,
+            Some(&import),
+            Some(&committed),
+        )
+        .await?;
+
+        // another import may have brought the same lines since this preview was made
+        let imported = self.list_imported_external_ids(account_id).await?;
+
+        let lines = self
+            .import_repository
+            .list_import_lines(current_workspace()?, import_id)
+            .await?;
+
+        for line in lines.iter().filter(|l| l.status == ImportLineStatus::New) {
+            if imported.contains(&line.external_id) {
+                self.import_repository
+                    .update_import_line(
+                        current_workspace()?,
+                        line.line_id,
+                        ImportLineStatus::Duplicate,
+                        None,
+                    )
+                    .await?;
+                continue;
+            }
+
+            if !payload.rejected_matches.contains(&line.line_id) {
+                if let Some(transaction_id) = self.settle_match(line).await? {
+                    self.import_repository
+                        .update_import_line(
+                            current_workspace()?,
+                            line.line_id,
+                            ImportLineStatus::Matched,
+                            Some(transaction_id),
+                        )
+                        .await?;
+                    continue;
+                }
+            }
+
+            // a matching rule takes precedence over the default categories of the import
+            let category_id = match self
+                .find_rule(&line.description, line.movement_type)
+                .await?
+            {
+                Some(_) => None,
+                None => Some(match line.movement_type {
+                    MovementType::Income => payload.income_category_id,
+                    MovementType::Expense => payload.expense_category_id,
+                }),
+            };
+
+            let financial_plan = self.get_or_create_financial_plan(line.due_date).await?;
+
+            let transaction = self
+                .create_transaction(line.new_transaction(
+                    account_id,
+                    financial_plan.financial_plan_id,
+                    category_id,
+                ))
+                .await?;
+
+            self.create_settlement(
+                line.new_settlement(&line.value),
+                SettlementParams {
+                    transaction_id: transaction.transaction_id,
+                    installment_id: None,
+                },
+            )
+            .await?;
+
+            self.import_repository
+                .update_import_line(
+                    current_workspace()?,
+                    line.line_id,
+                    ImportLineStatus::Imported,
+                    Some(transaction.transaction_id),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// settle the proposed match of the line, none when it is no longer pending
+    async fn settle_match(&self, line: &ImportLine) -> Result<Option<Uuid>> {
+        let Some(transaction_id) = line.match_transaction_id else {
+            return Ok(None);
+        };
+
+        let transaction = self.get_transaction_by_id(transaction_id).await?;
+
+        if transaction.is_deleted() || transaction.is_finished() {
+            return Ok(None);
+        }
+
+        let expected = match line.match_installment_id {
+            Some(installment_id) => {
+                let installment = self.get_installment_by_id(installment_id).await?;
+
+                if installment.is_deleted() || installment.is_finished() {
+                    return Ok(None);
+                }
+
+                installment.value
+            }
+            None => transaction.value,
+        };
+
+        self.create_settlement(
+            line.new_settlement(&expected),
+            SettlementParams {
+                transaction_id,
+                installment_id: line.match_installment_id,
+            },
+        )
+        .await?;
+
+        Ok(Some(transaction_id))
+    }
+
+    async fn list_imported_external_ids(&self, account_id: Uuid) -> Result<HashSet<String>> {
+        Ok(self
+            .import_repository
+            .list_imported_external_ids(current_workspace()?, account_id)
+            .await?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    use crate::{
+        domains::{
+            accounts::{AccountType, CreateAccount},
+            categories::{CategoryKind, CreateCategory},
+            imports::ImportFormat,
+            institutions::CreateInstitution,
+        },
+        handlers::as_owner,
+        repositories::memory::MemoryRepository,
+    };
+
+    const STATEMENT: &str = r#"
+OFXHEADER:100
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20260310
+<TRNAMT>-42.90
+<FITID>abc-1
+<MEMO>UBER *TRIP
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20260305
+<TRNAMT>5000.00
+<FITID>abc-2
+<NAME>SALARIO
+</STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>
+"#;
+
+    async fn new_category(handler: &Handler, kind: CategoryKind) -> Uuid {
+        handler
+            .create_category(CreateCategory {
+                parent_id: None,
+                name: format!("{kind:?}"),
+                icon: None,
+                color: None,
+                kind,
+            })
+            .await
+            .unwrap()
+            .category_id
+    }
+
+    #[tokio::test]
+    async fn should_commit_an_import_only_once() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let workspace_id = Uuid::new_v4();
+
+        as_owner(workspace_id, async {
+            let institution = handler
+                .create_institution(CreateInstitution {
+                    name: String::from("Nubank"),
+                    code: None,
+                    logo_url: None,
+                })
+                .await
+                .unwrap();
+            let account = handler
+                .create_account(CreateAccount {
+                    institution_id: institution.institution_id,
+                    owner: String::from("owner"),
+                    account_type: AccountType::Debit,
+                })
+                .await
+                .unwrap();
+            let income_category_id = new_category(&handler, CategoryKind::Income).await;
+            let expense_category_id = new_category(&handler, CategoryKind::Expense).await;
+
+            let preview = handler
+                .create_import(
+                    account.account_id,
+                    CreateImport {
+                        format: ImportFormat::Ofx,
+                        preset: None,
+                        layout: None,
+                        content: String::from(STATEMENT),
+                        matching: Default::default(),
+                    },
+                )
+                .await
+                .unwrap();
+            let import_id = preview.import.import_id;
+
+            let commit = || {
+                handler.commit_import(
+                    account.account_id,
+                    import_id,
+                    CommitImport {
+                        income_category_id,
+                        expense_category_id,
+                        rejected_matches: Vec::new(),
+                    },
+                )
+            };
+
+            let (first, second) = tokio::join!(commit(), commit());
+            let (committed, refused) = match first {
+                Ok(preview) => (preview, second),
+                Err(_) => (second.unwrap(), first),
+            };
+
+            assert!(committed.import.is_committed());
+            assert!(matches!(
+                refused,
+                Err(Error::ImportAlreadyCommitted(id)) if id == import_id
+            ));
+
+            let again = commit().await;
+            assert!(matches!(again, Err(Error::ImportAlreadyCommitted(_))));
+
+            let transactions = handler
+                .transaction_repository
+                .list_transactions(workspace_id)
+                .await
+                .unwrap();
+            assert_eq!(transactions.len(), 2);
+        })
+        .await;
+    }
+}
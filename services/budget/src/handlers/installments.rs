@@ -1,8 +1,9 @@
 use chrono::Months;
 use uuid::Uuid;
 
-use super::Handler;
+use super::{workspaces::current_workspace, Handler};
 use crate::domains::{
+    audit::{AuditAction, AuditEntity},
     errors::{Error, Result},
     installments::{Installment, InstallmentParams, PartialInstallment},
     transactions::{Transaction, TransactionStatus},
@@ -21,24 +22,65 @@ impl Handler {
             params.installment_number = step;
             due_date = due_date.checked_add_months(Months::new(1)).unwrap();
 
-            let mut partial_installment = PartialInstallment::from_payload(&transaction, &params);
+            let mut partial_installment = PartialInstallment::from_payload(transaction, &params);
 
             partial_installment.due_date = due_date;
 
-            let _ = self
+            let installment = self
                 .installment_repository
-                .create_installment(&partial_installment)
+                .create_installment(current_workspace()?, &partial_installment)
                 .await?;
+
+            self.audit(
+                AuditEntity::Installment,
+                installment.installment_id,
+                AuditAction::Create,
+                None,
+                Some(&installment),
+            )
+            .await?;
         }
 
         Ok(())
     }
 
+    /// installments of accounts the caller can't see are not found
     pub async fn get_installment_by_id(&self, installment_id: Uuid) -> Result<Installment> {
-        self.installment_repository
-            .get_installment_by_id(installment_id)
+        let installment = self
+            .installment_repository
+            .get_installment_by_id(current_workspace()?, installment_id)
             .await?
-            .ok_or(Error::InstallmentNotFound(installment_id))
+            .ok_or(Error::InstallmentNotFound(installment_id))?;
+
+        match self.get_transaction_by_id(installment.transaction_id).await {
+            Err(Error::TransactionNotFound(_)) => Err(Error::InstallmentNotFound(installment_id)),
+            Err(err) => Err(err),
+            Ok(_) => Ok(installment),
+        }
+    }
+
+    /// fetch an installment that is still valid to be referenced
+    pub async fn get_active_installment(&self, installment_id: Uuid) -> Result<Installment> {
+        let installment = self.get_installment_by_id(installment_id).await?;
+
+        if installment.is_deleted() {
+            return Err(Error::InstallmentDeleted(installment_id));
+        }
+
+        Ok(installment)
+    }
+
+    /// whether the transaction still has installments to be settled
+    pub async fn has_pending_installments(&self, transaction_id: Uuid) -> Result<bool> {
+        let installments = self
+            .installment_repository
+            .list_installments(current_workspace()?)
+            .await?;
+
+        Ok(installments
+            .iter()
+            .filter(|i| i.transaction_id == transaction_id && !i.is_deleted())
+            .any(|i| !i.is_finished()))
     }
 
     pub async fn update_installment_status(
@@ -48,13 +90,28 @@ impl Handler {
     ) -> Result<Installment> {
         let result = self.get_installment_by_id(installment_id).await?;
 
+        self.get_recordable_transaction(result.transaction_id)
+            .await?;
+
         if result.is_finished() {
             return Err(Error::InstallmentFinished(installment_id));
         }
 
-        self.installment_repository
-            .update_status(installment_id, status)
+        let installment = self
+            .installment_repository
+            .update_status(current_workspace()?, installment_id, status)
             .await?
-            .ok_or(Error::InstallmentNotFound(installment_id))
+            .ok_or(Error::InstallmentNotFound(installment_id))?;
+
+        self.audit(
+            AuditEntity::Installment,
+            installment_id,
+            AuditAction::StatusChange,
+            Some(&result),
+            Some(&installment),
+        )
+        .await?;
+
+        Ok(installment)
     }
 }
@@ -0,0 +1,229 @@
+use uuid::Uuid;
+
+use crate::domains::{
+    audit::{AuditAction, AuditEntity},
+    errors::{Error, Result},
+    institutions::{CreateInstitution, Institution, UpdateInstitution},
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    pub async fn list_institutions(&self) -> Result<Vec<Institution>> {
+        self.institution_repository
+            .list_institutions(current_workspace()?)
+            .await
+    }
+
+    pub async fn create_institution(&self, payload: CreateInstitution) -> Result<Institution> {
+        let institution = self
+            .institution_repository
+            .create_institution(current_workspace()?, Institution::new_from_payload(payload))
+            .await?;
+
+        self.audit(
+            AuditEntity::Institution,
+            institution.institution_id,
+            AuditAction::Create,
+            None,
+            Some(&institution),
+        )
+        .await?;
+
+        Ok(institution)
+    }
+
+    pub async fn get_institution_by_id(&self, institution_id: Uuid) -> Result<Institution> {
+        self.institution_repository
+            .get_institution_by_id(current_workspace()?, institution_id)
+            .await?
+            .ok_or(Error::InstitutionNotFound(institution_id))
+    }
+
+    /// fetch an institution that can still be referenced by accounts
+    pub async fn get_active_institution(&self, institution_id: Uuid) -> Result<Institution> {
+        let institution = self.get_institution_by_id(institution_id).await?;
+
+        if institution.is_deleted() {
+            return Err(Error::InstitutionDeleted(institution_id));
+        }
+
+        Ok(institution)
+    }
+
+    pub async fn update_institution_by_id(
+        &self,
+        institution_id: Uuid,
+        payload: UpdateInstitution,
+    ) -> Result<Institution> {
+        let before = self.get_active_institution(institution_id).await?;
+
+        let mut institution = before.clone();
+        institution.update(payload);
+
+        let institution = self
+            .institution_repository
+            .update_institution(current_workspace()?, institution)
+            .await?
+            .ok_or(Error::InstitutionDeleted(institution_id))?;
+
+        self.audit(
+            AuditEntity::Institution,
+            institution_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&institution),
+        )
+        .await?;
+
+        Ok(institution)
+    }
+
+    pub async fn delete_institution_by_id(&self, institution_id: Uuid) -> Result<Institution> {
+        let before = self.get_active_institution(institution_id).await?;
+
+        let has_accounts = self
+            .account_repository
+            .list_accounts(current_workspace()?)
+            .await?
+            .iter()
+            .any(|a| a.institution_id == institution_id && !a.is_deleted());
+
+        if has_accounts {
+            return Err(Error::InstitutionHasAccounts(institution_id));
+        }
+
+        let institution = self
+            .institution_repository
+            .delete_institution_by_id(current_workspace()?, institution_id)
+            .await?
+            .ok_or(Error::InstitutionDeleted(institution_id))?;
+
+        self.audit(
+            AuditEntity::Institution,
+            institution_id,
+            AuditAction::Delete,
+            Some(&before),
+            Some(&institution),
+        )
+        .await?;
+
+        Ok(institution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::error::ErrorKind;
+
+    use super::*;
+
+    use crate::{
+        domains::{
+            accounts::{AccountType, CreateAccount},
+            errors::database_error_kind,
+        },
+        handlers::as_owner,
+        repositories::memory::MemoryRepository,
+    };
+
+    fn new_institution(name: &str) -> CreateInstitution {
+        CreateInstitution {
+            name: String::from(name),
+            code: None,
+            logo_url: None,
+        }
+    }
+
+    fn is_unique_violation(result: Result<Institution>) -> bool {
+        matches!(
+            result,
+            Err(Error::DatabaseError(ref err))
+                if database_error_kind(err) == Some(ErrorKind::UniqueViolation)
+        )
+    }
+
+    #[tokio::test]
+    async fn should_not_delete_institution_with_active_accounts() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+
+        as_owner(Uuid::new_v4(), async {
+            let institution = handler
+                .create_institution(new_institution("C6 Bank"))
+                .await
+                .unwrap();
+            let account = handler
+                .create_account(CreateAccount {
+                    institution_id: institution.institution_id,
+                    owner: String::from("owner"),
+                    account_type: AccountType::Debit,
+                })
+                .await
+                .unwrap();
+
+            let result = handler
+                .delete_institution_by_id(institution.institution_id)
+                .await;
+
+            assert!(matches!(
+                result,
+                Err(Error::InstitutionHasAccounts(id)) if id == institution.institution_id
+            ));
+
+            handler
+                .delete_account_by_id(account.account_id)
+                .await
+                .unwrap();
+
+            let institution = handler
+                .delete_institution_by_id(institution.institution_id)
+                .await
+                .unwrap();
+
+            assert!(institution.is_deleted());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn should_not_repeat_institution_names() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+
+        as_owner(Uuid::new_v4(), async {
+            let xp = handler
+                .create_institution(new_institution("XP"))
+                .await
+                .unwrap();
+            let wallet = handler
+                .create_institution(new_institution("Wallet"))
+                .await
+                .unwrap();
+
+            let duplicate = handler.create_institution(new_institution("xp")).await;
+            assert!(is_unique_violation(duplicate));
+
+            let renamed = handler
+                .update_institution_by_id(
+                    wallet.institution_id,
+                    UpdateInstitution {
+                        name: Some(String::from("XP")),
+                        code: None,
+                        logo_url: None,
+                    },
+                )
+                .await;
+            assert!(is_unique_violation(renamed));
+
+            handler
+                .delete_institution_by_id(xp.institution_id)
+                .await
+                .unwrap();
+
+            let reused = handler.create_institution(new_institution("XP")).await;
+            assert!(reused.is_ok());
+        })
+        .await;
+    }
+}
@@ -1,17 +1,43 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use crate::repositories::{
-    accounts::AccountRepository, financial_plans::FinancialPlanRepository,
-    installments::InstallmentRepository, recurrences::RecurrenceRepository,
-    settlements::SettlementRepository, transactions::TransactionRepository,
+use crate::{
+    domains::errors::Result,
+    repositories::{
+        accounts::AccountRepository,
+        audit::AuditRepository,
+        backups::BackupRepository,
+        categories::CategoryRepository,
+        financial_plans::FinancialPlanRepository,
+        imports::ImportRepository,
+        installments::InstallmentRepository,
+        institutions::InstitutionRepository,
+        recurrences::RecurrenceRepository,
+        rules::RuleRepository,
+        settlements::SettlementRepository,
+        tags::TagRepository,
+        transactions::TransactionRepository,
+        unit_of_work::{UnitOfWork, UnitOfWorkRepository},
+        workspaces::WorkspaceRepository,
+        Repository,
+    },
 };
 
 pub mod accounts;
+pub mod audit;
+pub mod backups;
+pub mod categories;
+pub mod exports;
+pub mod financial_plans;
+pub mod imports;
 pub mod installments;
+pub mod institutions;
 pub mod recurrences;
+pub mod reports;
+pub mod rules;
 pub mod settlements;
+pub mod tags;
 pub mod transactions;
-pub mod financial_plans;
+pub mod workspaces;
 
 #[derive(Clone)]
 pub struct Handler {
@@ -21,9 +47,19 @@ pub struct Handler {
     settlement_repository: Arc<dyn SettlementRepository + Send + Sync>,
     recurrence_repository: Arc<dyn RecurrenceRepository + Send + Sync>,
     financial_plan_repository: Arc<dyn FinancialPlanRepository + Send + Sync>,
+    category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+    tag_repository: Arc<dyn TagRepository + Send + Sync>,
+    institution_repository: Arc<dyn InstitutionRepository + Send + Sync>,
+    import_repository: Arc<dyn ImportRepository + Send + Sync>,
+    rule_repository: Arc<dyn RuleRepository + Send + Sync>,
+    backup_repository: Arc<dyn BackupRepository + Send + Sync>,
+    audit_repository: Arc<dyn AuditRepository + Send + Sync>,
+    workspace_repository: Arc<dyn WorkspaceRepository + Send + Sync>,
+    unit_of_work_repository: Arc<dyn UnitOfWorkRepository + Send + Sync>,
 }
 
 impl Handler {
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         transactions_repository: Arc<dyn TransactionRepository + Send + Sync>,
         accounts_repository: Arc<dyn AccountRepository + Send + Sync>,
@@ -31,14 +67,140 @@ impl Handler {
         settlement_repository: Arc<dyn SettlementRepository + Send + Sync>,
         recurrence_repository: Arc<dyn RecurrenceRepository + Send + Sync>,
         financial_plan_repository: Arc<dyn FinancialPlanRepository + Send + Sync>,
+        category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+        tag_repository: Arc<dyn TagRepository + Send + Sync>,
+        institution_repository: Arc<dyn InstitutionRepository + Send + Sync>,
+        import_repository: Arc<dyn ImportRepository + Send + Sync>,
+        rule_repository: Arc<dyn RuleRepository + Send + Sync>,
+        backup_repository: Arc<dyn BackupRepository + Send + Sync>,
+        audit_repository: Arc<dyn AuditRepository + Send + Sync>,
+        workspace_repository: Arc<dyn WorkspaceRepository + Send + Sync>,
+        unit_of_work_repository: Arc<dyn UnitOfWorkRepository + Send + Sync>,
     ) -> Self {
         Self {
             transaction_repository: transactions_repository,
             account_repository: accounts_repository,
-            installment_repository: installment_repository,
-            settlement_repository: settlement_repository,
-            recurrence_repository: recurrence_repository,
-            financial_plan_repository: financial_plan_repository,
+            installment_repository,
+            settlement_repository,
+            recurrence_repository,
+            financial_plan_repository,
+            category_repository,
+            tag_repository,
+            institution_repository,
+            import_repository,
+            rule_repository,
+            backup_repository,
+            audit_repository,
+            workspace_repository,
+            unit_of_work_repository,
+        }
+    }
+
+    /// handler with a single storage behind every repository
+    pub fn with_repository<R: Repository>(repository: Arc<R>) -> Self {
+        Self::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository,
+        )
+    }
+
+    /// run the future in a single unit of work, its changes are kept only when it succeeds
+    pub async fn atomically<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        self.in_unit_of_work(future, Result::is_ok).await?
+    }
+
+    /// run the future in a single unit of work, its changes are kept only when `keep`
+    /// accepts what it returned
+    pub async fn in_unit_of_work<T>(
+        &self,
+        future: impl Future<Output = T>,
+        keep: impl FnOnce(&T) -> bool,
+    ) -> Result<T> {
+        // nested calls join the unit of work already running
+        if UnitOfWork::in_scope() {
+            return Ok(future.await);
         }
+
+        let Some(work) = self.unit_of_work_repository.begin().await? else {
+            return Ok(future.await);
+        };
+
+        let output = work.scope(future).await;
+
+        if keep(&output) {
+            work.commit().await?;
+        }
+
+        Ok(output)
+    }
+}
+
+/// Mocks behind every repository of a handler, tests set the expectations of the ones they use
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockRepositories {
+    pub transaction_repository: crate::repositories::transactions::MockTransactionRepository,
+    pub account_repository: crate::repositories::accounts::MockAccountRepository,
+    pub installment_repository: crate::repositories::installments::MockInstallmentRepository,
+    pub settlement_repository: crate::repositories::settlements::MockSettlementRepository,
+    pub recurrence_repository: crate::repositories::recurrences::MockRecurrenceRepository,
+    pub financial_plan_repository:
+        crate::repositories::financial_plans::MockFinancialPlanRepository,
+    pub category_repository: crate::repositories::categories::MockCategoryRepository,
+    pub tag_repository: crate::repositories::tags::MockTagRepository,
+    pub institution_repository: crate::repositories::institutions::MockInstitutionRepository,
+    pub import_repository: crate::repositories::imports::MockImportRepository,
+    pub rule_repository: crate::repositories::rules::MockRuleRepository,
+    pub backup_repository: crate::repositories::backups::MockBackupRepository,
+    pub audit_repository: crate::repositories::audit::MockAuditRepository,
+    pub workspace_repository: crate::repositories::workspaces::MockWorkspaceRepository,
+    pub unit_of_work_repository: crate::repositories::unit_of_work::MockUnitOfWorkRepository,
+}
+
+/// run the future as the owner of the workspace, the way the routes scope a request
+#[cfg(test)]
+pub async fn as_owner<F: std::future::Future>(workspace_id: uuid::Uuid, future: F) -> F::Output {
+    use crate::domains::workspaces::{WorkspaceMember, WorkspaceRole};
+
+    let member = WorkspaceMember::new(workspace_id, uuid::Uuid::new_v4(), WorkspaceRole::Owner);
+
+    workspaces::WORKSPACE
+        .scope(workspace_id, workspaces::MEMBER.scope(member, future))
+        .await
+}
+
+#[cfg(test)]
+impl MockRepositories {
+    pub fn into_handler(self) -> Handler {
+        Handler::new(
+            Arc::new(self.transaction_repository),
+            Arc::new(self.account_repository),
+            Arc::new(self.installment_repository),
+            Arc::new(self.settlement_repository),
+            Arc::new(self.recurrence_repository),
+            Arc::new(self.financial_plan_repository),
+            Arc::new(self.category_repository),
+            Arc::new(self.tag_repository),
+            Arc::new(self.institution_repository),
+            Arc::new(self.import_repository),
+            Arc::new(self.rule_repository),
+            Arc::new(self.backup_repository),
+            Arc::new(self.audit_repository),
+            Arc::new(self.workspace_repository),
+            Arc::new(self.unit_of_work_repository),
+        )
     }
 }
@@ -1,100 +1,399 @@
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use uuid::Uuid;
 
 use crate::domains::{
+    accounts::AccountPermission,
+    audit::{AuditAction, AuditEntity},
     errors::{Error, Result},
     recurrences::{CreateRecurrence, CreateRecurrenceLink, Recurrence, UpdateRecurrence},
     transactions::Transaction,
 };
 
-use super::Handler;
+use super::{
+    workspaces::{current_workspace, WORKSPACE},
+    Handler,
+};
 
 impl Handler {
     pub async fn list_recurrences(&self) -> Result<Vec<Recurrence>> {
-        let recurrences = self.recurrence_repository.list_recurrences().await?;
+        let access = self.account_access().await?;
 
-        Ok(recurrences)
+        let recurrences = self
+            .recurrence_repository
+            .list_recurrences(current_workspace()?)
+            .await?;
+
+        Ok(recurrences
+            .into_iter()
+            .filter(|r| access.allows(r.account_id, AccountPermission::View))
+            .collect())
     }
 
     pub async fn create_recurrence(&self, payload: CreateRecurrence) -> Result<Recurrence> {
+        self.get_recordable_account(payload.account_id).await?;
+
+        let rule = self
+            .find_rule(&payload.title, payload.movement_type)
+            .await?;
+        let category_id = payload
+            .category_id
+            .or(rule.as_ref().map(|rule| rule.category_id))
+            .ok_or(Error::CategoryRequired)?;
+
+        self.validate_category(category_id, payload.movement_type)
+            .await?;
+
         let recurrence = self
             .recurrence_repository
-            .create_recurrence(Recurrence::new_from_payload(payload))
+            .create_recurrence(
+                current_workspace()?,
+                Recurrence::new_from_payload(payload, category_id),
+            )
             .await?;
 
+        self.audit(
+            AuditEntity::Recurrence,
+            recurrence.recurrence_id,
+            AuditAction::Create,
+            None,
+            Some(&recurrence),
+        )
+        .await?;
+
+        if let Some(rule) = rule {
+            for tag_id in &rule.tag_ids {
+                self.tag_repository
+                    .attach_recurrence_tag(current_workspace()?, recurrence.recurrence_id, *tag_id)
+                    .await?;
+
+                self.audit_tag(
+                    AuditEntity::Recurrence,
+                    recurrence.recurrence_id,
+                    AuditAction::TagAttach,
+                    *tag_id,
+                )
+                .await?;
+            }
+
+            self.rule_repository
+                .record_hits(current_workspace()?, rule.rule_id, 1)
+                .await?;
+        }
+
         Ok(recurrence)
     }
 
+    /// recurrences of accounts the caller can't see are not found
     pub async fn get_recurrence_by_id(&self, recurrence_id: Uuid) -> Result<Recurrence> {
+        let access = self.account_access().await?;
+
         self.recurrence_repository
-            .get_recurrence_by_id(recurrence_id)
+            .get_recurrence_by_id(current_workspace()?, recurrence_id)
             .await?
+            .filter(|r| access.allows(r.account_id, AccountPermission::View))
             .ok_or(Error::RecurrenceNotFound(recurrence_id))
     }
 
+    /// fetch a recurrence of an account the caller can record in
+    pub async fn get_recordable_recurrence(&self, recurrence_id: Uuid) -> Result<Recurrence> {
+        let recurrence = self.get_recurrence_by_id(recurrence_id).await?;
+
+        self.check_account_permission(recurrence.account_id, AccountPermission::Record)
+            .await?;
+
+        Ok(recurrence)
+    }
+
     pub async fn update_recurrence(
         &self,
         recurrence_id: Uuid,
         payload: UpdateRecurrence,
     ) -> Result<Recurrence> {
-        let mut recurrence = self.get_recurrence_by_id(recurrence_id).await?;
+        let mut recurrence = self.get_recordable_recurrence(recurrence_id).await?;
+
+        if let Some(account_id) = payload.account_id {
+            self.get_recordable_account(account_id).await?;
+        }
+
+        let category_changed = payload.category_id.is_some() || payload.movement_type.is_some();
 
+        let before = recurrence.clone();
         recurrence.update(payload);
 
+        if category_changed {
+            self.validate_category(recurrence.category_id, recurrence.movement_type)
+                .await?;
+        }
+
         let result = self
             .recurrence_repository
-            .update_recurrence(recurrence)
+            .update_recurrence(current_workspace()?, recurrence)
             .await?
             .ok_or(Error::RecurrenceNotFound(recurrence_id))?;
 
+        self.audit(
+            AuditEntity::Recurrence,
+            recurrence_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&result),
+        )
+        .await?;
+
         Ok(result)
     }
 
-    // REFAC: change logic to financial_plan
+    /// generate the due transactions of every workspace, a failing workspace
+    /// does not stop the others
+    pub async fn generate_all_recurrences(&self) -> Result<()> {
+        for workspace_id in self.workspace_repository.list_workspace_ids().await? {
+            if let Err(err) = WORKSPACE
+                .scope(workspace_id, self.generate_recurrences())
+                .await
+            {
+                log::error!("Could not generate recurrences of workspace {workspace_id}: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// create the next transaction of every active recurrence once it is due
     pub async fn generate_recurrences(&self) -> Result<()> {
-        todo!()
-        // let recurrences = self.recurrence_repository.list_recurrences().await?;
-
-        // let active_recurrences: Vec<&Recurrence> =
-        //     recurrences.iter().filter(|r| r.is_active()).collect();
-        // let recurrence_ids: Vec<Uuid> =
-        //     active_recurrences.iter().map(|r| r.recurrence_id).collect();
-
-        // let references = self
-        //     .recurrence_repository
-        //     .get_recurrence_link(recurrence_ids)
-        //     .await?;
-
-        // for recurrence in active_recurrences {
-        //     let last_recurrency = references
-        //         .get(&recurrence.recurrence_id)
-        //         .and_then(|r| r.iter().max_by_key(|item| item.due_date));
-
-        //     let next_due_date = match last_recurrency {
-        //         Some(r) => recurrence.get_next_date_from_frequency(r.due_date),
-        //         None => recurrence.get_next_date_from_frequency(recurrence.start_date),
-        //     };
-
-        //     let today = Utc::now().date_naive();
-        //     if next_due_date <= today {
-        //         let payload = recurrence.new_recurrency_transaction(next_due_date);
-
-        //         let transaction = self
-        //             .transaction_repository
-        //             .create_transaction(Transaction::from_payload(payload))
-        //             .await?;
-
-        //         let link = CreateRecurrenceLink {
-        //             recurrence_id: recurrence.recurrence_id,
-        //             transaction_id: transaction.transaction_id,
-        //         };
-
-        //         self.recurrence_repository
-        //             .create_recurrence_link(link)
-        //             .await?;
-        //     }
-        // }
-
-        // Ok(())
+        let recurrences = self
+            .recurrence_repository
+            .list_recurrences(current_workspace()?)
+            .await?;
+
+        let active_recurrences: Vec<&Recurrence> = recurrences
+            .iter()
+            .filter(|r| r.is_active() && r.deleted_at.is_none())
+            .collect();
+        let recurrence_ids: Vec<Uuid> =
+            active_recurrences.iter().map(|r| r.recurrence_id).collect();
+
+        let references = self
+            .recurrence_repository
+            .get_recurrence_link(current_workspace()?, recurrence_ids)
+            .await?;
+
+        let today = Utc::now().date_naive();
+
+        for recurrence in active_recurrences {
+            let last_recurrency = references
+                .get(&recurrence.recurrence_id)
+                .and_then(|r| r.iter().max_by_key(|item| item.due_date));
+
+            let next_due_date = match last_recurrency {
+                Some(r) => recurrence.get_next_date_from_frequency(r.due_date),
+                None => recurrence.start_date,
+            };
+
+            if next_due_date > today {
+                continue;
+            }
+
+            if let Err(err) = self
+                .atomically(self.generate_recurrence(recurrence, next_due_date))
+                .await
+            {
+                log::warn!(
+                    "Could not generate recurrence {}: {err}",
+                    recurrence.recurrence_id
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the service generates the transaction, so the checks of the caller are skipped, the
+    /// account and category were checked with the recurrence
+    async fn generate_recurrence(
+        &self,
+        recurrence: &Recurrence,
+        due_date: NaiveDate,
+    ) -> Result<Transaction> {
+        let financial_plan = self.get_or_create_financial_plan(due_date).await?;
+
+        if financial_plan.is_closed() {
+            return Err(Error::FinancialPlanClosed(financial_plan.financial_plan_id));
+        }
+
+        let transaction = Transaction::from_payload(
+            recurrence.new_recurrency_transaction(due_date, financial_plan.financial_plan_id),
+            recurrence.category_id,
+        );
+
+        let transaction = self
+            .transaction_repository
+            .create_transaction(current_workspace()?, transaction)
+            .await?;
+
+        self.audit(
+            AuditEntity::Transaction,
+            transaction.transaction_id,
+            AuditAction::Create,
+            None,
+            Some(&transaction),
+        )
+        .await?;
+
+        self.recurrence_repository
+            .create_recurrence_link(
+                current_workspace()?,
+                CreateRecurrenceLink {
+                    recurrence_id: recurrence.recurrence_id,
+                    transaction_id: transaction.transaction_id,
+                },
+            )
+            .await?;
+
+        self.copy_recurrence_tags(recurrence.recurrence_id, transaction.transaction_id)
+            .await?;
+
+        Ok(transaction)
+    }
+
+    /// attach the tags of the recurrence to a transaction generated from it
+    async fn copy_recurrence_tags(&self, recurrence_id: Uuid, transaction_id: Uuid) -> Result<()> {
+        let tags = self
+            .tag_repository
+            .list_recurrence_tags(current_workspace()?, recurrence_id)
+            .await?;
+
+        for tag in tags {
+            self.tag_repository
+                .attach_transaction_tag(current_workspace()?, transaction_id, tag.tag_id)
+                .await?;
+
+            self.audit_tag(
+                AuditEntity::Transaction,
+                transaction_id,
+                AuditAction::TagAttach,
+                tag.tag_id,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bigdecimal::BigDecimal;
+
+    use super::*;
+
+    use crate::{
+        domains::{
+            accounts::{AccountType, CreateAccount},
+            categories::{CategoryKind, CreateCategory},
+            institutions::CreateInstitution,
+            recurrences::Frequency,
+            tags::CreateTag,
+            transactions::MovementType,
+            workspaces::CreateWorkspace,
+        },
+        handlers::as_owner,
+        repositories::memory::MemoryRepository,
+    };
+
+    #[tokio::test]
+    async fn should_generate_transactions_with_the_tags_of_their_recurrence() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let workspace_id = handler
+            .create_workspace(
+                Uuid::new_v4(),
+                CreateWorkspace {
+                    name: String::from("Home"),
+                },
+            )
+            .await
+            .unwrap()
+            .workspace
+            .workspace_id;
+
+        let (recurrence, tag) = as_owner(workspace_id, async {
+            let institution = handler
+                .create_institution(CreateInstitution {
+                    name: String::from("C6 Bank"),
+                    code: None,
+                    logo_url: None,
+                })
+                .await
+                .unwrap();
+            let account = handler
+                .create_account(CreateAccount {
+                    institution_id: institution.institution_id,
+                    owner: String::from("owner"),
+                    account_type: AccountType::Debit,
+                })
+                .await
+                .unwrap();
+            let category = handler
+                .create_category(CreateCategory {
+                    parent_id: None,
+                    name: String::from("Housing"),
+                    icon: None,
+                    color: None,
+                    kind: CategoryKind::Expense,
+                })
+                .await
+                .unwrap();
+            let tag = handler
+                .create_tag(CreateTag {
+                    name: String::from("reimbursable"),
+                    color: None,
+                })
+                .await
+                .unwrap();
+            let recurrence = handler
+                .create_recurrence(CreateRecurrence {
+                    account_id: account.account_id,
+                    title: String::from("Rent"),
+                    frequency: Frequency::Monthly,
+                    category_id: Some(category.category_id),
+                    is_active: true,
+                    start_date: Utc::now().date_naive(),
+                    value: BigDecimal::from(1500),
+                    movement_type: MovementType::Expense,
+                })
+                .await
+                .unwrap();
+
+            handler
+                .attach_recurrence_tag(recurrence.recurrence_id, tag.tag_id)
+                .await
+                .unwrap();
+
+            (recurrence, tag)
+        })
+        .await;
+
+        // the scheduler runs without a caller, and the next date is not due on the second run
+        handler.generate_all_recurrences().await.unwrap();
+        handler.generate_all_recurrences().await.unwrap();
+
+        let transactions = handler
+            .transaction_repository
+            .list_transactions(workspace_id)
+            .await
+            .unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].description, recurrence.title);
+
+        let tags = handler
+            .tag_repository
+            .list_transaction_tags(workspace_id, transactions[0].transaction_id)
+            .await
+            .unwrap();
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].tag_id, tag.tag_id);
     }
 }
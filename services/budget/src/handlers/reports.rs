@@ -0,0 +1,64 @@
+use uuid::Uuid;
+
+use crate::domains::{
+    accounts::AccountPermission,
+    errors::Result,
+    reports::{CategoryTotal, ReportFilter, ReportParams, TagTotal},
+    transactions::Transaction,
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    pub async fn category_report(&self, params: ReportParams) -> Result<Vec<CategoryTotal>> {
+        let categories = self.list_categories().await?;
+
+        let category_ids = match params.category_id {
+            Some(category_id) => {
+                let category = self.get_active_category(category_id).await?;
+
+                let mut ids: Vec<Uuid> = categories
+                    .iter()
+                    .filter(|c| c.parent_id == Some(category.category_id) && !c.is_deleted())
+                    .map(|c| c.category_id)
+                    .collect();
+                ids.push(category.category_id);
+
+                Some(ids)
+            }
+            None => None,
+        };
+
+        let transactions = self
+            .list_report_transactions(ReportFilter::new(&params, category_ids))
+            .await?;
+
+        Ok(CategoryTotal::rollup(&categories, &transactions))
+    }
+
+    pub async fn tag_report(&self, params: ReportParams) -> Result<Vec<TagTotal>> {
+        let tags = self.list_tags().await?;
+        let transactions = self
+            .list_report_transactions(ReportFilter::new(&params, None))
+            .await?;
+        let links = self
+            .tag_repository
+            .list_transaction_tag_links(current_workspace()?)
+            .await?;
+
+        Ok(TagTotal::summarize(&tags, &transactions, &links))
+    }
+
+    /// transactions matching the filter in the accounts the caller can see
+    async fn list_report_transactions(&self, filter: ReportFilter) -> Result<Vec<Transaction>> {
+        let access = self.account_access().await?;
+
+        Ok(self
+            .transaction_repository
+            .list_report_transactions(current_workspace()?, filter)
+            .await?
+            .into_iter()
+            .filter(|t| access.allows(t.account_id, AccountPermission::View))
+            .collect())
+    }
+}
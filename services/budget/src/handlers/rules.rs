@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domains::{
+    accounts::AccountPermission,
+    audit::{AuditAction, AuditEntity},
+    errors::{Error, Result},
+    rules::{CreateRule, Rule, RuleChange, RuleTarget, UpdateRule},
+    tags::TransactionTag,
+    transactions::MovementType,
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    pub async fn list_rules(&self) -> Result<Vec<Rule>> {
+        self.rule_repository.list_rules(current_workspace()?).await
+    }
+
+    pub async fn create_rule(&self, payload: CreateRule) -> Result<Rule> {
+        self.validate_category(payload.category_id, payload.movement_type)
+            .await?;
+
+        for tag_id in &payload.tag_ids {
+            self.get_active_tag(*tag_id).await?;
+        }
+
+        let rule = self
+            .rule_repository
+            .create_rule(current_workspace()?, Rule::new_from_payload(payload))
+            .await?;
+
+        self.audit(
+            AuditEntity::Rule,
+            rule.rule_id,
+            AuditAction::Create,
+            None,
+            Some(&rule),
+        )
+        .await?;
+
+        Ok(rule)
+    }
+
+    pub async fn get_rule_by_id(&self, rule_id: Uuid) -> Result<Rule> {
+        self.rule_repository
+            .get_rule_by_id(current_workspace()?, rule_id)
+            .await?
+            .ok_or(Error::RuleNotFound(rule_id))
+    }
+
+    pub async fn update_rule_by_id(&self, rule_id: Uuid, payload: UpdateRule) -> Result<Rule> {
+        let mut rule = self.get_rule_by_id(rule_id).await?;
+
+        if rule.is_deleted() {
+            return Err(Error::RuleDeleted(rule_id));
+        }
+
+        if let Some(tag_ids) = &payload.tag_ids {
+            for tag_id in tag_ids {
+                self.get_active_tag(*tag_id).await?;
+            }
+        }
+
+        let category_changed = payload.category_id.is_some() || payload.movement_type.is_some();
+
+        let before = rule.clone();
+        rule.update(payload);
+
+        if category_changed {
+            self.validate_category(rule.category_id, rule.movement_type)
+                .await?;
+        }
+
+        let rule = self
+            .rule_repository
+            .update_rule(current_workspace()?, rule)
+            .await?
+            .ok_or(Error::RuleDeleted(rule_id))?;
+
+        self.audit(
+            AuditEntity::Rule,
+            rule_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&rule),
+        )
+        .await?;
+
+        Ok(rule)
+    }
+
+    pub async fn delete_rule_by_id(&self, rule_id: Uuid) -> Result<Rule> {
+        let before = self.get_rule_by_id(rule_id).await?;
+
+        let rule = self
+            .rule_repository
+            .delete_rule_by_id(current_workspace()?, rule_id)
+            .await?
+            .ok_or(Error::RuleDeleted(rule_id))?;
+
+        self.audit(
+            AuditEntity::Rule,
+            rule_id,
+            AuditAction::Delete,
+            Some(&before),
+            Some(&rule),
+        )
+        .await?;
+
+        Ok(rule)
+    }
+
+    /// rule that applies to a new record, its hit is recorded by the caller once applied
+    pub async fn find_rule(
+        &self,
+        description: &str,
+        movement_type: MovementType,
+    ) -> Result<Option<Rule>> {
+        let rules = self
+            .rule_repository
+            .list_rules(current_workspace()?)
+            .await?;
+
+        Ok(Rule::find(&rules, description, movement_type).cloned())
+    }
+
+    /// run every rule over the existing transactions and recurrences,
+    /// a dry run only reports what would change
+    pub async fn apply_rules(&self, dry_run: bool) -> Result<Vec<RuleChange>> {
+        let access = self.account_access().await?;
+        let recordable = |account_id| access.allows(account_id, AccountPermission::Record);
+
+        let rules = self
+            .rule_repository
+            .list_rules(current_workspace()?)
+            .await?;
+
+        let transactions = self
+            .transaction_repository
+            .list_transactions(current_workspace()?)
+            .await?;
+        let transaction_tags = TransactionTag::group_by_transaction(
+            &self
+                .tag_repository
+                .list_transaction_tag_links(current_workspace()?)
+                .await?,
+        );
+
+        let mut changes = vec![];
+
+        for transaction in transactions
+            .iter()
+            .filter(|t| !t.is_deleted() && recordable(t.account_id))
+        {
+            let Some(rule) =
+                Rule::find(&rules, &transaction.description, transaction.movement_type)
+            else {
+                continue;
+            };
+
+            changes.extend(RuleChange::new(
+                rule,
+                RuleTarget::Transaction,
+                transaction.transaction_id,
+                &transaction.description,
+                transaction.category_id,
+                transaction_tags.get(&transaction.transaction_id),
+            ));
+        }
+
+        let recurrences = self
+            .recurrence_repository
+            .list_recurrences(current_workspace()?)
+            .await?;
+
+        for recurrence in recurrences
+            .iter()
+            .filter(|r| r.deleted_at.is_none() && recordable(r.account_id))
+        {
+            let Some(rule) = Rule::find(&rules, &recurrence.title, recurrence.movement_type) else {
+                continue;
+            };
+
+            let tags: HashSet<Uuid> = self
+                .tag_repository
+                .list_recurrence_tags(current_workspace()?, recurrence.recurrence_id)
+                .await?
+                .into_iter()
+                .map(|tag| tag.tag_id)
+                .collect();
+
+            changes.extend(RuleChange::new(
+                rule,
+                RuleTarget::Recurrence,
+                recurrence.recurrence_id,
+                &recurrence.title,
+                recurrence.category_id,
+                Some(&tags),
+            ));
+        }
+
+        if dry_run {
+            return Ok(changes);
+        }
+
+        for change in &changes {
+            self.apply_rule_change(change).await?;
+        }
+
+        let mut hits: HashMap<Uuid, i64> = HashMap::new();
+
+        for change in &changes {
+            *hits.entry(change.rule_id).or_default() += 1;
+        }
+
+        for (rule_id, hits) in hits {
+            self.rule_repository
+                .record_hits(current_workspace()?, rule_id, hits)
+                .await?;
+        }
+
+        Ok(changes)
+    }
+
+    async fn apply_rule_change(&self, change: &RuleChange) -> Result<()> {
+        match change.target {
+            RuleTarget::Transaction => {
+                let before = self.get_transaction_by_id(change.record_id).await?;
+
+                if before.category_id != change.category_id {
+                    let mut transaction = before.clone();
+                    transaction.category_id = change.category_id;
+                    transaction.updated_at = Some(Utc::now());
+
+                    let after = self
+                        .transaction_repository
+                        .update_transaction_by_id(current_workspace()?, transaction)
+                        .await?;
+
+                    self.audit(
+                        AuditEntity::Transaction,
+                        change.record_id,
+                        AuditAction::Update,
+                        Some(&before),
+                        after.as_ref(),
+                    )
+                    .await?;
+                }
+
+                for tag_id in &change.added_tag_ids {
+                    self.tag_repository
+                        .attach_transaction_tag(current_workspace()?, change.record_id, *tag_id)
+                        .await?;
+
+                    self.audit_tag(
+                        AuditEntity::Transaction,
+                        change.record_id,
+                        AuditAction::TagAttach,
+                        *tag_id,
+                    )
+                    .await?;
+                }
+            }
+            RuleTarget::Recurrence => {
+                let before = self.get_recurrence_by_id(change.record_id).await?;
+
+                if before.category_id != change.category_id {
+                    let mut recurrence = before.clone();
+                    recurrence.category_id = change.category_id;
+                    recurrence.updated_at = Some(Utc::now());
+
+                    let after = self
+                        .recurrence_repository
+                        .update_recurrence(current_workspace()?, recurrence)
+                        .await?;
+
+                    self.audit(
+                        AuditEntity::Recurrence,
+                        change.record_id,
+                        AuditAction::Update,
+                        Some(&before),
+                        after.as_ref(),
+                    )
+                    .await?;
+                }
+
+                for tag_id in &change.added_tag_ids {
+                    self.tag_repository
+                        .attach_recurrence_tag(current_workspace()?, change.record_id, *tag_id)
+                        .await?;
+
+                    self.audit_tag(
+                        AuditEntity::Recurrence,
+                        change.record_id,
+                        AuditAction::TagAttach,
+                        *tag_id,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
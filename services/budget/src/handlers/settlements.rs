@@ -1,10 +1,16 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
 use crate::domains::{
-    errors::Result,
+    accounts::AccountPermission,
+    audit::{AuditAction, AuditEntity},
+    errors::{Error, Result},
     settlements::{CreateSettlement, Settlement, SettlementParams},
     transactions::TransactionStatus,
 };
 
-use super::Handler;
+use super::{workspaces::current_workspace, Handler};
 
 impl Handler {
     pub async fn create_settlement(
@@ -12,33 +18,198 @@ impl Handler {
         payload: CreateSettlement,
         query: SettlementParams,
     ) -> Result<Settlement> {
-        let transaction = self.get_transaction_by_id(query.transaction_id).await?;
+        let transaction = self.get_active_transaction(query.transaction_id).await?;
 
-        if let Some(installment_id) = query.installment_id.clone() {
-            self.get_installment_by_id(installment_id).await?;
+        self.check_account_permission(transaction.account_id, AccountPermission::Record)
+            .await?;
+
+        if let Some(installment_id) = query.installment_id {
+            let installment = self.get_active_installment(installment_id).await?;
+
+            if installment.transaction_id != transaction.transaction_id {
+                return Err(Error::InstallmentTransactionMismatch(installment_id));
+            }
         }
 
         let new_settlement = Settlement::new_from_payload(payload, query);
 
         let settlement = self
             .settlement_repository
-            .create_settlement(new_settlement)
+            .create_settlement(current_workspace()?, new_settlement)
             .await?;
 
-        self.finish_transaction(transaction.transaction_id, TransactionStatus::Completed)
-            .await?;
+        self.audit(
+            AuditEntity::Settlement,
+            settlement.settlement_id,
+            AuditAction::Create,
+            None,
+            Some(&settlement),
+        )
+        .await?;
 
         if let Some(installment_id) = query.installment_id {
             self.update_installment_status(installment_id, TransactionStatus::Completed)
                 .await?;
         }
 
+        if self
+            .has_pending_installments(transaction.transaction_id)
+            .await?
+        {
+            return Ok(settlement);
+        }
+
+        self.finish_transaction(transaction.transaction_id, TransactionStatus::Completed)
+            .await?;
+
         Ok(settlement)
     }
 
+    /// settlements of the transactions in accounts the caller can see
     pub async fn list_settlements(&self) -> Result<Vec<Settlement>> {
-        let settlements = self.settlement_repository.list_settlements().await?;
+        let access = self.account_access().await?;
+
+        let visible: HashSet<Uuid> = self
+            .transaction_repository
+            .list_transactions(current_workspace()?)
+            .await?
+            .into_iter()
+            .filter(|t| access.allows(t.account_id, AccountPermission::View))
+            .map(|t| t.transaction_id)
+            .collect();
+
+        let settlements = self
+            .settlement_repository
+            .list_settlements(current_workspace()?)
+            .await?;
+
+        Ok(settlements
+            .into_iter()
+            .filter(|s| visible.contains(&s.transaction_id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use super::*;
+
+    use crate::{
+        domains::{
+            accounts::{AccountType, CreateAccount},
+            categories::{CategoryKind, CreateCategory},
+            financial_plans::{CreateFinancialPlan, MonthReference},
+            institutions::CreateInstitution,
+            transactions::{CreateTransaction, MovementType},
+        },
+        handlers::as_owner,
+        repositories::memory::MemoryRepository,
+    };
+
+    fn new_settlement() -> CreateSettlement {
+        CreateSettlement {
+            paid_date: NaiveDate::from_ymd_opt(2024, 5, 10).unwrap(),
+            paid_value: BigDecimal::from(50),
+            discount: None,
+            fees: None,
+            attachment: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_complete_transaction_once_every_installment_is_settled() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let workspace_id = Uuid::new_v4();
+
+        as_owner(workspace_id, async {
+            let institution = handler
+                .create_institution(CreateInstitution {
+                    name: String::from("Nubank"),
+                    code: Some(String::from("260")),
+                    logo_url: None,
+                })
+                .await
+                .unwrap();
+            let account = handler
+                .create_account(CreateAccount {
+                    institution_id: institution.institution_id,
+                    owner: String::from("owner"),
+                    account_type: AccountType::Credit,
+                })
+                .await
+                .unwrap();
+            let category = handler
+                .create_category(CreateCategory {
+                    parent_id: None,
+                    name: String::from("Home"),
+                    icon: None,
+                    color: None,
+                    kind: CategoryKind::Expense,
+                })
+                .await
+                .unwrap();
+            let financial_plan = handler
+                .create_financial_plan(CreateFinancialPlan {
+                    title: None,
+                    month: MonthReference::May,
+                    year: 2024,
+                })
+                .await
+                .unwrap();
+
+            let transaction = handler
+                .create_transaction(CreateTransaction {
+                    financial_plan_id: financial_plan.financial_plan_id,
+                    movement_type: MovementType::Expense,
+                    description: String::from("sofa"),
+                    value: BigDecimal::from(100),
+                    due_date: NaiveDate::from_ymd_opt(2024, 5, 10).unwrap(),
+                    category_id: Some(category.category_id),
+                    account_id: account.account_id,
+                    installments: 2,
+                })
+                .await
+                .unwrap();
+
+            let installments = handler
+                .installment_repository
+                .list_installments(workspace_id)
+                .await
+                .unwrap();
+
+            assert_eq!(installments.len(), 2);
+
+            for (index, installment) in installments.iter().enumerate() {
+                let params = SettlementParams {
+                    transaction_id: transaction.transaction_id,
+                    installment_id: Some(installment.installment_id),
+                };
+
+                handler
+                    .create_settlement(new_settlement(), params)
+                    .await
+                    .unwrap();
+
+                let status = handler
+                    .get_transaction_by_id(transaction.transaction_id)
+                    .await
+                    .unwrap()
+                    .status;
+                let expected = if index == 0 {
+                    TransactionStatus::Pending
+                } else {
+                    TransactionStatus::Completed
+                };
 
-        Ok(settlements)
+                assert_eq!(status, expected);
+            }
+        })
+        .await;
     }
 }
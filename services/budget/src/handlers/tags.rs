@@ -0,0 +1,213 @@
+use uuid::Uuid;
+
+use crate::domains::{
+    accounts::AccountPermission,
+    audit::{AuditAction, AuditEntity},
+    errors::{Error, Result},
+    tags::{CreateTag, Tag, UpdateTag},
+};
+
+use super::{workspaces::current_workspace, Handler};
+
+impl Handler {
+    pub async fn list_tags(&self) -> Result<Vec<Tag>> {
+        self.tag_repository.list_tags(current_workspace()?).await
+    }
+
+    pub async fn create_tag(&self, payload: CreateTag) -> Result<Tag> {
+        let tag = self
+            .tag_repository
+            .create_tag(current_workspace()?, Tag::new_from_payload(payload))
+            .await?;
+
+        self.audit(
+            AuditEntity::Tag,
+            tag.tag_id,
+            AuditAction::Create,
+            None,
+            Some(&tag),
+        )
+        .await?;
+
+        Ok(tag)
+    }
+
+    pub async fn get_tag_by_id(&self, tag_id: Uuid) -> Result<Tag> {
+        self.tag_repository
+            .get_tag_by_id(current_workspace()?, tag_id)
+            .await?
+            .ok_or(Error::TagNotFound(tag_id))
+    }
+
+    /// fetch a tag that can still be attached to records
+    pub async fn get_active_tag(&self, tag_id: Uuid) -> Result<Tag> {
+        let tag = self.get_tag_by_id(tag_id).await?;
+
+        if tag.is_deleted() {
+            return Err(Error::TagDeleted(tag_id));
+        }
+
+        Ok(tag)
+    }
+
+    pub async fn update_tag_by_id(&self, tag_id: Uuid, payload: UpdateTag) -> Result<Tag> {
+        let before = self.get_active_tag(tag_id).await?;
+
+        let mut tag = before.clone();
+        tag.update(payload);
+
+        let tag = self
+            .tag_repository
+            .update_tag(current_workspace()?, tag)
+            .await?
+            .ok_or(Error::TagDeleted(tag_id))?;
+
+        self.audit(
+            AuditEntity::Tag,
+            tag_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&tag),
+        )
+        .await?;
+
+        Ok(tag)
+    }
+
+    pub async fn delete_tag_by_id(&self, tag_id: Uuid) -> Result<Tag> {
+        let before = self.get_active_tag(tag_id).await?;
+
+        let tag = self
+            .tag_repository
+            .delete_tag_by_id(current_workspace()?, tag_id)
+            .await?
+            .ok_or(Error::TagDeleted(tag_id))?;
+
+        self.audit(
+            AuditEntity::Tag,
+            tag_id,
+            AuditAction::Delete,
+            Some(&before),
+            Some(&tag),
+        )
+        .await?;
+
+        Ok(tag)
+    }
+
+    pub async fn list_transaction_tags(&self, transaction_id: Uuid) -> Result<Vec<Tag>> {
+        self.get_transaction_by_id(transaction_id).await?;
+
+        self.tag_repository
+            .list_transaction_tags(current_workspace()?, transaction_id)
+            .await
+    }
+
+    pub async fn attach_transaction_tag(
+        &self,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        let transaction = self.get_active_transaction(transaction_id).await?;
+        self.check_account_permission(transaction.account_id, AccountPermission::Record)
+            .await?;
+        self.get_active_tag(tag_id).await?;
+
+        self.tag_repository
+            .attach_transaction_tag(current_workspace()?, transaction_id, tag_id)
+            .await?;
+
+        self.audit_tag(
+            AuditEntity::Transaction,
+            transaction_id,
+            AuditAction::TagAttach,
+            tag_id,
+        )
+        .await?;
+
+        self.tag_repository
+            .list_transaction_tags(current_workspace()?, transaction_id)
+            .await
+    }
+
+    pub async fn detach_transaction_tag(
+        &self,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        self.get_recordable_transaction(transaction_id).await?;
+
+        self.tag_repository
+            .detach_transaction_tag(current_workspace()?, transaction_id, tag_id)
+            .await?;
+
+        self.audit_tag(
+            AuditEntity::Transaction,
+            transaction_id,
+            AuditAction::TagDetach,
+            tag_id,
+        )
+        .await?;
+
+        self.tag_repository
+            .list_transaction_tags(current_workspace()?, transaction_id)
+            .await
+    }
+
+    pub async fn list_recurrence_tags(&self, recurrence_id: Uuid) -> Result<Vec<Tag>> {
+        self.get_recurrence_by_id(recurrence_id).await?;
+
+        self.tag_repository
+            .list_recurrence_tags(current_workspace()?, recurrence_id)
+            .await
+    }
+
+    pub async fn attach_recurrence_tag(
+        &self,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        self.get_recordable_recurrence(recurrence_id).await?;
+        self.get_active_tag(tag_id).await?;
+
+        self.tag_repository
+            .attach_recurrence_tag(current_workspace()?, recurrence_id, tag_id)
+            .await?;
+
+        self.audit_tag(
+            AuditEntity::Recurrence,
+            recurrence_id,
+            AuditAction::TagAttach,
+            tag_id,
+        )
+        .await?;
+
+        self.tag_repository
+            .list_recurrence_tags(current_workspace()?, recurrence_id)
+            .await
+    }
+
+    pub async fn detach_recurrence_tag(
+        &self,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        self.get_recordable_recurrence(recurrence_id).await?;
+
+        self.tag_repository
+            .detach_recurrence_tag(current_workspace()?, recurrence_id, tag_id)
+            .await?;
+
+        self.audit_tag(
+            AuditEntity::Recurrence,
+            recurrence_id,
+            AuditAction::TagDetach,
+            tag_id,
+        )
+        .await?;
+
+        self.tag_repository
+            .list_recurrence_tags(current_workspace()?, recurrence_id)
+            .await
+    }
+}
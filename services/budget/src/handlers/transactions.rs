@@ -2,53 +2,171 @@ use bigdecimal::Zero;
 use uuid::Uuid;
 
 use crate::domains::{
+    accounts::AccountPermission,
+    audit::{AuditAction, AuditEntity},
     errors::{Error, Result},
+    tags::TransactionTag,
     transactions::{CreateTransaction, Transaction, TransactionStatus, UpdateTransaction},
+    views::TransactionFilterParams,
 };
 
-use super::Handler;
+use super::{workspaces::current_workspace, Handler};
 
 impl Handler {
     pub async fn create_transaction(&self, payload: CreateTransaction) -> Result<Transaction> {
+        self.get_recordable_account(payload.account_id).await?;
+        self.get_open_financial_plan(payload.financial_plan_id)
+            .await?;
+
+        let rule = self
+            .find_rule(&payload.description, payload.movement_type)
+            .await?;
+        let category_id = payload
+            .category_id
+            .or(rule.as_ref().map(|rule| rule.category_id))
+            .ok_or(Error::CategoryRequired)?;
+
+        self.validate_category(category_id, payload.movement_type)
+            .await?;
+
         let total_installments = payload.installments;
 
-        let transaction = Transaction::from_payload(payload);
+        let transaction = Transaction::from_payload(payload, category_id);
 
         let transaction = self
             .transaction_repository
-            .create_transaction(transaction)
+            .create_transaction(current_workspace()?, transaction)
             .await?;
 
+        self.audit(
+            AuditEntity::Transaction,
+            transaction.transaction_id,
+            AuditAction::Create,
+            None,
+            Some(&transaction),
+        )
+        .await?;
+
         if !total_installments.is_zero() {
             self.create_installment(&transaction, total_installments)
                 .await?;
         }
 
+        if let Some(rule) = rule {
+            for tag_id in &rule.tag_ids {
+                self.tag_repository
+                    .attach_transaction_tag(
+                        current_workspace()?,
+                        transaction.transaction_id,
+                        *tag_id,
+                    )
+                    .await?;
+
+                self.audit_tag(
+                    AuditEntity::Transaction,
+                    transaction.transaction_id,
+                    AuditAction::TagAttach,
+                    *tag_id,
+                )
+                .await?;
+            }
+
+            self.rule_repository
+                .record_hits(current_workspace()?, rule.rule_id, 1)
+                .await?;
+        }
+
         Ok(transaction)
     }
 
-    pub async fn list_transactions(&self) -> Result<Vec<Transaction>> {
-        self.transaction_repository.list_transactions().await
+    pub async fn list_transactions(
+        &self,
+        params: TransactionFilterParams,
+    ) -> Result<Vec<Transaction>> {
+        let access = self.account_access().await?;
+        let transactions = self
+            .transaction_repository
+            .list_transactions(current_workspace()?)
+            .await?;
+
+        let tags = if params.tags.is_empty() {
+            Default::default()
+        } else {
+            let links = self
+                .tag_repository
+                .list_transaction_tag_links(current_workspace()?)
+                .await?;
+            TransactionTag::group_by_transaction(&links)
+        };
+
+        Ok(transactions
+            .into_iter()
+            .filter(|t| access.allows(t.account_id, AccountPermission::View))
+            .filter(|t| params.status.is_none_or(|status| t.status == status))
+            .filter(|t| {
+                params.tags.is_empty()
+                    || params
+                        .tag_match
+                        .matches(&params.tags, tags.get(&t.transaction_id))
+            })
+            .collect())
     }
 
+    /// transactions of accounts the caller can't see are not found
     pub async fn get_transaction_by_id(&self, transaction_id: Uuid) -> Result<Transaction> {
+        let access = self.account_access().await?;
+
         self.transaction_repository
-            .get_transaction_by_id(transaction_id)
+            .get_transaction_by_id(current_workspace()?, transaction_id)
             .await?
+            .filter(|t| access.allows(t.account_id, AccountPermission::View))
             .ok_or(Error::TransactionNotFound(transaction_id))
     }
 
+    /// fetch a transaction of an account the caller can record in
+    pub async fn get_recordable_transaction(&self, transaction_id: Uuid) -> Result<Transaction> {
+        let transaction = self.get_transaction_by_id(transaction_id).await?;
+
+        self.check_account_permission(transaction.account_id, AccountPermission::Record)
+            .await?;
+
+        Ok(transaction)
+    }
+
+    /// fetch a transaction that is still valid to be referenced
+    pub async fn get_active_transaction(&self, transaction_id: Uuid) -> Result<Transaction> {
+        let transaction = self.get_transaction_by_id(transaction_id).await?;
+
+        if transaction.is_deleted() {
+            return Err(Error::TransactionDeleted(transaction_id));
+        }
+
+        Ok(transaction)
+    }
+
     pub async fn delete_transaction_by_id(&self, transaction_id: Uuid) -> Result<Transaction> {
-        let result = self.get_transaction_by_id(transaction_id).await?;
+        let result = self.get_recordable_transaction(transaction_id).await?;
 
         if result.is_finished() {
             return Err(Error::TransactionFinished(result.transaction_id));
         }
 
-        self.transaction_repository
-            .delete_transaction_by_id(transaction_id)
+        let transaction = self
+            .transaction_repository
+            .delete_transaction_by_id(current_workspace()?, transaction_id)
             .await?
-            .ok_or(Error::TransactionNotFound(transaction_id))
+            .ok_or(Error::TransactionNotFound(transaction_id))?;
+
+        self.audit(
+            AuditEntity::Transaction,
+            transaction_id,
+            AuditAction::Delete,
+            Some(&result),
+            Some(&transaction),
+        )
+        .await?;
+
+        Ok(transaction)
     }
 
     pub async fn update_transaction_by_id(
@@ -57,18 +175,51 @@ impl Handler {
         payload: UpdateTransaction,
     ) -> Result<Transaction> {
         // REFAC this route when the rules is defined
-        let mut result = self.get_transaction_by_id(transaction_id).await?;
+        let mut result = self.get_recordable_transaction(transaction_id).await?;
 
         if result.is_finished() {
             return Err(Error::TransactionFinished(result.transaction_id));
         }
 
+        if let Some(account_id) = payload.account_id {
+            self.get_recordable_account(account_id).await?;
+        }
+
+        let category_changed = payload.category_id.is_some() || payload.movement_type.is_some();
+        // fixing an old transaction of a closed plan is fine, moving one into a plan is not
+        let plan_changed = payload
+            .financial_plan_id
+            .is_some_and(|financial_plan_id| financial_plan_id != result.financial_plan_id);
+
+        let before = result.clone();
         result.update(payload);
 
-        self.transaction_repository
-            .update_transaction_by_id(result)
+        if plan_changed {
+            self.get_open_financial_plan(result.financial_plan_id)
+                .await?;
+        }
+
+        if category_changed {
+            self.validate_category(result.category_id, result.movement_type)
+                .await?;
+        }
+
+        let transaction = self
+            .transaction_repository
+            .update_transaction_by_id(current_workspace()?, result)
             .await?
-            .ok_or(Error::TransactionNotFound(transaction_id))
+            .ok_or(Error::TransactionNotFound(transaction_id))?;
+
+        self.audit(
+            AuditEntity::Transaction,
+            transaction_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&transaction),
+        )
+        .await?;
+
+        Ok(transaction)
     }
 
     pub async fn finish_transaction(
@@ -76,57 +227,272 @@ impl Handler {
         transaction_id: Uuid,
         status: TransactionStatus,
     ) -> Result<Transaction> {
-        let result = self.get_transaction_by_id(transaction_id).await?;
+        let result = self.get_recordable_transaction(transaction_id).await?;
 
         if result.is_finished() {
             return Err(Error::TransactionFinished(result.transaction_id));
         }
 
-        self.transaction_repository
-            .update_status(transaction_id, status)
+        let transaction = self
+            .transaction_repository
+            .update_status(current_workspace()?, transaction_id, status)
             .await?
-            .ok_or(Error::TransactionNotFound(transaction_id))
+            .ok_or(Error::TransactionNotFound(transaction_id))?;
+
+        self.audit(
+            AuditEntity::Transaction,
+            transaction_id,
+            AuditAction::StatusChange,
+            Some(&result),
+            Some(&transaction),
+        )
+        .await?;
+
+        Ok(transaction)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use bigdecimal::BigDecimal;
+    use chrono::{NaiveDate, Utc};
 
     use super::*;
 
-    use crate::repositories::{
-        accounts::MockAccountRepository, financial_plans::MockFinancialPlanRepository, installments::MockInstallmentRepository, recurrences::MockRecurrenceRepository, settlements::MockSettlementRepository, transactions::MockTransactionRepository
+    use crate::domains::{
+        accounts::{Account, AccountType},
+        financial_plans::{CreateFinancialPlan, FinancialPlan, MonthReference},
+        transactions::MovementType,
     };
 
+    use crate::handlers::{as_owner, MockRepositories};
+
     #[tokio::test]
     async fn should_list_transactions() {
-        let mut transaction_repository = MockTransactionRepository::new();
-        let account_repository = MockAccountRepository::new();
-        let installment_repository = MockInstallmentRepository::new();
-        let settlement_repository = MockSettlementRepository::new();
-        let recurrence_repository = MockRecurrenceRepository::new();
-        let financial_plans_repository = MockFinancialPlanRepository::new();
-
-        transaction_repository
+        let mut mocks = MockRepositories::default();
+
+        mocks
+            .account_repository
+            .expect_list_account_grants()
+            .returning(|_| Ok(vec![]));
+
+        mocks
+            .transaction_repository
             .expect_list_transactions()
-            .returning(move || {
+            .returning(move |_| {
                 let records = vec![Transaction::default(), Transaction::default()];
 
                 Ok(records)
             });
 
-        let handler = Handler::new(
-            Arc::new(transaction_repository),
-            Arc::new(account_repository),
-            Arc::new(installment_repository),
-            Arc::new(settlement_repository),
-            Arc::new(recurrence_repository),
-            Arc::new(financial_plans_repository),
-        );
+        let handler = mocks.into_handler();
 
-        let transactions = handler.list_transactions().await.unwrap();
+        let transactions = as_owner(
+            Uuid::new_v4(),
+            handler.list_transactions(TransactionFilterParams::default()),
+        )
+        .await
+        .unwrap();
 
         assert_eq!(transactions.len(), 2);
     }
+
+    fn new_account(account_id: Uuid, deleted: bool) -> Account {
+        Account {
+            account_id,
+            institution_id: Uuid::new_v4(),
+            owner: String::from("owner"),
+            account_type: AccountType::Debit,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: deleted.then(Utc::now),
+        }
+    }
+
+    fn new_create_transaction() -> CreateTransaction {
+        CreateTransaction {
+            financial_plan_id: Uuid::new_v4(),
+            movement_type: MovementType::Expense,
+            description: String::from("groceries"),
+            value: BigDecimal::from(10),
+            due_date: NaiveDate::default(),
+            category_id: Some(Uuid::new_v4()),
+            account_id: Uuid::new_v4(),
+            installments: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_not_create_transaction_for_deleted_account() {
+        let mut mocks = MockRepositories::default();
+
+        mocks
+            .account_repository
+            .expect_list_account_grants()
+            .returning(|_| Ok(vec![]));
+        let payload = new_create_transaction();
+        let account_id = payload.account_id;
+
+        mocks
+            .account_repository
+            .expect_get_account_by_id()
+            .returning(move |_, id| Ok(Some(new_account(id, true))));
+
+        let handler = mocks.into_handler();
+
+        let result = as_owner(Uuid::new_v4(), handler.create_transaction(payload)).await;
+
+        assert!(matches!(result, Err(Error::AccountDeleted(id)) if id == account_id));
+    }
+
+    #[tokio::test]
+    async fn should_not_create_transaction_in_closed_financial_plan() {
+        let mut mocks = MockRepositories::default();
+
+        mocks
+            .account_repository
+            .expect_list_account_grants()
+            .returning(|_| Ok(vec![]));
+        let payload = new_create_transaction();
+        let financial_plan_id = payload.financial_plan_id;
+
+        mocks
+            .account_repository
+            .expect_get_account_by_id()
+            .returning(move |_, id| Ok(Some(new_account(id, false))));
+
+        mocks
+            .financial_plan_repository
+            .expect_get_financial_plan_by_id()
+            .returning(move |_, id| {
+                let mut financial_plan = FinancialPlan::new_from_payload(CreateFinancialPlan {
+                    title: None,
+                    month: MonthReference::January,
+                    year: 2024,
+                });
+                financial_plan.financial_plan_id = id;
+                financial_plan.closed_at = Some(Utc::now());
+
+                Ok(Some(financial_plan))
+            });
+
+        let handler = mocks.into_handler();
+
+        let result = as_owner(Uuid::new_v4(), handler.create_transaction(payload)).await;
+
+        assert!(matches!(result, Err(Error::FinancialPlanClosed(id)) if id == financial_plan_id));
+    }
+
+    #[tokio::test]
+    async fn should_not_move_transaction_into_deleted_financial_plan() {
+        let mut mocks = MockRepositories::default();
+        let transaction = Transaction::default();
+        let transaction_id = transaction.transaction_id;
+        let financial_plan_id = Uuid::new_v4();
+
+        mocks
+            .account_repository
+            .expect_list_account_grants()
+            .returning(|_| Ok(vec![]));
+
+        mocks
+            .transaction_repository
+            .expect_get_transaction_by_id()
+            .returning(move |_, _| Ok(Some(transaction.clone())));
+
+        mocks
+            .financial_plan_repository
+            .expect_get_financial_plan_by_id()
+            .returning(move |_, id| {
+                let mut financial_plan = FinancialPlan::new_from_payload(CreateFinancialPlan {
+                    title: None,
+                    month: MonthReference::January,
+                    year: 2024,
+                });
+                financial_plan.financial_plan_id = id;
+                financial_plan.deleted_at = Some(Utc::now());
+
+                Ok(Some(financial_plan))
+            });
+
+        mocks
+            .transaction_repository
+            .expect_update_transaction_by_id()
+            .never();
+
+        let handler = mocks.into_handler();
+
+        let payload = UpdateTransaction {
+            movement_type: None,
+            description: None,
+            value: None,
+            due_date: None,
+            category_id: None,
+            account_id: None,
+            financial_plan_id: Some(financial_plan_id),
+        };
+
+        let result = as_owner(
+            Uuid::new_v4(),
+            handler.update_transaction_by_id(transaction_id, payload),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::FinancialPlanDeleted(id)) if id == financial_plan_id));
+    }
+
+    #[tokio::test]
+    async fn should_update_transaction_in_closed_financial_plan() {
+        let mut mocks = MockRepositories::default();
+        let transaction = Transaction::default();
+        let transaction_id = transaction.transaction_id;
+        let financial_plan_id = transaction.financial_plan_id;
+
+        mocks
+            .account_repository
+            .expect_list_account_grants()
+            .returning(|_| Ok(vec![]));
+
+        mocks
+            .transaction_repository
+            .expect_get_transaction_by_id()
+            .returning(move |_, _| Ok(Some(transaction.clone())));
+
+        mocks
+            .financial_plan_repository
+            .expect_get_financial_plan_by_id()
+            .never();
+
+        mocks
+            .transaction_repository
+            .expect_update_transaction_by_id()
+            .returning(|_, transaction| Ok(Some(transaction)));
+
+        mocks
+            .audit_repository
+            .expect_record_audit()
+            .returning(|_, _| Ok(()));
+
+        let handler = mocks.into_handler();
+
+        let payload = UpdateTransaction {
+            movement_type: None,
+            description: Some(String::from("market")),
+            value: Some(BigDecimal::from(12)),
+            due_date: None,
+            category_id: None,
+            account_id: None,
+            financial_plan_id: Some(financial_plan_id),
+        };
+
+        let transaction = as_owner(
+            Uuid::new_v4(),
+            handler.update_transaction_by_id(transaction_id, payload),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(transaction.description, "market");
+        assert_eq!(transaction.financial_plan_id, financial_plan_id);
+    }
 }
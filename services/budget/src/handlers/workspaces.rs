@@ -0,0 +1,391 @@
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::{Error, Result},
+    workspaces::{
+        AddMember, CreateWorkspace, UpdateMember, UpdateWorkspace, Workspace, WorkspaceMember,
+        WorkspaceMembership, WorkspaceRole, LEGACY_WORKSPACE, PERSONAL_WORKSPACE,
+    },
+};
+
+use super::Handler;
+
+tokio::task_local! {
+    /// workspace the request acts on, set by the routes
+    pub static WORKSPACE: Uuid;
+    /// membership of the caller, set by the routes along with the workspace
+    pub static MEMBER: WorkspaceMember;
+}
+
+/// workspace of the request being handled
+pub fn current_workspace() -> Result<Uuid> {
+    WORKSPACE
+        .try_with(|workspace_id| *workspace_id)
+        .map_err(|_| Error::WorkspaceRequired)
+}
+
+/// membership of the caller of the request being handled
+pub fn current_member() -> Option<WorkspaceMember> {
+    MEMBER.try_with(Clone::clone).ok()
+}
+
+impl Handler {
+    /// membership the user acts with, in the requested workspace or in the
+    /// oldest one, users without any get a personal workspace
+    pub async fn resolve_workspace(
+        &self,
+        user_id: Uuid,
+        requested: Option<Uuid>,
+    ) -> Result<WorkspaceMember> {
+        if let Some(workspace_id) = requested {
+            return self.get_membership(user_id, workspace_id).await;
+        }
+
+        let workspaces = self.workspace_repository.list_workspaces(user_id).await?;
+
+        if let Some(membership) = workspaces.into_iter().next() {
+            return Ok(WorkspaceMember {
+                workspace_id: membership.workspace.workspace_id,
+                user_id,
+                role: membership.role,
+                created_at: membership.workspace.created_at,
+            });
+        }
+
+        // the first requests of a user may race here, they all get the same workspace
+        let workspace = self
+            .workspace_repository
+            .create_personal_workspace(Workspace::new(PERSONAL_WORKSPACE.to_string()), user_id)
+            .await?;
+
+        // the user may have been removed from the personal workspace they got before
+        self.get_membership(user_id, workspace.workspace_id).await
+    }
+
+    /// hands the records from before workspaces existed to the owner named by the deploy,
+    /// nothing changes once the workspace has members
+    pub async fn assign_legacy_workspace(&self, owner_id: Uuid) -> Result<Option<WorkspaceMember>> {
+        self.workspace_repository
+            .assign_owner(LEGACY_WORKSPACE, owner_id)
+            .await
+    }
+
+    pub async fn list_workspaces(&self, user_id: Uuid) -> Result<Vec<WorkspaceMembership>> {
+        self.workspace_repository.list_workspaces(user_id).await
+    }
+
+    pub async fn create_workspace(
+        &self,
+        user_id: Uuid,
+        payload: CreateWorkspace,
+    ) -> Result<WorkspaceMembership> {
+        let workspace = self
+            .workspace_repository
+            .create_workspace(Workspace::new(payload.name), user_id)
+            .await?;
+
+        Ok(WorkspaceMembership {
+            workspace,
+            role: WorkspaceRole::Owner,
+        })
+    }
+
+    pub async fn get_workspace_by_id(
+        &self,
+        user_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<WorkspaceMembership> {
+        let member = self.get_membership(user_id, workspace_id).await?;
+
+        let workspace = self
+            .workspace_repository
+            .get_workspace_by_id(workspace_id)
+            .await?
+            .ok_or(Error::WorkspaceNotFound(workspace_id))?;
+
+        Ok(WorkspaceMembership {
+            workspace,
+            role: member.role,
+        })
+    }
+
+    pub async fn update_workspace_by_id(
+        &self,
+        user_id: Uuid,
+        workspace_id: Uuid,
+        payload: UpdateWorkspace,
+    ) -> Result<WorkspaceMembership> {
+        self.get_ownership(user_id, workspace_id).await?;
+
+        let mut workspace = self
+            .workspace_repository
+            .get_workspace_by_id(workspace_id)
+            .await?
+            .ok_or(Error::WorkspaceNotFound(workspace_id))?;
+
+        workspace.update(payload);
+
+        let workspace = self
+            .workspace_repository
+            .update_workspace(workspace)
+            .await?
+            .ok_or(Error::WorkspaceNotFound(workspace_id))?;
+
+        Ok(WorkspaceMembership {
+            workspace,
+            role: WorkspaceRole::Owner,
+        })
+    }
+
+    pub async fn list_members(
+        &self,
+        user_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Vec<WorkspaceMember>> {
+        self.get_membership(user_id, workspace_id).await?;
+
+        self.workspace_repository.list_members(workspace_id).await
+    }
+
+    pub async fn add_member(
+        &self,
+        user_id: Uuid,
+        workspace_id: Uuid,
+        payload: AddMember,
+    ) -> Result<WorkspaceMember> {
+        self.get_ownership(user_id, workspace_id).await?;
+
+        self.workspace_repository
+            .add_member(WorkspaceMember::new(
+                workspace_id,
+                payload.user_id,
+                payload.role,
+            ))
+            .await
+    }
+
+    pub async fn update_member(
+        &self,
+        user_id: Uuid,
+        workspace_id: Uuid,
+        member_id: Uuid,
+        payload: UpdateMember,
+    ) -> Result<WorkspaceMember> {
+        self.get_ownership(user_id, workspace_id).await?;
+
+        let member = self.get_member(workspace_id, member_id).await?;
+
+        if payload.role != WorkspaceRole::Owner {
+            self.keep_an_owner(&member).await?;
+        }
+
+        self.workspace_repository
+            .update_member_role(workspace_id, member_id, payload.role)
+            .await?
+            .ok_or(Error::MemberNotFound(member_id))
+    }
+
+    /// owners remove anyone, the other members can only leave
+    pub async fn remove_member(
+        &self,
+        user_id: Uuid,
+        workspace_id: Uuid,
+        member_id: Uuid,
+    ) -> Result<WorkspaceMember> {
+        if member_id == user_id {
+            self.get_membership(user_id, workspace_id).await?;
+        } else {
+            self.get_ownership(user_id, workspace_id).await?;
+        }
+
+        let member = self.get_member(workspace_id, member_id).await?;
+
+        self.keep_an_owner(&member).await?;
+
+        self.workspace_repository
+            .remove_member(workspace_id, member_id)
+            .await?
+            .ok_or(Error::MemberNotFound(member_id))
+    }
+
+    /// membership of the user, workspaces of other users are not found
+    async fn get_membership(&self, user_id: Uuid, workspace_id: Uuid) -> Result<WorkspaceMember> {
+        self.workspace_repository
+            .get_member(workspace_id, user_id)
+            .await?
+            .ok_or(Error::WorkspaceNotFound(workspace_id))
+    }
+
+    async fn get_ownership(&self, user_id: Uuid, workspace_id: Uuid) -> Result<WorkspaceMember> {
+        let member = self.get_membership(user_id, workspace_id).await?;
+
+        if member.role != WorkspaceRole::Owner {
+            return Err(Error::InsufficientRole(workspace_id));
+        }
+
+        Ok(member)
+    }
+
+    pub async fn get_member(&self, workspace_id: Uuid, member_id: Uuid) -> Result<WorkspaceMember> {
+        self.workspace_repository
+            .get_member(workspace_id, member_id)
+            .await?
+            .ok_or(Error::MemberNotFound(member_id))
+    }
+
+    /// the member can stop being an owner only when another one is left
+    async fn keep_an_owner(&self, member: &WorkspaceMember) -> Result<()> {
+        if member.role != WorkspaceRole::Owner {
+            return Ok(());
+        }
+
+        let owners = self
+            .workspace_repository
+            .list_members(member.workspace_id)
+            .await?
+            .into_iter()
+            .filter(|m| m.role == WorkspaceRole::Owner)
+            .count();
+
+        if owners <= 1 {
+            return Err(Error::LastWorkspaceOwner(member.workspace_id));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{handlers::MockRepositories, repositories::memory::MemoryRepository};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_only_hand_the_legacy_workspace_to_the_configured_owner() {
+        let owner = Uuid::new_v4();
+        let stranger = Uuid::new_v4();
+        let mut mocks = MockRepositories::default();
+
+        mocks
+            .workspace_repository
+            .expect_list_workspaces()
+            .returning(|_| Ok(vec![]));
+        mocks
+            .workspace_repository
+            .expect_create_personal_workspace()
+            .withf(move |workspace, owner_id| {
+                workspace.name == PERSONAL_WORKSPACE && *owner_id == stranger
+            })
+            .times(1)
+            .returning(|workspace, _| Ok(workspace));
+        mocks
+            .workspace_repository
+            .expect_get_member()
+            .returning(|workspace_id, user_id| {
+                Ok(Some(WorkspaceMember::new(
+                    workspace_id,
+                    user_id,
+                    WorkspaceRole::Owner,
+                )))
+            });
+        mocks
+            .workspace_repository
+            .expect_assign_owner()
+            .withf(move |workspace_id, user_id| {
+                *workspace_id == LEGACY_WORKSPACE && *user_id == owner
+            })
+            .times(1)
+            .returning(|workspace_id, user_id| {
+                Ok(Some(WorkspaceMember::new(
+                    workspace_id,
+                    user_id,
+                    WorkspaceRole::Owner,
+                )))
+            });
+
+        let handler = mocks.into_handler();
+
+        // a user without workspaces gets their own, never the legacy records
+        let member = handler.resolve_workspace(stranger, None).await.unwrap();
+        assert_ne!(member.workspace_id, LEGACY_WORKSPACE);
+        assert_eq!(member.role, WorkspaceRole::Owner);
+
+        let member = handler
+            .assign_legacy_workspace(owner)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(member.workspace_id, LEGACY_WORKSPACE);
+    }
+
+    #[tokio::test]
+    async fn should_give_a_user_a_single_personal_workspace() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let user = Uuid::new_v4();
+
+        let (first, second) = tokio::join!(
+            handler.resolve_workspace(user, None),
+            handler.resolve_workspace(user, None)
+        );
+        assert_eq!(first.unwrap().workspace_id, second.unwrap().workspace_id);
+
+        let workspaces = handler.list_workspaces(user).await.unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0].workspace.name, PERSONAL_WORKSPACE);
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_last_owner_of_a_workspace() {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let owner = Uuid::new_v4();
+        let viewer = Uuid::new_v4();
+
+        let member = handler.resolve_workspace(owner, None).await.unwrap();
+        let workspace_id = member.workspace_id;
+
+        handler
+            .add_member(
+                owner,
+                workspace_id,
+                AddMember {
+                    user_id: viewer,
+                    role: WorkspaceRole::Viewer,
+                },
+            )
+            .await
+            .unwrap();
+
+        let err = handler
+            .remove_member(owner, workspace_id, owner)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::LastWorkspaceOwner(id) if id == workspace_id));
+
+        let err = handler
+            .update_member(
+                viewer,
+                workspace_id,
+                viewer,
+                UpdateMember {
+                    role: WorkspaceRole::Owner,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InsufficientRole(id) if id == workspace_id));
+
+        handler
+            .remove_member(viewer, workspace_id, viewer)
+            .await
+            .unwrap();
+
+        let err = handler
+            .resolve_workspace(viewer, Some(workspace_id))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::WorkspaceNotFound(id) if id == workspace_id));
+    }
+}
@@ -1,11 +1,19 @@
-use std::{sync::Arc, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::time;
 
+use domains::backups::Operators;
+use grpc::BudgetGrpc;
 use handlers::Handler;
-use repositories::SqlxRepository;
+use repositories::{memory::MemoryRepository, SqlxRepository};
+use auth_layer::{ApiKeyVerifier, AuthLayer, JwksVerifier};
+use axum::middleware;
 use sqlx::postgres::PgPoolOptions;
-use tower_http::cors::CorsLayer;
+use tower_http::{
+    cors::CorsLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+};
 mod domains;
+mod grpc;
 mod handlers;
 mod repositories;
 mod routes;
@@ -15,46 +23,125 @@ mod logger;
 async fn main() {
     dotenv::dotenv().ok();
 
-    let conn_str = std::env::var("DATABASE_URL").expect("Could not fetch connection string.");
-
     logger::init().expect("Failed to initialize logger");
 
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&conn_str)
-        .await
-        .expect("Couldn't connect to the database");
-
-    let sqlx_repository = Arc::new(SqlxRepository::new(pool));
-    let handler = Handler::new(
-        sqlx_repository.clone(),
-        sqlx_repository.clone(),
-        sqlx_repository.clone(),
-        sqlx_repository.clone(),
-        sqlx_repository.clone(),
-        sqlx_repository.clone(),
-    );
+    // deploys run the migrations once before starting the new instances
+    let migrate_only = std::env::args().any(|arg| arg == "--migrate-only");
+
+    let handler = match std::env::var("STORAGE").as_deref() {
+        Ok("memory") => {
+            if migrate_only {
+                log::info!("Memory storage has no migrations to apply");
+                return;
+            }
+
+            log::warn!("Using the memory storage, records are lost on restart");
+
+            Handler::with_repository(Arc::new(MemoryRepository::default()))
+        }
+        Ok("postgres") | Err(_) => {
+            let conn_str =
+                std::env::var("DATABASE_URL").expect("Could not fetch connection string.");
+
+            let pool = PgPoolOptions::new()
+                .max_connections(5)
+                .connect(&conn_str)
+                .await
+                .expect("Couldn't connect to the database");
+
+            sqlx::migrate!()
+                .run(&pool)
+                .await
+                .expect("Couldn't run the database migrations");
+
+            let handler = Handler::with_repository(Arc::new(SqlxRepository::new(pool)));
+
+            // the records from before workspaces only get an owner named by the deploy
+            if let Ok(owner_id) = std::env::var("LEGACY_WORKSPACE_OWNER") {
+                let owner_id = owner_id
+                    .parse()
+                    .expect("Invalid LEGACY_WORKSPACE_OWNER user id");
+
+                if let Some(member) = handler
+                    .assign_legacy_workspace(owner_id)
+                    .await
+                    .expect("Couldn't assign the legacy workspace")
+                {
+                    log::info!("Legacy workspace assigned to {}", member.user_id);
+                }
+            }
+
+            if migrate_only {
+                log::info!("Database migrations applied");
+                return;
+            }
+
+            handler
+        }
+        Ok(storage) => panic!("Unknown storage {storage}, expected postgres or memory"),
+    };
 
     let generator_handler = Arc::clone(&handler.clone().into());
 
     tokio::spawn(periodic_task(generator_handler));
 
-    let app = routes::configure_routes()
+    let auth = AuthLayer::new(Arc::new(JwksVerifier::from_env()))
+        .api_keys(Arc::new(ApiKeyVerifier::from_env()))
+        .public_path("/health")
+        .scoped("budget");
+
+    let grpc = BudgetGrpc::new(handler.clone(), auth.clone());
+
+    let app = routes::configure_routes(handler.clone(), operators())
         .with_state(handler)
+        .layer(middleware::from_fn(routes::set_audit_context))
+        .layer(middleware::from_fn(routes::attach_request_id))
+        .layer(auth)
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(CorsLayer::permissive());
 
     let port = std::env::var("PORT").expect("Could not fetch port data.");
     let url = format!("0.0.0.0:{}", port);
 
     let listener = tokio::net::TcpListener::bind(url).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+
+    let grpc_port = std::env::var("GRPC_PORT").unwrap_or_else(|_| String::from("50051"));
+    let grpc_addr: SocketAddr = format!("0.0.0.0:{}", grpc_port)
+        .parse()
+        .expect("Invalid gRPC port");
+
+    tokio::select! {
+        result = async { axum::serve(listener, app).await } => result.unwrap(),
+        result = grpc.serve(grpc_addr) => result.expect("gRPC server failed"),
+    }
+}
+
+/// OPERATOR_USER_IDS lists the users allowed to back up and restore every workspace
+fn operators() -> Operators {
+    let user_ids = std::env::var("OPERATOR_USER_IDS").unwrap_or_default();
+
+    Operators::new(
+        user_ids
+            .split(',')
+            .map(str::trim)
+            .filter(|user_id| !user_id.is_empty())
+            .map(|user_id| {
+                user_id
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Invalid operator user id {user_id}"))
+            }),
+    )
 }
 
-#[warn(unused_variables)]
 async fn periodic_task(handler: Arc<Handler>) {
     loop {
         println!("Executando scheduler");
 
+        if let Err(err) = handler.generate_all_recurrences().await {
+            log::error!("Could not generate recurrences: {err}");
+        }
+
         time::sleep(Duration::from_secs(3600)).await;
     }
 }
@@ -1,5 +1,7 @@
 use crate::domains::{
-    accounts::{Account, AccountType, Bank, CreateAccount, UpdateAccount},
+    accounts::{
+        Account, AccountGrant, AccountPermission, AccountType, CreateAccount, UpdateAccount,
+    },
     errors::Result,
 };
 
@@ -10,51 +12,76 @@ use uuid::Uuid;
 #[automock]
 #[async_trait::async_trait]
 pub trait AccountRepository {
-    async fn list_accounts(&self) -> Result<Vec<Account>>;
-    async fn create_account(&self, account: CreateAccount) -> Result<Account>;
-    async fn get_account_by_id(&self, account_id: Uuid) -> Result<Option<Account>>;
+    async fn list_accounts(&self, workspace_id: Uuid) -> Result<Vec<Account>>;
+    async fn create_account(&self, workspace_id: Uuid, account: CreateAccount) -> Result<Account>;
+    async fn get_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>>;
     async fn update_account_by_id(
         &self,
+        workspace_id: Uuid,
         account: Account,
         payload: UpdateAccount,
     ) -> Result<Option<Account>>;
-    async fn delete_account_by_id(&self, account_id: Uuid) -> Result<Option<Account>>;
+    async fn delete_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>>;
+    /// grants of every account of the workspace
+    async fn list_account_grants(&self, workspace_id: Uuid) -> Result<Vec<AccountGrant>>;
+    /// create or replace the grant of the user, none when the account is not in the workspace
+    async fn save_account_grant(
+        &self,
+        workspace_id: Uuid,
+        grant: AccountGrant,
+    ) -> Result<Option<AccountGrant>>;
+    async fn delete_account_grant(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AccountGrant>>;
 }
 
 #[async_trait::async_trait]
 impl AccountRepository for SqlxRepository {
-    async fn list_accounts(&self) -> Result<Vec<Account>> {
+    async fn list_accounts(&self, workspace_id: Uuid) -> Result<Vec<Account>> {
         let accounts = sqlx::query_as!(
             Account,
             r#"
             SELECT 
                 account_id,
-                bank_name as "bank_name!: Bank",
+                institution_id,
                 owner,
                 account_type as "account_type!: AccountType",
                 created_at,
                 updated_at,
                 deleted_at
             FROM accounts
-            "#
+            WHERE workspace_id = $1
+            "#,
+            workspace_id
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.connection().await?)
         .await?;
 
         Ok(accounts)
     }
 
-    async fn create_account(&self, account: CreateAccount) -> Result<Account> {
+    async fn create_account(&self, workspace_id: Uuid, account: CreateAccount) -> Result<Account> {
         let account = sqlx::query_as!(
             Account,
             r#"
             INSERT INTO accounts (
-                account_id, bank_name, owner, account_type
+                account_id, institution_id, owner, account_type, workspace_id
             ) VALUES (
-                $1, $2, $3, $4
+                $1, $2, $3, $4, $5
             ) RETURNING
                 account_id,
-                bank_name as "bank_name!: Bank",
+                institution_id,
                 owner,
                 account_type as "account_type!: AccountType",
                 created_at,
@@ -62,33 +89,39 @@ impl AccountRepository for SqlxRepository {
                 deleted_at
             "#,
             Uuid::new_v4(),
-            account.bank_name as Bank,
+            account.institution_id,
             account.owner,
-            account.account_type as AccountType
+            account.account_type as AccountType,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await?;
 
         Ok(account)
     }
 
-    async fn get_account_by_id(&self, account_id: Uuid) -> Result<Option<Account>> {
+    async fn get_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>> {
         let account = sqlx::query_as!(
             Account,
             r#"
             SELECT 
                 account_id,
-                bank_name as "bank_name!: Bank",
+                institution_id,
                 owner,
                 account_type as "account_type!: AccountType",
                 created_at, 
                 updated_at, 
                 deleted_at 
-            FROM accounts WHERE account_id = $1
+            FROM accounts WHERE account_id = $1 AND workspace_id = $2
             "#,
-            account_id
+            account_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(account)
@@ -96,6 +129,7 @@ impl AccountRepository for SqlxRepository {
 
     async fn update_account_by_id(
         &self,
+        workspace_id: Uuid,
         account: Account,
         payload: UpdateAccount,
     ) -> Result<Option<Account>> {
@@ -104,14 +138,14 @@ impl AccountRepository for SqlxRepository {
             r#"
             UPDATE accounts SET
                 updated_at = now(),
-                bank_name = $2,
+                institution_id = $2,
                 owner = $3,
                 account_type = $4
             WHERE
-                account_id = $1 AND deleted_at is null
+                account_id = $1 AND workspace_id = $5 AND deleted_at is null
             RETURNING
                 account_id,
-                bank_name as "bank_name!: Bank",
+                institution_id,
                 owner,
                 account_type as "account_type!: AccountType",
                 created_at, 
@@ -119,17 +153,22 @@ impl AccountRepository for SqlxRepository {
                 deleted_at 
             "#,
             account.account_id,
-            payload.bank_name.unwrap_or(account.bank_name) as Bank,
+            payload.institution_id.unwrap_or(account.institution_id),
             payload.owner.unwrap_or(account.owner),
-            payload.account_type.unwrap_or(account.account_type) as AccountType
+            payload.account_type.unwrap_or(account.account_type) as AccountType,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(account)
     }
 
-    async fn delete_account_by_id(&self, account_id: Uuid) -> Result<Option<Account>> {
+    async fn delete_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>> {
         let account = sqlx::query_as!(
             Account,
             r#"
@@ -137,21 +176,105 @@ impl AccountRepository for SqlxRepository {
                 updated_at = now(),
                 deleted_at = now()
             WHERE
-                account_id = $1 and deleted_at is null
+                account_id = $1 and workspace_id = $2 and deleted_at is null
             RETURNING
                 account_id,
-                bank_name as "bank_name!: Bank",
+                institution_id,
                 owner,
                 account_type as "account_type!: AccountType",
                 created_at, 
                 updated_at, 
                 deleted_at 
             "#,
-            account_id
+            account_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(account)
     }
+
+    async fn list_account_grants(&self, workspace_id: Uuid) -> Result<Vec<AccountGrant>> {
+        let grants = sqlx::query_as!(
+            AccountGrant,
+            r#"
+            SELECT
+                g.account_id,
+                g.user_id,
+                g.permission as "permission!: AccountPermission",
+                g.created_at
+            FROM account_grants g
+            JOIN accounts a ON a.account_id = g.account_id
+            WHERE a.workspace_id = $1
+            ORDER BY g.account_id, g.created_at
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(grants)
+    }
+
+    async fn save_account_grant(
+        &self,
+        workspace_id: Uuid,
+        grant: AccountGrant,
+    ) -> Result<Option<AccountGrant>> {
+        let grant = sqlx::query_as!(
+            AccountGrant,
+            r#"
+            INSERT INTO account_grants (account_id, user_id, permission, created_at)
+            SELECT account_id, $2, $3, $4
+            FROM accounts
+            WHERE account_id = $1 AND workspace_id = $5
+            ON CONFLICT (account_id, user_id) DO UPDATE SET permission = EXCLUDED.permission
+            RETURNING
+                account_id,
+                user_id,
+                permission as "permission!: AccountPermission",
+                created_at
+            "#,
+            grant.account_id,
+            grant.user_id,
+            grant.permission as AccountPermission,
+            grant.created_at,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(grant)
+    }
+
+    async fn delete_account_grant(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AccountGrant>> {
+        let grant = sqlx::query_as!(
+            AccountGrant,
+            r#"
+            DELETE FROM account_grants g
+            USING accounts a
+            WHERE
+                g.account_id = $1 AND g.user_id = $2
+                AND a.account_id = g.account_id AND a.workspace_id = $3
+            RETURNING
+                g.account_id,
+                g.user_id,
+                g.permission as "permission!: AccountPermission",
+                g.created_at
+            "#,
+            account_id,
+            user_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(grant)
+    }
 }
@@ -0,0 +1,90 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    audit::{AuditAction, AuditEntity, AuditEntry},
+    errors::Result,
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait AuditRepository {
+    async fn record_audit(&self, workspace_id: Uuid, entry: AuditEntry) -> Result<()>;
+    /// history of the record, oldest change first
+    async fn list_audit(
+        &self,
+        workspace_id: Uuid,
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<AuditEntry>>;
+}
+
+#[async_trait::async_trait]
+impl AuditRepository for SqlxRepository {
+    async fn record_audit(&self, workspace_id: Uuid, entry: AuditEntry) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_log (
+                audit_id,
+                entity_type,
+                entity_id,
+                action,
+                changes,
+                actor,
+                request_id,
+                created_at,
+                workspace_id
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9
+            )
+            "#,
+            entry.audit_id,
+            entry.entity_type as AuditEntity,
+            entry.entity_id,
+            entry.action as AuditAction,
+            entry.changes,
+            entry.actor,
+            entry.request_id,
+            entry.created_at,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_audit(
+        &self,
+        workspace_id: Uuid,
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<AuditEntry>> {
+        let entries = sqlx::query_as!(
+            AuditEntry,
+            r#"
+            SELECT
+                audit_id,
+                entity_type as "entity_type!: AuditEntity",
+                entity_id,
+                action as "action!: AuditAction",
+                changes,
+                actor,
+                request_id,
+                created_at
+            FROM audit_log
+            WHERE workspace_id = $1 AND entity_type = $2 AND entity_id = $3
+            ORDER BY created_at, audit_id
+            "#,
+            workspace_id,
+            entity_type as AuditEntity,
+            entity_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,112 @@
+use mockall::automock;
+use sqlx::types::Json;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+use crate::domains::{
+    backups::{BackupRow, BACKUP_BUFFER, BACKUP_TABLES},
+    errors::Result,
+};
+
+use super::SqlxRepository;
+
+/// kept by forced restores, the audit log outlives the records it tells about
+const AUDIT_TABLE: &str = "audit_log";
+
+#[automock]
+#[async_trait::async_trait]
+pub trait BackupRepository {
+    /// rows of every table in `BACKUP_TABLES` order, sent as they are read, the channel closes
+    /// early after an error
+    async fn read_tables(&self) -> Result<mpsc::Receiver<Result<BackupRow>>>;
+    async fn has_records(&self) -> Result<bool>;
+    /// delete the records of every table but the audit log
+    async fn clear_tables(&self) -> Result<()>;
+    async fn insert_rows(&self, table: &'static str, rows: &[serde_json::Value]) -> Result<()>;
+}
+
+// table names only come from BACKUP_TABLES, so they are safe to format into the queries
+#[async_trait::async_trait]
+impl BackupRepository for SqlxRepository {
+    async fn read_tables(&self) -> Result<mpsc::Receiver<Result<BackupRow>>> {
+        let mut tx = self.pool.begin().await?;
+
+        // a single snapshot keeps the references between tables consistent
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let (sender, rows) = mpsc::channel(BACKUP_BUFFER);
+
+        tokio::spawn(async move {
+            for table in BACKUP_TABLES {
+                let query = format!("SELECT row_to_json(t) FROM {table} t");
+                let mut records =
+                    sqlx::query_scalar::<_, Json<serde_json::Value>>(&query).fetch(&mut *tx);
+
+                while let Some(record) = records.next().await {
+                    let row = record
+                        .map(|Json(row)| BackupRow { table, row })
+                        .map_err(Into::into);
+                    let failed = row.is_err();
+
+                    // the client went away or the read failed, the snapshot is dropped
+                    if sender.send(row).await.is_err() || failed {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(rows)
+    }
+
+    async fn has_records(&self) -> Result<bool> {
+        for name in BACKUP_TABLES {
+            let exists =
+                sqlx::query_scalar::<_, bool>(&format!("SELECT EXISTS (SELECT 1 FROM {name})"))
+                    .fetch_one(&mut *self.connection().await?)
+                    .await?;
+
+            if exists {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn clear_tables(&self) -> Result<()> {
+        let mut conn = self.connection().await?;
+
+        for name in BACKUP_TABLES
+            .iter()
+            .rev()
+            .filter(|name| **name != AUDIT_TABLE)
+        {
+            sqlx::query(&format!("DELETE FROM {name}"))
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn insert_rows(&self, table: &'static str, rows: &[serde_json::Value]) -> Result<()> {
+        // the entries kept by a forced restore are not inserted twice
+        let conflict = if table == AUDIT_TABLE {
+            " ON CONFLICT (audit_id) DO NOTHING"
+        } else {
+            ""
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1){conflict}"
+        ))
+        .bind(Json(rows))
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    categories::{Category, CategoryKind},
+    errors::Result,
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait CategoryRepository {
+    async fn list_categories(&self, workspace_id: Uuid) -> Result<Vec<Category>>;
+    async fn create_category(&self, workspace_id: Uuid, payload: Category) -> Result<Category>;
+    async fn get_category_by_id(
+        &self,
+        workspace_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<Category>>;
+    async fn update_category(
+        &self,
+        workspace_id: Uuid,
+        payload: Category,
+    ) -> Result<Option<Category>>;
+    async fn delete_category_by_id(
+        &self,
+        workspace_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<Category>>;
+}
+
+#[async_trait::async_trait]
+impl CategoryRepository for SqlxRepository {
+    async fn list_categories(&self, workspace_id: Uuid) -> Result<Vec<Category>> {
+        let categories = sqlx::query_as!(
+            Category,
+            r#"
+            SELECT
+                category_id,
+                parent_id,
+                name,
+                icon,
+                color,
+                kind as "kind!: CategoryKind",
+                created_at,
+                updated_at,
+                deleted_at
+            FROM categories
+            WHERE workspace_id = $1
+            ORDER BY name
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(categories)
+    }
+
+    async fn create_category(&self, workspace_id: Uuid, payload: Category) -> Result<Category> {
+        let category = sqlx::query_as!(
+            Category,
+            r#"
+            INSERT INTO categories (
+                category_id,
+                parent_id,
+                name,
+                icon,
+                color,
+                kind,
+                workspace_id
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7
+            ) RETURNING
+                category_id,
+                parent_id,
+                name,
+                icon,
+                color,
+                kind as "kind!: CategoryKind",
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            payload.category_id,
+            payload.parent_id,
+            payload.name,
+            payload.icon,
+            payload.color,
+            payload.kind as CategoryKind,
+            workspace_id
+        )
+        .fetch_one(&mut *self.connection().await?)
+        .await?;
+
+        Ok(category)
+    }
+
+    async fn get_category_by_id(
+        &self,
+        workspace_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<Category>> {
+        let category = sqlx::query_as!(
+            Category,
+            r#"
+            SELECT
+                category_id,
+                parent_id,
+                name,
+                icon,
+                color,
+                kind as "kind!: CategoryKind",
+                created_at,
+                updated_at,
+                deleted_at
+            FROM categories
+            WHERE category_id = $1 AND workspace_id = $2
+            "#,
+            category_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(category)
+    }
+
+    async fn update_category(
+        &self,
+        workspace_id: Uuid,
+        payload: Category,
+    ) -> Result<Option<Category>> {
+        let category = sqlx::query_as!(
+            Category,
+            r#"
+            UPDATE categories SET
+                name = $2,
+                icon = $3,
+                color = $4,
+                updated_at = $5
+            WHERE
+                category_id = $1 AND workspace_id = $6 AND deleted_at is null
+            RETURNING
+                category_id,
+                parent_id,
+                name,
+                icon,
+                color,
+                kind as "kind!: CategoryKind",
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            payload.category_id,
+            payload.name,
+            payload.icon,
+            payload.color,
+            payload.updated_at,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(category)
+    }
+
+    async fn delete_category_by_id(
+        &self,
+        workspace_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<Category>> {
+        let category = sqlx::query_as!(
+            Category,
+            r#"
+            UPDATE categories SET
+                updated_at = now(),
+                deleted_at = now()
+            WHERE
+                category_id = $1 AND workspace_id = $2 AND deleted_at is null
+            RETURNING
+                category_id,
+                parent_id,
+                name,
+                icon,
+                color,
+                kind as "kind!: CategoryKind",
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            category_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(category)
+    }
+}
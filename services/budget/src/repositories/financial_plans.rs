@@ -11,17 +11,27 @@ use super::SqlxRepository;
 #[automock]
 #[async_trait::async_trait]
 pub trait FinancialPlanRepository {
-    async fn create_financial_plan(&self, payload: FinancialPlan) -> Result<FinancialPlan>;
-    async fn list_financial_plans(&self) -> Result<Vec<FinancialPlan>>;
+    async fn create_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        payload: FinancialPlan,
+    ) -> Result<FinancialPlan>;
+    async fn list_financial_plans(&self, workspace_id: Uuid) -> Result<Vec<FinancialPlan>>;
     async fn get_financial_plan_by_id(
         &self,
+        workspace_id: Uuid,
+        financial_plan_id: Uuid,
+    ) -> Result<Option<FinancialPlan>>;
+    async fn close_financial_plan(
+        &self,
+        workspace_id: Uuid,
         financial_plan_id: Uuid,
     ) -> Result<Option<FinancialPlan>>;
 }
 
 #[async_trait::async_trait]
 impl FinancialPlanRepository for SqlxRepository {
-    async fn list_financial_plans(&self) -> Result<Vec<FinancialPlan>> {
+    async fn list_financial_plans(&self, workspace_id: Uuid) -> Result<Vec<FinancialPlan>> {
         let result = sqlx::query_as!(
             FinancialPlan,
             r#"
@@ -30,13 +40,16 @@ impl FinancialPlanRepository for SqlxRepository {
                     title,
                     month as "month!: MonthReference",
                     year,
+                    closed_at,
                     created_at,
                     updated_at,
                     deleted_at
                 FROM financial_plans
-            "#
+                WHERE workspace_id = $1
+            "#,
+            workspace_id
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.connection().await?)
         .await?;
 
         log::info!("List financial plans: {:?}", result);
@@ -44,18 +57,23 @@ impl FinancialPlanRepository for SqlxRepository {
         Ok(result)
     }
 
-    async fn create_financial_plan(&self, payload: FinancialPlan) -> Result<FinancialPlan> {
+    async fn create_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        payload: FinancialPlan,
+    ) -> Result<FinancialPlan> {
         let financial_plan = sqlx::query_as!(
             FinancialPlan,
             r#"
                 INSERT INTO financial_plans 
-                    (financial_plan_id, title, month, year, created_at)
-                VALUES ($1, $2, $3, $4, $5) 
+                    (financial_plan_id, title, month, year, created_at, workspace_id)
+                VALUES ($1, $2, $3, $4, $5, $6) 
                 RETURNING
                     financial_plan_id,
                     title,
                     month as "month: MonthReference",
                     year,
+                    closed_at,
                     created_at, 
                     updated_at, 
                     deleted_at
@@ -64,9 +82,10 @@ impl FinancialPlanRepository for SqlxRepository {
             payload.title,
             payload.month as MonthReference,
             payload.year,
-            payload.created_at
+            payload.created_at,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await?;
 
         Ok(financial_plan)
@@ -74,6 +93,7 @@ impl FinancialPlanRepository for SqlxRepository {
 
     async fn get_financial_plan_by_id(
         &self,
+        workspace_id: Uuid,
         financial_plan_id: Uuid,
     ) -> Result<Option<FinancialPlan>> {
         let financial_plan = sqlx::query_as!(
@@ -84,15 +104,52 @@ impl FinancialPlanRepository for SqlxRepository {
                     title,
                     month as "month!: MonthReference",
                     year,
+                    closed_at,
                     created_at,
                     updated_at,
                     deleted_at
                 FROM financial_plans
-                WHERE financial_plan_id = $1
+                WHERE financial_plan_id = $1 AND workspace_id = $2
+            "#,
+            financial_plan_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(financial_plan)
+    }
+
+    async fn close_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        financial_plan_id: Uuid,
+    ) -> Result<Option<FinancialPlan>> {
+        let financial_plan = sqlx::query_as!(
+            FinancialPlan,
+            r#"
+                UPDATE financial_plans SET
+                    closed_at = now(),
+                    updated_at = now()
+                WHERE
+                    financial_plan_id = $1
+                    AND workspace_id = $2
+                    AND closed_at is null
+                    AND deleted_at is null
+                RETURNING
+                    financial_plan_id,
+                    title,
+                    month as "month!: MonthReference",
+                    year,
+                    closed_at,
+                    created_at,
+                    updated_at,
+                    deleted_at
             "#,
-            financial_plan_id
+            financial_plan_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(financial_plan)
@@ -0,0 +1,300 @@
+use mockall::automock;
+use sqlx::Connection;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::Result,
+    imports::{ImportFormat, ImportLine, ImportLineStatus, ImportStatus, StatementImport},
+    transactions::MovementType,
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait ImportRepository {
+    async fn list_imports(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<StatementImport>>;
+    async fn create_import(
+        &self,
+        workspace_id: Uuid,
+        payload: StatementImport,
+        lines: Vec<ImportLine>,
+    ) -> Result<StatementImport>;
+    async fn get_import_by_id(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Option<StatementImport>>;
+    async fn list_import_lines(&self, workspace_id: Uuid, import_id: Uuid)
+        -> Result<Vec<ImportLine>>;
+    /// external ids of the lines already turned into transactions for the account
+    async fn list_imported_external_ids(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<String>>;
+    async fn update_import_line(
+        &self,
+        workspace_id: Uuid,
+        line_id: Uuid,
+        status: ImportLineStatus,
+        transaction_id: Option<Uuid>,
+    ) -> Result<()>;
+    /// claim the import for the commit, none when it was committed already
+    async fn commit_import(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Option<StatementImport>>;
+}
+
+#[async_trait::async_trait]
+impl ImportRepository for SqlxRepository {
+    async fn list_imports(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<StatementImport>> {
+        let imports = sqlx::query_as!(
+            StatementImport,
+            r#"
+            SELECT
+                import_id,
+                account_id,
+                format as "format!: ImportFormat",
+                status as "status!: ImportStatus",
+                created_at,
+                committed_at
+            FROM statement_imports
+            WHERE account_id = $1 AND workspace_id = $2
+            ORDER BY created_at DESC
+            "#,
+            account_id,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(imports)
+    }
+
+    async fn create_import(
+        &self,
+        workspace_id: Uuid,
+        payload: StatementImport,
+        lines: Vec<ImportLine>,
+    ) -> Result<StatementImport> {
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin().await?;
+
+        let import = sqlx::query_as!(
+            StatementImport,
+            r#"
+            INSERT INTO statement_imports (
+                import_id,
+                account_id,
+                format,
+                status,
+                workspace_id
+            ) VALUES (
+                $1, $2, $3, $4, $5
+            ) RETURNING
+                import_id,
+                account_id,
+                format as "format!: ImportFormat",
+                status as "status!: ImportStatus",
+                created_at,
+                committed_at
+            "#,
+            payload.import_id,
+            payload.account_id,
+            payload.format as ImportFormat,
+            payload.status as ImportStatus,
+            workspace_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for line in lines {
+            sqlx::query!(
+                r#"
+                INSERT INTO statement_import_lines (
+                    line_id,
+                    import_id,
+                    external_id,
+                    due_date,
+                    description,
+                    value,
+                    movement_type,
+                    status,
+                    match_transaction_id,
+                    match_installment_id,
+                    match_score
+                ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+                )
+                "#,
+                line.line_id,
+                line.import_id,
+                line.external_id,
+                line.due_date,
+                line.description,
+                line.value,
+                line.movement_type as MovementType,
+                line.status as ImportLineStatus,
+                line.match_transaction_id,
+                line.match_installment_id,
+                line.match_score
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(import)
+    }
+
+    async fn get_import_by_id(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Option<StatementImport>> {
+        let import = sqlx::query_as!(
+            StatementImport,
+            r#"
+            SELECT
+                import_id,
+                account_id,
+                format as "format!: ImportFormat",
+                status as "status!: ImportStatus",
+                created_at,
+                committed_at
+            FROM statement_imports
+            WHERE import_id = $1 AND workspace_id = $2
+            "#,
+            import_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(import)
+    }
+
+    async fn list_import_lines(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Vec<ImportLine>> {
+        let lines = sqlx::query_as!(
+            ImportLine,
+            r#"
+            SELECT
+                l.line_id,
+                l.import_id,
+                l.external_id,
+                l.due_date,
+                l.description,
+                l.value,
+                l.movement_type as "movement_type!: MovementType",
+                l.status as "status!: ImportLineStatus",
+                l.transaction_id,
+                l.match_transaction_id,
+                l.match_installment_id,
+                l.match_score
+            FROM statement_import_lines l
+            JOIN statement_imports i ON i.import_id = l.import_id
+            WHERE l.import_id = $1 AND i.workspace_id = $2
+            ORDER BY l.due_date, l.created_at
+            "#,
+            import_id,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(lines)
+    }
+
+    async fn list_imported_external_ids(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<String>> {
+        let external_ids = sqlx::query_scalar!(
+            r#"
+            SELECT l.external_id
+            FROM statement_import_lines l
+            JOIN statement_imports i ON i.import_id = l.import_id
+            WHERE i.account_id = $1 AND i.workspace_id = $2 AND l.status IN ('IMPORTED', 'MATCHED')
+            "#,
+            account_id,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(external_ids)
+    }
+
+    async fn update_import_line(
+        &self,
+        workspace_id: Uuid,
+        line_id: Uuid,
+        status: ImportLineStatus,
+        transaction_id: Option<Uuid>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE statement_import_lines l SET
+                status = $2,
+                transaction_id = $3
+            FROM statement_imports i
+            WHERE l.line_id = $1 AND i.import_id = l.import_id AND i.workspace_id = $4
+            "#,
+            line_id,
+            status as ImportLineStatus,
+            transaction_id,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn commit_import(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Option<StatementImport>> {
+        let import = sqlx::query_as!(
+            StatementImport,
+            r#"
+            UPDATE statement_imports SET
+                status = 'COMMITTED',
+                committed_at = now()
+            WHERE
+                import_id = $1 AND workspace_id = $2 AND committed_at is null
+            RETURNING
+                import_id,
+                account_id,
+                format as "format!: ImportFormat",
+                status as "status!: ImportStatus",
+                created_at,
+                committed_at
+            "#,
+            import_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(import)
+    }
+}
@@ -12,10 +12,20 @@ use super::SqlxRepository;
 #[automock]
 #[async_trait::async_trait]
 pub trait InstallmentRepository {
-    async fn create_installment(&self, payload: &PartialInstallment) -> Result<Installment>;
-    async fn get_installment_by_id(&self, id: Uuid) -> Result<Option<Installment>>;
+    async fn create_installment(
+        &self,
+        workspace_id: Uuid,
+        payload: &PartialInstallment,
+    ) -> Result<Installment>;
+    async fn get_installment_by_id(
+        &self,
+        workspace_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Installment>>;
+    async fn list_installments(&self, workspace_id: Uuid) -> Result<Vec<Installment>>;
     async fn update_status(
         &self,
+        workspace_id: Uuid,
         installment_id: Uuid,
         status: TransactionStatus,
     ) -> Result<Option<Installment>>;
@@ -23,7 +33,11 @@ pub trait InstallmentRepository {
 
 #[async_trait::async_trait]
 impl InstallmentRepository for SqlxRepository {
-    async fn create_installment(&self, payload: &PartialInstallment) -> Result<Installment> {
+    async fn create_installment(
+        &self,
+        workspace_id: Uuid,
+        payload: &PartialInstallment,
+    ) -> Result<Installment> {
         let installment = sqlx::query_as!(
             Installment,
             r#"
@@ -34,9 +48,10 @@ impl InstallmentRepository for SqlxRepository {
                 due_date, 
                 value, 
                 status,
-                total_installment
+                total_installment,
+                workspace_id
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7
+                $1, $2, $3, $4, $5, $6, $7, $8
             ) RETURNING
                 installment_id,
                 transaction_id,
@@ -55,15 +70,20 @@ impl InstallmentRepository for SqlxRepository {
             payload.due_date,
             payload.value,
             payload.status as TransactionStatus,
-            payload.params.total_installment
+            payload.params.total_installment,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await?;
 
         Ok(installment)
     }
 
-    async fn get_installment_by_id(&self, installment_id: Uuid) -> Result<Option<Installment>> {
+    async fn get_installment_by_id(
+        &self,
+        workspace_id: Uuid,
+        installment_id: Uuid,
+    ) -> Result<Option<Installment>> {
         let installment = sqlx::query_as!(
             Installment,
             r#"
@@ -80,25 +100,54 @@ impl InstallmentRepository for SqlxRepository {
                 deleted_at 
             FROM installments
             WHERE
-                installment_id = $1
+                installment_id = $1 AND workspace_id = $2
             "#,
-            installment_id
+            installment_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(installment)
     }
 
+    async fn list_installments(&self, workspace_id: Uuid) -> Result<Vec<Installment>> {
+        let installments = sqlx::query_as!(
+            Installment,
+            r#"
+            SELECT
+                installment_id,
+                transaction_id,
+                installment_number,
+                total_installment,
+                due_date,
+                value,
+                status as "status!: TransactionStatus",
+                created_at,
+                updated_at,
+                deleted_at
+            FROM installments
+            WHERE workspace_id = $1
+            ORDER BY transaction_id, installment_number
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(installments)
+    }
+
     async fn update_status(
         &self,
+        workspace_id: Uuid,
         installment_id: Uuid,
         status: TransactionStatus,
     ) -> Result<Option<Installment>> {
         let installment = sqlx::query_as!(
             Installment,
             r#"
-            UPDATE installments SET status = $2 WHERE installment_id = $1
+            UPDATE installments SET status = $2 WHERE installment_id = $1 AND workspace_id = $3
             RETURNING 
                 installment_id,
                 transaction_id,
@@ -112,9 +161,10 @@ impl InstallmentRepository for SqlxRepository {
                 deleted_at 
             "#,
             installment_id,
-            status as TransactionStatus
+            status as TransactionStatus,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(installment)
@@ -0,0 +1,192 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{errors::Result, institutions::Institution};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait InstitutionRepository {
+    async fn list_institutions(&self, workspace_id: Uuid) -> Result<Vec<Institution>>;
+    async fn create_institution(
+        &self,
+        workspace_id: Uuid,
+        payload: Institution,
+    ) -> Result<Institution>;
+    async fn get_institution_by_id(
+        &self,
+        workspace_id: Uuid,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>>;
+    async fn update_institution(
+        &self,
+        workspace_id: Uuid,
+        payload: Institution,
+    ) -> Result<Option<Institution>>;
+    async fn delete_institution_by_id(
+        &self,
+        workspace_id: Uuid,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>>;
+}
+
+#[async_trait::async_trait]
+impl InstitutionRepository for SqlxRepository {
+    async fn list_institutions(&self, workspace_id: Uuid) -> Result<Vec<Institution>> {
+        let institutions = sqlx::query_as!(
+            Institution,
+            r#"
+            SELECT
+                institution_id,
+                name,
+                code,
+                logo_url,
+                created_at,
+                updated_at,
+                deleted_at
+            FROM institutions
+            WHERE workspace_id = $1
+            ORDER BY name
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(institutions)
+    }
+
+    async fn create_institution(
+        &self,
+        workspace_id: Uuid,
+        payload: Institution,
+    ) -> Result<Institution> {
+        let institution = sqlx::query_as!(
+            Institution,
+            r#"
+            INSERT INTO institutions (
+                institution_id,
+                name,
+                code,
+                logo_url,
+                workspace_id
+            ) VALUES (
+                $1, $2, $3, $4, $5
+            ) RETURNING
+                institution_id,
+                name,
+                code,
+                logo_url,
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            payload.institution_id,
+            payload.name,
+            payload.code,
+            payload.logo_url,
+            workspace_id
+        )
+        .fetch_one(&mut *self.connection().await?)
+        .await?;
+
+        Ok(institution)
+    }
+
+    async fn get_institution_by_id(
+        &self,
+        workspace_id: Uuid,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>> {
+        let institution = sqlx::query_as!(
+            Institution,
+            r#"
+            SELECT
+                institution_id,
+                name,
+                code,
+                logo_url,
+                created_at,
+                updated_at,
+                deleted_at
+            FROM institutions
+            WHERE institution_id = $1 AND workspace_id = $2
+            "#,
+            institution_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(institution)
+    }
+
+    async fn update_institution(
+        &self,
+        workspace_id: Uuid,
+        payload: Institution,
+    ) -> Result<Option<Institution>> {
+        let institution = sqlx::query_as!(
+            Institution,
+            r#"
+            UPDATE institutions SET
+                name = $2,
+                code = $3,
+                logo_url = $4,
+                updated_at = $5
+            WHERE
+                institution_id = $1 AND workspace_id = $6 AND deleted_at is null
+            RETURNING
+                institution_id,
+                name,
+                code,
+                logo_url,
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            payload.institution_id,
+            payload.name,
+            payload.code,
+            payload.logo_url,
+            payload.updated_at,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(institution)
+    }
+
+    async fn delete_institution_by_id(
+        &self,
+        workspace_id: Uuid,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>> {
+        let institution = sqlx::query_as!(
+            Institution,
+            r#"
+            UPDATE institutions SET
+                updated_at = now(),
+                deleted_at = now()
+            WHERE
+                institution_id = $1 AND workspace_id = $2 AND deleted_at is null
+            RETURNING
+                institution_id,
+                name,
+                code,
+                logo_url,
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            institution_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(institution)
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        accounts::{Account, AccountGrant, CreateAccount, UpdateAccount},
+        errors::Result,
+    },
+    repositories::accounts::AccountRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl AccountRepository for MemoryRepository {
+    async fn list_accounts(&self, workspace_id: Uuid) -> Result<Vec<Account>> {
+        let store = self.store();
+
+        Ok(store
+            .accounts
+            .iter()
+            .filter(|a| store.owns(workspace_id, a.account_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn create_account(&self, workspace_id: Uuid, payload: CreateAccount) -> Result<Account> {
+        let account = Account {
+            account_id: Uuid::new_v4(),
+            institution_id: payload.institution_id,
+            owner: payload.owner,
+            account_type: payload.account_type,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, account.account_id);
+        store.accounts.push(account.clone());
+
+        Ok(account)
+    }
+
+    async fn get_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>> {
+        let store = self.store();
+
+        Ok(store
+            .accounts
+            .iter()
+            .find(|a| a.account_id == account_id && store.owns(workspace_id, a.account_id))
+            .cloned())
+    }
+
+    async fn update_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account: Account,
+        payload: UpdateAccount,
+    ) -> Result<Option<Account>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, account.account_id) {
+            return Ok(None);
+        }
+
+        let Some(stored) = store
+            .accounts
+            .iter_mut()
+            .find(|a| a.account_id == account.account_id && a.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        stored.institution_id = payload.institution_id.unwrap_or(account.institution_id);
+        stored.owner = payload.owner.unwrap_or(account.owner);
+        stored.account_type = payload.account_type.unwrap_or(account.account_type);
+        stored.updated_at = Some(Utc::now());
+
+        Ok(Some(stored.clone()))
+    }
+
+    async fn delete_account_by_id(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, account_id) {
+            return Ok(None);
+        }
+
+        let Some(account) = store
+            .accounts
+            .iter_mut()
+            .find(|a| a.account_id == account_id && a.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        account.updated_at = Some(now);
+        account.deleted_at = Some(now);
+
+        Ok(Some(account.clone()))
+    }
+
+    async fn list_account_grants(&self, workspace_id: Uuid) -> Result<Vec<AccountGrant>> {
+        let store = self.store();
+
+        Ok(store
+            .account_grants
+            .iter()
+            .filter(|g| store.owns(workspace_id, g.account_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn save_account_grant(
+        &self,
+        workspace_id: Uuid,
+        grant: AccountGrant,
+    ) -> Result<Option<AccountGrant>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, grant.account_id) {
+            return Ok(None);
+        }
+
+        if let Some(stored) = store
+            .account_grants
+            .iter_mut()
+            .find(|g| g.account_id == grant.account_id && g.user_id == grant.user_id)
+        {
+            stored.permission = grant.permission;
+
+            return Ok(Some(stored.clone()));
+        }
+
+        store.account_grants.push(grant.clone());
+
+        Ok(Some(grant))
+    }
+
+    async fn delete_account_grant(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AccountGrant>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, account_id) {
+            return Ok(None);
+        }
+
+        let Some(position) = store
+            .account_grants
+            .iter()
+            .position(|g| g.account_id == account_id && g.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(store.account_grants.remove(position)))
+    }
+}
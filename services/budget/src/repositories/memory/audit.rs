@@ -0,0 +1,44 @@
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        audit::{AuditEntity, AuditEntry},
+        errors::Result,
+    },
+    repositories::audit::AuditRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl AuditRepository for MemoryRepository {
+    async fn record_audit(&self, workspace_id: Uuid, entry: AuditEntry) -> Result<()> {
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, entry.audit_id);
+        store.audit.push(entry);
+
+        Ok(())
+    }
+
+    async fn list_audit(
+        &self,
+        workspace_id: Uuid,
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<AuditEntry>> {
+        let store = self.store();
+
+        // entries are appended in order, no need to sort them
+        Ok(store
+            .audit
+            .iter()
+            .filter(|e| {
+                e.entity_type == entity_type
+                    && e.entity_id == entity_id
+                    && store.owns(workspace_id, e.audit_id)
+            })
+            .cloned()
+            .collect())
+    }
+}
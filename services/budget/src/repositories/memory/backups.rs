@@ -0,0 +1,31 @@
+use tokio::sync::mpsc;
+
+use crate::{
+    domains::{
+        backups::BackupRow,
+        errors::{Error, Result},
+    },
+    repositories::backups::BackupRepository,
+};
+
+use super::MemoryRepository;
+
+// backups are made of the database rows, there is nothing to back up in memory
+#[async_trait::async_trait]
+impl BackupRepository for MemoryRepository {
+    async fn read_tables(&self) -> Result<mpsc::Receiver<Result<BackupRow>>> {
+        Err(Error::UnsupportedByStorage("memory"))
+    }
+
+    async fn has_records(&self) -> Result<bool> {
+        Err(Error::UnsupportedByStorage("memory"))
+    }
+
+    async fn clear_tables(&self) -> Result<()> {
+        Err(Error::UnsupportedByStorage("memory"))
+    }
+
+    async fn insert_rows(&self, _table: &'static str, _rows: &[serde_json::Value]) -> Result<()> {
+        Err(Error::UnsupportedByStorage("memory"))
+    }
+}
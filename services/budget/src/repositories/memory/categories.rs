@@ -0,0 +1,129 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{categories::Category, errors::Result},
+    repositories::categories::CategoryRepository,
+};
+
+use super::{MemoryRepository, Store, UniqueViolation};
+
+/// same rule as categories_parent_name_idx
+fn name_taken(store: &Store, workspace_id: Uuid, payload: &Category) -> bool {
+    store.categories.iter().any(|c| {
+        c.category_id != payload.category_id
+            && store.owns(workspace_id, c.category_id)
+            && c.deleted_at.is_none()
+            && c.parent_id == payload.parent_id
+            && c.name.to_lowercase() == payload.name.to_lowercase()
+    })
+}
+
+#[async_trait::async_trait]
+impl CategoryRepository for MemoryRepository {
+    async fn list_categories(&self, workspace_id: Uuid) -> Result<Vec<Category>> {
+        let store = self.store();
+
+        let mut categories: Vec<Category> = store
+            .categories
+            .iter()
+            .filter(|c| store.owns(workspace_id, c.category_id))
+            .cloned()
+            .collect();
+
+        categories.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(categories)
+    }
+
+    async fn create_category(&self, workspace_id: Uuid, payload: Category) -> Result<Category> {
+        let mut store = self.store();
+
+        if name_taken(&store, workspace_id, &payload) {
+            return Err(UniqueViolation::error("categories_parent_name_idx"));
+        }
+
+        let category = Category {
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        store.set_owner(workspace_id, category.category_id);
+        store.categories.push(category.clone());
+
+        Ok(category)
+    }
+
+    async fn get_category_by_id(
+        &self,
+        workspace_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<Category>> {
+        let store = self.store();
+
+        Ok(store
+            .categories
+            .iter()
+            .find(|c| c.category_id == category_id && store.owns(workspace_id, c.category_id))
+            .cloned())
+    }
+
+    async fn update_category(
+        &self,
+        workspace_id: Uuid,
+        payload: Category,
+    ) -> Result<Option<Category>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, payload.category_id) {
+            return Ok(None);
+        }
+
+        if name_taken(&store, workspace_id, &payload) {
+            return Err(UniqueViolation::error("categories_parent_name_idx"));
+        }
+
+        let Some(category) = store
+            .categories
+            .iter_mut()
+            .find(|c| c.category_id == payload.category_id && c.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        category.name = payload.name;
+        category.icon = payload.icon;
+        category.color = payload.color;
+        category.updated_at = payload.updated_at;
+
+        Ok(Some(category.clone()))
+    }
+
+    async fn delete_category_by_id(
+        &self,
+        workspace_id: Uuid,
+        category_id: Uuid,
+    ) -> Result<Option<Category>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, category_id) {
+            return Ok(None);
+        }
+
+        let Some(category) = store
+            .categories
+            .iter_mut()
+            .find(|c| c.category_id == category_id && c.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        category.updated_at = Some(now);
+        category.deleted_at = Some(now);
+
+        Ok(Some(category.clone()))
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{errors::Result, financial_plans::FinancialPlan},
+    repositories::financial_plans::FinancialPlanRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl FinancialPlanRepository for MemoryRepository {
+    async fn create_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        payload: FinancialPlan,
+    ) -> Result<FinancialPlan> {
+        let financial_plan = FinancialPlan {
+            closed_at: None,
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, financial_plan.financial_plan_id);
+        store.financial_plans.push(financial_plan.clone());
+
+        Ok(financial_plan)
+    }
+
+    async fn list_financial_plans(&self, workspace_id: Uuid) -> Result<Vec<FinancialPlan>> {
+        let store = self.store();
+
+        Ok(store
+            .financial_plans
+            .iter()
+            .filter(|p| store.owns(workspace_id, p.financial_plan_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_financial_plan_by_id(
+        &self,
+        workspace_id: Uuid,
+        financial_plan_id: Uuid,
+    ) -> Result<Option<FinancialPlan>> {
+        let store = self.store();
+
+        Ok(store
+            .financial_plans
+            .iter()
+            .find(|p| {
+                p.financial_plan_id == financial_plan_id
+                    && store.owns(workspace_id, p.financial_plan_id)
+            })
+            .cloned())
+    }
+
+    async fn close_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        financial_plan_id: Uuid,
+    ) -> Result<Option<FinancialPlan>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, financial_plan_id) {
+            return Ok(None);
+        }
+
+        let Some(financial_plan) = store.financial_plans.iter_mut().find(|p| {
+            p.financial_plan_id == financial_plan_id
+                && p.closed_at.is_none()
+                && p.deleted_at.is_none()
+        }) else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        financial_plan.closed_at = Some(now);
+        financial_plan.updated_at = Some(now);
+
+        Ok(Some(financial_plan.clone()))
+    }
+}
@@ -0,0 +1,182 @@
+use std::cmp::Reverse;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        imports::{ImportLine, ImportLineStatus, ImportStatus, StatementImport},
+    },
+    repositories::imports::ImportRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl ImportRepository for MemoryRepository {
+    async fn list_imports(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<StatementImport>> {
+        let store = self.store();
+
+        let mut imports: Vec<StatementImport> = store
+            .imports
+            .iter()
+            .filter(|i| i.account_id == account_id && store.owns(workspace_id, i.import_id))
+            .cloned()
+            .collect();
+
+        imports.sort_by_key(|i| Reverse(i.created_at));
+
+        Ok(imports)
+    }
+
+    async fn create_import(
+        &self,
+        workspace_id: Uuid,
+        payload: StatementImport,
+        lines: Vec<ImportLine>,
+    ) -> Result<StatementImport> {
+        let import = StatementImport {
+            created_at: Utc::now(),
+            committed_at: None,
+            ..payload
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, import.import_id);
+        store.imports.push(import.clone());
+        store
+            .import_lines
+            .extend(lines.into_iter().map(|line| ImportLine {
+                transaction_id: None,
+                ..line
+            }));
+
+        Ok(import)
+    }
+
+    async fn get_import_by_id(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Option<StatementImport>> {
+        let store = self.store();
+
+        Ok(store
+            .imports
+            .iter()
+            .find(|i| i.import_id == import_id && store.owns(workspace_id, import_id))
+            .cloned())
+    }
+
+    async fn list_import_lines(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Vec<ImportLine>> {
+        let store = self.store();
+
+        if !store.owns(workspace_id, import_id) {
+            return Ok(vec![]);
+        }
+
+        let mut lines: Vec<ImportLine> = store
+            .import_lines
+            .iter()
+            .filter(|l| l.import_id == import_id)
+            .cloned()
+            .collect();
+
+        // stable sort, lines of the same day keep their insertion order
+        lines.sort_by_key(|l| l.due_date);
+
+        Ok(lines)
+    }
+
+    async fn list_imported_external_ids(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<String>> {
+        let store = self.store();
+
+        Ok(store
+            .import_lines
+            .iter()
+            .filter(|l| {
+                matches!(
+                    l.status,
+                    ImportLineStatus::Imported | ImportLineStatus::Matched
+                )
+            })
+            .filter(|l| {
+                store.imports.iter().any(|i| {
+                    i.import_id == l.import_id
+                        && i.account_id == account_id
+                        && store.owns(workspace_id, i.import_id)
+                })
+            })
+            .map(|l| l.external_id.clone())
+            .collect())
+    }
+
+    async fn update_import_line(
+        &self,
+        workspace_id: Uuid,
+        line_id: Uuid,
+        status: ImportLineStatus,
+        transaction_id: Option<Uuid>,
+    ) -> Result<()> {
+        let mut store = self.store();
+
+        let Some(import_id) = store
+            .import_lines
+            .iter()
+            .find(|l| l.line_id == line_id)
+            .map(|l| l.import_id)
+        else {
+            return Ok(());
+        };
+
+        if !store.owns(workspace_id, import_id) {
+            return Ok(());
+        }
+
+        if let Some(line) = store.import_lines.iter_mut().find(|l| l.line_id == line_id) {
+            line.status = status;
+            line.transaction_id = transaction_id;
+        }
+
+        Ok(())
+    }
+
+    async fn commit_import(
+        &self,
+        workspace_id: Uuid,
+        import_id: Uuid,
+    ) -> Result<Option<StatementImport>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, import_id) {
+            return Ok(None);
+        }
+
+        let Some(import) = store
+            .imports
+            .iter_mut()
+            .find(|i| i.import_id == import_id && i.committed_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        import.status = ImportStatus::Committed;
+        import.committed_at = Some(Utc::now());
+
+        Ok(Some(import.clone()))
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        installments::{Installment, PartialInstallment},
+        transactions::TransactionStatus,
+    },
+    repositories::installments::InstallmentRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl InstallmentRepository for MemoryRepository {
+    async fn create_installment(
+        &self,
+        workspace_id: Uuid,
+        payload: &PartialInstallment,
+    ) -> Result<Installment> {
+        let installment = Installment {
+            installment_id: Uuid::new_v4(),
+            transaction_id: payload.transaction_id,
+            installment_number: payload.params.installment_number,
+            total_installment: payload.params.total_installment,
+            due_date: payload.due_date,
+            value: payload.value.clone(),
+            status: payload.status,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, installment.installment_id);
+        store.installments.push(installment.clone());
+
+        Ok(installment)
+    }
+
+    async fn get_installment_by_id(
+        &self,
+        workspace_id: Uuid,
+        installment_id: Uuid,
+    ) -> Result<Option<Installment>> {
+        let store = self.store();
+
+        Ok(store
+            .installments
+            .iter()
+            .find(|i| {
+                i.installment_id == installment_id && store.owns(workspace_id, installment_id)
+            })
+            .cloned())
+    }
+
+    async fn list_installments(&self, workspace_id: Uuid) -> Result<Vec<Installment>> {
+        let store = self.store();
+
+        let mut installments: Vec<Installment> = store
+            .installments
+            .iter()
+            .filter(|i| store.owns(workspace_id, i.installment_id))
+            .cloned()
+            .collect();
+
+        installments.sort_by_key(|i| (i.transaction_id, i.installment_number));
+
+        Ok(installments)
+    }
+
+    async fn update_status(
+        &self,
+        workspace_id: Uuid,
+        installment_id: Uuid,
+        status: TransactionStatus,
+    ) -> Result<Option<Installment>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, installment_id) {
+            return Ok(None);
+        }
+
+        let Some(installment) = store
+            .installments
+            .iter_mut()
+            .find(|i| i.installment_id == installment_id)
+        else {
+            return Ok(None);
+        };
+
+        installment.status = status;
+
+        Ok(Some(installment.clone()))
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{errors::Result, institutions::Institution},
+    repositories::institutions::InstitutionRepository,
+};
+
+use super::{MemoryRepository, Store, UniqueViolation};
+
+/// same rule as institutions_name_idx
+fn name_taken(store: &Store, workspace_id: Uuid, payload: &Institution) -> bool {
+    store.institutions.iter().any(|i| {
+        i.institution_id != payload.institution_id
+            && store.owns(workspace_id, i.institution_id)
+            && i.deleted_at.is_none()
+            && i.name.to_lowercase() == payload.name.to_lowercase()
+    })
+}
+
+#[async_trait::async_trait]
+impl InstitutionRepository for MemoryRepository {
+    async fn list_institutions(&self, workspace_id: Uuid) -> Result<Vec<Institution>> {
+        let store = self.store();
+
+        let mut institutions: Vec<Institution> = store
+            .institutions
+            .iter()
+            .filter(|i| store.owns(workspace_id, i.institution_id))
+            .cloned()
+            .collect();
+
+        institutions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(institutions)
+    }
+
+    async fn create_institution(
+        &self,
+        workspace_id: Uuid,
+        payload: Institution,
+    ) -> Result<Institution> {
+        let mut store = self.store();
+
+        if name_taken(&store, workspace_id, &payload) {
+            return Err(UniqueViolation::error("institutions_name_idx"));
+        }
+
+        let institution = Institution {
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        store.set_owner(workspace_id, institution.institution_id);
+        store.institutions.push(institution.clone());
+
+        Ok(institution)
+    }
+
+    async fn get_institution_by_id(
+        &self,
+        workspace_id: Uuid,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>> {
+        let store = self.store();
+
+        Ok(store
+            .institutions
+            .iter()
+            .find(|i| {
+                i.institution_id == institution_id && store.owns(workspace_id, i.institution_id)
+            })
+            .cloned())
+    }
+
+    async fn update_institution(
+        &self,
+        workspace_id: Uuid,
+        payload: Institution,
+    ) -> Result<Option<Institution>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, payload.institution_id) {
+            return Ok(None);
+        }
+
+        if name_taken(&store, workspace_id, &payload) {
+            return Err(UniqueViolation::error("institutions_name_idx"));
+        }
+
+        let Some(institution) = store
+            .institutions
+            .iter_mut()
+            .find(|i| i.institution_id == payload.institution_id && i.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        institution.name = payload.name;
+        institution.code = payload.code;
+        institution.logo_url = payload.logo_url;
+        institution.updated_at = payload.updated_at;
+
+        Ok(Some(institution.clone()))
+    }
+
+    async fn delete_institution_by_id(
+        &self,
+        workspace_id: Uuid,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, institution_id) {
+            return Ok(None);
+        }
+
+        let Some(institution) = store
+            .institutions
+            .iter_mut()
+            .find(|i| i.institution_id == institution_id && i.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        institution.updated_at = Some(now);
+        institution.deleted_at = Some(now);
+
+        Ok(Some(institution.clone()))
+    }
+}
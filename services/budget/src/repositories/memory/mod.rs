@@ -0,0 +1,146 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error::Error as StdError,
+    fmt,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use sqlx::error::{DatabaseError, ErrorKind};
+use uuid::Uuid;
+
+use crate::domains::{
+    accounts::{Account, AccountGrant},
+    audit::AuditEntry,
+    categories::Category,
+    errors::Error,
+    financial_plans::FinancialPlan,
+    imports::{ImportLine, StatementImport},
+    installments::Installment,
+    institutions::Institution,
+    recurrences::Recurrence,
+    rules::Rule,
+    settlements::Settlement,
+    tags::{Tag, TransactionTag},
+    transactions::Transaction,
+    workspaces::{Workspace, WorkspaceMember},
+};
+
+pub mod accounts;
+pub mod audit;
+pub mod backups;
+pub mod categories;
+pub mod financial_plans;
+pub mod imports;
+pub mod installments;
+pub mod institutions;
+pub mod recurrences;
+pub mod rules;
+pub mod settlements;
+pub mod tags;
+pub mod transactions;
+pub mod unit_of_work;
+pub mod workspaces;
+
+/// Keeps every record in the process memory, the service runs without Postgres
+/// and loses its records on restart
+#[derive(Default)]
+pub struct MemoryRepository {
+    store: Mutex<Store>,
+}
+
+#[derive(Default)]
+struct Store {
+    transactions: Vec<Transaction>,
+    accounts: Vec<Account>,
+    account_grants: Vec<AccountGrant>,
+    installments: Vec<Installment>,
+    settlements: Vec<Settlement>,
+    financial_plans: Vec<FinancialPlan>,
+    recurrences: Vec<Recurrence>,
+    /// recurrence id and transaction id
+    recurrence_links: Vec<(Uuid, Uuid)>,
+    categories: Vec<Category>,
+    tags: Vec<Tag>,
+    transaction_tags: Vec<TransactionTag>,
+    /// recurrence id and tag id
+    recurrence_tags: Vec<(Uuid, Uuid)>,
+    institutions: Vec<Institution>,
+    imports: Vec<StatementImport>,
+    import_lines: Vec<ImportLine>,
+    rules: Vec<Rule>,
+    audit: Vec<AuditEntry>,
+    workspaces: Vec<Workspace>,
+    members: Vec<WorkspaceMember>,
+    /// personal workspace of every user, by the user id
+    personal_workspaces: HashMap<Uuid, Uuid>,
+    /// workspace of every record, by the record id
+    owners: HashMap<Uuid, Uuid>,
+}
+
+impl MemoryRepository {
+    fn store(&self) -> MutexGuard<'_, Store> {
+        // a panic while holding the lock leaves the records as they were, keep serving them
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Store {
+    /// whether the record belongs to the workspace
+    fn owns(&self, workspace_id: Uuid, record_id: Uuid) -> bool {
+        self.owners.get(&record_id) == Some(&workspace_id)
+    }
+
+    fn set_owner(&mut self, workspace_id: Uuid, record_id: Uuid) {
+        self.owners.insert(record_id, workspace_id);
+    }
+}
+
+/// Same error the database raises when a unique index is violated,
+/// so the routes answer memory and Postgres storages alike
+#[derive(Debug)]
+struct UniqueViolation {
+    message: String,
+}
+
+impl UniqueViolation {
+    fn error(index: &str) -> Error {
+        Error::DatabaseError(sqlx::Error::Database(Box::new(UniqueViolation {
+            message: format!("duplicate key value violates unique constraint \"{index}\""),
+        })))
+    }
+}
+
+impl fmt::Display for UniqueViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for UniqueViolation {}
+
+impl DatabaseError for UniqueViolation {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed("23505"))
+    }
+
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::UniqueViolation
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        recurrences::{CreateRecurrenceLink, Recurrence, RecurrenceLink},
+    },
+    repositories::recurrences::RecurrenceRepository,
+};
+
+use super::{MemoryRepository, UniqueViolation};
+
+#[async_trait::async_trait]
+impl RecurrenceRepository for MemoryRepository {
+    async fn list_recurrences(&self, workspace_id: Uuid) -> Result<Vec<Recurrence>> {
+        let store = self.store();
+
+        Ok(store
+            .recurrences
+            .iter()
+            .filter(|r| store.owns(workspace_id, r.recurrence_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn create_recurrence(
+        &self,
+        workspace_id: Uuid,
+        payload: Recurrence,
+    ) -> Result<Recurrence> {
+        let recurrence = Recurrence {
+            value: payload.value.normalized(),
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, recurrence.recurrence_id);
+        store.recurrences.push(recurrence.clone());
+
+        Ok(recurrence)
+    }
+
+    async fn get_recurrence_by_id(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+    ) -> Result<Option<Recurrence>> {
+        let store = self.store();
+
+        Ok(store
+            .recurrences
+            .iter()
+            .find(|r| r.recurrence_id == recurrence_id && store.owns(workspace_id, recurrence_id))
+            .cloned())
+    }
+
+    async fn update_recurrence(
+        &self,
+        workspace_id: Uuid,
+        payload: Recurrence,
+    ) -> Result<Option<Recurrence>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, payload.recurrence_id) {
+            return Ok(None);
+        }
+
+        let Some(recurrence) = store
+            .recurrences
+            .iter_mut()
+            .find(|r| r.recurrence_id == payload.recurrence_id)
+        else {
+            return Ok(None);
+        };
+
+        recurrence.account_id = payload.account_id;
+        recurrence.title = payload.title;
+        recurrence.frequency = payload.frequency;
+        recurrence.is_active = payload.is_active;
+        recurrence.category_id = payload.category_id;
+        recurrence.start_date = payload.start_date;
+        recurrence.value = payload.value.normalized();
+        recurrence.movement_type = payload.movement_type;
+        recurrence.updated_at = payload.updated_at;
+
+        Ok(Some(recurrence.clone()))
+    }
+
+    async fn get_recurrence_link(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Vec<Uuid>,
+    ) -> Result<BTreeMap<Uuid, Vec<RecurrenceLink>>> {
+        let store = self.store();
+
+        let mut links: BTreeMap<Uuid, Vec<RecurrenceLink>> = BTreeMap::new();
+
+        for (link_recurrence_id, transaction_id) in &store.recurrence_links {
+            if !recurrence_id.contains(link_recurrence_id) {
+                continue;
+            }
+
+            let Some(transaction) = store.transactions.iter().find(|t| {
+                t.transaction_id == *transaction_id && store.owns(workspace_id, *transaction_id)
+            }) else {
+                continue;
+            };
+
+            links
+                .entry(*link_recurrence_id)
+                .or_default()
+                .push(RecurrenceLink {
+                    recurrence_id: *link_recurrence_id,
+                    transaction_id: *transaction_id,
+                    due_date: transaction.due_date,
+                });
+        }
+
+        Ok(links)
+    }
+
+    async fn create_recurrence_link(
+        &self,
+        workspace_id: Uuid,
+        payload: CreateRecurrenceLink,
+    ) -> Result<()> {
+        let mut store = self.store();
+        let link = (payload.recurrence_id, payload.transaction_id);
+
+        if !store.owns(workspace_id, payload.recurrence_id)
+            || !store.owns(workspace_id, payload.transaction_id)
+        {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+
+        if store.recurrence_links.contains(&link) {
+            return Err(UniqueViolation::error("transaction_recurrence_links_pkey"));
+        }
+
+        store.recurrence_links.push(link);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{errors::Result, rules::Rule},
+    repositories::rules::RuleRepository,
+};
+
+use super::{MemoryRepository, Store};
+
+/// rule as the database returns it, without the tags deleted since it was saved
+fn with_active_tags(store: &Store, rule: &Rule) -> Rule {
+    let tag_ids = rule
+        .tag_ids
+        .iter()
+        .filter(|tag_id| {
+            store
+                .tags
+                .iter()
+                .any(|t| t.tag_id == **tag_id && t.deleted_at.is_none())
+        })
+        .copied()
+        .collect();
+
+    Rule {
+        tag_ids,
+        ..rule.clone()
+    }
+}
+
+/// tags saved with the rule, like the database only links the tags of the workspace once
+fn dedup(store: &Store, workspace_id: Uuid, tag_ids: Vec<Uuid>) -> Vec<Uuid> {
+    let mut unique = Vec::with_capacity(tag_ids.len());
+
+    for tag_id in tag_ids {
+        if store.owns(workspace_id, tag_id) && !unique.contains(&tag_id) {
+            unique.push(tag_id);
+        }
+    }
+
+    unique
+}
+
+#[async_trait::async_trait]
+impl RuleRepository for MemoryRepository {
+    async fn list_rules(&self, workspace_id: Uuid) -> Result<Vec<Rule>> {
+        let store = self.store();
+
+        let mut rules: Vec<Rule> = store
+            .rules
+            .iter()
+            .filter(|rule| store.owns(workspace_id, rule.rule_id))
+            .map(|rule| with_active_tags(&store, rule))
+            .collect();
+
+        rules.sort_by_key(|rule| (rule.priority, rule.created_at));
+
+        Ok(rules)
+    }
+
+    async fn create_rule(&self, workspace_id: Uuid, payload: Rule) -> Result<Rule> {
+        let mut store = self.store();
+
+        let rule = Rule {
+            tag_ids: dedup(&store, workspace_id, payload.tag_ids),
+            hits: 0,
+            last_hit_at: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        store.set_owner(workspace_id, rule.rule_id);
+        store.rules.push(rule.clone());
+
+        Ok(with_active_tags(&store, &rule))
+    }
+
+    async fn get_rule_by_id(&self, workspace_id: Uuid, rule_id: Uuid) -> Result<Option<Rule>> {
+        let store = self.store();
+
+        Ok(store
+            .rules
+            .iter()
+            .find(|r| r.rule_id == rule_id && store.owns(workspace_id, rule_id))
+            .map(|rule| with_active_tags(&store, rule)))
+    }
+
+    async fn update_rule(&self, workspace_id: Uuid, payload: Rule) -> Result<Option<Rule>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, payload.rule_id) {
+            return Ok(None);
+        }
+
+        let tag_ids = dedup(&store, workspace_id, payload.tag_ids);
+
+        let Some(rule) = store
+            .rules
+            .iter_mut()
+            .find(|r| r.rule_id == payload.rule_id && r.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        rule.name = payload.name;
+        rule.pattern = payload.pattern;
+        rule.movement_type = payload.movement_type;
+        rule.category_id = payload.category_id;
+        rule.tag_ids = tag_ids;
+        rule.priority = payload.priority;
+        rule.updated_at = payload.updated_at;
+
+        let rule = rule.clone();
+
+        Ok(Some(with_active_tags(&store, &rule)))
+    }
+
+    async fn delete_rule_by_id(&self, workspace_id: Uuid, rule_id: Uuid) -> Result<Option<Rule>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, rule_id) {
+            return Ok(None);
+        }
+
+        let Some(rule) = store
+            .rules
+            .iter_mut()
+            .find(|r| r.rule_id == rule_id && r.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        rule.updated_at = Some(now);
+        rule.deleted_at = Some(now);
+
+        let rule = rule.clone();
+
+        Ok(Some(with_active_tags(&store, &rule)))
+    }
+
+    async fn record_hits(&self, workspace_id: Uuid, rule_id: Uuid, hits: i64) -> Result<()> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, rule_id) {
+            return Ok(());
+        }
+
+        if let Some(rule) = store.rules.iter_mut().find(|r| r.rule_id == rule_id) {
+            rule.hits += hits;
+            rule.last_hit_at = Some(Utc::now());
+        }
+
+        Ok(())
+    }
+}
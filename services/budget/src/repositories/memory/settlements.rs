@@ -0,0 +1,44 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{errors::Result, settlements::Settlement},
+    repositories::settlements::SettlementRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl SettlementRepository for MemoryRepository {
+    async fn create_settlement(
+        &self,
+        workspace_id: Uuid,
+        payload: Settlement,
+    ) -> Result<Settlement> {
+        let settlement = Settlement {
+            settlement_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, settlement.settlement_id);
+        store.settlements.push(settlement.clone());
+
+        Ok(settlement)
+    }
+
+    async fn list_settlements(&self, workspace_id: Uuid) -> Result<Vec<Settlement>> {
+        let store = self.store();
+
+        Ok(store
+            .settlements
+            .iter()
+            .filter(|s| store.owns(workspace_id, s.settlement_id))
+            .cloned()
+            .collect())
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        tags::{Tag, TransactionTag},
+    },
+    repositories::tags::TagRepository,
+};
+
+use super::{MemoryRepository, Store, UniqueViolation};
+
+/// same rule as tags_name_idx
+fn name_taken(store: &Store, workspace_id: Uuid, payload: &Tag) -> bool {
+    store.tags.iter().any(|t| {
+        t.tag_id != payload.tag_id
+            && store.owns(workspace_id, t.tag_id)
+            && t.deleted_at.is_none()
+            && t.name.to_lowercase() == payload.name.to_lowercase()
+    })
+}
+
+/// active tags of the workspace among the given ids, sorted by name
+fn active_tags(store: &Store, workspace_id: Uuid, tag_ids: impl Iterator<Item = Uuid>) -> Vec<Tag> {
+    let tag_ids: Vec<Uuid> = tag_ids.collect();
+
+    let mut tags: Vec<Tag> = store
+        .tags
+        .iter()
+        .filter(|t| {
+            t.deleted_at.is_none()
+                && tag_ids.contains(&t.tag_id)
+                && store.owns(workspace_id, t.tag_id)
+        })
+        .cloned()
+        .collect();
+
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+    tags
+}
+
+#[async_trait::async_trait]
+impl TagRepository for MemoryRepository {
+    async fn list_tags(&self, workspace_id: Uuid) -> Result<Vec<Tag>> {
+        let store = self.store();
+
+        let mut tags: Vec<Tag> = store
+            .tags
+            .iter()
+            .filter(|t| store.owns(workspace_id, t.tag_id))
+            .cloned()
+            .collect();
+
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(tags)
+    }
+
+    async fn create_tag(&self, workspace_id: Uuid, payload: Tag) -> Result<Tag> {
+        let mut store = self.store();
+
+        if name_taken(&store, workspace_id, &payload) {
+            return Err(UniqueViolation::error("tags_name_idx"));
+        }
+
+        let tag = Tag {
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..payload
+        };
+
+        store.set_owner(workspace_id, tag.tag_id);
+        store.tags.push(tag.clone());
+
+        Ok(tag)
+    }
+
+    async fn get_tag_by_id(&self, workspace_id: Uuid, tag_id: Uuid) -> Result<Option<Tag>> {
+        let store = self.store();
+
+        Ok(store
+            .tags
+            .iter()
+            .find(|t| t.tag_id == tag_id && store.owns(workspace_id, tag_id))
+            .cloned())
+    }
+
+    async fn update_tag(&self, workspace_id: Uuid, payload: Tag) -> Result<Option<Tag>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, payload.tag_id) {
+            return Ok(None);
+        }
+
+        if name_taken(&store, workspace_id, &payload) {
+            return Err(UniqueViolation::error("tags_name_idx"));
+        }
+
+        let Some(tag) = store
+            .tags
+            .iter_mut()
+            .find(|t| t.tag_id == payload.tag_id && t.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        tag.name = payload.name;
+        tag.color = payload.color;
+        tag.updated_at = payload.updated_at;
+
+        Ok(Some(tag.clone()))
+    }
+
+    async fn delete_tag_by_id(&self, workspace_id: Uuid, tag_id: Uuid) -> Result<Option<Tag>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, tag_id) {
+            return Ok(None);
+        }
+
+        let Some(tag) = store
+            .tags
+            .iter_mut()
+            .find(|t| t.tag_id == tag_id && t.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        tag.updated_at = Some(now);
+        tag.deleted_at = Some(now);
+
+        Ok(Some(tag.clone()))
+    }
+
+    async fn list_transaction_tags(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        let store = self.store();
+
+        let tag_ids = store
+            .transaction_tags
+            .iter()
+            .filter(|link| link.transaction_id == transaction_id)
+            .map(|link| link.tag_id);
+
+        Ok(active_tags(&store, workspace_id, tag_ids))
+    }
+
+    async fn list_transaction_tag_links(&self, workspace_id: Uuid) -> Result<Vec<TransactionTag>> {
+        let store = self.store();
+
+        Ok(store
+            .transaction_tags
+            .iter()
+            .filter(|link| {
+                store.owns(workspace_id, link.tag_id)
+                    && store
+                        .tags
+                        .iter()
+                        .any(|t| t.tag_id == link.tag_id && t.deleted_at.is_none())
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn attach_transaction_tag(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        let mut store = self.store();
+        let link = TransactionTag {
+            transaction_id,
+            tag_id,
+        };
+
+        if store.owns(workspace_id, transaction_id)
+            && store.owns(workspace_id, tag_id)
+            && !store.transaction_tags.contains(&link)
+        {
+            store.transaction_tags.push(link);
+        }
+
+        Ok(())
+    }
+
+    async fn detach_transaction_tag(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        let mut store = self.store();
+
+        if store.owns(workspace_id, tag_id) {
+            store
+                .transaction_tags
+                .retain(|link| link.transaction_id != transaction_id || link.tag_id != tag_id);
+        }
+
+        Ok(())
+    }
+
+    async fn list_recurrence_tags(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        let store = self.store();
+
+        let tag_ids = store
+            .recurrence_tags
+            .iter()
+            .filter(|(link_recurrence_id, _)| *link_recurrence_id == recurrence_id)
+            .map(|(_, tag_id)| *tag_id);
+
+        Ok(active_tags(&store, workspace_id, tag_ids))
+    }
+
+    async fn attach_recurrence_tag(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        let mut store = self.store();
+        let link = (recurrence_id, tag_id);
+
+        if store.owns(workspace_id, recurrence_id)
+            && store.owns(workspace_id, tag_id)
+            && !store.recurrence_tags.contains(&link)
+        {
+            store.recurrence_tags.push(link);
+        }
+
+        Ok(())
+    }
+
+    async fn detach_recurrence_tag(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        let mut store = self.store();
+
+        if store.owns(workspace_id, tag_id) {
+            store
+                .recurrence_tags
+                .retain(|link| *link != (recurrence_id, tag_id));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        reports::ReportFilter,
+        transactions::{Transaction, TransactionStatus},
+    },
+    repositories::transactions::TransactionRepository,
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl TransactionRepository for MemoryRepository {
+    async fn create_transaction(
+        &self,
+        workspace_id: Uuid,
+        transaction: Transaction,
+    ) -> Result<Transaction> {
+        let transaction = Transaction {
+            value: transaction.value.normalized(),
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            ..transaction
+        };
+
+        let mut store = self.store();
+
+        store.set_owner(workspace_id, transaction.transaction_id);
+        store.transactions.push(transaction.clone());
+
+        Ok(transaction)
+    }
+
+    async fn list_transactions(&self, workspace_id: Uuid) -> Result<Vec<Transaction>> {
+        let store = self.store();
+
+        Ok(store
+            .transactions
+            .iter()
+            .filter(|t| store.owns(workspace_id, t.transaction_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_report_transactions(
+        &self,
+        workspace_id: Uuid,
+        filter: ReportFilter,
+    ) -> Result<Vec<Transaction>> {
+        let store = self.store();
+
+        Ok(store
+            .transactions
+            .iter()
+            .filter(|t| store.owns(workspace_id, t.transaction_id))
+            .filter(|t| filter.matches(t))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Option<Transaction>> {
+        let store = self.store();
+
+        Ok(store
+            .transactions
+            .iter()
+            .find(|t| {
+                t.transaction_id == transaction_id && store.owns(workspace_id, transaction_id)
+            })
+            .cloned())
+    }
+
+    async fn delete_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Option<Transaction>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, transaction_id) {
+            return Ok(None);
+        }
+
+        let Some(transaction) = store
+            .transactions
+            .iter_mut()
+            .find(|t| t.transaction_id == transaction_id && t.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        transaction.updated_at = Some(now);
+        transaction.deleted_at = Some(now);
+
+        Ok(Some(transaction.clone()))
+    }
+
+    async fn update_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        payload: Transaction,
+    ) -> Result<Option<Transaction>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, payload.transaction_id) {
+            return Ok(None);
+        }
+
+        let Some(transaction) = store
+            .transactions
+            .iter_mut()
+            .find(|t| t.transaction_id == payload.transaction_id)
+        else {
+            return Ok(None);
+        };
+
+        transaction.movement_type = payload.movement_type;
+        transaction.description = payload.description;
+        transaction.value = payload.value.normalized();
+        transaction.due_date = payload.due_date;
+        transaction.category_id = payload.category_id;
+        transaction.account_id = payload.account_id;
+        transaction.financial_plan_id = payload.financial_plan_id;
+        transaction.updated_at = payload.updated_at;
+
+        Ok(Some(transaction.clone()))
+    }
+
+    async fn update_status(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        status: TransactionStatus,
+    ) -> Result<Option<Transaction>> {
+        let mut store = self.store();
+
+        if !store.owns(workspace_id, transaction_id) {
+            return Ok(None);
+        }
+
+        let Some(transaction) = store
+            .transactions
+            .iter_mut()
+            .find(|t| t.transaction_id == transaction_id)
+        else {
+            return Ok(None);
+        };
+
+        transaction.status = status;
+
+        Ok(Some(transaction.clone()))
+    }
+
+    async fn bulk_transactions_into_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        payload: Vec<Transaction>,
+    ) {
+        let mut store = self.store();
+
+        for transaction in &payload {
+            store.set_owner(workspace_id, transaction.transaction_id);
+        }
+
+        store.transactions.extend(payload);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::{
+    domains::errors::Result,
+    repositories::unit_of_work::{UnitOfWork, UnitOfWorkRepository},
+};
+
+use super::MemoryRepository;
+
+#[async_trait::async_trait]
+impl UnitOfWorkRepository for MemoryRepository {
+    /// every change lands in the store at once, there is nothing to roll back
+    async fn begin(&self) -> Result<Option<UnitOfWork>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,240 @@
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        categories::Category,
+        errors::Result,
+        institutions::Institution,
+        workspaces::{Workspace, WorkspaceMember, WorkspaceMembership, WorkspaceRole},
+    },
+    repositories::workspaces::WorkspaceRepository,
+};
+
+use super::{MemoryRepository, Store, UniqueViolation};
+
+#[async_trait::async_trait]
+impl WorkspaceRepository for MemoryRepository {
+    async fn list_workspaces(&self, user_id: Uuid) -> Result<Vec<WorkspaceMembership>> {
+        let store = self.store();
+
+        let mut members: Vec<&WorkspaceMember> = store
+            .members
+            .iter()
+            .filter(|m| m.user_id == user_id)
+            .collect();
+
+        members.sort_by_key(|m| (m.created_at, m.workspace_id));
+
+        Ok(members
+            .into_iter()
+            .filter_map(|member| {
+                store
+                    .workspaces
+                    .iter()
+                    .find(|w| w.workspace_id == member.workspace_id)
+                    .map(|workspace| WorkspaceMembership {
+                        workspace: workspace.clone(),
+                        role: member.role,
+                    })
+            })
+            .collect())
+    }
+
+    async fn list_workspace_ids(&self) -> Result<Vec<Uuid>> {
+        Ok(self
+            .store()
+            .workspaces
+            .iter()
+            .map(|w| w.workspace_id)
+            .collect())
+    }
+
+    async fn create_workspace(&self, workspace: Workspace, owner_id: Uuid) -> Result<Workspace> {
+        let mut store = self.store();
+
+        store.members.push(WorkspaceMember::new(
+            workspace.workspace_id,
+            owner_id,
+            WorkspaceRole::Owner,
+        ));
+        store.workspaces.push(workspace.clone());
+        seed_workspace(&mut store, workspace.workspace_id);
+
+        Ok(workspace)
+    }
+
+    async fn create_personal_workspace(
+        &self,
+        workspace: Workspace,
+        owner_id: Uuid,
+    ) -> Result<Workspace> {
+        let mut store = self.store();
+
+        if let Some(workspace_id) = store.personal_workspaces.get(&owner_id).copied() {
+            return store
+                .workspaces
+                .iter()
+                .find(|w| w.workspace_id == workspace_id)
+                .cloned()
+                .ok_or(sqlx::Error::RowNotFound.into());
+        }
+
+        store
+            .personal_workspaces
+            .insert(owner_id, workspace.workspace_id);
+        store.members.push(WorkspaceMember::new(
+            workspace.workspace_id,
+            owner_id,
+            WorkspaceRole::Owner,
+        ));
+        store.workspaces.push(workspace.clone());
+        seed_workspace(&mut store, workspace.workspace_id);
+
+        Ok(workspace)
+    }
+
+    async fn get_workspace_by_id(&self, workspace_id: Uuid) -> Result<Option<Workspace>> {
+        Ok(self
+            .store()
+            .workspaces
+            .iter()
+            .find(|w| w.workspace_id == workspace_id)
+            .cloned())
+    }
+
+    async fn update_workspace(&self, payload: Workspace) -> Result<Option<Workspace>> {
+        let mut store = self.store();
+
+        let Some(workspace) = store
+            .workspaces
+            .iter_mut()
+            .find(|w| w.workspace_id == payload.workspace_id)
+        else {
+            return Ok(None);
+        };
+
+        workspace.name = payload.name;
+        workspace.updated_at = payload.updated_at;
+
+        Ok(Some(workspace.clone()))
+    }
+
+    async fn assign_owner(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>> {
+        let mut store = self.store();
+
+        let exists = store
+            .workspaces
+            .iter()
+            .any(|w| w.workspace_id == workspace_id);
+        let has_members = store.members.iter().any(|m| m.workspace_id == workspace_id);
+
+        if !exists || has_members {
+            return Ok(None);
+        }
+
+        let member = WorkspaceMember::new(workspace_id, user_id, WorkspaceRole::Owner);
+
+        store.members.push(member.clone());
+
+        Ok(Some(member))
+    }
+
+    async fn get_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>> {
+        Ok(self
+            .store()
+            .members
+            .iter()
+            .find(|m| m.workspace_id == workspace_id && m.user_id == user_id)
+            .cloned())
+    }
+
+    async fn list_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>> {
+        let mut members: Vec<WorkspaceMember> = self
+            .store()
+            .members
+            .iter()
+            .filter(|m| m.workspace_id == workspace_id)
+            .cloned()
+            .collect();
+
+        members.sort_by_key(|m| (m.created_at, m.user_id));
+
+        Ok(members)
+    }
+
+    async fn add_member(&self, member: WorkspaceMember) -> Result<WorkspaceMember> {
+        let mut store = self.store();
+
+        if store
+            .members
+            .iter()
+            .any(|m| m.workspace_id == member.workspace_id && m.user_id == member.user_id)
+        {
+            return Err(UniqueViolation::error("workspace_members_pkey"));
+        }
+
+        store.members.push(member.clone());
+
+        Ok(member)
+    }
+
+    async fn update_member_role(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<Option<WorkspaceMember>> {
+        let mut store = self.store();
+
+        let Some(member) = store
+            .members
+            .iter_mut()
+            .find(|m| m.workspace_id == workspace_id && m.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        member.role = role;
+
+        Ok(Some(member.clone()))
+    }
+
+    async fn remove_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>> {
+        let mut store = self.store();
+
+        let Some(position) = store
+            .members
+            .iter()
+            .position(|m| m.workspace_id == workspace_id && m.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(store.members.remove(position)))
+    }
+}
+
+/// defaults of a new workspace, so it can record transactions right away
+fn seed_workspace(store: &mut Store, workspace_id: Uuid) {
+    for category in Category::defaults() {
+        store.set_owner(workspace_id, category.category_id);
+        store.categories.push(category);
+    }
+
+    for institution in Institution::defaults() {
+        store.set_owner(workspace_id, institution.institution_id);
+        store.institutions.push(institution);
+    }
+}
@@ -1,9 +1,29 @@
 use sqlx::PgPool;
+
+use self::{
+    accounts::AccountRepository, audit::AuditRepository, backups::BackupRepository,
+    categories::CategoryRepository, financial_plans::FinancialPlanRepository,
+    imports::ImportRepository, installments::InstallmentRepository,
+    institutions::InstitutionRepository, recurrences::RecurrenceRepository, rules::RuleRepository,
+    settlements::SettlementRepository, tags::TagRepository, transactions::TransactionRepository,
+    unit_of_work::UnitOfWorkRepository, workspaces::WorkspaceRepository,
+};
+
 pub mod accounts;
+pub mod audit;
+pub mod backups;
+pub mod categories;
+pub mod imports;
 pub mod installments;
+pub mod institutions;
+pub mod memory;
 pub mod recurrences;
+pub mod rules;
 pub mod settlements;
+pub mod tags;
 pub mod transactions;
+pub mod unit_of_work;
+pub mod workspaces;
 pub mod financial_plans;
 
 #[derive(Clone)]
@@ -16,3 +36,48 @@ impl SqlxRepository {
         Self { pool }
     }
 }
+
+/// Storage backing every repository of the handler
+pub trait Repository:
+    TransactionRepository
+    + AccountRepository
+    + InstallmentRepository
+    + SettlementRepository
+    + RecurrenceRepository
+    + FinancialPlanRepository
+    + CategoryRepository
+    + TagRepository
+    + InstitutionRepository
+    + ImportRepository
+    + RuleRepository
+    + BackupRepository
+    + AuditRepository
+    + WorkspaceRepository
+    + UnitOfWorkRepository
+    + Send
+    + Sync
+    + 'static
+{
+}
+
+impl<T> Repository for T where
+    T: TransactionRepository
+        + AccountRepository
+        + InstallmentRepository
+        + SettlementRepository
+        + RecurrenceRepository
+        + FinancialPlanRepository
+        + CategoryRepository
+        + TagRepository
+        + InstitutionRepository
+        + ImportRepository
+        + RuleRepository
+        + BackupRepository
+        + AuditRepository
+        + WorkspaceRepository
+        + UnitOfWorkRepository
+        + Send
+        + Sync
+        + 'static
+{
+}
@@ -6,7 +6,7 @@ use uuid::Uuid;
 use crate::domains::{
     errors::Result,
     recurrences::{CreateRecurrenceLink, Frequency, Recurrence, RecurrenceLink},
-    transactions::{Category, MovementType},
+    transactions::MovementType,
 };
 
 use super::SqlxRepository;
@@ -14,20 +14,34 @@ use super::SqlxRepository;
 #[automock]
 #[async_trait::async_trait]
 pub trait RecurrenceRepository {
-    async fn list_recurrences(&self) -> Result<Vec<Recurrence>>;
-    async fn create_recurrence(&self, payload: Recurrence) -> Result<Recurrence>;
-    async fn get_recurrence_by_id(&self, recurrence_id: Uuid) -> Result<Option<Recurrence>>;
-    async fn update_recurrence(&self, payload: Recurrence) -> Result<Option<Recurrence>>;
+    async fn list_recurrences(&self, workspace_id: Uuid) -> Result<Vec<Recurrence>>;
+    async fn create_recurrence(&self, workspace_id: Uuid, payload: Recurrence)
+        -> Result<Recurrence>;
+    async fn get_recurrence_by_id(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+    ) -> Result<Option<Recurrence>>;
+    async fn update_recurrence(
+        &self,
+        workspace_id: Uuid,
+        payload: Recurrence,
+    ) -> Result<Option<Recurrence>>;
     async fn get_recurrence_link(
         &self,
+        workspace_id: Uuid,
         recurrence_id: Vec<Uuid>,
     ) -> Result<BTreeMap<Uuid, Vec<RecurrenceLink>>>;
-    async fn create_recurrence_link(&self, payload: CreateRecurrenceLink) -> Result<()>;
+    async fn create_recurrence_link(
+        &self,
+        workspace_id: Uuid,
+        payload: CreateRecurrenceLink,
+    ) -> Result<()>;
 }
 
 #[async_trait::async_trait]
 impl RecurrenceRepository for SqlxRepository {
-    async fn list_recurrences(&self) -> Result<Vec<Recurrence>> {
+    async fn list_recurrences(&self, workspace_id: Uuid) -> Result<Vec<Recurrence>> {
         let recurrences = sqlx::query_as!(
             Recurrence,
             r#"
@@ -37,7 +51,7 @@ impl RecurrenceRepository for SqlxRepository {
                 title,
                 frequency as "frequency!: Frequency",
                 is_active,
-                category as "category: Category",
+                category_id,
                 start_date,
                 value,
                 movement_type as "movement_type!: MovementType",
@@ -45,15 +59,21 @@ impl RecurrenceRepository for SqlxRepository {
                 updated_at, 
                 deleted_at
             FROM recurrences
-            "#
+            WHERE workspace_id = $1
+            "#,
+            workspace_id
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.connection().await?)
         .await?;
 
         Ok(recurrences)
     }
 
-    async fn create_recurrence(&self, recurrence: Recurrence) -> Result<Recurrence> {
+    async fn create_recurrence(
+        &self,
+        workspace_id: Uuid,
+        recurrence: Recurrence,
+    ) -> Result<Recurrence> {
         let recurrence = sqlx::query_as!(
             Recurrence,
             r#"
@@ -63,19 +83,20 @@ impl RecurrenceRepository for SqlxRepository {
                 title, 
                 frequency, 
                 is_active, 
-                category, 
+                category_id, 
                 start_date, 
                 value, 
-                movement_type
+                movement_type,
+                workspace_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING 
                 recurrence_id,
                 account_id,
                 title,
                 frequency as "frequency!: Frequency",
                 is_active,
-                category as "category: Category",
+                category_id,
                 start_date,
                 value,
                 movement_type as "movement_type!: MovementType",
@@ -88,18 +109,23 @@ impl RecurrenceRepository for SqlxRepository {
             recurrence.title,
             recurrence.frequency as Frequency,
             recurrence.is_active,
-            recurrence.category as Category,
+            recurrence.category_id,
             recurrence.start_date,
             recurrence.value.normalized(),
-            recurrence.movement_type as MovementType
+            recurrence.movement_type as MovementType,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await?;
 
         Ok(recurrence)
     }
 
-    async fn get_recurrence_by_id(&self, recurrence_id: Uuid) -> Result<Option<Recurrence>> {
+    async fn get_recurrence_by_id(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+    ) -> Result<Option<Recurrence>> {
         let result = sqlx::query_as!(
             Recurrence,
             r#"
@@ -109,7 +135,7 @@ impl RecurrenceRepository for SqlxRepository {
                 title,
                 frequency as "frequency!: Frequency",
                 is_active,
-                category as "category: Category",
+                category_id,
                 start_date,
                 value,
                 movement_type as "movement_type!: MovementType",
@@ -119,17 +145,22 @@ impl RecurrenceRepository for SqlxRepository {
             FROM 
                 recurrences
             WHERE
-                recurrence_id = $1
+                recurrence_id = $1 AND workspace_id = $2
             "#,
-            recurrence_id
+            recurrence_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(result)
     }
 
-    async fn update_recurrence(&self, payload: Recurrence) -> Result<Option<Recurrence>> {
+    async fn update_recurrence(
+        &self,
+        workspace_id: Uuid,
+        payload: Recurrence,
+    ) -> Result<Option<Recurrence>> {
         let result = sqlx::query_as!(
             Recurrence,
             r#"
@@ -140,20 +171,20 @@ impl RecurrenceRepository for SqlxRepository {
                 title = $3,
                 frequency = $4,
                 is_active = $5,
-                category = $6,
+                category_id = $6,
                 start_date = $7,
                 value = $8,
                 movement_type = $9,
                 updated_at = $10
             WHERE
-                recurrence_id = $1
+                recurrence_id = $1 AND workspace_id = $11
             RETURNING
                 recurrence_id,
                 account_id,
                 title,
                 frequency as "frequency!: Frequency",
                 is_active,
-                category as "category: Category",
+                category_id,
                 start_date,
                 value,
                 movement_type as "movement_type!: MovementType",
@@ -166,13 +197,14 @@ impl RecurrenceRepository for SqlxRepository {
             payload.title,
             payload.frequency as Frequency,
             payload.is_active,
-            payload.category as Category,
+            payload.category_id,
             payload.start_date,
             payload.value.normalized(),
             payload.movement_type as MovementType,
-            payload.updated_at
+            payload.updated_at,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(result)
@@ -180,6 +212,7 @@ impl RecurrenceRepository for SqlxRepository {
 
     async fn get_recurrence_link(
         &self,
+        workspace_id: Uuid,
         recurrence_id: Vec<Uuid>,
     ) -> Result<BTreeMap<Uuid, Vec<RecurrenceLink>>> {
         let result = sqlx::query_as!(
@@ -193,11 +226,12 @@ impl RecurrenceRepository for SqlxRepository {
                 transaction_recurrence_links links
             INNER JOIN transactions tr ON links.transaction_id = tr.transaction_id 
             WHERE
-                links.recurrence_id = any($1::uuid[])
+                links.recurrence_id = any($1::uuid[]) AND tr.workspace_id = $2
             "#,
-            &recurrence_id
+            &recurrence_id,
+            workspace_id
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.connection().await?)
         .await?;
 
         let mut links: BTreeMap<Uuid, Vec<RecurrenceLink>> = BTreeMap::new();
@@ -205,22 +239,32 @@ impl RecurrenceRepository for SqlxRepository {
         for r in result {
             links
                 .entry(r.recurrence_id)
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(r)
         }
 
         Ok(links)
     }
 
-    async fn create_recurrence_link(&self, payload: CreateRecurrenceLink) -> Result<()> {
+    async fn create_recurrence_link(
+        &self,
+        workspace_id: Uuid,
+        payload: CreateRecurrenceLink,
+    ) -> Result<()> {
         match sqlx::query!(
             r#"
-            INSERT INTO transaction_recurrence_links(transaction_id, recurrence_id) VALUES ($1, $2) RETURNING *
+            INSERT INTO transaction_recurrence_links(transaction_id, recurrence_id)
+            SELECT tr.transaction_id, rc.recurrence_id
+            FROM transactions tr, recurrences rc
+            WHERE tr.transaction_id = $1 AND rc.recurrence_id = $2
+                AND tr.workspace_id = $3 AND rc.workspace_id = $3
+            RETURNING *
             "#,
             payload.transaction_id,
-            payload.recurrence_id
+            payload.recurrence_id,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await 
         {
             Ok(record) => {
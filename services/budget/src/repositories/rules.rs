@@ -0,0 +1,252 @@
+use mockall::automock;
+use sqlx::Connection;
+use uuid::Uuid;
+
+use crate::domains::{errors::Result, rules::Rule, transactions::MovementType};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait RuleRepository {
+    async fn list_rules(&self, workspace_id: Uuid) -> Result<Vec<Rule>>;
+    async fn create_rule(&self, workspace_id: Uuid, payload: Rule) -> Result<Rule>;
+    async fn get_rule_by_id(&self, workspace_id: Uuid, rule_id: Uuid) -> Result<Option<Rule>>;
+    async fn update_rule(&self, workspace_id: Uuid, payload: Rule) -> Result<Option<Rule>>;
+    async fn delete_rule_by_id(&self, workspace_id: Uuid, rule_id: Uuid)
+        -> Result<Option<Rule>>;
+    /// add applications of the rule to its statistics
+    async fn record_hits(&self, workspace_id: Uuid, rule_id: Uuid, hits: i64) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl RuleRepository for SqlxRepository {
+    async fn list_rules(&self, workspace_id: Uuid) -> Result<Vec<Rule>> {
+        let rules = sqlx::query_as!(
+            Rule,
+            r#"
+            SELECT
+                r.rule_id,
+                r.name,
+                r.pattern,
+                r.movement_type as "movement_type!: MovementType",
+                r.category_id,
+                COALESCE(
+                    array_agg(t.tag_id) FILTER (WHERE t.tag_id IS NOT NULL),
+                    '{}'
+                ) as "tag_ids!",
+                r.priority,
+                r.hits,
+                r.last_hit_at,
+                r.created_at,
+                r.updated_at,
+                r.deleted_at
+            FROM categorization_rules r
+            LEFT JOIN categorization_rule_tags rt ON rt.rule_id = r.rule_id
+            LEFT JOIN tags t ON t.tag_id = rt.tag_id AND t.deleted_at is null
+            WHERE r.workspace_id = $1
+            GROUP BY r.rule_id
+            ORDER BY r.priority, r.created_at
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(rules)
+    }
+
+    async fn create_rule(&self, workspace_id: Uuid, payload: Rule) -> Result<Rule> {
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO categorization_rules (
+                rule_id,
+                name,
+                pattern,
+                movement_type,
+                category_id,
+                priority,
+                workspace_id
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7
+            )
+            "#,
+            payload.rule_id,
+            payload.name,
+            payload.pattern,
+            payload.movement_type as MovementType,
+            payload.category_id,
+            payload.priority,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO categorization_rule_tags (rule_id, tag_id)
+            SELECT $1, tag_id FROM tags
+            WHERE tag_id = ANY($2::UUID[]) AND workspace_id = $3
+            ON CONFLICT DO NOTHING
+            "#,
+            payload.rule_id,
+            &payload.tag_ids,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        // the read below takes the connection again
+        drop(conn);
+
+        self.get_rule_by_id(workspace_id, payload.rule_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound.into())
+    }
+
+    async fn get_rule_by_id(&self, workspace_id: Uuid, rule_id: Uuid) -> Result<Option<Rule>> {
+        let rule = sqlx::query_as!(
+            Rule,
+            r#"
+            SELECT
+                r.rule_id,
+                r.name,
+                r.pattern,
+                r.movement_type as "movement_type!: MovementType",
+                r.category_id,
+                COALESCE(
+                    array_agg(t.tag_id) FILTER (WHERE t.tag_id IS NOT NULL),
+                    '{}'
+                ) as "tag_ids!",
+                r.priority,
+                r.hits,
+                r.last_hit_at,
+                r.created_at,
+                r.updated_at,
+                r.deleted_at
+            FROM categorization_rules r
+            LEFT JOIN categorization_rule_tags rt ON rt.rule_id = r.rule_id
+            LEFT JOIN tags t ON t.tag_id = rt.tag_id AND t.deleted_at is null
+            WHERE r.rule_id = $1 AND r.workspace_id = $2
+            GROUP BY r.rule_id
+            "#,
+            rule_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(rule)
+    }
+
+    async fn update_rule(&self, workspace_id: Uuid, payload: Rule) -> Result<Option<Rule>> {
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin().await?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE categorization_rules SET
+                name = $2,
+                pattern = $3,
+                movement_type = $4,
+                category_id = $5,
+                priority = $6,
+                updated_at = $7
+            WHERE
+                rule_id = $1 AND workspace_id = $8 AND deleted_at is null
+            "#,
+            payload.rule_id,
+            payload.name,
+            payload.pattern,
+            payload.movement_type as MovementType,
+            payload.category_id,
+            payload.priority,
+            payload.updated_at,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM categorization_rule_tags
+            WHERE rule_id = $1
+            "#,
+            payload.rule_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO categorization_rule_tags (rule_id, tag_id)
+            SELECT $1, tag_id FROM tags
+            WHERE tag_id = ANY($2::UUID[]) AND workspace_id = $3
+            ON CONFLICT DO NOTHING
+            "#,
+            payload.rule_id,
+            &payload.tag_ids,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        // the read below takes the connection again
+        drop(conn);
+
+        self.get_rule_by_id(workspace_id, payload.rule_id).await
+    }
+
+    async fn delete_rule_by_id(
+        &self,
+        workspace_id: Uuid,
+        rule_id: Uuid,
+    ) -> Result<Option<Rule>> {
+        let deleted = sqlx::query!(
+            r#"
+            UPDATE categorization_rules SET
+                updated_at = now(),
+                deleted_at = now()
+            WHERE
+                rule_id = $1 AND workspace_id = $2 AND deleted_at is null
+            "#,
+            rule_id,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        if deleted.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        self.get_rule_by_id(workspace_id, rule_id).await
+    }
+
+    async fn record_hits(&self, workspace_id: Uuid, rule_id: Uuid, hits: i64) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE categorization_rules SET
+                hits = hits + $2,
+                last_hit_at = now()
+            WHERE rule_id = $1 AND workspace_id = $3
+            "#,
+            rule_id,
+            hits,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+}
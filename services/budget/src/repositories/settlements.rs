@@ -8,26 +8,45 @@ use super::SqlxRepository;
 #[automock]
 #[async_trait::async_trait]
 pub trait SettlementRepository {
-    async fn create_settlement(&self, payload: Settlement) -> Result<Settlement>;
-    async fn list_settlements(&self) -> Result<Vec<Settlement>>;
+    async fn create_settlement(&self, workspace_id: Uuid, payload: Settlement)
+        -> Result<Settlement>;
+    async fn list_settlements(&self, workspace_id: Uuid) -> Result<Vec<Settlement>>;
 }
 
 #[async_trait::async_trait]
 impl SettlementRepository for SqlxRepository {
-    async fn list_settlements(&self) -> Result<Vec<Settlement>> {
+    async fn list_settlements(&self, workspace_id: Uuid) -> Result<Vec<Settlement>> {
         let settlements = sqlx::query_as!(
             Settlement,
             r#"
-                SELECT * FROM settlements
-            "#
+                SELECT
+                    settlement_id,
+                    transaction_id,
+                    installment_id,
+                    paid_date,
+                    paid_value,
+                    discount,
+                    fees,
+                    attachment,
+                    created_at,
+                    updated_at,
+                    deleted_at
+                FROM settlements
+                WHERE workspace_id = $1
+            "#,
+            workspace_id
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.connection().await?)
         .await?;
 
         Ok(settlements)
     }
 
-    async fn create_settlement(&self, payload: Settlement) -> Result<Settlement> {
+    async fn create_settlement(
+        &self,
+        workspace_id: Uuid,
+        payload: Settlement,
+    ) -> Result<Settlement> {
         let settlement = sqlx::query_as!(
             Settlement,
             r#"
@@ -39,9 +58,10 @@ impl SettlementRepository for SqlxRepository {
                     paid_value,
                     discount,
                     fees,
-                    attachment
+                    attachment,
+                    workspace_id
                 ) VALUES (
-                    $1, $2, $3, $4, $5, $6, $7, $8
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9
                 ) RETURNING 
                 settlement_id,
                     transaction_id, 
@@ -62,9 +82,10 @@ impl SettlementRepository for SqlxRepository {
             payload.paid_value,
             payload.discount,
             payload.fees,
-            payload.attachment
+            payload.attachment,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await?;
 
         Ok(settlement)
@@ -0,0 +1,363 @@
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domains::{
+    errors::Result,
+    tags::{Tag, TransactionTag},
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait TagRepository {
+    async fn list_tags(&self, workspace_id: Uuid) -> Result<Vec<Tag>>;
+    async fn create_tag(&self, workspace_id: Uuid, payload: Tag) -> Result<Tag>;
+    async fn get_tag_by_id(&self, workspace_id: Uuid, tag_id: Uuid) -> Result<Option<Tag>>;
+    async fn update_tag(&self, workspace_id: Uuid, payload: Tag) -> Result<Option<Tag>>;
+    async fn delete_tag_by_id(&self, workspace_id: Uuid, tag_id: Uuid) -> Result<Option<Tag>>;
+    async fn list_transaction_tags(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Vec<Tag>>;
+    async fn list_transaction_tag_links(&self, workspace_id: Uuid)
+        -> Result<Vec<TransactionTag>>;
+    async fn attach_transaction_tag(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()>;
+    async fn detach_transaction_tag(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()>;
+    async fn list_recurrence_tags(&self, workspace_id: Uuid, recurrence_id: Uuid)
+        -> Result<Vec<Tag>>;
+    async fn attach_recurrence_tag(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()>;
+    async fn detach_recurrence_tag(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl TagRepository for SqlxRepository {
+    async fn list_tags(&self, workspace_id: Uuid) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT
+                tag_id,
+                name,
+                color,
+                created_at,
+                updated_at,
+                deleted_at
+            FROM tags
+            WHERE workspace_id = $1
+            ORDER BY name
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tags)
+    }
+
+    async fn create_tag(&self, workspace_id: Uuid, payload: Tag) -> Result<Tag> {
+        let tag = sqlx::query_as!(
+            Tag,
+            r#"
+            INSERT INTO tags (
+                tag_id,
+                name,
+                color,
+                workspace_id
+            ) VALUES (
+                $1, $2, $3, $4
+            ) RETURNING
+                tag_id,
+                name,
+                color,
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            payload.tag_id,
+            payload.name,
+            payload.color,
+            workspace_id
+        )
+        .fetch_one(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tag)
+    }
+
+    async fn get_tag_by_id(&self, workspace_id: Uuid, tag_id: Uuid) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT
+                tag_id,
+                name,
+                color,
+                created_at,
+                updated_at,
+                deleted_at
+            FROM tags
+            WHERE tag_id = $1 AND workspace_id = $2
+            "#,
+            tag_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tag)
+    }
+
+    async fn update_tag(&self, workspace_id: Uuid, payload: Tag) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as!(
+            Tag,
+            r#"
+            UPDATE tags SET
+                name = $2,
+                color = $3,
+                updated_at = $4
+            WHERE
+                tag_id = $1 AND workspace_id = $5 AND deleted_at is null
+            RETURNING
+                tag_id,
+                name,
+                color,
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            payload.tag_id,
+            payload.name,
+            payload.color,
+            payload.updated_at,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tag)
+    }
+
+    async fn delete_tag_by_id(&self, workspace_id: Uuid, tag_id: Uuid) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as!(
+            Tag,
+            r#"
+            UPDATE tags SET
+                updated_at = now(),
+                deleted_at = now()
+            WHERE
+                tag_id = $1 AND workspace_id = $2 AND deleted_at is null
+            RETURNING
+                tag_id,
+                name,
+                color,
+                created_at,
+                updated_at,
+                deleted_at
+            "#,
+            tag_id,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tag)
+    }
+
+    async fn list_transaction_tags(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT
+                t.tag_id,
+                t.name,
+                t.color,
+                t.created_at,
+                t.updated_at,
+                t.deleted_at
+            FROM tags t
+            JOIN transaction_tags tt ON tt.tag_id = t.tag_id
+            WHERE tt.transaction_id = $1 AND t.workspace_id = $2 AND t.deleted_at is null
+            ORDER BY t.name
+            "#,
+            transaction_id,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tags)
+    }
+
+    async fn list_transaction_tag_links(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<Vec<TransactionTag>> {
+        let links = sqlx::query_as!(
+            TransactionTag,
+            r#"
+            SELECT
+                tt.transaction_id,
+                tt.tag_id
+            FROM transaction_tags tt
+            JOIN tags t ON t.tag_id = tt.tag_id
+            WHERE t.workspace_id = $1 AND t.deleted_at is null
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(links)
+    }
+
+    async fn attach_transaction_tag(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO transaction_tags (transaction_id, tag_id)
+            SELECT tr.transaction_id, t.tag_id
+            FROM transactions tr, tags t
+            WHERE tr.transaction_id = $1 AND t.tag_id = $2
+                AND tr.workspace_id = $3 AND t.workspace_id = $3
+            ON CONFLICT DO NOTHING
+            "#,
+            transaction_id,
+            tag_id,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn detach_transaction_tag(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM transaction_tags tt
+            USING tags t
+            WHERE tt.transaction_id = $1 AND tt.tag_id = $2
+                AND t.tag_id = tt.tag_id AND t.workspace_id = $3
+            "#,
+            transaction_id,
+            tag_id,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_recurrence_tags(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT
+                t.tag_id,
+                t.name,
+                t.color,
+                t.created_at,
+                t.updated_at,
+                t.deleted_at
+            FROM tags t
+            JOIN recurrence_tags rt ON rt.tag_id = t.tag_id
+            WHERE rt.recurrence_id = $1 AND t.workspace_id = $2 AND t.deleted_at is null
+            ORDER BY t.name
+            "#,
+            recurrence_id,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(tags)
+    }
+
+    async fn attach_recurrence_tag(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO recurrence_tags (recurrence_id, tag_id)
+            SELECT rc.recurrence_id, t.tag_id
+            FROM recurrences rc, tags t
+            WHERE rc.recurrence_id = $1 AND t.tag_id = $2
+                AND rc.workspace_id = $3 AND t.workspace_id = $3
+            ON CONFLICT DO NOTHING
+            "#,
+            recurrence_id,
+            tag_id,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn detach_recurrence_tag(
+        &self,
+        workspace_id: Uuid,
+        recurrence_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM recurrence_tags rt
+            USING tags t
+            WHERE rt.recurrence_id = $1 AND rt.tag_id = $2
+                AND t.tag_id = rt.tag_id AND t.workspace_id = $3
+            "#,
+            recurrence_id,
+            tag_id,
+            workspace_id
+        )
+        .execute(&mut *self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+}
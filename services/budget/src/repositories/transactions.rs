@@ -1,6 +1,7 @@
 use crate::domains::{
     errors::Result,
-    transactions::{Category, MovementType, Transaction, TransactionStatus},
+    reports::ReportFilter,
+    transactions::{MovementType, Transaction, TransactionStatus},
 };
 
 use mockall::automock;
@@ -11,25 +12,50 @@ use uuid::Uuid;
 #[automock]
 #[async_trait::async_trait]
 pub trait TransactionRepository {
-    async fn create_transaction(&self, transaction: Transaction) -> Result<Transaction>;
-    async fn list_transactions(&self) -> Result<Vec<Transaction>>;
-    async fn get_transaction_by_id(&self, transaction_id: Uuid) -> Result<Option<Transaction>>;
-    async fn delete_transaction_by_id(&self, transaction_id: Uuid) -> Result<Option<Transaction>>;
+    async fn create_transaction(
+        &self,
+        workspace_id: Uuid,
+        transaction: Transaction,
+    ) -> Result<Transaction>;
+    async fn list_transactions(&self, workspace_id: Uuid) -> Result<Vec<Transaction>>;
+    /// the transactions counted by a report
+    async fn list_report_transactions(
+        &self,
+        workspace_id: Uuid,
+        filter: ReportFilter,
+    ) -> Result<Vec<Transaction>>;
+    async fn get_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Option<Transaction>>;
+    async fn delete_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Option<Transaction>>;
     async fn update_transaction_by_id(
         &self,
+        workspace_id: Uuid,
         transaction: Transaction,
     ) -> Result<Option<Transaction>>;
     async fn update_status(
         &self,
+        workspace_id: Uuid,
         transaction_id: Uuid,
         status: TransactionStatus,
     ) -> Result<Option<Transaction>>;
-    async fn bulk_transactions_into_financial_plan(&self, payload: Vec<Transaction>);
+    #[allow(dead_code)]
+    async fn bulk_transactions_into_financial_plan(
+        &self,
+        workspace_id: Uuid,
+        payload: Vec<Transaction>,
+    );
 }
 
 #[async_trait::async_trait]
 impl TransactionRepository for SqlxRepository {
-    async fn list_transactions(&self) -> Result<Vec<Transaction>> {
+    async fn list_transactions(&self, workspace_id: Uuid) -> Result<Vec<Transaction>> {
         let transactions = sqlx::query_as!(
             Transaction,
             r#"
@@ -40,22 +66,71 @@ impl TransactionRepository for SqlxRepository {
                 description, 
                 value, 
                 due_date, 
-                category as "category: Category", 
+                category_id, 
                 account_id, 
                 status as "status: TransactionStatus", 
                 created_at, 
                 updated_at, 
                 deleted_at
             FROM TRANSACTIONS
-            "#
+            WHERE workspace_id = $1
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    async fn list_report_transactions(
+        &self,
+        workspace_id: Uuid,
+        filter: ReportFilter,
+    ) -> Result<Vec<Transaction>> {
+        let transactions = sqlx::query_as!(
+            Transaction,
+            r#"
+            SELECT
+                transaction_id,
+                financial_plan_id,
+                movement_type as "movement_type!: MovementType",
+                description,
+                value,
+                due_date,
+                category_id,
+                account_id,
+                status as "status: TransactionStatus",
+                created_at,
+                updated_at,
+                deleted_at
+            FROM TRANSACTIONS
+            WHERE
+                workspace_id = $1
+                AND deleted_at is null
+                AND status != 'CANCELED'
+                AND ($2::uuid is null OR financial_plan_id = $2)
+                AND ($3::date is null OR due_date >= $3)
+                AND ($4::date is null OR due_date <= $4)
+                AND ($5::uuid[] is null OR category_id = any($5))
+            "#,
+            workspace_id,
+            filter.financial_plan_id,
+            filter.from,
+            filter.to,
+            filter.category_ids.as_deref()
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.connection().await?)
         .await?;
 
         Ok(transactions)
     }
 
-    async fn create_transaction(&self, transaction: Transaction) -> Result<Transaction> {
+    async fn create_transaction(
+        &self,
+        workspace_id: Uuid,
+        transaction: Transaction,
+    ) -> Result<Transaction> {
         let transaction = sqlx::query_as!(
             Transaction,
             r#"
@@ -66,11 +141,12 @@ impl TransactionRepository for SqlxRepository {
                 description,
                 value,
                 due_date,
-                category,
+                category_id,
                 account_id,
-                status
+                status,
+                workspace_id
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
             ) RETURNING 
                 transaction_id, 
                 financial_plan_id,
@@ -78,7 +154,7 @@ impl TransactionRepository for SqlxRepository {
                 description, 
                 value, 
                 due_date, 
-                category as "category: Category", 
+                category_id, 
                 account_id, 
                 status as "status: TransactionStatus", 
                 created_at, 
@@ -91,17 +167,22 @@ impl TransactionRepository for SqlxRepository {
             transaction.description,
             transaction.value.normalized(),
             transaction.due_date,
-            transaction.category as Category,
+            transaction.category_id,
             transaction.account_id,
-            transaction.status as TransactionStatus
+            transaction.status as TransactionStatus,
+            workspace_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.connection().await?)
         .await?;
 
         Ok(transaction)
     }
 
-    async fn get_transaction_by_id(&self, transaction_id: Uuid) -> Result<Option<Transaction>> {
+    async fn get_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Option<Transaction>> {
         let transaction = sqlx::query_as!(
             Transaction,
             r#"
@@ -112,24 +193,29 @@ impl TransactionRepository for SqlxRepository {
                 description, 
                 value, 
                 due_date, 
-                category as "category: Category", 
+                category_id, 
                 account_id, 
                 status as "status: TransactionStatus", 
                 created_at, 
                 updated_at, 
                 deleted_at
             FROM TRANSACTIONS
-            WHERE transaction_id = $1
+            WHERE transaction_id = $1 AND workspace_id = $2
             "#,
-            transaction_id
+            transaction_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(transaction)
     }
 
-    async fn delete_transaction_by_id(&self, transaction_id: Uuid) -> Result<Option<Transaction>> {
+    async fn delete_transaction_by_id(
+        &self,
+        workspace_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Option<Transaction>> {
         let transaction = sqlx::query_as!(
             Transaction,
             r#"
@@ -138,6 +224,7 @@ impl TransactionRepository for SqlxRepository {
                 deleted_at = now()
             WHERE
                 transaction_id = $1
+                AND workspace_id = $2
                 AND deleted_at is null
             RETURNING 
                 transaction_id, 
@@ -146,16 +233,17 @@ impl TransactionRepository for SqlxRepository {
                 description, 
                 value, 
                 due_date, 
-                category as "category: Category", 
+                category_id, 
                 account_id, 
                 status as "status: TransactionStatus", 
                 created_at, 
                 updated_at, 
                 deleted_at
             "#,
-            transaction_id
+            transaction_id,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(transaction)
@@ -163,6 +251,7 @@ impl TransactionRepository for SqlxRepository {
 
     async fn update_transaction_by_id(
         &self,
+        workspace_id: Uuid,
         transaction: Transaction,
     ) -> Result<Option<Transaction>> {
         let transaction = sqlx::query_as!(
@@ -173,11 +262,12 @@ impl TransactionRepository for SqlxRepository {
                 description = $3,
                 value = $4,
                 due_date = $5,
-                category = $6,
+                category_id = $6,
                 account_id = $7,
-                updated_at = $8
+                updated_at = $8,
+                financial_plan_id = $10
             WHERE 
-                transaction_id = $1
+                transaction_id = $1 AND workspace_id = $9
             RETURNING
                 transaction_id, 
                 financial_plan_id,
@@ -185,7 +275,7 @@ impl TransactionRepository for SqlxRepository {
                 description, 
                 value, 
                 due_date, 
-                category as "category: Category", 
+                category_id, 
                 account_id, 
                 status as "status: TransactionStatus", 
                 created_at, 
@@ -197,11 +287,13 @@ impl TransactionRepository for SqlxRepository {
             transaction.description,
             transaction.value.normalized(),
             transaction.due_date,
-            transaction.category as Category,
+            transaction.category_id,
             transaction.account_id,
-            transaction.updated_at
+            transaction.updated_at,
+            workspace_id,
+            transaction.financial_plan_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(transaction)
@@ -209,13 +301,14 @@ impl TransactionRepository for SqlxRepository {
 
     async fn update_status(
         &self,
+        workspace_id: Uuid,
         transaction_id: Uuid,
         status: TransactionStatus,
     ) -> Result<Option<Transaction>> {
         let transaction = sqlx::query_as!(
             Transaction,
             r#"
-            UPDATE transactions SET status = $2 WHERE transaction_id = $1
+            UPDATE transactions SET status = $2 WHERE transaction_id = $1 AND workspace_id = $3
             RETURNING
                 transaction_id, 
                 financial_plan_id,
@@ -223,7 +316,7 @@ impl TransactionRepository for SqlxRepository {
                 description, 
                 value, 
                 due_date, 
-                category as "category: Category", 
+                category_id, 
                 account_id, 
                 status as "status: TransactionStatus", 
                 created_at, 
@@ -231,15 +324,20 @@ impl TransactionRepository for SqlxRepository {
                 deleted_at
             "#,
             transaction_id,
-            status as TransactionStatus
+            status as TransactionStatus,
+            workspace_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.connection().await?)
         .await?;
 
         Ok(transaction)
     }
 
-    async fn bulk_transactions_into_financial_plan(&self, payload: Vec<Transaction>) {
+    async fn bulk_transactions_into_financial_plan(
+        &self,
+        _workspace_id: Uuid,
+        _payload: Vec<Transaction>,
+    ) {
         todo!()
     }
 }
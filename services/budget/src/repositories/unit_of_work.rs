@@ -0,0 +1,99 @@
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use mockall::automock;
+use sqlx::{pool::PoolConnection, PgConnection, Postgres, Transaction};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::domains::errors::Result;
+
+use super::SqlxRepository;
+
+tokio::task_local! {
+    /// set while a unit of work runs, the repositories run their queries in it
+    static UNIT_OF_WORK: UnitOfWork;
+}
+
+/// Database transaction shared by every repository call of a unit of work,
+/// dropping it without a commit rolls every change back
+#[derive(Clone)]
+pub struct UnitOfWork(Arc<Mutex<Option<Transaction<'static, Postgres>>>>);
+
+impl UnitOfWork {
+    /// whether the caller already runs inside a unit of work
+    pub fn in_scope() -> bool {
+        UNIT_OF_WORK.try_with(|_| ()).is_ok()
+    }
+
+    /// run the future with the repository calls inside this unit of work
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        UNIT_OF_WORK.scope(self.clone(), future).await
+    }
+
+    pub async fn commit(self) -> Result<()> {
+        if let Some(transaction) = self.0.lock().await.take() {
+            transaction.commit().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[automock]
+#[async_trait::async_trait]
+pub trait UnitOfWorkRepository {
+    /// None when the storage applies every change on its own
+    async fn begin(&self) -> Result<Option<UnitOfWork>>;
+}
+
+#[async_trait::async_trait]
+impl UnitOfWorkRepository for SqlxRepository {
+    async fn begin(&self) -> Result<Option<UnitOfWork>> {
+        let transaction = self.pool.begin().await?;
+
+        Ok(Some(UnitOfWork(Arc::new(Mutex::new(Some(transaction))))))
+    }
+}
+
+/// Connection the queries of a repository call run on
+pub enum Connection {
+    Pool(Box<PoolConnection<Postgres>>),
+    UnitOfWork(OwnedMutexGuard<Option<Transaction<'static, Postgres>>>),
+}
+
+impl SqlxRepository {
+    /// the transaction of the unit of work in scope, a connection of the pool otherwise
+    pub(super) async fn connection(&self) -> Result<Connection> {
+        match UNIT_OF_WORK.try_with(|work| work.0.clone()) {
+            Ok(transaction) => Ok(Connection::UnitOfWork(transaction.lock_owned().await)),
+            Err(_) => Ok(Connection::Pool(Box::new(self.pool.acquire().await?))),
+        }
+    }
+}
+
+impl Deref for Connection {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Self::Pool(connection) => connection,
+            Self::UnitOfWork(transaction) => transaction
+                .as_ref()
+                .expect("unit of work used after its commit"),
+        }
+    }
+}
+
+impl DerefMut for Connection {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Self::Pool(connection) => connection,
+            Self::UnitOfWork(transaction) => transaction
+                .as_mut()
+                .expect("unit of work used after its commit"),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use mockall::automock;
+use sqlx::{Connection, PgConnection};
+use uuid::Uuid;
+
+use crate::domains::{
+    categories::{Category, CategoryKind},
+    errors::Result,
+    institutions::Institution,
+    workspaces::{Workspace, WorkspaceMember, WorkspaceMembership, WorkspaceRole},
+};
+
+use super::SqlxRepository;
+
+#[automock]
+#[async_trait::async_trait]
+pub trait WorkspaceRepository {
+    /// workspaces the user is a member of, the oldest membership first
+    async fn list_workspaces(&self, user_id: Uuid) -> Result<Vec<WorkspaceMembership>>;
+    async fn list_workspace_ids(&self) -> Result<Vec<Uuid>>;
+    /// create the workspace with the user as its owner and the default categories and
+    /// institutions
+    async fn create_workspace(&self, workspace: Workspace, owner_id: Uuid) -> Result<Workspace>;
+    /// personal workspace of the user, created with them as its owner unless it already exists
+    async fn create_personal_workspace(
+        &self,
+        workspace: Workspace,
+        owner_id: Uuid,
+    ) -> Result<Workspace>;
+    async fn get_workspace_by_id(&self, workspace_id: Uuid) -> Result<Option<Workspace>>;
+    async fn update_workspace(&self, workspace: Workspace) -> Result<Option<Workspace>>;
+    /// make the user owner of the workspace, only while it has no members
+    async fn assign_owner(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>>;
+    async fn get_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>>;
+    async fn list_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>>;
+    async fn add_member(&self, member: WorkspaceMember) -> Result<WorkspaceMember>;
+    async fn update_member_role(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<Option<WorkspaceMember>>;
+    async fn remove_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>>;
+}
+
+#[async_trait::async_trait]
+impl WorkspaceRepository for SqlxRepository {
+    async fn list_workspaces(&self, user_id: Uuid) -> Result<Vec<WorkspaceMembership>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                w.workspace_id,
+                w.name,
+                w.created_at,
+                w.updated_at,
+                m.role as "role!: WorkspaceRole"
+            FROM workspaces w
+            JOIN workspace_members m ON m.workspace_id = w.workspace_id
+            WHERE m.user_id = $1
+            ORDER BY m.created_at, w.workspace_id
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| WorkspaceMembership {
+                workspace: Workspace {
+                    workspace_id: row.workspace_id,
+                    name: row.name,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                },
+                role: row.role,
+            })
+            .collect())
+    }
+
+    async fn list_workspace_ids(&self) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar!("SELECT workspace_id FROM workspaces")
+            .fetch_all(&mut *self.connection().await?)
+            .await?;
+
+        Ok(ids)
+    }
+
+    async fn create_workspace(&self, workspace: Workspace, owner_id: Uuid) -> Result<Workspace> {
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin().await?;
+
+        let workspace = sqlx::query_as!(
+            Workspace,
+            r#"
+            INSERT INTO workspaces (workspace_id, name, created_at)
+            VALUES ($1, $2, $3)
+            RETURNING workspace_id, name, created_at, updated_at
+            "#,
+            workspace.workspace_id,
+            workspace.name,
+            workspace.created_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO workspace_members (workspace_id, user_id, role)
+            VALUES ($1, $2, 'OWNER')
+            "#,
+            workspace.workspace_id,
+            owner_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        seed_workspace(&mut tx, workspace.workspace_id).await?;
+
+        tx.commit().await?;
+
+        Ok(workspace)
+    }
+
+    async fn create_personal_workspace(
+        &self,
+        workspace: Workspace,
+        owner_id: Uuid,
+    ) -> Result<Workspace> {
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin().await?;
+
+        // a concurrent call waits for the other insert and then leaves its workspace as is
+        let created = sqlx::query!(
+            r#"
+            INSERT INTO workspaces (workspace_id, name, created_at, personal_owner_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (personal_owner_id) DO NOTHING
+            "#,
+            workspace.workspace_id,
+            workspace.name,
+            workspace.created_at,
+            owner_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if created.rows_affected() > 0 {
+            sqlx::query!(
+                r#"
+                INSERT INTO workspace_members (workspace_id, user_id, role)
+                VALUES ($1, $2, 'OWNER')
+                "#,
+                workspace.workspace_id,
+                owner_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            seed_workspace(&mut tx, workspace.workspace_id).await?;
+        }
+
+        let workspace = sqlx::query_as!(
+            Workspace,
+            r#"
+            SELECT workspace_id, name, created_at, updated_at
+            FROM workspaces
+            WHERE personal_owner_id = $1
+            "#,
+            owner_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(workspace)
+    }
+
+    async fn get_workspace_by_id(&self, workspace_id: Uuid) -> Result<Option<Workspace>> {
+        let workspace = sqlx::query_as!(
+            Workspace,
+            r#"
+            SELECT workspace_id, name, created_at, updated_at
+            FROM workspaces
+            WHERE workspace_id = $1
+            "#,
+            workspace_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(workspace)
+    }
+
+    async fn update_workspace(&self, workspace: Workspace) -> Result<Option<Workspace>> {
+        let workspace = sqlx::query_as!(
+            Workspace,
+            r#"
+            UPDATE workspaces SET
+                name = $2,
+                updated_at = $3
+            WHERE workspace_id = $1
+            RETURNING workspace_id, name, created_at, updated_at
+            "#,
+            workspace.workspace_id,
+            workspace.name,
+            workspace.updated_at
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(workspace)
+    }
+
+    async fn assign_owner(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>> {
+        // the row lock keeps two instances starting together from both assigning an owner
+        let member = sqlx::query_as!(
+            WorkspaceMember,
+            r#"
+            WITH unowned AS (
+                SELECT w.workspace_id
+                FROM workspaces w
+                WHERE w.workspace_id = $1 AND NOT EXISTS (
+                    SELECT 1 FROM workspace_members m WHERE m.workspace_id = w.workspace_id
+                )
+                FOR UPDATE
+            )
+            INSERT INTO workspace_members (workspace_id, user_id, role)
+            SELECT workspace_id, $2, 'OWNER' FROM unowned
+            ON CONFLICT DO NOTHING
+            RETURNING workspace_id, user_id, role as "role!: WorkspaceRole", created_at
+            "#,
+            workspace_id,
+            user_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(member)
+    }
+
+    async fn get_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>> {
+        let member = sqlx::query_as!(
+            WorkspaceMember,
+            r#"
+            SELECT workspace_id, user_id, role as "role!: WorkspaceRole", created_at
+            FROM workspace_members
+            WHERE workspace_id = $1 AND user_id = $2
+            "#,
+            workspace_id,
+            user_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(member)
+    }
+
+    async fn list_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>> {
+        let members = sqlx::query_as!(
+            WorkspaceMember,
+            r#"
+            SELECT workspace_id, user_id, role as "role!: WorkspaceRole", created_at
+            FROM workspace_members
+            WHERE workspace_id = $1
+            ORDER BY created_at, user_id
+            "#,
+            workspace_id
+        )
+        .fetch_all(&mut *self.connection().await?)
+        .await?;
+
+        Ok(members)
+    }
+
+    async fn add_member(&self, member: WorkspaceMember) -> Result<WorkspaceMember> {
+        let member = sqlx::query_as!(
+            WorkspaceMember,
+            r#"
+            INSERT INTO workspace_members (workspace_id, user_id, role, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING workspace_id, user_id, role as "role!: WorkspaceRole", created_at
+            "#,
+            member.workspace_id,
+            member.user_id,
+            member.role as WorkspaceRole,
+            member.created_at
+        )
+        .fetch_one(&mut *self.connection().await?)
+        .await?;
+
+        Ok(member)
+    }
+
+    async fn update_member_role(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<Option<WorkspaceMember>> {
+        let member = sqlx::query_as!(
+            WorkspaceMember,
+            r#"
+            UPDATE workspace_members SET role = $3
+            WHERE workspace_id = $1 AND user_id = $2
+            RETURNING workspace_id, user_id, role as "role!: WorkspaceRole", created_at
+            "#,
+            workspace_id,
+            user_id,
+            role as WorkspaceRole
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(member)
+    }
+
+    async fn remove_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>> {
+        let member = sqlx::query_as!(
+            WorkspaceMember,
+            r#"
+            DELETE FROM workspace_members
+            WHERE workspace_id = $1 AND user_id = $2
+            RETURNING workspace_id, user_id, role as "role!: WorkspaceRole", created_at
+            "#,
+            workspace_id,
+            user_id
+        )
+        .fetch_optional(&mut *self.connection().await?)
+        .await?;
+
+        Ok(member)
+    }
+}
+
+/// defaults of a new workspace, so it can record transactions right away
+async fn seed_workspace(conn: &mut PgConnection, workspace_id: Uuid) -> Result<()> {
+    for category in Category::defaults() {
+        sqlx::query!(
+            r#"
+            INSERT INTO categories (category_id, name, kind, workspace_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            category.category_id,
+            category.name,
+            category.kind as CategoryKind,
+            workspace_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for institution in Institution::defaults() {
+        sqlx::query!(
+            r#"
+            INSERT INTO institutions (institution_id, name, code, workspace_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            institution.institution_id,
+            institution.name,
+            institution.code,
+            workspace_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
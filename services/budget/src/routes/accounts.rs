@@ -8,7 +8,7 @@ use uuid::Uuid;
 
 use crate::{
     domains::{
-        accounts::{CreateAccount, UpdateAccount},
+        accounts::{CreateAccount, GrantAccount, UpdateAccount},
         errors::Result,
     },
     handlers::Handler,
@@ -22,7 +22,10 @@ pub(super) fn configure_routes() -> Router<Handler> {
             .route("/", post(create_account))
             .route("/:account_id", get(get_account_by_id))
             .route("/:account_id", delete(delete_account_by_id))
-            .route("/:account_id", patch(update_account_by_id)),
+            .route("/:account_id", patch(update_account_by_id))
+            .route("/:account_id/grants", get(list_account_grants))
+            .route("/:account_id/grants", post(grant_account))
+            .route("/:account_id/grants/:user_id", delete(revoke_account_grant)),
     )
 }
 
@@ -68,3 +71,145 @@ async fn update_account_by_id(
 
     Ok(Json(account))
 }
+
+async fn list_account_grants(
+    State(handler): State<Handler>,
+    Path(account_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let grants = handler.list_account_grants(account_id).await?;
+
+    Ok(Json(grants))
+}
+
+async fn grant_account(
+    State(handler): State<Handler>,
+    Path(account_id): Path<Uuid>,
+    Json(payload): Json<GrantAccount>,
+) -> Result<impl IntoResponse> {
+    let grant = handler.grant_account(account_id, payload).await?;
+
+    Ok(Json(grant))
+}
+
+async fn revoke_account_grant(
+    State(handler): State<Handler>,
+    Path((account_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let grant = handler.revoke_account_grant(account_id, user_id).await?;
+
+    Ok(Json(grant))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::routes::test_app::TestApp;
+
+    #[tokio::test]
+    async fn should_update_and_soft_delete_account() {
+        let app = TestApp::new();
+        let account_id = app.new_account().await;
+        let uri = format!("/accounts/{account_id}");
+
+        let response = app.patch(&uri, json!({ "owner": "new owner" })).await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body["owner"], "new owner");
+
+        let response = app.delete(&uri).await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(response.body["deletedAt"].is_string());
+
+        let response = app.delete(&uri).await;
+
+        assert_eq!(response.status, StatusCode::CONFLICT);
+        assert_eq!(response.code(), "ACCOUNT_ALREADY_DELETED");
+
+        let response = app.get(&uri).await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body["owner"], "new owner");
+    }
+
+    #[tokio::test]
+    async fn should_restrict_accounts_to_their_grants() {
+        let app = TestApp::new();
+        let shared_id = app.new_account().await;
+        let private_id = app.new_account().await;
+        let financial_plan_id = app.new_financial_plan().await;
+        let category_id = app.new_category("EXPENSE").await;
+
+        let workspaces = app.get("/workspaces").await;
+        let workspace_id = workspaces.body[0]["workspaceId"].as_str().unwrap();
+        let partner = app
+            .as_user(Uuid::new_v4())
+            .in_workspace(workspace_id.parse().unwrap());
+
+        let response = app
+            .post(
+                &format!("/workspaces/{workspace_id}/members"),
+                json!({ "userId": partner.user_id(), "role": "EDITOR" }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let grants_uri = format!("/accounts/{private_id}/grants");
+        let response = app
+            .post(
+                &grants_uri,
+                json!({ "userId": app.user_id(), "permission": "MANAGE" }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let accounts = partner.get("/accounts").await;
+        assert_eq!(accounts.body.as_array().unwrap().len(), 1);
+        assert_eq!(accounts.body[0]["accountId"], shared_id.to_string());
+
+        let response = partner.get(&format!("/accounts/{private_id}")).await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+
+        let response = app
+            .post(
+                &grants_uri,
+                json!({ "userId": partner.user_id(), "permission": "VIEW" }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let response = partner.get(&format!("/accounts/{private_id}")).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let response = partner
+            .post(
+                "/transactions",
+                json!({
+                    "financialPlanId": financial_plan_id,
+                    "movementType": "EXPENSE",
+                    "description": "groceries",
+                    "value": "50",
+                    "dueDate": "2024-05-10",
+                    "categoryId": category_id,
+                    "accountId": private_id,
+                    "installments": 1,
+                }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::FORBIDDEN);
+        assert_eq!(response.code(), "ACCOUNT_PERMISSION_DENIED");
+
+        let response = app.delete(&format!("{grants_uri}/{}", app.user_id())).await;
+        assert_eq!(response.status, StatusCode::CONFLICT);
+        assert_eq!(response.code(), "LAST_ACCOUNT_MANAGER");
+
+        let response = app
+            .delete(&format!("{grants_uri}/{}", partner.user_id()))
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(app.get(&grants_uri).await.body.as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+
+use crate::{
+    domains::{audit::AuditParams, errors::Result},
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().route("/audit", get(list_audit))
+}
+
+async fn list_audit(
+    State(handler): State<Handler>,
+    Query(params): Query<AuditParams>,
+) -> Result<impl IntoResponse> {
+    let entries = handler.list_audit(params).await?;
+
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::routes::test_app::TestApp;
+
+    #[tokio::test]
+    async fn should_return_the_history_of_a_record() {
+        let app = TestApp::new();
+
+        let account_id = app.new_account().await;
+        let category_id = app.new_category("EXPENSE").await;
+        let financial_plan_id = app.new_financial_plan().await;
+
+        let transaction_id = app
+            .post(
+                "/transactions",
+                json!({
+                    "financialPlanId": financial_plan_id,
+                    "movementType": "EXPENSE",
+                    "description": "rent",
+                    "value": "1000",
+                    "dueDate": "2024-05-10",
+                    "categoryId": category_id,
+                    "accountId": account_id,
+                    "installments": 0,
+                }),
+            )
+            .await
+            .created("transactionId");
+
+        let uri = format!("/transactions/{transaction_id}");
+        app.patch(&uri, json!({ "value": "1200" })).await;
+        // nothing changes, so nothing is recorded
+        app.patch(&uri, json!({ "value": "1200" })).await;
+        app.delete(&uri).await;
+
+        let response = app
+            .get(&format!("/audit?entity=TRANSACTION&id={transaction_id}"))
+            .await;
+
+        assert_eq!(response.status, StatusCode::OK);
+
+        let entries = response.body.as_array().unwrap();
+        let actions: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry["action"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(actions, ["CREATE", "UPDATE", "DELETE"]);
+        assert_eq!(
+            entries[1]["changes"],
+            json!({ "value": { "before": "1000", "after": "1200" } })
+        );
+        assert_eq!(entries[1]["requestId"], "test-request");
+
+        let response = app.get("/audit?entity=UNKNOWN&id=1").await;
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn should_hide_the_history_of_private_accounts() {
+        let app = TestApp::new();
+        let account_id = app.new_account().await;
+        let category_id = app.new_category("EXPENSE").await;
+        let financial_plan_id = app.new_financial_plan().await;
+
+        let workspaces = app.get("/workspaces").await;
+        let workspace_id = workspaces.body[0]["workspaceId"].as_str().unwrap();
+        let partner = app
+            .as_user(Uuid::new_v4())
+            .in_workspace(workspace_id.parse().unwrap());
+
+        app.post(
+            &format!("/workspaces/{workspace_id}/members"),
+            json!({ "userId": partner.user_id(), "role": "EDITOR" }),
+        )
+        .await;
+        app.post(
+            &format!("/accounts/{account_id}/grants"),
+            json!({ "userId": app.user_id(), "permission": "MANAGE" }),
+        )
+        .await;
+
+        let transaction_id = app
+            .post(
+                "/transactions",
+                json!({
+                    "financialPlanId": financial_plan_id,
+                    "movementType": "EXPENSE",
+                    "description": "doctor",
+                    "value": "300",
+                    "dueDate": "2024-05-10",
+                    "categoryId": category_id,
+                    "accountId": account_id,
+                    "installments": 0,
+                }),
+            )
+            .await
+            .created("transactionId");
+
+        let transaction_uri = format!("/audit?entity=TRANSACTION&id={transaction_id}");
+        let account_uri = format!("/audit?entity=ACCOUNT&id={account_id}");
+
+        for uri in [&transaction_uri, &account_uri] {
+            assert_eq!(app.get(uri).await.status, StatusCode::OK);
+            assert_eq!(partner.get(uri).await.status, StatusCode::NOT_FOUND);
+        }
+    }
+}
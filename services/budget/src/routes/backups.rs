@@ -0,0 +1,188 @@
+use auth_layer::CurrentUser;
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, Query, Request, State},
+    http::header,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+
+use crate::{
+    domains::{
+        backups::{BackupLine, Operators, RestoreParams, BACKUP_BUFFER},
+        errors::{Error, Result},
+    },
+    handlers::Handler,
+};
+
+/// restores are read line by line, so only a single row has to fit in memory
+const RESTORE_LINE_LIMIT: usize = 16 * 1024 * 1024;
+
+pub(super) fn configure_routes(operators: Operators) -> Router<Handler> {
+    Router::new().nest(
+        "/admin",
+        Router::new()
+            .route("/backup", get(create_backup))
+            .route(
+                "/restore",
+                post(restore_backup).layer(DefaultBodyLimit::disable()),
+            )
+            .route_layer(middleware::from_fn_with_state(operators, require_operator)),
+    )
+}
+
+/// backups hold every workspace, workspace roles don't reach them
+async fn require_operator(
+    State(operators): State<Operators>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let user_id = request
+        .extensions()
+        .get::<CurrentUser>()
+        .map(|user| user.user_id)
+        .ok_or(Error::Unauthenticated)?;
+
+    if !operators.contains(user_id) {
+        return Err(Error::OperatorRequired);
+    }
+
+    Ok(next.run(request).await)
+}
+
+async fn create_backup(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let backup = handler.create_backup().await?;
+
+    let disposition = format!(
+        "attachment; filename=\"budget-backup-{}.ndjson\"",
+        backup.created_at.format("%Y%m%d%H%M%S")
+    );
+
+    // a failing read aborts the body, the restore refuses it for the missing end line
+    let body = Body::from_stream(
+        ReceiverStream::new(backup.lines).map(|line| line.map(|line| line.to_ndjson())),
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+async fn restore_backup(
+    State(handler): State<Handler>,
+    Query(params): Query<RestoreParams>,
+    body: Body,
+) -> Result<impl IntoResponse> {
+    let restored = handler.restore_backup(read_lines(body), params).await?;
+
+    Ok(Json(restored))
+}
+
+/// parse the lines of the body as they arrive, stopping at the first invalid one
+fn read_lines(body: Body) -> mpsc::Receiver<Result<BackupLine>> {
+    let (sender, lines) = mpsc::channel(BACKUP_BUFFER);
+
+    tokio::spawn(async move {
+        let mut chunks = body.into_data_stream();
+        let mut buffer = Vec::new();
+
+        loop {
+            let chunk = match chunks.next().await {
+                Some(Ok(chunk)) => Some(chunk),
+                Some(Err(error)) => {
+                    let error = Error::InvalidBackup(format!("unreadable body: {error}"));
+                    let _ = sender.send(Err(error)).await;
+                    return;
+                }
+                None => None,
+            };
+            let done = chunk.is_none();
+
+            // only the new bytes can end the pending line
+            let mut start = buffer.len();
+            let mut parsed = vec![];
+
+            if let Some(chunk) = chunk {
+                buffer.extend_from_slice(&chunk);
+            }
+
+            while let Some(offset) = buffer[start..].iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=start + offset).collect();
+                parsed.extend(parse_line(&line));
+                start = 0;
+            }
+
+            if done {
+                parsed.extend(parse_line(&buffer));
+            } else if buffer.len() > RESTORE_LINE_LIMIT {
+                parsed.push(Err(Error::InvalidBackup(format!(
+                    "lines are limited to {RESTORE_LINE_LIMIT} bytes"
+                ))));
+            }
+
+            for line in parsed {
+                let failed = line.is_err();
+
+                if sender.send(line).await.is_err() || failed {
+                    return;
+                }
+            }
+
+            if done {
+                return;
+            }
+        }
+    });
+
+    lines
+}
+
+/// blank lines are skipped
+fn parse_line(line: &[u8]) -> Option<Result<BackupLine>> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return None;
+    }
+
+    Some(BackupLine::parse(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::routes::test_app::TestApp;
+
+    #[tokio::test]
+    async fn should_only_let_operators_back_up_and_restore() {
+        let operator = TestApp::new();
+        let owner = operator.as_user(Uuid::new_v4());
+
+        // owning a workspace doesn't reach the records of the others
+        owner.new_account().await;
+
+        let backup = owner.get("/admin/backup").await;
+        assert_eq!(backup.status, StatusCode::FORBIDDEN);
+        assert_eq!(backup.code(), "OPERATOR_REQUIRED");
+
+        let restore = owner.post("/admin/restore?force=true", json!({})).await;
+        assert_eq!(restore.status, StatusCode::FORBIDDEN);
+
+        // the memory storage has no backups, but the operator gets past the check
+        let backup = operator.get("/admin/backup").await;
+        assert_eq!(backup.code(), "UNSUPPORTED_BY_STORAGE");
+
+        // a backup starts with its header line
+        let restore = operator.post("/admin/restore", json!({})).await;
+        assert_eq!(restore.code(), "INVALID_BACKUP");
+    }
+}
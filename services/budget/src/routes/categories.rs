@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{delete, get, patch, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        categories::{CreateCategory, UpdateCategory},
+        errors::Result,
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/categories",
+        Router::new()
+            .route("/", get(list_categories))
+            .route("/", post(create_category))
+            .route("/:category_id", get(get_category_by_id))
+            .route("/:category_id", patch(update_category_by_id))
+            .route("/:category_id", delete(delete_category_by_id)),
+    )
+}
+
+async fn list_categories(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let categories = handler.list_categories().await?;
+
+    Ok(Json(categories))
+}
+
+async fn create_category(
+    State(handler): State<Handler>,
+    Json(payload): Json<CreateCategory>,
+) -> Result<impl IntoResponse> {
+    let category = handler.create_category(payload).await?;
+
+    Ok(Json(category))
+}
+
+async fn get_category_by_id(
+    State(handler): State<Handler>,
+    Path(category_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let category = handler.get_category_by_id(category_id).await?;
+
+    Ok(Json(category))
+}
+
+async fn update_category_by_id(
+    State(handler): State<Handler>,
+    Path(category_id): Path<Uuid>,
+    Json(payload): Json<UpdateCategory>,
+) -> Result<impl IntoResponse> {
+    let category = handler.update_category_by_id(category_id, payload).await?;
+
+    Ok(Json(category))
+}
+
+async fn delete_category_by_id(
+    State(handler): State<Handler>,
+    Path(category_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let category = handler.delete_category_by_id(category_id).await?;
+
+    Ok(Json(category))
+}
@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    domains::{
+        errors::Result,
+        exports::{ExportFile, ExportParams},
+        views::TransactionFilterParams,
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/exports",
+        Router::new().route("/transactions", get(export_transactions)),
+    )
+}
+
+impl IntoResponse for ExportFile {
+    fn into_response(self) -> Response {
+        let disposition = format!("attachment; filename=\"{}\"", self.file_name);
+
+        (
+            [
+                (header::CONTENT_TYPE, self.content_type.to_string()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            self.body,
+        )
+            .into_response()
+    }
+}
+
+async fn export_transactions(
+    State(handler): State<Handler>,
+    Query(filters): Query<TransactionFilterParams>,
+    Query(params): Query<ExportParams>,
+) -> Result<impl IntoResponse> {
+    let file = handler.export_transactions(filters, params).await?;
+
+    Ok(file)
+}
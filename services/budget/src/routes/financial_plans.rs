@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
@@ -7,7 +7,10 @@ use axum::{
 use uuid::Uuid;
 
 use crate::{
-    domains::{errors::Result, financial_plans::CreateFinancialPlan},
+    domains::{
+        errors::Result, exports::ExportParams, financial_plans::CreateFinancialPlan,
+        views::TransactionFilterParams,
+    },
     handlers::Handler,
 };
 
@@ -17,7 +20,9 @@ pub(super) fn configure_routes() -> Router<Handler> {
         Router::new()
             .route("/", post(create_financial_plan))
             .route("/", get(list_financial_plans))
-            .route("/:id", get(get_financial_plan_by_id)),
+            .route("/:id", get(get_financial_plan_by_id))
+            .route("/:id/close", post(close_financial_plan))
+            .route("/:id/export", get(export_financial_plan)),
     )
 }
 
@@ -44,3 +49,25 @@ async fn get_financial_plan_by_id(
 
     Ok(Json(financial_plan))
 }
+
+async fn close_financial_plan(
+    State(handler): State<Handler>,
+    Path(financial_plan_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let financial_plan = handler.close_financial_plan(financial_plan_id).await?;
+
+    Ok(Json(financial_plan))
+}
+
+async fn export_financial_plan(
+    State(handler): State<Handler>,
+    Path(financial_plan_id): Path<Uuid>,
+    Query(filters): Query<TransactionFilterParams>,
+    Query(params): Query<ExportParams>,
+) -> Result<impl IntoResponse> {
+    let file = handler
+        .export_financial_plan(financial_plan_id, filters, params)
+        .await?;
+
+    Ok(file)
+}
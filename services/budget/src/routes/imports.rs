@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        imports::{CommitImport, CreateImport},
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new()
+        .route("/accounts/:account_id/imports", get(list_imports))
+        .route("/accounts/:account_id/imports", post(create_import))
+        .route(
+            "/accounts/:account_id/imports/:import_id",
+            get(get_import_preview),
+        )
+        .route(
+            "/accounts/:account_id/imports/:import_id/commit",
+            post(commit_import),
+        )
+}
+
+async fn list_imports(
+    State(handler): State<Handler>,
+    Path(account_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let imports = handler.list_imports(account_id).await?;
+
+    Ok(Json(imports))
+}
+
+async fn create_import(
+    State(handler): State<Handler>,
+    Path(account_id): Path<Uuid>,
+    Json(payload): Json<CreateImport>,
+) -> Result<impl IntoResponse> {
+    let preview = handler.create_import(account_id, payload).await?;
+
+    Ok(Json(preview))
+}
+
+async fn get_import_preview(
+    State(handler): State<Handler>,
+    Path((account_id, import_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let preview = handler.get_import_preview(account_id, import_id).await?;
+
+    Ok(Json(preview))
+}
+
+async fn commit_import(
+    State(handler): State<Handler>,
+    Path((account_id, import_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<CommitImport>,
+) -> Result<impl IntoResponse> {
+    let preview = handler
+        .commit_import(account_id, import_id, payload)
+        .await?;
+
+    Ok(Json(preview))
+}
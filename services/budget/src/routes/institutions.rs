@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{delete, get, patch, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        institutions::{CreateInstitution, UpdateInstitution},
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/institutions",
+        Router::new()
+            .route("/", get(list_institutions))
+            .route("/", post(create_institution))
+            .route("/:institution_id", get(get_institution_by_id))
+            .route("/:institution_id", patch(update_institution_by_id))
+            .route("/:institution_id", delete(delete_institution_by_id)),
+    )
+}
+
+async fn list_institutions(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let institutions = handler.list_institutions().await?;
+
+    Ok(Json(institutions))
+}
+
+async fn create_institution(
+    State(handler): State<Handler>,
+    Json(payload): Json<CreateInstitution>,
+) -> Result<impl IntoResponse> {
+    let institution = handler.create_institution(payload).await?;
+
+    Ok(Json(institution))
+}
+
+async fn get_institution_by_id(
+    State(handler): State<Handler>,
+    Path(institution_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let institution = handler.get_institution_by_id(institution_id).await?;
+
+    Ok(Json(institution))
+}
+
+async fn update_institution_by_id(
+    State(handler): State<Handler>,
+    Path(institution_id): Path<Uuid>,
+    Json(payload): Json<UpdateInstitution>,
+) -> Result<impl IntoResponse> {
+    let institution = handler
+        .update_institution_by_id(institution_id, payload)
+        .await?;
+
+    Ok(Json(institution))
+}
+
+async fn delete_institution_by_id(
+    State(handler): State<Handler>,
+    Path(institution_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let institution = handler.delete_institution_by_id(institution_id).await?;
+
+    Ok(Json(institution))
+}
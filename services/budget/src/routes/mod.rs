@@ -1,58 +1,571 @@
 pub mod accounts;
+pub mod audit;
+pub mod backups;
+pub mod categories;
+pub mod exports;
 pub mod financial_plans;
+pub mod imports;
+pub mod institutions;
 pub mod recurrences;
+pub mod reports;
+pub mod rules;
 pub mod settlements;
+pub mod tags;
 pub mod transactions;
+pub mod workspaces;
 
-use axum::{http::StatusCode, response::IntoResponse, Router};
+#[cfg(test)]
+mod test_app;
+use auth_layer::CurrentUser;
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use sqlx::error::ErrorKind;
+use uuid::Uuid;
 
-use crate::{domains::errors::Error, handlers::Handler};
+use crate::{
+    domains::{
+        audit::AuditContext,
+        backups::Operators,
+        errors::{database_error_kind, Error},
+    },
+    handlers::{
+        audit::AUDIT_CONTEXT,
+        workspaces::{MEMBER, WORKSPACE},
+        Handler,
+    },
+};
 
-pub(super) fn configure_routes() -> Router<Handler> {
-    Router::new()
+/// header selecting the workspace of the request, the oldest membership is used without it
+const WORKSPACE_HEADER: &str = "x-workspace-id";
+
+pub(super) fn configure_routes(handler: Handler, operators: Operators) -> Router<Handler> {
+    // records of a workspace, the backups and the workspaces themselves are not scoped
+    let scoped = Router::new()
         .merge(transactions::configure_routes())
         .merge(accounts::configure_routes())
         .merge(settlements::configure_routes())
         .merge(recurrences::configure_routes())
         .merge(financial_plans::configure_routes())
+        .merge(categories::configure_routes())
+        .merge(reports::configure_routes())
+        .merge(tags::configure_routes())
+        .merge(institutions::configure_routes())
+        .merge(imports::configure_routes())
+        .merge(rules::configure_routes())
+        .merge(exports::configure_routes())
+        .merge(audit::configure_routes())
+        .route_layer(middleware::from_fn_with_state(handler, set_workspace));
+
+    Router::new()
+        .route("/health", get(health))
+        .merge(scoped)
+        .merge(workspaces::configure_routes())
+        .merge(backups::configure_routes(operators))
 }
 
-impl IntoResponse for Error {
-    fn into_response(self) -> axum::response::Response {
+async fn health() -> &'static str {
+    "ok"
+}
+
+/// Envelope returned by every failed request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorResponse {
+    pub code: &'static str,
+    pub message: String,
+    pub entity_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    /// internal details, only written to the logs
+    #[serde(skip)]
+    pub detail: Option<String>,
+}
+
+impl Error {
+    pub(crate) fn status_code(&self) -> StatusCode {
         match self {
-            Self::DatabaseError(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}")),
-            Self::TransactionNotFound(id) => (
-                StatusCode::NOT_FOUND,
-                format!("Transaction id {id} not found."),
-            ),
-            Self::AccountNotFound(id) => {
-                (StatusCode::NOT_FOUND, format!("Account id {id} not found."))
+            Self::DatabaseError(err) => match database_error_kind(err) {
+                Some(ErrorKind::UniqueViolation) => StatusCode::CONFLICT,
+                Some(
+                    ErrorKind::ForeignKeyViolation
+                    | ErrorKind::NotNullViolation
+                    | ErrorKind::CheckViolation,
+                ) => StatusCode::UNPROCESSABLE_ENTITY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            Self::TransactionNotFound(_)
+            | Self::AccountNotFound(_)
+            | Self::AccountGrantNotFound(_)
+            | Self::InstallmentNotFound(_)
+            | Self::SettlementNotFound(_)
+            | Self::RecurrenceNotFound(_)
+            | Self::FinancialPlanNotFound(_)
+            | Self::CategoryNotFound(_)
+            | Self::TagNotFound(_)
+            | Self::InstitutionNotFound(_)
+            | Self::ImportNotFound(_)
+            | Self::RuleNotFound(_)
+            | Self::WorkspaceNotFound(_)
+            | Self::MemberNotFound(_) => StatusCode::NOT_FOUND,
+            Self::AccountAlreadyDeleted(_) | Self::LastAccountManager(_) => StatusCode::CONFLICT,
+            Self::TransactionFinished(_) | Self::InstallmentFinished(_) => StatusCode::BAD_REQUEST,
+            Self::AccountDeleted(_)
+            | Self::TransactionDeleted(_)
+            | Self::InstallmentDeleted(_)
+            | Self::InstallmentTransactionMismatch(_)
+            | Self::FinancialPlanDeleted(_)
+            | Self::FinancialPlanClosed(_)
+            | Self::CategoryDeleted(_)
+            | Self::CategoryKindMismatch(_)
+            | Self::InvalidCategoryParent(_)
+            | Self::TagDeleted(_)
+            | Self::InstitutionDeleted(_)
+            | Self::InvalidStatementFile(_)
+            | Self::RuleDeleted(_)
+            | Self::CategoryRequired
+            | Self::InvalidExportParams(_)
+            | Self::InvalidBackup(_)
+            | Self::InvalidWorkspaceHeader(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::CategoryHasSubcategories(_)
+            | Self::InstitutionHasAccounts(_)
+            | Self::ImportAlreadyCommitted(_)
+            | Self::RestoreTargetNotEmpty
+            | Self::LastWorkspaceOwner(_) => StatusCode::CONFLICT,
+            Self::ExportFailed(_) | Self::WorkspaceRequired => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::UnsupportedByStorage(_) => StatusCode::NOT_IMPLEMENTED,
+            Self::Unauthenticated => StatusCode::UNAUTHORIZED,
+            Self::InsufficientRole(_)
+            | Self::AccountPermissionDenied(_)
+            | Self::OperatorRequired => StatusCode::FORBIDDEN,
+        }
+    }
+
+    pub(crate) fn message(&self) -> String {
+        match self {
+            Self::DatabaseError(err) => match database_error_kind(err) {
+                Some(ErrorKind::UniqueViolation) => "Record conflicts with an existing one.".into(),
+                Some(ErrorKind::ForeignKeyViolation) => "Referenced record does not exist.".into(),
+                Some(ErrorKind::NotNullViolation | ErrorKind::CheckViolation) => {
+                    "Record violates a data constraint.".into()
+                }
+                _ => "Internal server error.".into(),
+            },
+            Self::TransactionNotFound(id) => format!("Transaction id {id} not found."),
+            Self::AccountNotFound(id) => format!("Account id {id} not found."),
+            Self::AccountAlreadyDeleted(id) => format!("Account id {id} has been already deleted."),
+            Self::AccountPermissionDenied(id) => {
+                format!("Permission on account id {id} does not allow the operation.")
+            }
+            Self::LastAccountManager(id) => format!("Account id {id} must keep a manager."),
+            Self::AccountGrantNotFound(id) => {
+                format!("User id {id} has no grant on the account.")
+            }
+            Self::TransactionFinished(id) => {
+                format!("Transaction id {id} has been already finished.")
+            }
+            Self::InstallmentFinished(id) => {
+                format!("Installment id {id} has been already finished.")
+            }
+            Self::InstallmentNotFound(id) => format!("Installment id {id} not found."),
+            Self::SettlementNotFound(id) => format!("Settlement id {id} not found."),
+            Self::RecurrenceNotFound(id) => format!("Recurrence id {id} not found."),
+            Self::FinancialPlanNotFound(id) => format!("Financial plan id {id} not found."),
+            Self::AccountDeleted(id) => format!("Account id {id} has been deleted."),
+            Self::TransactionDeleted(id) => format!("Transaction id {id} has been deleted."),
+            Self::InstallmentDeleted(id) => format!("Installment id {id} has been deleted."),
+            Self::InstallmentTransactionMismatch(id) => {
+                format!("Installment id {id} does not belong to the given transaction.")
+            }
+            Self::FinancialPlanDeleted(id) => format!("Financial plan id {id} has been deleted."),
+            Self::FinancialPlanClosed(id) => {
+                format!("Financial plan id {id} has been already closed.")
+            }
+            Self::CategoryNotFound(id) => format!("Category id {id} not found."),
+            Self::CategoryDeleted(id) => format!("Category id {id} has been deleted."),
+            Self::CategoryKindMismatch(id) => {
+                format!("Category id {id} kind does not match the movement type.")
+            }
+            Self::InvalidCategoryParent(id) => {
+                format!("Category id {id} can't be used as parent category.")
+            }
+            Self::CategoryHasSubcategories(id) => {
+                format!("Category id {id} still has active subcategories.")
+            }
+            Self::TagNotFound(id) => format!("Tag id {id} not found."),
+            Self::TagDeleted(id) => format!("Tag id {id} has been deleted."),
+            Self::InstitutionNotFound(id) => format!("Institution id {id} not found."),
+            Self::InstitutionDeleted(id) => format!("Institution id {id} has been deleted."),
+            Self::InstitutionHasAccounts(id) => {
+                format!("Institution id {id} still has active accounts.")
+            }
+            Self::ImportNotFound(id) => format!("Statement import id {id} not found."),
+            Self::ImportAlreadyCommitted(id) => {
+                format!("Statement import id {id} has been already committed.")
+            }
+            Self::InvalidStatementFile(reason) => format!("Invalid statement file: {reason}."),
+            Self::RuleNotFound(id) => format!("Categorization rule id {id} not found."),
+            Self::RuleDeleted(id) => format!("Categorization rule id {id} has been deleted."),
+            Self::CategoryRequired => {
+                "Category is required when no categorization rule matches the description.".into()
             }
-            Self::AccountAlreadyDeleted(id) => (
-                StatusCode::NOT_FOUND,
-                format!("Account id {id} has been already deleted."),
+            Self::InvalidExportParams(reason) => format!("Invalid export parameters: {reason}."),
+            Self::ExportFailed(_) => "Export could not be generated.".into(),
+            Self::InvalidBackup(reason) => format!("Invalid backup: {reason}."),
+            Self::RestoreTargetNotEmpty => {
+                "Database already has budget records, use force to replace them.".into()
+            }
+            Self::UnsupportedByStorage(storage) => {
+                format!("Operation is not supported by the {storage} storage.")
+            }
+            Self::Unauthenticated => "Request has no authenticated user.".into(),
+            Self::WorkspaceRequired => "Internal server error.".into(),
+            Self::InvalidWorkspaceHeader(value) => {
+                format!("Workspace header {value} is not a valid id.")
+            }
+            Self::WorkspaceNotFound(id) => format!("Workspace id {id} not found."),
+            Self::InsufficientRole(id) => {
+                format!("Role in workspace id {id} does not allow the operation.")
+            }
+            Self::MemberNotFound(id) => format!("User id {id} is not a member of the workspace."),
+            Self::LastWorkspaceOwner(id) => {
+                format!("Workspace id {id} must keep at least one owner.")
+            }
+            Self::OperatorRequired => {
+                "Only the operators of the deployment can back up and restore.".into()
+            }
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let body = ErrorResponse {
+            code: self.code(),
+            message: self.message(),
+            entity_id: self.entity_id(),
+            request_id: None,
+            detail: match &self {
+                Self::DatabaseError(err) => Some(format!("{err:?}")),
+                Self::ExportFailed(reason) => Some(reason.clone()),
+                _ => None,
+            },
+        };
+
+        let mut response = (status, Json(body.clone())).into_response();
+        response.extensions_mut().insert(body);
+
+        response
+    }
+}
+
+/// Fills the error envelope with the request id and logs the failure
+pub(super) async fn attach_request_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+
+    let response = next.run(request).await;
+
+    let Some(mut body) = response.extensions().get::<ErrorResponse>().cloned() else {
+        return response;
+    };
+
+    let status = response.status();
+    let request_id_log = request_id.as_deref().unwrap_or("-");
+
+    if status.is_server_error() {
+        log::error!(
+            "[{request_id_log}] {} {}: {}",
+            status,
+            body.code,
+            body.detail.as_deref().unwrap_or(&body.message)
+        );
+    } else {
+        log::warn!(
+            "[{request_id_log}] {} {}: {}",
+            status,
+            body.code,
+            body.message
+        );
+    }
+
+    body.request_id = request_id;
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    Response::from_parts(parts, Json(body).into_response().into_body())
+}
+
+/// Makes the caller and request id available to the audit log while the request is handled
+pub(super) async fn set_audit_context(request: Request, next: Next) -> Response {
+    let context = AuditContext {
+        actor: request
+            .extensions()
+            .get::<CurrentUser>()
+            .map(|user| user.user_id.to_string()),
+        request_id: request
+            .headers()
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(String::from),
+    };
+
+    AUDIT_CONTEXT.scope(context, next.run(request)).await
+}
+
+/// Runs the request in a workspace of the caller, viewers can only read its records
+/// and writes run in a single unit of work
+pub(super) async fn set_workspace(
+    State(handler): State<Handler>,
+    request: Request,
+    next: Next,
+) -> Result<Response, Error> {
+    let user_id = request
+        .extensions()
+        .get::<CurrentUser>()
+        .map(|user| user.user_id)
+        .ok_or(Error::Unauthenticated)?;
+
+    let requested = request
+        .headers()
+        .get(WORKSPACE_HEADER)
+        .map(|value| {
+            let value = String::from_utf8_lossy(value.as_bytes());
+
+            value
+                .parse::<Uuid>()
+                .map_err(|_| Error::InvalidWorkspaceHeader(value.into_owned()))
+        })
+        .transpose()?;
+
+    let member = handler.resolve_workspace(user_id, requested).await?;
+
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD);
+
+    if !read_only && !member.role.can_write() {
+        return Err(Error::InsufficientRole(member.workspace_id));
+    }
+
+    let workspace_id = member.workspace_id;
+    let scoped = WORKSPACE.scope(workspace_id, MEMBER.scope(member, next.run(request)));
+
+    if read_only {
+        return Ok(scoped.await);
+    }
+
+    // the changes of a write and their audit entries are kept or dropped together
+    handler
+        .in_unit_of_work(scoped, |response| response.status().is_success())
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_not_found_errors_to_404() {
+        let id = Uuid::new_v4();
+        let error = Error::TransactionNotFound(id);
+
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(error.code(), "TRANSACTION_NOT_FOUND");
+        assert_eq!(error.entity_id(), Some(id));
+    }
+
+    #[test]
+    fn should_map_already_deleted_account_to_conflict() {
+        let error = Error::AccountAlreadyDeleted(Uuid::new_v4());
+
+        assert_eq!(error.status_code(), StatusCode::CONFLICT);
+        assert_eq!(error.code(), "ACCOUNT_ALREADY_DELETED");
+    }
+
+    #[test]
+    fn should_hide_database_details() {
+        let error = Error::DatabaseError(sqlx::Error::PoolTimedOut);
+
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error.code(), "DATABASE_ERROR");
+        assert_eq!(error.message(), "Internal server error.");
+    }
+
+    #[tokio::test]
+    async fn should_map_unique_violations_to_conflict() {
+        let app = test_app::TestApp::new();
+
+        let response = app
+            .post("/institutions", serde_json::json!({ "name": "C6 Bank" }))
+            .await;
+
+        assert_eq!(response.status, StatusCode::OK);
+
+        let response = app
+            .post("/institutions", serde_json::json!({ "name": "C6 BANK" }))
+            .await;
+
+        assert_eq!(response.status, StatusCode::CONFLICT);
+        assert_eq!(response.code(), "UNIQUE_VIOLATION");
+    }
+
+    #[tokio::test]
+    async fn should_answer_every_error_with_its_status_and_code() {
+        let id = Uuid::new_v4();
+        let reason = String::from("reason");
+
+        let cases = [
+            (
+                Error::DatabaseError(sqlx::Error::RowNotFound),
+                500,
+                "DATABASE_ERROR",
+            ),
+            (Error::TransactionNotFound(id), 404, "TRANSACTION_NOT_FOUND"),
+            (Error::AccountNotFound(id), 404, "ACCOUNT_NOT_FOUND"),
+            (
+                Error::AccountAlreadyDeleted(id),
+                409,
+                "ACCOUNT_ALREADY_DELETED",
+            ),
+            (
+                Error::AccountPermissionDenied(id),
+                403,
+                "ACCOUNT_PERMISSION_DENIED",
+            ),
+            (Error::LastAccountManager(id), 409, "LAST_ACCOUNT_MANAGER"),
+            (
+                Error::AccountGrantNotFound(id),
+                404,
+                "ACCOUNT_GRANT_NOT_FOUND",
+            ),
+            (Error::TransactionFinished(id), 400, "TRANSACTION_FINISHED"),
+            (Error::InstallmentNotFound(id), 404, "INSTALLMENT_NOT_FOUND"),
+            (Error::InstallmentFinished(id), 400, "INSTALLMENT_FINISHED"),
+            (Error::SettlementNotFound(id), 404, "SETTLEMENT_NOT_FOUND"),
+            (Error::RecurrenceNotFound(id), 404, "RECURRENCE_NOT_FOUND"),
+            (
+                Error::FinancialPlanNotFound(id),
+                404,
+                "FINANCIAL_PLAN_NOT_FOUND",
+            ),
+            (Error::AccountDeleted(id), 422, "ACCOUNT_DELETED"),
+            (Error::TransactionDeleted(id), 422, "TRANSACTION_DELETED"),
+            (Error::InstallmentDeleted(id), 422, "INSTALLMENT_DELETED"),
+            (
+                Error::InstallmentTransactionMismatch(id),
+                422,
+                "INSTALLMENT_TRANSACTION_MISMATCH",
             ),
-            Self::TransactionFinished(id) => (
-                StatusCode::BAD_REQUEST,
-                format!("Transaction id {id} has been already finished."),
+            (
+                Error::FinancialPlanDeleted(id),
+                422,
+                "FINANCIAL_PLAN_DELETED",
             ),
-            Self::InstallmentFinished(id) => (
-                StatusCode::BAD_REQUEST,
-                format!("Installment id {id} has been already finished."),
+            (Error::FinancialPlanClosed(id), 422, "FINANCIAL_PLAN_CLOSED"),
+            (Error::CategoryNotFound(id), 404, "CATEGORY_NOT_FOUND"),
+            (Error::CategoryDeleted(id), 422, "CATEGORY_DELETED"),
+            (
+                Error::CategoryKindMismatch(id),
+                422,
+                "CATEGORY_KIND_MISMATCH",
             ),
-            Self::InstallmentNotFound(id) => (
-                StatusCode::NOT_FOUND,
-                format!("Installment id {id} not found."),
+            (
+                Error::InvalidCategoryParent(id),
+                422,
+                "INVALID_CATEGORY_PARENT",
             ),
-            Self::RecurrenceNotFound(id) => (
-                StatusCode::NOT_FOUND,
-                format!("Recurrence id {id} not found."),
+            (
+                Error::CategoryHasSubcategories(id),
+                409,
+                "CATEGORY_HAS_SUBCATEGORIES",
             ),
-            Self::FinancialPlanNotFound(id) => (
-                StatusCode::NOT_FOUND,
-                format!("Financial plan id {id} not found."),
+            (Error::TagNotFound(id), 404, "TAG_NOT_FOUND"),
+            (Error::TagDeleted(id), 422, "TAG_DELETED"),
+            (Error::InstitutionNotFound(id), 404, "INSTITUTION_NOT_FOUND"),
+            (Error::InstitutionDeleted(id), 422, "INSTITUTION_DELETED"),
+            (
+                Error::InstitutionHasAccounts(id),
+                409,
+                "INSTITUTION_HAS_ACCOUNTS",
             ),
+            (Error::ImportNotFound(id), 404, "IMPORT_NOT_FOUND"),
+            (
+                Error::ImportAlreadyCommitted(id),
+                409,
+                "IMPORT_ALREADY_COMMITTED",
+            ),
+            (
+                Error::InvalidStatementFile(reason.clone()),
+                422,
+                "INVALID_STATEMENT_FILE",
+            ),
+            (Error::RuleNotFound(id), 404, "RULE_NOT_FOUND"),
+            (Error::RuleDeleted(id), 422, "RULE_DELETED"),
+            (Error::CategoryRequired, 422, "CATEGORY_REQUIRED"),
+            (
+                Error::InvalidExportParams(reason.clone()),
+                422,
+                "INVALID_EXPORT_PARAMS",
+            ),
+            (Error::ExportFailed(reason.clone()), 500, "EXPORT_FAILED"),
+            (
+                Error::InvalidBackup(reason.clone()),
+                422,
+                "INVALID_BACKUP",
+            ),
+            (
+                Error::RestoreTargetNotEmpty,
+                409,
+                "RESTORE_TARGET_NOT_EMPTY",
+            ),
+            (
+                Error::UnsupportedByStorage("memory"),
+                501,
+                "UNSUPPORTED_BY_STORAGE",
+            ),
+            (Error::Unauthenticated, 401, "UNAUTHENTICATED"),
+            (Error::WorkspaceRequired, 500, "WORKSPACE_REQUIRED"),
+            (
+                Error::InvalidWorkspaceHeader(reason),
+                422,
+                "INVALID_WORKSPACE_HEADER",
+            ),
+            (Error::WorkspaceNotFound(id), 404, "WORKSPACE_NOT_FOUND"),
+            (Error::InsufficientRole(id), 403, "INSUFFICIENT_ROLE"),
+            (Error::MemberNotFound(id), 404, "MEMBER_NOT_FOUND"),
+            (
+                Error::LastWorkspaceOwner(id),
+                409,
+                "LAST_WORKSPACE_OWNER",
+            ),
+            (Error::OperatorRequired, 403, "OPERATOR_REQUIRED"),
+        ];
+
+        for (error, status, code) in cases {
+            let entity_id = error.entity_id();
+            let response = error.into_response();
+
+            assert_eq!(response.status().as_u16(), status, "{code}");
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+            assert_eq!(body["code"], code);
+            assert!(!body["message"].as_str().unwrap().is_empty());
+            assert_eq!(
+                body["entityId"].as_str(),
+                entity_id.map(|_| id.to_string()).as_deref()
+            );
         }
-        .into_response()
     }
 }
@@ -1,7 +1,7 @@
 use axum::{
     extract::{Path, State},
     response::IntoResponse,
-    routing::{get, patch, post},
+    routing::{delete, get, patch, post},
     Json, Router,
 };
 use uuid::Uuid;
@@ -21,7 +21,13 @@ pub(super) fn configure_routes() -> Router<Handler> {
             .route("/", get(list_recurrences))
             .route("/", post(create_recurrence))
             .route("/:recurrence_id", get(get_recurrence_by_id))
-            .route("/:recurrence_id", patch(update_recurrence_by_id)),
+            .route("/:recurrence_id", patch(update_recurrence_by_id))
+            .route("/:recurrence_id/tags", get(list_recurrence_tags))
+            .route("/:recurrence_id/tags/:tag_id", post(attach_recurrence_tag))
+            .route(
+                "/:recurrence_id/tags/:tag_id",
+                delete(detach_recurrence_tag),
+            ),
     )
 }
 
@@ -58,3 +64,30 @@ async fn update_recurrence_by_id(
 
     Ok(Json::from(recurrence))
 }
+
+async fn list_recurrence_tags(
+    State(handler): State<Handler>,
+    Path(recurrence_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let tags = handler.list_recurrence_tags(recurrence_id).await?;
+
+    Ok(Json::from(tags))
+}
+
+async fn attach_recurrence_tag(
+    State(handler): State<Handler>,
+    Path((recurrence_id, tag_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let tags = handler.attach_recurrence_tag(recurrence_id, tag_id).await?;
+
+    Ok(Json::from(tags))
+}
+
+async fn detach_recurrence_tag(
+    State(handler): State<Handler>,
+    Path((recurrence_id, tag_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let tags = handler.detach_recurrence_tag(recurrence_id, tag_id).await?;
+
+    Ok(Json::from(tags))
+}
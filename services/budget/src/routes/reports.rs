@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+
+use crate::{
+    domains::{errors::Result, reports::ReportParams},
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/reports",
+        Router::new()
+            .route("/categories", get(category_report))
+            .route("/tags", get(tag_report)),
+    )
+}
+
+async fn category_report(
+    State(handler): State<Handler>,
+    Query(params): Query<ReportParams>,
+) -> Result<impl IntoResponse> {
+    let report = handler.category_report(params).await?;
+
+    Ok(Json(report))
+}
+
+async fn tag_report(
+    State(handler): State<Handler>,
+    Query(params): Query<ReportParams>,
+) -> Result<impl IntoResponse> {
+    let report = handler.tag_report(params).await?;
+
+    Ok(Json(report))
+}
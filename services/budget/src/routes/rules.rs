@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{delete, get, patch, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        rules::{CreateRule, UpdateRule},
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/rules",
+        Router::new()
+            .route("/", get(list_rules))
+            .route("/", post(create_rule))
+            .route("/apply", post(apply_rules))
+            .route("/dry-run", post(dry_run_rules))
+            .route("/:rule_id", get(get_rule_by_id))
+            .route("/:rule_id", patch(update_rule_by_id))
+            .route("/:rule_id", delete(delete_rule_by_id)),
+    )
+}
+
+async fn list_rules(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let rules = handler.list_rules().await?;
+
+    Ok(Json(rules))
+}
+
+async fn create_rule(
+    State(handler): State<Handler>,
+    Json(payload): Json<CreateRule>,
+) -> Result<impl IntoResponse> {
+    let rule = handler.create_rule(payload).await?;
+
+    Ok(Json(rule))
+}
+
+async fn apply_rules(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let changes = handler.apply_rules(false).await?;
+
+    Ok(Json(changes))
+}
+
+async fn dry_run_rules(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let changes = handler.apply_rules(true).await?;
+
+    Ok(Json(changes))
+}
+
+async fn get_rule_by_id(
+    State(handler): State<Handler>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let rule = handler.get_rule_by_id(rule_id).await?;
+
+    Ok(Json(rule))
+}
+
+async fn update_rule_by_id(
+    State(handler): State<Handler>,
+    Path(rule_id): Path<Uuid>,
+    Json(payload): Json<UpdateRule>,
+) -> Result<impl IntoResponse> {
+    let rule = handler.update_rule_by_id(rule_id, payload).await?;
+
+    Ok(Json(rule))
+}
+
+async fn delete_rule_by_id(
+    State(handler): State<Handler>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let rule = handler.delete_rule_by_id(rule_id).await?;
+
+    Ok(Json(rule))
+}
@@ -37,3 +37,123 @@ async fn create_settlement(
 
     Ok(Json::from(settlement))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use crate::routes::test_app::TestApp;
+
+    async fn new_transaction(app: &TestApp, installments: i16) -> Uuid {
+        let account_id = app.new_account().await;
+        let category_id = app.new_category("EXPENSE").await;
+        let financial_plan_id = app.new_financial_plan().await;
+
+        app.post(
+            "/transactions",
+            json!({
+                "financialPlanId": financial_plan_id,
+                "movementType": "EXPENSE",
+                "description": "sofa",
+                "value": "300",
+                "dueDate": "2024-05-10",
+                "categoryId": category_id,
+                "accountId": account_id,
+                "installments": installments,
+            }),
+        )
+        .await
+        .created("transactionId")
+    }
+
+    /// rows of the json export, one per installment
+    async fn installment_rows(app: &TestApp) -> Vec<Value> {
+        let response = app.get("/exports/transactions?format=json").await;
+
+        assert_eq!(response.status, StatusCode::OK);
+
+        response.body.as_array().cloned().unwrap()
+    }
+
+    async fn transaction_status(app: &TestApp, transaction_id: Uuid) -> Value {
+        app.get(&format!("/transactions/{transaction_id}"))
+            .await
+            .body["status"]
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn should_complete_transaction_after_settling_every_installment() {
+        let app = TestApp::new();
+        let transaction_id = new_transaction(&app, 3).await;
+
+        let rows = installment_rows(&app).await;
+
+        assert_eq!(rows.len(), 3);
+
+        for (index, row) in rows.iter().enumerate() {
+            let installment_id = row["installmentId"].as_str().unwrap();
+
+            let response = app
+                .post(
+                    &format!(
+                        "/settlements?transaction_id={transaction_id}&installment_id={installment_id}"
+                    ),
+                    json!({ "paidDate": "2024-05-10", "paidValue": "100" }),
+                )
+                .await;
+
+            assert_eq!(response.status, StatusCode::OK);
+
+            let expected = if index < 2 { "PENDING" } else { "COMPLETED" };
+
+            assert_eq!(transaction_status(&app, transaction_id).await, expected);
+        }
+
+        let rows = installment_rows(&app).await;
+
+        assert!(rows
+            .iter()
+            .all(|row| row["installmentStatus"] == "COMPLETED"));
+
+        let response = app
+            .post(
+                &format!("/settlements?transaction_id={transaction_id}"),
+                json!({ "paidDate": "2024-05-10", "paidValue": "300" }),
+            )
+            .await;
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert_eq!(response.code(), "TRANSACTION_FINISHED");
+    }
+
+    #[tokio::test]
+    async fn should_refuse_installment_of_another_transaction() {
+        let app = TestApp::new();
+        let transaction_id = new_transaction(&app, 2).await;
+        let other_transaction_id = new_transaction(&app, 0).await;
+
+        let rows = installment_rows(&app).await;
+        let installment_id = rows
+            .iter()
+            .find_map(|row| row["installmentId"].as_str())
+            .unwrap();
+
+        let response = app
+            .post(
+                &format!(
+                    "/settlements?transaction_id={other_transaction_id}&installment_id={installment_id}"
+                ),
+                json!({ "paidDate": "2024-05-10", "paidValue": "150" }),
+            )
+            .await;
+
+        assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.code(), "INSTALLMENT_TRANSACTION_MISMATCH");
+        assert_eq!(response.body["entityId"], installment_id);
+        assert_eq!(response.body["requestId"], "test-request");
+        assert_eq!(transaction_status(&app, transaction_id).await, "PENDING");
+    }
+}
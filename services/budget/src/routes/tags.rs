@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{delete, get, patch, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        tags::{CreateTag, UpdateTag},
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/tags",
+        Router::new()
+            .route("/", get(list_tags))
+            .route("/", post(create_tag))
+            .route("/:tag_id", get(get_tag_by_id))
+            .route("/:tag_id", patch(update_tag_by_id))
+            .route("/:tag_id", delete(delete_tag_by_id)),
+    )
+}
+
+async fn list_tags(State(handler): State<Handler>) -> Result<impl IntoResponse> {
+    let tags = handler.list_tags().await?;
+
+    Ok(Json(tags))
+}
+
+async fn create_tag(
+    State(handler): State<Handler>,
+    Json(payload): Json<CreateTag>,
+) -> Result<impl IntoResponse> {
+    let tag = handler.create_tag(payload).await?;
+
+    Ok(Json(tag))
+}
+
+async fn get_tag_by_id(
+    State(handler): State<Handler>,
+    Path(tag_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let tag = handler.get_tag_by_id(tag_id).await?;
+
+    Ok(Json(tag))
+}
+
+async fn update_tag_by_id(
+    State(handler): State<Handler>,
+    Path(tag_id): Path<Uuid>,
+    Json(payload): Json<UpdateTag>,
+) -> Result<impl IntoResponse> {
+    let tag = handler.update_tag_by_id(tag_id, payload).await?;
+
+    Ok(Json(tag))
+}
+
+async fn delete_tag_by_id(
+    State(handler): State<Handler>,
+    Path(tag_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let tag = handler.delete_tag_by_id(tag_id).await?;
+
+    Ok(Json(tag))
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use auth_layer::CurrentUser;
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    middleware, Router,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::{
+    domains::backups::Operators, handlers::Handler, repositories::memory::MemoryRepository,
+};
+
+/// Router of the service driven with in-process requests, backed by the memory storage
+pub struct TestApp {
+    router: Router,
+    /// caller of the requests, as the auth layer would add it
+    user_id: Uuid,
+    workspace_id: Option<Uuid>,
+}
+
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    /// null when the body is not json
+    pub body: Value,
+}
+
+impl TestApp {
+    /// the first caller is the operator of the deployment, other users are not
+    pub fn new() -> Self {
+        let handler = Handler::with_repository(Arc::new(MemoryRepository::default()));
+        let user_id = Uuid::new_v4();
+
+        TestApp {
+            router: super::configure_routes(handler.clone(), Operators::new([user_id]))
+                .with_state(handler)
+                .layer(middleware::from_fn(super::set_audit_context))
+                .layer(middleware::from_fn(super::attach_request_id)),
+            user_id,
+            workspace_id: None,
+        }
+    }
+
+    /// same service called by another user
+    pub fn as_user(&self, user_id: Uuid) -> Self {
+        TestApp {
+            router: self.router.clone(),
+            user_id,
+            workspace_id: None,
+        }
+    }
+
+    /// same caller acting on the given workspace
+    pub fn in_workspace(&self, workspace_id: Uuid) -> Self {
+        TestApp {
+            router: self.router.clone(),
+            user_id: self.user_id,
+            workspace_id: Some(workspace_id),
+        }
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.send(Method::GET, uri, None).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value) -> TestResponse {
+        self.send(Method::POST, uri, Some(body)).await
+    }
+
+    pub async fn patch(&self, uri: &str, body: Value) -> TestResponse {
+        self.send(Method::PATCH, uri, Some(body)).await
+    }
+
+    pub async fn delete(&self, uri: &str) -> TestResponse {
+        self.send(Method::DELETE, uri, None).await
+    }
+
+    async fn send(&self, method: Method, uri: &str, body: Option<Value>) -> TestResponse {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("x-request-id", "test-request")
+            .extension(CurrentUser {
+                user_id: self.user_id,
+                session_id: Uuid::nil(),
+                scopes: None,
+            });
+
+        if let Some(workspace_id) = self.workspace_id {
+            request = request.header("x-workspace-id", workspace_id.to_string());
+        }
+
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        TestResponse {
+            status,
+            body: serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        }
+    }
+
+    /// debit account of a new institution
+    pub async fn new_account(&self) -> Uuid {
+        let institution = self
+            .post(
+                "/institutions",
+                json!({ "name": format!("Bank {}", Uuid::new_v4()) }),
+            )
+            .await
+            .created("institutionId");
+
+        self.post(
+            "/accounts",
+            json!({ "institutionId": institution, "owner": "owner", "accountType": "DEBIT" }),
+        )
+        .await
+        .created("accountId")
+    }
+
+    pub async fn new_category(&self, kind: &str) -> Uuid {
+        self.post(
+            "/categories",
+            json!({ "name": format!("Category {}", Uuid::new_v4()), "kind": kind }),
+        )
+        .await
+        .created("categoryId")
+    }
+
+    pub async fn new_financial_plan(&self) -> Uuid {
+        self.post("/financial_plans", json!({ "month": "MAY", "year": 2024 }))
+            .await
+            .created("financialPlanId")
+    }
+}
+
+impl TestResponse {
+    /// id of the record created by the request
+    pub fn created(&self, field: &str) -> Uuid {
+        assert!(self.status.is_success(), "request failed: {self:?}");
+
+        self.body[field]
+            .as_str()
+            .and_then(|id| id.parse().ok())
+            .unwrap_or_else(|| panic!("{field} missing from {}", self.body))
+    }
+
+    /// code of the error envelope
+    pub fn code(&self) -> &str {
+        self.body["code"].as_str().unwrap_or_default()
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::IntoResponse,
     routing::{delete, get, patch, post},
     Json, Router,
@@ -10,6 +10,7 @@ use crate::{
     domains::{
         errors::Result,
         transactions::{CreateTransaction, TransactionStatus, UpdateTransaction},
+        views::TransactionFilterParams,
     },
     handlers::Handler,
 };
@@ -23,7 +24,16 @@ pub(super) fn configure_routes() -> Router<Handler> {
             .route("/:transaction_id", get(get_transaction_by_id))
             .route("/:transaction_id", delete(delete_transaction_by_id))
             .route("/:transaction_id", patch(update_transaction_by_id))
-            .route("/:transaction_id/:status", post(finish_transaction)),
+            .route("/:transaction_id/:status", post(finish_transaction))
+            .route("/:transaction_id/tags", get(list_transaction_tags))
+            .route(
+                "/:transaction_id/tags/:tag_id",
+                post(attach_transaction_tag),
+            )
+            .route(
+                "/:transaction_id/tags/:tag_id",
+                delete(detach_transaction_tag),
+            ),
     )
 }
 
@@ -36,8 +46,11 @@ async fn create_transaction(
     Ok(Json(transaction))
 }
 
-async fn list_transactions(State(handler): State<Handler>) -> Result<impl IntoResponse> {
-    let transactions = handler.list_transactions().await?;
+async fn list_transactions(
+    State(handler): State<Handler>,
+    Query(params): Query<TransactionFilterParams>,
+) -> Result<impl IntoResponse> {
+    let transactions = handler.list_transactions(params).await?;
 
     Ok(Json(transactions))
 }
@@ -80,3 +93,34 @@ async fn finish_transaction(
 
     Ok(Json(transaction))
 }
+
+async fn list_transaction_tags(
+    State(handler): State<Handler>,
+    Path(transaction_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let tags = handler.list_transaction_tags(transaction_id).await?;
+
+    Ok(Json(tags))
+}
+
+async fn attach_transaction_tag(
+    State(handler): State<Handler>,
+    Path((transaction_id, tag_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let tags = handler
+        .attach_transaction_tag(transaction_id, tag_id)
+        .await?;
+
+    Ok(Json(tags))
+}
+
+async fn detach_transaction_tag(
+    State(handler): State<Handler>,
+    Path((transaction_id, tag_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let tags = handler
+        .detach_transaction_tag(transaction_id, tag_id)
+        .await?;
+
+    Ok(Json(tags))
+}
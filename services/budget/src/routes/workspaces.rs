@@ -0,0 +1,259 @@
+use auth_layer::CurrentUser;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{delete, get, patch, post},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        errors::Result,
+        workspaces::{AddMember, CreateWorkspace, UpdateMember, UpdateWorkspace},
+    },
+    handlers::Handler,
+};
+
+pub(super) fn configure_routes() -> Router<Handler> {
+    Router::new().nest(
+        "/workspaces",
+        Router::new()
+            .route("/", get(list_workspaces))
+            .route("/", post(create_workspace))
+            .route("/:workspace_id", get(get_workspace_by_id))
+            .route("/:workspace_id", patch(update_workspace_by_id))
+            .route("/:workspace_id/members", get(list_members))
+            .route("/:workspace_id/members", post(add_member))
+            .route("/:workspace_id/members/:user_id", patch(update_member))
+            .route("/:workspace_id/members/:user_id", delete(remove_member)),
+    )
+}
+
+async fn list_workspaces(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    // users get their first workspace on the first request, listing included
+    handler.resolve_workspace(user.user_id, None).await?;
+
+    let workspaces = handler.list_workspaces(user.user_id).await?;
+
+    Ok(Json(workspaces))
+}
+
+async fn create_workspace(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Json(payload): Json<CreateWorkspace>,
+) -> Result<impl IntoResponse> {
+    let workspace = handler.create_workspace(user.user_id, payload).await?;
+
+    Ok(Json(workspace))
+}
+
+async fn get_workspace_by_id(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let workspace = handler
+        .get_workspace_by_id(user.user_id, workspace_id)
+        .await?;
+
+    Ok(Json(workspace))
+}
+
+async fn update_workspace_by_id(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkspace>,
+) -> Result<impl IntoResponse> {
+    let workspace = handler
+        .update_workspace_by_id(user.user_id, workspace_id, payload)
+        .await?;
+
+    Ok(Json(workspace))
+}
+
+async fn list_members(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let members = handler.list_members(user.user_id, workspace_id).await?;
+
+    Ok(Json(members))
+}
+
+async fn add_member(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<AddMember>,
+) -> Result<impl IntoResponse> {
+    let member = handler
+        .add_member(user.user_id, workspace_id, payload)
+        .await?;
+
+    Ok(Json(member))
+}
+
+async fn update_member(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Path((workspace_id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateMember>,
+) -> Result<impl IntoResponse> {
+    let member = handler
+        .update_member(user.user_id, workspace_id, member_id, payload)
+        .await?;
+
+    Ok(Json(member))
+}
+
+async fn remove_member(
+    State(handler): State<Handler>,
+    Extension(user): Extension<CurrentUser>,
+    Path((workspace_id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let member = handler
+        .remove_member(user.user_id, workspace_id, member_id)
+        .await?;
+
+    Ok(Json(member))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::routes::test_app::TestApp;
+
+    #[tokio::test]
+    async fn should_share_records_only_with_workspace_members() {
+        let app = TestApp::new();
+        let account_id = app.new_account().await;
+        let account_uri = format!("/accounts/{account_id}");
+
+        let workspaces = app.get("/workspaces").await;
+        assert_eq!(workspaces.body[0]["role"], "OWNER");
+        let workspace_id = workspaces.body[0]["workspaceId"].as_str().unwrap();
+
+        // another user gets a workspace of their own
+        let partner = app.as_user(Uuid::new_v4());
+
+        let response = partner.get(&account_uri).await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(partner.get("/accounts").await.body, json!([]));
+
+        let response = partner
+            .in_workspace(workspace_id.parse().unwrap())
+            .get("/accounts")
+            .await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.code(), "WORKSPACE_NOT_FOUND");
+
+        let members_uri = format!("/workspaces/{workspace_id}/members");
+        let response = app
+            .post(
+                &members_uri,
+                json!({ "userId": partner.user_id(), "role": "VIEWER" }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let shared = partner.in_workspace(workspace_id.parse().unwrap());
+
+        let response = shared.get(&account_uri).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let response = shared
+            .patch(&account_uri, json!({ "owner": "partner" }))
+            .await;
+        assert_eq!(response.status, StatusCode::FORBIDDEN);
+        assert_eq!(response.code(), "INSUFFICIENT_ROLE");
+
+        let response = app
+            .patch(
+                &format!("{members_uri}/{}", partner.user_id()),
+                json!({ "role": "EDITOR" }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let response = shared
+            .patch(&account_uri, json!({ "owner": "partner" }))
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let response = app
+            .delete(&format!("{members_uri}/{}", app.user_id()))
+            .await;
+        assert_eq!(response.status, StatusCode::CONFLICT);
+        assert_eq!(response.code(), "LAST_WORKSPACE_OWNER");
+    }
+
+    #[tokio::test]
+    async fn should_let_a_new_workspace_record_right_away() {
+        let app = TestApp::new();
+
+        let response = app
+            .post("/workspaces", json!({ "name": "Household" }))
+            .await;
+        let workspace = app.in_workspace(response.created("workspaceId"));
+
+        // the defaults are the only categories and institutions of the workspace
+        let categories = workspace.get("/categories").await.body;
+        let category_id = categories
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|category| category["name"] == "Food")
+            .map(|category| category["categoryId"].clone())
+            .unwrap();
+
+        let institutions = workspace.get("/institutions").await.body;
+        let institution_id = institutions
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|institution| institution["name"] == "Nubank")
+            .map(|institution| institution["institutionId"].clone())
+            .unwrap();
+
+        let account_id = workspace
+            .post(
+                "/accounts",
+                json!({ "institutionId": institution_id, "owner": "owner", "accountType": "DEBIT" }),
+            )
+            .await
+            .created("accountId");
+        let financial_plan_id = workspace.new_financial_plan().await;
+
+        let response = workspace
+            .post(
+                "/transactions",
+                json!({
+                    "financialPlanId": financial_plan_id,
+                    "movementType": "EXPENSE",
+                    "description": "groceries",
+                    "value": "120",
+                    "dueDate": "2024-05-10",
+                    "categoryId": category_id,
+                    "accountId": account_id,
+                    "installments": 0,
+                }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        // the personal workspace of a new user starts with the same defaults
+        let partner = app.as_user(Uuid::new_v4());
+        let institutions = partner.get("/institutions").await.body;
+        assert_eq!(institutions.as_array().unwrap().len(), 7);
+    }
+}
@@ -0,0 +1,13 @@
+-- schema the service was running on before the versioned migrations, skipped when it exists
+CREATE TABLE IF NOT EXISTS worknotes (
+    work_note_id UUID PRIMARY KEY,
+    category VARCHAR NOT NULL,
+    work_date DATE NOT NULL,
+    work_hours DOUBLE PRECISION NOT NULL,
+    observation VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS worknotes_work_date_idx ON worknotes (work_date) WHERE deleted_at IS NULL;
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
+use auth_layer::{ApiKeyVerifier, AuthLayer, JwksVerifier};
 use handlers::Handler;
 use repositories::SqlxRepository;
 use sqlx::postgres::PgPoolOptions;
 mod domain;
 mod handlers;
+mod logger;
 mod repositories;
 mod routes;
 
@@ -12,6 +14,8 @@ mod routes;
 async fn main() {
     dotenv::dotenv().ok();
 
+    logger::init().expect("Failed to initialize logger");
+
     let conn_str = std::env::var("DATABASE_URL").expect("Could not fetch connection string.");
 
     let pool = PgPoolOptions::new()
@@ -20,10 +24,26 @@ async fn main() {
         .await
         .expect("Couldn't connect to the database");
 
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("Couldn't run the database migrations");
+
+    // deploys run the migrations once before starting the new instances
+    if std::env::args().any(|arg| arg == "--migrate-only") {
+        log::info!("Database migrations applied");
+        return;
+    }
+
     let sqlx_repository = Arc::new(SqlxRepository::new(pool));
     let handler = Handler::new(sqlx_repository);
 
-    let app = routes::configure_routes().with_state(handler);
+    let auth = AuthLayer::new(Arc::new(JwksVerifier::from_env()))
+        .api_keys(Arc::new(ApiKeyVerifier::from_env()))
+        .public_path("/health")
+        .scoped("work_notes");
+
+    let app = routes::configure_routes().with_state(handler).layer(auth);
 
     let port = std::env::var("PORT").expect("Could not fetch port data.");
     let url = format!("0.0.0.0:{}", port);
@@ -1,9 +1,15 @@
 use crate::{domain::errors::Error, handlers::Handler};
-use axum::{http::StatusCode, response::IntoResponse, Router};
+use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
 pub mod work_note;
 
 pub(super) fn configure_routes() -> Router<Handler> {
-    Router::new().merge(work_note::configure_routes())
+    Router::new()
+        .route("/health", get(health))
+        .merge(work_note::configure_routes())
+}
+
+async fn health() -> &'static str {
+    "ok"
 }
 
 impl IntoResponse for Error {